@@ -1,10 +1,10 @@
 // Allocation Strategies Contract
 #![no_std]
 
-use shared_utils::{Pausable, RateLimiter};
+use shared_utils::{Pausable, RateLimiter, Sunset, SunsetState};
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Map,
-    Symbol, Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env,
+    IntoVal, Map, Symbol, Vec,
 };
 
 // Current storage version for migration checks.
@@ -34,6 +34,8 @@ pub enum Error {
     InvalidVersion = 16,
     AlreadyMigrated = 17,
     InsufficientCommitmentBalance = 18,
+    InvalidBacktest = 19,
+    ProtocolSunset = 20,
 }
 
 // ============================================================================
@@ -87,6 +89,41 @@ pub struct AllocationSummary {
     pub allocations: Vec<Allocation>,
 }
 
+/// Historical performance summary of a strategy over one period.
+/// `realized_apy` and `max_drawdown` are in basis points.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrategyPerformance {
+    pub strategy: Strategy,
+    pub period_start: u64,
+    pub period_end: u64,
+    pub realized_apy: u32,
+    pub max_drawdown: u32,
+    pub recorded_by: Address,
+    pub recorded_at: u64,
+}
+
+/// Allocation preview with expected (current pool APY) and historical
+/// (recorded backtest) figures, all in basis points.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct AllocationPreview {
+    pub strategy: Strategy,
+    /// Planned amount per pool (pool_id -> amount)
+    pub plan: Map<u32, i128>,
+    /// Allocation-weighted APY of the selected pools
+    pub expected_apy: u32,
+    /// Average realized APY over recorded periods (0 if none)
+    pub historical_apy: u32,
+    /// Worst max drawdown over recorded periods (0 if none)
+    pub historical_max_drawdown: u32,
+    /// Number of recorded periods
+    pub historical_periods: u32,
+}
+
+/// Maximum performance records kept per strategy; the oldest is dropped first.
+pub const MAX_BACKTESTS_PER_STRATEGY: u32 = 52;
+
 // ============================================================================
 // STORAGE KEYS
 // ============================================================================
@@ -105,6 +142,8 @@ pub enum DataKey {
     TotalAllocated(u64),  // Total amount allocated per commitment
     AllocationOwner(u64), // Track allocation ownership
     Version,              // Contract version
+    Verifier(Address),    // Address allowed to record backtests
+    Backtests(Strategy),  // Vec<StrategyPerformance> per strategy
 }
 
 // ============================================================================
@@ -161,11 +200,16 @@ impl AllocationStrategiesContract {
         apy: u32,
         max_capacity: i128,
     ) -> Result<(), Error> {
-        admin.require_auth();
+        // Bind the admin's signature to the exact pool parameters
+        admin.require_auth_for_args((pool_id, risk_level, apy, max_capacity).into_val(&env));
         Self::require_initialized(&env)?;
         Self::require_admin(&env, &admin)?;
         Self::require_no_reentrancy(&env)?;
 
+        if Sunset::state(&env) != SunsetState::Live {
+            return Err(Error::ProtocolSunset);
+        }
+
         // Input validation
         if max_capacity <= 0 {
             return Err(Error::InvalidCapacity);
@@ -307,6 +351,11 @@ impl AllocationStrategiesContract {
         Self::require_initialized(&env)?;
         Self::require_no_reentrancy(&env)?;
 
+        // No new allocations once a sunset has begun
+        if Sunset::state(&env) != SunsetState::Live {
+            return Err(Error::ProtocolSunset);
+        }
+
         // Rate limit allocations per caller address
         let fn_symbol = symbol_short!("alloc");
         RateLimiter::check(&env, &caller, &fn_symbol);
@@ -569,6 +618,93 @@ impl AllocationStrategiesContract {
         })
     }
 
+    /// Begin the protocol sunset (admin only): no new pools or allocations;
+    /// existing allocations can still be rebalanced. Cannot be undone.
+    pub fn begin_sunset(env: Env, admin: Address, final_date: u64) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+        Sunset::begin(&env, final_date);
+        Ok(())
+    }
+
+    /// Get the protocol sunset state
+    pub fn get_sunset_state(env: Env) -> SunsetState {
+        Sunset::state(&env)
+    }
+
+    /// Grant or revoke permission to record backtest data (admin only)
+    pub fn set_verifier(
+        env: Env,
+        admin: Address,
+        verifier: Address,
+        enabled: bool,
+    ) -> Result<(), Error> {
+        admin.require_auth_for_args((verifier.clone(), enabled).into_val(&env));
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+
+        if enabled {
+            env.storage()
+                .instance()
+                .set(&DataKey::Verifier(verifier.clone()), &true);
+        } else {
+            env.storage()
+                .instance()
+                .remove(&DataKey::Verifier(verifier.clone()));
+        }
+        env.events()
+            .publish((symbol_short!("verifier"), verifier), enabled);
+        Ok(())
+    }
+
+    /// Record a historical performance summary for a strategy (admin or verifier).
+    ///
+    /// Used by `preview_allocate` and by governance when approving strategies.
+    /// Only the latest `MAX_BACKTESTS_PER_STRATEGY` records are kept.
+    pub fn record_backtest(
+        env: Env,
+        caller: Address,
+        strategy: Strategy,
+        period_start: u64,
+        period_end: u64,
+        realized_apy: u32,
+        max_drawdown: u32,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        Self::require_initialized(&env)?;
+        if !Self::is_verifier(env.clone(), caller.clone()) {
+            Self::require_admin(&env, &caller)?;
+        }
+
+        if period_end <= period_start || realized_apy > 100_000 || max_drawdown > 10_000 {
+            return Err(Error::InvalidBacktest);
+        }
+
+        let mut backtests = Self::get_backtests(env.clone(), strategy);
+        if backtests.len() >= MAX_BACKTESTS_PER_STRATEGY {
+            backtests.pop_front();
+        }
+        backtests.push_back(StrategyPerformance {
+            strategy,
+            period_start,
+            period_end,
+            realized_apy,
+            max_drawdown,
+            recorded_by: caller.clone(),
+            recorded_at: env.ledger().timestamp(),
+        });
+        env.storage()
+            .persistent()
+            .set(&DataKey::Backtests(strategy), &backtests);
+
+        env.events().publish(
+            (symbol_short!("backtest"), strategy),
+            (period_start, period_end, realized_apy, max_drawdown),
+        );
+        Ok(())
+    }
+
     // ========================================================================
     // VIEW FUNCTIONS
     // ========================================================================
@@ -620,6 +756,83 @@ impl AllocationStrategiesContract {
         pools
     }
 
+    /// Preview how `amount` would be allocated under `strategy`, with the
+    /// expected APY of the selected pools next to the strategy's recorded history.
+    pub fn preview_allocate(
+        env: Env,
+        strategy: Strategy,
+        amount: i128,
+    ) -> Result<AllocationPreview, Error> {
+        Self::require_initialized(&env)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let pools = Self::select_pools(&env, strategy)?;
+        if pools.is_empty() {
+            return Err(Error::NoSuitablePools);
+        }
+        let plan = Self::calculate_allocation(&env, amount, &pools, strategy)?;
+
+        let mut weighted_apy = 0i128;
+        let mut planned = 0i128;
+        for pool in pools.iter() {
+            if let Some(alloc_amount) = plan.get(pool.pool_id) {
+                weighted_apy = alloc_amount
+                    .checked_mul(pool.apy as i128)
+                    .and_then(|x| x.checked_add(weighted_apy))
+                    .ok_or(Error::ArithmeticOverflow)?;
+                planned = planned
+                    .checked_add(alloc_amount)
+                    .ok_or(Error::ArithmeticOverflow)?;
+            }
+        }
+        let expected_apy = if planned > 0 {
+            (weighted_apy / planned) as u32
+        } else {
+            0
+        };
+
+        let backtests = Self::get_backtests(env.clone(), strategy);
+        let mut apy_sum = 0u64;
+        let mut historical_max_drawdown = 0u32;
+        for record in backtests.iter() {
+            apy_sum += record.realized_apy as u64;
+            historical_max_drawdown = historical_max_drawdown.max(record.max_drawdown);
+        }
+        let historical_periods = backtests.len();
+        let historical_apy = if historical_periods > 0 {
+            (apy_sum / historical_periods as u64) as u32
+        } else {
+            0
+        };
+
+        Ok(AllocationPreview {
+            strategy,
+            plan,
+            expected_apy,
+            historical_apy,
+            historical_max_drawdown,
+            historical_periods,
+        })
+    }
+
+    /// Check if an address may record backtest data
+    pub fn is_verifier(env: Env, verifier: Address) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::Verifier(verifier))
+            .unwrap_or(false)
+    }
+
+    /// Get recorded performance summaries for a strategy, oldest first
+    pub fn get_backtests(env: Env, strategy: Strategy) -> Vec<StrategyPerformance> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Backtests(strategy))
+            .unwrap_or(Vec::new(&env))
+    }
+
     pub fn is_initialized(env: Env) -> bool {
         env.storage()
             .instance()
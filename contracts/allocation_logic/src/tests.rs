@@ -1,7 +1,8 @@
 // Comprehensive Security-Focused Tests
 use crate::{
-    AllocationStrategiesContract, AllocationStrategiesContractClient, RiskLevel, Strategy,
+    AllocationStrategiesContract, AllocationStrategiesContractClient, Error, RiskLevel, Strategy,
 };
+use shared_utils::SunsetState;
 use soroban_sdk::{testutils::Address as _, testutils::Ledger, Address, Env};
 
 fn create_contract(env: &Env) -> (Address, Address, AllocationStrategiesContractClient<'_>) {
@@ -542,3 +543,154 @@ fn test_multiple_allocations_exceed_total_balance_fails() {
         &Strategy::Safe,
     );
 }
+
+// ============================================================================
+// BACKTEST DATA TESTS
+// ============================================================================
+
+#[test]
+fn test_record_backtest_by_admin_and_verifier() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    let verifier = Address::generate(&env);
+    client.set_verifier(&admin, &verifier, &true);
+    assert!(client.is_verifier(&verifier));
+
+    client.record_backtest(&admin, &Strategy::Safe, &0, &1_000, &550, &300);
+    client.record_backtest(&verifier, &Strategy::Safe, &1_000, &2_000, &450, &800);
+
+    let backtests = client.get_backtests(&Strategy::Safe);
+    assert_eq!(backtests.len(), 2);
+    assert_eq!(backtests.get(1).unwrap().recorded_by, verifier);
+    assert_eq!(backtests.get(1).unwrap().max_drawdown, 800);
+    assert_eq!(client.get_backtests(&Strategy::Aggressive).len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #3)")]
+fn test_record_backtest_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    let verifier = Address::generate(&env);
+    client.set_verifier(&admin, &verifier, &true);
+    client.set_verifier(&admin, &verifier, &false);
+
+    client.record_backtest(&verifier, &Strategy::Safe, &0, &1_000, &550, &300);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #19)")]
+fn test_record_backtest_invalid_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    client.record_backtest(&admin, &Strategy::Safe, &1_000, &1_000, &550, &300);
+}
+
+#[test]
+fn test_preview_allocate_with_history() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let preview = client.preview_allocate(&Strategy::Safe, &100_000_000);
+    assert_eq!(preview.historical_periods, 0);
+    assert_eq!(preview.historical_apy, 0);
+    assert!(preview.expected_apy >= 500 && preview.expected_apy <= 600);
+
+    let mut planned = 0i128;
+    for (_, amount) in preview.plan.iter() {
+        planned += amount;
+    }
+    assert_eq!(planned, 100_000_000);
+
+    client.record_backtest(&admin, &Strategy::Safe, &0, &1_000, &600, &300);
+    client.record_backtest(&admin, &Strategy::Safe, &1_000, &2_000, &400, &900);
+
+    let preview = client.preview_allocate(&Strategy::Safe, &100_000_000);
+    assert_eq!(preview.historical_periods, 2);
+    assert_eq!(preview.historical_apy, 500);
+    assert_eq!(preview.historical_max_drawdown, 900);
+
+    // Preview does not move liquidity
+    assert_eq!(client.get_pool(&0).total_liquidity, 0);
+}
+
+// ============================================================================
+// SUNSET TESTS
+// ============================================================================
+
+#[test]
+fn test_sunset_blocks_new_allocations_but_allows_rebalance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    client.allocate(&user, &5, &100_000_000, &Strategy::Safe);
+
+    client.begin_sunset(&admin, &1_000);
+    assert_eq!(client.get_sunset_state(), SunsetState::Sunsetting);
+
+    assert_eq!(
+        client.try_allocate(&user, &6, &100_000_000, &Strategy::Safe),
+        Err(Ok(Error::ProtocolSunset))
+    );
+    assert_eq!(
+        client.try_register_pool(&admin, &9, &RiskLevel::Low, &500, &1_000_000),
+        Err(Ok(Error::ProtocolSunset))
+    );
+
+    // Existing allocations keep running
+    let rebalanced = client.rebalance(&user, &5);
+    assert_eq!(rebalanced.strategy, Strategy::Safe);
+}
+
+// ============================================================================
+// AUTH CONTEXT TESTS
+// ============================================================================
+
+#[test]
+fn test_register_pool_auth_binds_to_pool_parameters() {
+    use soroban_sdk::testutils::{MockAuth, MockAuthInvoke};
+    use soroban_sdk::IntoVal;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (admin, _, client) = create_contract(&env);
+
+    // The signed capacity does not match the submitted one
+    env.mock_auths(&[MockAuth {
+        address: &admin,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "register_pool",
+            args: (0u32, RiskLevel::Low, 500u32, 1_000i128).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(client
+        .try_register_pool(&admin, &0, &RiskLevel::Low, &500, &1_000_000_000)
+        .is_err());
+
+    env.mock_auths(&[MockAuth {
+        address: &admin,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "register_pool",
+            args: (0u32, RiskLevel::Low, 500u32, 1_000_000_000i128).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.register_pool(&admin, &0, &RiskLevel::Low, &500, &1_000_000_000);
+    assert_eq!(client.get_pool(&0).max_capacity, 1_000_000_000);
+}
@@ -1,8 +1,11 @@
 #![no_std]
-use shared_utils::{BatchError, BatchMode, BatchProcessor, BatchResultVoid, Pausable, RateLimiter};
+use shared_utils::{
+    BatchError, BatchMode, BatchProcessor, BatchResultVoid, Pausable, RateLimiter, SnapshotVersion,
+    Sunset, SunsetState,
+};
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, BytesN, Env,
-    IntoVal, Map, String, Symbol, TryIntoVal, Val, Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, xdr::ToXdr, Address,
+    BytesN, Env, IntoVal, Map, String, Symbol, TryIntoVal, Val, Vec,
 };
 
 const CURRENT_VERSION: u32 = 1;
@@ -44,6 +47,26 @@ pub enum AttestationError {
     InvalidVersion = 13,
     /// Migration already applied.
     AlreadyMigrated = 14,
+    /// Sponsor does not hold enough pre-funded balance
+    InsufficientSponsorFunds = 15,
+    /// Epoch length must be greater than zero
+    InvalidEpoch = 16,
+    /// Commitment already holds MAX_SELF_REPORTS owner self-reports
+    TooManySelfReports = 17,
+    /// Protocol is sunsetting and accepts no new sponsorship funding
+    ProtocolSunset = 18,
+    /// Commitment owner has not approved the engine to draw the attestation fee
+    InsufficientFeeAllowance = 19,
+    /// Commitment needs a quorum claim (`submit_claim`) instead of a single attestation
+    QuorumRequired = 20,
+    /// Quorum attestations are not enabled
+    QuorumNotConfigured = 21,
+    /// Quorum must need at least two verifiers and a non-zero window
+    InvalidQuorumConfig = 22,
+    /// Verifier already backed this claim
+    DuplicateClaimVote = 23,
+    /// Claim already reached its quorum
+    ClaimAlreadyConfirmed = 24,
 }
 
 // ============================================================================
@@ -85,6 +108,41 @@ pub enum DataKey {
     CollectedFees(Address),
     /// Storage schema version
     Version,
+    /// Pre-funded sponsor balance ((sponsor, asset) -> i128)
+    SponsorBalance(Address, Address),
+    /// Sponsor covering fees for a user (user -> sponsor)
+    SponsoredUser(Address),
+    /// Sponsor covering fees for a commitment type (commitment_type -> sponsor)
+    SponsoredType(String),
+    /// Security council member flag (Address -> bool)
+    CouncilMember(Address),
+    /// Attestation epoch length in seconds
+    EpochSeconds,
+    /// Commitments a verifier attested during an epoch ((verifier, epoch) -> Vec<String>)
+    VerifierEpochCommitments(Address, u64),
+    /// Health metrics frozen after a verifier compromise (commitment_id -> revoked verifier)
+    HealthFrozen(String),
+    /// Log of emergency verifier revocations (Vec<EmergencyRevocation>)
+    EmergencyRevocations,
+    /// Owner self-reports for a commitment (commitment_id -> Vec<SelfReport>)
+    SelfReports(String),
+    HealthBatch(String),
+    /// Who pays the attestation fee (AttestationFeePayer)
+    AttestationFeePayer,
+    /// M-of-N quorum settings (QuorumConfig)
+    QuorumConfig,
+    /// Quorum claim state (claim_id -> QuorumClaim)
+    QuorumClaim(BytesN<32>),
+}
+
+/// Party charged the attestation fee when no sponsor covers it.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AttestationFeePayer {
+    /// The verifier submitting the attestation (default)
+    Verifier,
+    /// The commitment owner, through a token allowance granted to the engine
+    Owner,
 }
 
 #[contracttype]
@@ -116,8 +174,106 @@ pub struct AttestationsPage {
     pub attestations: Vec<Attestation>,
     /// Next offset to use for the following page; 0 means no more pages.
     pub next_offset: u32,
+    /// Snapshot version at read time; if it changes between pages,
+    /// attestations were modified mid-pagination and the read should restart.
+    pub snapshot: u64,
+}
+
+/// Default attestation epoch length (1 day). Attestations from the current
+/// epoch are unfinalized and may be invalidated by an emergency revocation.
+pub const DEFAULT_EPOCH_SECONDS: u64 = 86_400;
+
+/// Log entry for a council-initiated emergency verifier revocation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmergencyRevocation {
+    pub verifier: Address,
+    pub revoked_by: Address,
+    pub reason: String,
+    pub epoch: u64,
+    /// Number of unfinalized attestations removed
+    pub invalidated: u32,
+    /// Commitments whose health metrics were frozen
+    pub frozen_commitments: Vec<String>,
+    pub timestamp: u64,
+}
+
+/// Owner-submitted "self_report" (e.g. declaring an off-chain hedging action).
+/// Stored apart from verifier attestations and never affects compliance.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SelfReport {
+    pub commitment_id: String,
+    pub owner: Address,
+    pub timestamp: u64,
+    /// Must contain "action"; at most MAX_SELF_REPORT_FIELDS entries
+    pub data: Map<String, String>,
+}
+
+/// Health metrics together with the owner's self-reports, for verifier context
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HealthContext {
+    pub metrics: HealthMetrics,
+    pub self_reports: Vec<SelfReport>,
+}
+
+/// Same-ledger health recompute state for a commitment.
+///
+/// The first attestation in a ledger recomputes the metrics; later ones in the
+/// same ledger only bump `pending` and are folded in by the next recompute.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HealthBatch {
+    pub ledger: u32,
+    pub pending: u32,
+}
+
+/// M-of-N quorum settings for high-value commitments.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QuorumConfig {
+    /// Distinct verifiers that must submit a matching claim (M)
+    pub required: u32,
+    /// Seconds after the first vote within which the quorum must be reached
+    pub window_seconds: u64,
+    /// Commitments with at least this amount accept quorum claims only
+    pub min_amount: i128,
+}
+
+/// State of a quorum claim
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ClaimStatus {
+    /// Collecting votes within the window
+    Pending,
+    /// Quorum reached; the claim was recorded as an attestation. Once the
+    /// window passes, the next vote opens a new round of the same claim
+    Confirmed,
+    /// Window passed before the quorum was reached; the next vote restarts it
+    Expired,
+}
+
+/// A claim backed by verifiers until it reaches the configured quorum.
+/// Votes match when commitment, type, data and compliance are identical.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QuorumClaim {
+    pub commitment_id: String,
+    pub attestation_type: String,
+    pub data: Map<String, String>,
+    pub is_compliant: bool,
+    pub verifiers: Vec<Address>,
+    /// Timestamp of the first vote in the current window
+    pub opened_at: u64,
+    pub status: ClaimStatus,
 }
 
+/// Maximum self-reports kept per commitment
+pub const MAX_SELF_REPORTS: u32 = 20;
+/// Maximum data entries in a single self-report
+pub const MAX_SELF_REPORT_FIELDS: u32 = 8;
+
 /// Maximum number of attestations returned per page (avoids exceeding Soroban limits).
 pub const MAX_PAGE_SIZE: u32 = 100;
 
@@ -208,7 +364,7 @@ impl AttestationEngineContract {
         caller: Address,
         verifier: Address,
     ) -> Result<(), AttestationError> {
-        caller.require_auth();
+        caller.require_auth_for_args((verifier.clone(),).into_val(&e));
 
         // Check caller is admin
         let admin: Address = e
@@ -243,7 +399,7 @@ impl AttestationEngineContract {
         caller: Address,
         verifier: Address,
     ) -> Result<(), AttestationError> {
-        caller.require_auth();
+        caller.require_auth_for_args((verifier.clone(),).into_val(&e));
 
         // Check caller is admin
         let admin: Address = e
@@ -268,6 +424,190 @@ impl AttestationEngineContract {
         Ok(())
     }
 
+    /// Add or remove a security council member (admin only)
+    pub fn set_council_member(
+        e: Env,
+        caller: Address,
+        member: Address,
+        enabled: bool,
+    ) -> Result<(), AttestationError> {
+        require_admin(&e, &caller)?;
+        if enabled {
+            e.storage()
+                .instance()
+                .set(&DataKey::CouncilMember(member.clone()), &true);
+        } else {
+            e.storage()
+                .instance()
+                .remove(&DataKey::CouncilMember(member.clone()));
+        }
+        e.events()
+            .publish((Symbol::new(&e, "CouncilMemberSet"), member), enabled);
+        Ok(())
+    }
+
+    /// Check if an address is a security council member
+    pub fn is_council_member(e: Env, address: Address) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::CouncilMember(address))
+            .unwrap_or(false)
+    }
+
+    /// Set the attestation epoch length in seconds (admin only)
+    pub fn set_epoch_seconds(
+        e: Env,
+        caller: Address,
+        seconds: u64,
+    ) -> Result<(), AttestationError> {
+        require_admin(&e, &caller)?;
+        if seconds == 0 {
+            return Err(AttestationError::InvalidEpoch);
+        }
+        e.storage().instance().set(&DataKey::EpochSeconds, &seconds);
+        Ok(())
+    }
+
+    /// Get the current attestation epoch
+    pub fn current_epoch(e: Env) -> u64 {
+        e.ledger().timestamp() / Self::epoch_seconds(&e)
+    }
+
+    /// Immediately revoke a compromised verifier (council only).
+    ///
+    /// Bypasses the admin path (and any timelock guarding it) but is logged in
+    /// `get_emergency_revocations`. Attestations the verifier made in the
+    /// current, unfinalized epoch are removed, the affected commitments'
+    /// health metrics are rebuilt from the attestations kept, and they are
+    /// marked frozen until another verifier attests them.
+    ///
+    /// # Returns
+    /// The number of attestations invalidated.
+    pub fn emergency_revoke_verifier(
+        e: Env,
+        caller: Address,
+        verifier: Address,
+        reason: String,
+    ) -> Result<u32, AttestationError> {
+        caller.require_auth();
+        if !Self::is_council_member(e.clone(), caller.clone()) {
+            return Err(AttestationError::Unauthorized);
+        }
+
+        e.storage()
+            .instance()
+            .remove(&DataKey::Verifier(verifier.clone()));
+
+        let epoch = Self::current_epoch(e.clone());
+        let epoch_start = epoch * Self::epoch_seconds(&e);
+        let index_key = DataKey::VerifierEpochCommitments(verifier.clone(), epoch);
+        let commitment_ids: Vec<String> = e
+            .storage()
+            .persistent()
+            .get(&index_key)
+            .unwrap_or(Vec::new(&e));
+
+        let mut invalidated: u32 = 0;
+        for commitment_id in commitment_ids.iter() {
+            // Pending updates may include the revoked attestations; the
+            // rebuild below covers the legitimate ones
+            let batch_key = DataKey::HealthBatch(commitment_id.clone());
+            if let Some(mut batch) = e.storage().persistent().get::<_, HealthBatch>(&batch_key) {
+                batch.pending = 0;
+                e.storage().persistent().set(&batch_key, &batch);
+            }
+            let key = DataKey::Attestations(commitment_id.clone());
+            let attestations: Vec<Attestation> =
+                e.storage().persistent().get(&key).unwrap_or(Vec::new(&e));
+            let mut kept = Vec::new(&e);
+            for attestation in attestations.iter() {
+                if attestation.verified_by == verifier && attestation.timestamp >= epoch_start {
+                    invalidated += 1;
+                } else {
+                    kept.push_back(attestation);
+                }
+            }
+            let removed = (attestations.len() - kept.len()) as u64;
+            e.storage().persistent().set(&key, &kept);
+            SnapshotVersion::bump(&e);
+            Self::rebuild_health_metrics(&e, &commitment_id, &kept);
+
+            let counter_key = DataKey::AttestationCounter(commitment_id.clone());
+            let counter: u64 = e.storage().persistent().get(&counter_key).unwrap_or(0);
+            e.storage()
+                .persistent()
+                .set(&counter_key, &counter.saturating_sub(removed));
+
+            e.storage()
+                .persistent()
+                .set(&DataKey::HealthFrozen(commitment_id.clone()), &verifier);
+        }
+        e.storage().persistent().remove(&index_key);
+
+        let timestamp = e.ledger().timestamp();
+        let mut log: Vec<EmergencyRevocation> = e
+            .storage()
+            .persistent()
+            .get(&DataKey::EmergencyRevocations)
+            .unwrap_or(Vec::new(&e));
+        log.push_back(EmergencyRevocation {
+            verifier: verifier.clone(),
+            revoked_by: caller.clone(),
+            reason: reason.clone(),
+            epoch,
+            invalidated,
+            frozen_commitments: commitment_ids,
+            timestamp,
+        });
+        e.storage()
+            .persistent()
+            .set(&DataKey::EmergencyRevocations, &log);
+
+        e.events().publish(
+            (
+                Symbol::new(&e, "VerifierEmergencyRevoked"),
+                verifier,
+                caller,
+            ),
+            (reason, epoch, invalidated, timestamp),
+        );
+        Ok(invalidated)
+    }
+
+    /// Get the log of emergency verifier revocations
+    pub fn get_emergency_revocations(e: Env) -> Vec<EmergencyRevocation> {
+        e.storage()
+            .persistent()
+            .get(&DataKey::EmergencyRevocations)
+            .unwrap_or(Vec::new(&e))
+    }
+
+    /// Check if a commitment's health metrics are frozen pending re-attestation
+    pub fn is_health_frozen(e: Env, commitment_id: String) -> bool {
+        e.storage()
+            .persistent()
+            .has(&DataKey::HealthFrozen(commitment_id))
+    }
+
+    fn epoch_seconds(e: &Env) -> u64 {
+        e.storage()
+            .instance()
+            .get(&DataKey::EpochSeconds)
+            .unwrap_or(DEFAULT_EPOCH_SECONDS)
+    }
+
+    /// Record that `verifier` attested `commitment_id` in the current epoch
+    fn index_verifier_attestation(e: &Env, verifier: &Address, commitment_id: &String) {
+        let epoch = Self::current_epoch(e.clone());
+        let key = DataKey::VerifierEpochCommitments(verifier.clone(), epoch);
+        let mut commitment_ids: Vec<String> =
+            e.storage().persistent().get(&key).unwrap_or(Vec::new(e));
+        if !commitment_ids.contains(commitment_id) {
+            commitment_ids.push_back(commitment_id.clone());
+            e.storage().persistent().set(&key, &commitment_ids);
+        }
+    }
+
     /// Check if an address is an authorized verifier
     fn is_authorized_verifier(e: &Env, address: &Address) -> bool {
         // Admin is always authorized
@@ -416,7 +756,43 @@ impl AttestationEngineContract {
         Ok(())
     }
 
-    /// Get stored health metrics for a commitment (without recalculation)
+    /// Apply attestations deferred by same-ledger batching to the stored
+    /// health metrics. Anyone may call this.
+    ///
+    /// # Returns
+    /// The number of attestations applied.
+    pub fn flush_health_metrics(e: Env, commitment_id: String) -> u32 {
+        Self::flush_pending_health(&e, &commitment_id)
+    }
+
+    /// Number of attestations not yet reflected in the stored health metrics
+    pub fn get_pending_health_updates(e: Env, commitment_id: String) -> u32 {
+        e.storage()
+            .persistent()
+            .get::<_, HealthBatch>(&DataKey::HealthBatch(commitment_id))
+            .map(|batch| batch.pending)
+            .unwrap_or(0)
+    }
+
+    fn flush_pending_health(e: &Env, commitment_id: &String) -> u32 {
+        let key = DataKey::HealthBatch(commitment_id.clone());
+        let mut batch: HealthBatch = match e.storage().persistent().get(&key) {
+            Some(batch) => batch,
+            None => return 0,
+        };
+        let pending = batch.pending;
+        if pending > 0 {
+            Self::apply_pending_health(e, commitment_id, pending);
+            batch.pending = 0;
+            e.storage().persistent().set(&key, &batch);
+        }
+        pending
+    }
+
+    /// Get stored health metrics for a commitment (without recalculation).
+    ///
+    /// Attestations deferred within the current ledger are not included until
+    /// `flush_health_metrics` runs or the commitment is attested in a later ledger.
     pub fn get_stored_health_metrics(e: Env, commitment_id: String) -> Option<HealthMetrics> {
         let key = DataKey::HealthMetrics(commitment_id);
         e.storage().persistent().get(&key)
@@ -495,8 +871,80 @@ impl AttestationEngineContract {
     // Health Metrics Update
     // ========================================================================
 
+    /// Recompute health metrics for `commitment_id`, at most once per ledger.
+    ///
+    /// Attestations arriving after the first one in a ledger mark the metrics
+    /// dirty; they are applied by the next recompute in a later ledger or by
+    /// `flush_health_metrics`.
+    fn schedule_health_update(e: &Env, commitment_id: &String) {
+        let key = DataKey::HealthBatch(commitment_id.clone());
+        let sequence = e.ledger().sequence();
+        let batch = match e.storage().persistent().get::<_, HealthBatch>(&key) {
+            Some(mut batch) if batch.ledger == sequence => {
+                batch.pending += 1;
+                batch
+            }
+            stale => {
+                let pending = stale.map(|batch| batch.pending).unwrap_or(0);
+                Self::apply_pending_health(e, commitment_id, pending + 1);
+                HealthBatch {
+                    ledger: sequence,
+                    pending: 0,
+                }
+            }
+        };
+        e.storage().persistent().set(&key, &batch);
+    }
+
+    /// Fold the last `count` attestations into the stored health metrics
+    fn apply_pending_health(e: &Env, commitment_id: &String, count: u32) {
+        let attestations: Vec<Attestation> = e
+            .storage()
+            .persistent()
+            .get(&DataKey::Attestations(commitment_id.clone()))
+            .unwrap_or(Vec::new(e));
+        let len = attestations.len();
+        let pending = attestations.slice(len.saturating_sub(count)..len);
+        Self::update_health_metrics(e, commitment_id, &pending);
+    }
+
+    /// Recompute stored health metrics from scratch over `attestations`,
+    /// keeping the global fee total in step.
+    fn rebuild_health_metrics(e: &Env, commitment_id: &String, attestations: &Vec<Attestation>) {
+        let key = DataKey::HealthMetrics(commitment_id.clone());
+        let Some(previous) = e.storage().persistent().get::<_, HealthMetrics>(&key) else {
+            return;
+        };
+        let mut metrics = HealthMetrics {
+            drawdown_percent: 0,
+            fees_generated: 0,
+            last_attestation: 0,
+            compliance_score: 100,
+            ..previous.clone()
+        };
+        for attestation in attestations.iter() {
+            Self::apply_attestation(e, &mut metrics, &attestation);
+        }
+
+        let fees = metrics
+            .fees_generated
+            .saturating_sub(previous.fees_generated);
+        if fees != 0 {
+            let total_fees: i128 = e.storage().instance().get(&DataKey::TotalFees).unwrap_or(0);
+            e.storage()
+                .instance()
+                .set(&DataKey::TotalFees, &total_fees.saturating_add(fees));
+        }
+        e.storage().persistent().set(&key, &metrics);
+    }
+
     /// Update health metrics after an attestation
-    fn update_health_metrics(e: &Env, commitment_id: &String, attestation: &Attestation) {
+    fn update_health_metrics(e: &Env, commitment_id: &String, attestations: &Vec<Attestation>) {
+        // Frozen metrics resume only once a different verifier re-attests
+        let frozen_key = DataKey::HealthFrozen(commitment_id.clone());
+        let mut frozen: Option<Address> = e.storage().persistent().get(&frozen_key);
+        let was_frozen = frozen.is_some();
+
         // Get or create health metrics
         let key = DataKey::HealthMetrics(commitment_id.clone());
         let mut metrics: HealthMetrics =
@@ -514,6 +962,37 @@ impl AttestationEngineContract {
                     compliance_score: 100,
                 });
 
+        let mut applied = false;
+        let mut fees: i128 = 0;
+        for attestation in attestations.iter() {
+            if let Some(revoked) = &frozen {
+                if attestation.verified_by == *revoked {
+                    continue;
+                }
+                frozen = None;
+            }
+            fees = fees.saturating_add(Self::apply_attestation(e, &mut metrics, &attestation));
+            applied = true;
+        }
+
+        if was_frozen && frozen.is_none() {
+            e.storage().persistent().remove(&frozen_key);
+        }
+        if fees != 0 {
+            // Update global total fees analytics
+            let total_fees: i128 = e.storage().instance().get(&DataKey::TotalFees).unwrap_or(0);
+            let new_total = total_fees.checked_add(fees).unwrap_or(total_fees);
+            e.storage().instance().set(&DataKey::TotalFees, &new_total);
+        }
+        if applied {
+            e.storage().persistent().set(&key, &metrics);
+        }
+    }
+
+    /// Apply one attestation to `metrics`, returning the fees it adds
+    fn apply_attestation(e: &Env, metrics: &mut HealthMetrics, attestation: &Attestation) -> i128 {
+        let mut fees: i128 = 0;
+
         // Update last_attestation timestamp
         metrics.last_attestation = attestation.timestamp;
 
@@ -528,16 +1007,10 @@ impl AttestationEngineContract {
             if let Some(fee_str) = attestation.data.get(fee_amount_key) {
                 // Parse fee amount from string
                 if let Some(fee_amount) = Self::parse_i128_from_string(e, &fee_str) {
-                    metrics.fees_generated = metrics
-                        .fees_generated
-                        .checked_add(fee_amount)
-                        .unwrap_or(metrics.fees_generated);
-
-                    // Update global total fees analytics
-                    let total_fees: i128 =
-                        e.storage().instance().get(&DataKey::TotalFees).unwrap_or(0);
-                    let new_total = total_fees.checked_add(fee_amount).unwrap_or(total_fees);
-                    e.storage().instance().set(&DataKey::TotalFees, &new_total);
+                    if let Some(total) = metrics.fees_generated.checked_add(fee_amount) {
+                        metrics.fees_generated = total;
+                        fees = fee_amount;
+                    }
                 }
             }
         } else if attestation.attestation_type == drawdown_type {
@@ -575,8 +1048,7 @@ impl AttestationEngineContract {
                 core::cmp::min(100, metrics.compliance_score.saturating_add(1));
         }
 
-        // Store updated metrics
-        e.storage().persistent().set(&key, &metrics);
+        fees
     }
 
     /// Parse i128 from String (optimized implementation)
@@ -691,140 +1163,105 @@ impl AttestationEngineContract {
             return Err(AttestationError::InvalidAttestationData);
         }
 
+        // 7a. High-value commitments only accept quorum claims
+        if Self::requires_quorum(&e, &commitment_id) {
+            e.storage().instance().remove(&DataKey::ReentrancyGuard);
+            return Err(AttestationError::QuorumRequired);
+        }
+
         // 7b. Collect attestation verification fee if configured
-        let fee_amount: i128 = e
-            .storage()
-            .instance()
-            .get(&DataKey::AttestationFeeAmount)
-            .unwrap_or(0);
-        if fee_amount > 0 {
-            if let Some(fee_asset) = e
-                .storage()
-                .instance()
-                .get::<DataKey, Address>(&DataKey::AttestationFeeAsset)
-            {
-                let contract_address = e.current_contract_address();
-                let token_client = token::Client::new(&e, &fee_asset);
-                token_client.transfer(&caller, &contract_address, &fee_amount);
-                let key = DataKey::CollectedFees(fee_asset.clone());
-                let current: i128 = e.storage().instance().get(&key).unwrap_or(0);
-                e.storage().instance().set(&key, &(current + fee_amount));
-            }
+        if let Err(err) = Self::charge_attestation_fee(&e, &caller, &commitment_id) {
+            e.storage().instance().remove(&DataKey::ReentrancyGuard);
+            return Err(err);
         }
 
-        // 8. Create attestation record
-        let timestamp = e.ledger().timestamp();
-        let attestation = Attestation {
-            commitment_id: commitment_id.clone(),
-            timestamp,
-            attestation_type: attestation_type.clone(),
-            data: data.clone(),
+        // 8-12. Store the attestation, update metrics and counters, emit event
+        Self::record_attestation(
+            &e,
+            &caller,
+            &commitment_id,
+            &attestation_type,
+            &data,
             is_compliant,
-            verified_by: caller.clone(),
-        };
+        );
+
+        // 13. Clear reentrancy guard
+        e.storage().instance().remove(&DataKey::ReentrancyGuard);
+
+        Ok(())
+    }
+
+    /// Get all attestations for a commitment
+    pub fn get_attestations(e: Env, commitment_id: String) -> Vec<Attestation> {
+        // Retrieve attestations from persistent storage using commitment_id as key
+        let key = DataKey::Attestations(commitment_id);
+        e.storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Vec::new(&e))
+    }
+
+    /// Get the drawdown percent reported by the most recent "drawdown" attestation.
+    ///
+    /// Reads local storage only, so commitment_core can call it without re-entry.
+    pub fn get_latest_drawdown_percent(e: Env, commitment_id: String) -> Option<i128> {
+        let attestations = Self::get_attestations(e.clone(), commitment_id);
+        let drawdown_type = String::from_str(&e, "drawdown");
+        let drawdown_key = String::from_str(&e, "drawdown_percent");
+        for att in attestations.iter().rev() {
+            if att.attestation_type == drawdown_type {
+                return att
+                    .data
+                    .get(drawdown_key.clone())
+                    .and_then(|v| Self::parse_i128_from_string(&e, &v));
+            }
+        }
+        None
+    }
+
+    /// Get the fees reported by "fee_generation" attestations and the timestamp
+    /// of the latest attestation of any type (0 if none).
+    ///
+    /// Reads local storage only, so commitment_core can call it without re-entry.
+    pub fn get_fee_summary(e: Env, commitment_id: String) -> (i128, u64) {
+        let fee_key = String::from_str(&e, "fee_amount");
+        let fee_type = String::from_str(&e, "fee_generation");
+        let mut fees_generated: i128 = 0;
+        let mut last_attestation: u64 = 0;
+        for att in Self::get_attestations(e.clone(), commitment_id).iter() {
+            last_attestation = last_attestation.max(att.timestamp);
+            if att.attestation_type == fee_type {
+                if let Some(v) = att
+                    .data
+                    .get(fee_key.clone())
+                    .and_then(|fee_str| Self::parse_i128_from_string(&e, &fee_str))
+                {
+                    fees_generated = fees_generated.saturating_add(v);
+                }
+            }
+        }
+        (fees_generated, last_attestation)
+    }
 
-        // 9. Store attestation in commitment's list
+    /// Get a page of attestations for a commitment (ordered by timestamp, oldest first).
+    /// Use this for large lists to stay within Soroban limits.
+    ///
+    /// # Arguments
+    /// * `commitment_id` - The commitment to list attestations for
+    /// * `offset` - Index to start from (0-based)
+    /// * `limit` - Max number of attestations to return (capped at MAX_PAGE_SIZE)
+    ///
+    /// # Returns
+    /// * `attestations` - Slice of attestations for this page
+    /// * `next_offset` - Offset for the next page; 0 if no more pages
+    pub fn get_attestations_page(
+        e: Env,
+        commitment_id: String,
+        offset: u32,
+        limit: u32,
+    ) -> AttestationsPage {
         let key = DataKey::Attestations(commitment_id.clone());
-        let mut attestations: Vec<Attestation> = e
-            .storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or_else(|| Vec::new(&e));
-
-        // Add new attestation
-        attestations.push_back(attestation.clone());
-
-        // Store updated list
-        e.storage().persistent().set(&key, &attestations);
-
-        // 10. Update health metrics
-        Self::update_health_metrics(&e, &commitment_id, &attestation);
-
-        // 11. Increment attestation counter
-        let counter_key = DataKey::AttestationCounter(commitment_id.clone());
-        let counter: u64 = e.storage().persistent().get(&counter_key).unwrap_or(0);
-        e.storage().persistent().set(&counter_key, &(counter + 1));
-
-        // 11b. OPTIMIZATION: Batch update all analytics counters
-        let (total_attestations, total_violations, verifier_count) = {
-            let total_att = e
-                .storage()
-                .instance()
-                .get(&DataKey::TotalAttestations)
-                .unwrap_or(0u64);
-            let total_viol = e
-                .storage()
-                .instance()
-                .get(&DataKey::TotalViolations)
-                .unwrap_or(0u64);
-            let verifier_key = DataKey::VerifierAttestationCount(caller.clone());
-            let ver_count = e.storage().instance().get(&verifier_key).unwrap_or(0u64);
-            (total_att, total_viol, ver_count)
-        };
-
-        e.storage()
-            .instance()
-            .set(&DataKey::TotalAttestations, &(total_attestations + 1));
-
-        // Track violations (explicit or non-compliant)
-        let violation_type = String::from_str(&e, "violation");
-        if attestation.attestation_type == violation_type || !attestation.is_compliant {
-            e.storage()
-                .instance()
-                .set(&DataKey::TotalViolations, &(total_violations + 1));
-        }
-
-        // Track per-verifier attestation count
-        let verifier_key = DataKey::VerifierAttestationCount(caller.clone());
-        e.storage()
-            .instance()
-            .set(&verifier_key, &(verifier_count + 1));
-
-        // 12. Emit enhanced AttestationRecorded event
-        e.events().publish(
-            (
-                Symbol::new(&e, "AttestationRecorded"),
-                commitment_id,
-                caller,
-            ),
-            (attestation_type, is_compliant, timestamp),
-        );
-
-        // 13. Clear reentrancy guard
-        e.storage().instance().remove(&DataKey::ReentrancyGuard);
-
-        Ok(())
-    }
-
-    /// Get all attestations for a commitment
-    pub fn get_attestations(e: Env, commitment_id: String) -> Vec<Attestation> {
-        // Retrieve attestations from persistent storage using commitment_id as key
-        let key = DataKey::Attestations(commitment_id);
-        e.storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or_else(|| Vec::new(&e))
-    }
-
-    /// Get a page of attestations for a commitment (ordered by timestamp, oldest first).
-    /// Use this for large lists to stay within Soroban limits.
-    ///
-    /// # Arguments
-    /// * `commitment_id` - The commitment to list attestations for
-    /// * `offset` - Index to start from (0-based)
-    /// * `limit` - Max number of attestations to return (capped at MAX_PAGE_SIZE)
-    ///
-    /// # Returns
-    /// * `attestations` - Slice of attestations for this page
-    /// * `next_offset` - Offset for the next page; 0 if no more pages
-    pub fn get_attestations_page(
-        e: Env,
-        commitment_id: String,
-        offset: u32,
-        limit: u32,
-    ) -> AttestationsPage {
-        let key = DataKey::Attestations(commitment_id.clone());
-        let all: Vec<Attestation> = e
+        let all: Vec<Attestation> = e
             .storage()
             .persistent()
             .get(&key)
@@ -837,6 +1274,7 @@ impl AttestationEngineContract {
             return AttestationsPage {
                 attestations: Vec::new(&e),
                 next_offset: 0,
+                snapshot: SnapshotVersion::current(&e),
             };
         }
 
@@ -852,15 +1290,86 @@ impl AttestationEngineContract {
         AttestationsPage {
             attestations: page,
             next_offset,
+            snapshot: SnapshotVersion::current(&e),
         }
     }
 
+    /// Current snapshot version, bumped whenever stored attestations change
+    pub fn get_snapshot_version(e: Env) -> u64 {
+        SnapshotVersion::current(&e)
+    }
+
     /// Get attestation count for a commitment
     pub fn get_attestation_count(e: Env, commitment_id: String) -> u64 {
         let key = DataKey::AttestationCounter(commitment_id);
         e.storage().persistent().get(&key).unwrap_or(0)
     }
 
+    /// Submit an owner "self_report" for a commitment.
+    ///
+    /// Self-reports are kept apart from verifier attestations: they do not
+    /// touch health metrics, counters or compliance, but are returned by
+    /// `get_self_reports` and `get_health_context` for verifiers to consider.
+    ///
+    /// # Errors
+    /// * `CommitmentNotFound` - the core contract does not know the commitment
+    /// * `Unauthorized` - `owner` does not own the commitment
+    /// * `InvalidAttestationData` - missing "action" or more than `MAX_SELF_REPORT_FIELDS` entries
+    /// * `TooManySelfReports` - `MAX_SELF_REPORTS` already submitted
+    pub fn self_report(
+        e: Env,
+        owner: Address,
+        commitment_id: String,
+        data: Map<String, String>,
+    ) -> Result<(), AttestationError> {
+        Pausable::require_not_paused(&e);
+        owner.require_auth();
+
+        let commitment = Self::fetch_commitment(&e, &commitment_id)
+            .ok_or(AttestationError::CommitmentNotFound)?;
+        if commitment.owner != owner {
+            return Err(AttestationError::Unauthorized);
+        }
+        if !data.contains_key(String::from_str(&e, "action")) || data.len() > MAX_SELF_REPORT_FIELDS
+        {
+            return Err(AttestationError::InvalidAttestationData);
+        }
+
+        let key = DataKey::SelfReports(commitment_id.clone());
+        let mut reports = Self::get_self_reports(e.clone(), commitment_id.clone());
+        if reports.len() >= MAX_SELF_REPORTS {
+            return Err(AttestationError::TooManySelfReports);
+        }
+        let timestamp = e.ledger().timestamp();
+        reports.push_back(SelfReport {
+            commitment_id: commitment_id.clone(),
+            owner: owner.clone(),
+            timestamp,
+            data,
+        });
+        e.storage().persistent().set(&key, &reports);
+
+        e.events()
+            .publish((symbol_short!("SelfRept"), commitment_id, owner), timestamp);
+        Ok(())
+    }
+
+    /// Get owner self-reports for a commitment, oldest first
+    pub fn get_self_reports(e: Env, commitment_id: String) -> Vec<SelfReport> {
+        e.storage()
+            .persistent()
+            .get(&DataKey::SelfReports(commitment_id))
+            .unwrap_or_else(|| Vec::new(&e))
+    }
+
+    /// Get health metrics alongside the owner's self-reports
+    pub fn get_health_context(e: Env, commitment_id: String) -> HealthContext {
+        HealthContext {
+            metrics: Self::get_health_metrics(e.clone(), commitment_id.clone()),
+            self_reports: Self::get_self_reports(e, commitment_id),
+        }
+    }
+
     /// Get current health metrics for a commitment
     pub fn get_health_metrics(e: Env, commitment_id: String) -> HealthMetrics {
         let commitment_core: Address = e
@@ -941,6 +1450,16 @@ impl AttestationEngineContract {
             Err(_) => return false,
         };
 
+        // A value core reports as stale cannot vouch for compliance
+        let stale = e.try_invoke_contract::<bool, soroban_sdk::Error>(
+            &commitment_core,
+            &Symbol::new(&e, "is_value_stale"),
+            (commitment_id.clone(),).into_val(&e),
+        );
+        if let Ok(Ok(true)) = stale {
+            return false;
+        }
+
         let metrics = Self::get_health_metrics(e.clone(), commitment_id);
         let max_loss = commitment.rules.max_loss_percent as i128;
         metrics.drawdown_percent <= max_loss && metrics.compliance_score >= 50
@@ -1427,6 +1946,46 @@ impl AttestationEngineContract {
                 }
             }
 
+            // High-value commitments only accept quorum claims
+            if Self::requires_quorum(&e, &params.commitment_id) {
+                if mode == BatchMode::Atomic {
+                    e.storage().instance().remove(&DataKey::ReentrancyGuard);
+                    errors.push_back(BatchError {
+                        index: i,
+                        error_code: AttestationError::QuorumRequired as u32,
+                        context: String::from_str(&e, "quorum_required"),
+                    });
+                    return BatchResultVoid::failure(&e, errors);
+                } else {
+                    errors.push_back(BatchError {
+                        index: i,
+                        error_code: AttestationError::QuorumRequired as u32,
+                        context: String::from_str(&e, "quorum_required"),
+                    });
+                    continue;
+                }
+            }
+
+            // Collect attestation verification fee if configured
+            if let Err(err) = Self::charge_attestation_fee(&e, &caller, &params.commitment_id) {
+                if mode == BatchMode::Atomic {
+                    e.storage().instance().remove(&DataKey::ReentrancyGuard);
+                    errors.push_back(BatchError {
+                        index: i,
+                        error_code: err as u32,
+                        context: String::from_str(&e, "fee_payment"),
+                    });
+                    return BatchResultVoid::failure(&e, errors);
+                } else {
+                    errors.push_back(BatchError {
+                        index: i,
+                        error_code: err as u32,
+                        context: String::from_str(&e, "fee_payment"),
+                    });
+                    continue;
+                }
+            }
+
             // Create attestation record
             let attestation = Attestation {
                 commitment_id: params.commitment_id.clone(),
@@ -1446,9 +2005,11 @@ impl AttestationEngineContract {
                 .unwrap_or_else(|| Vec::new(&e));
             attestations.push_back(attestation.clone());
             e.storage().persistent().set(&key, &attestations);
+            SnapshotVersion::bump(&e);
 
             // Update health metrics
-            Self::update_health_metrics(&e, &params.commitment_id, &attestation);
+            Self::index_verifier_attestation(&e, &caller, &params.commitment_id);
+            Self::schedule_health_update(&e, &params.commitment_id);
 
             // Increment attestation counter
             let counter_key = DataKey::AttestationCounter(params.commitment_id.clone());
@@ -1548,6 +2109,112 @@ impl AttestationEngineContract {
         Ok(())
     }
 
+    // ========================================================================
+    // Quorum attestations
+    // ========================================================================
+
+    /// Require `required` distinct verifiers to back a claim within
+    /// `window_seconds` before it takes effect, for commitments of at least
+    /// `min_amount`. `required` of 0 disables quorum mode. Admin only.
+    pub fn set_quorum_config(
+        e: Env,
+        caller: Address,
+        required: u32,
+        window_seconds: u64,
+        min_amount: i128,
+    ) -> Result<(), AttestationError> {
+        caller.require_auth();
+        let admin: Address = e
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(AttestationError::NotInitialized)?;
+        if caller != admin {
+            return Err(AttestationError::Unauthorized);
+        }
+        if required == 0 {
+            e.storage().instance().remove(&DataKey::QuorumConfig);
+        } else {
+            if required < 2 || window_seconds == 0 || min_amount < 0 {
+                return Err(AttestationError::InvalidQuorumConfig);
+            }
+            let config = QuorumConfig {
+                required,
+                window_seconds,
+                min_amount,
+            };
+            e.storage().instance().set(&DataKey::QuorumConfig, &config);
+        }
+        e.events().publish(
+            (Symbol::new(&e, "QuorumConfigSet"), caller),
+            (required, window_seconds, min_amount, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Get the quorum settings. None if quorum mode is disabled.
+    pub fn get_quorum_config(e: Env) -> Option<QuorumConfig> {
+        e.storage().instance().get(&DataKey::QuorumConfig)
+    }
+
+    /// Back a claim about a commitment as one of the M-of-N verifiers.
+    ///
+    /// Matching submissions (same commitment, type, data and compliance) from
+    /// distinct verifiers are counted; once `required` of them arrive within
+    /// the window the claim is confirmed and recorded as an attestation by the
+    /// verifier completing the quorum. Each vote pays the attestation fee.
+    /// A vote on an expired claim, or on a confirmed one whose window has
+    /// passed, restarts it. Returns the claim id.
+    pub fn submit_claim(
+        e: Env,
+        caller: Address,
+        commitment_id: String,
+        attestation_type: String,
+        data: Map<String, String>,
+        is_compliant: bool,
+    ) -> Result<BytesN<32>, AttestationError> {
+        if e.storage().instance().has(&DataKey::ReentrancyGuard) {
+            panic!("Reentrancy detected");
+        }
+        e.storage().instance().set(&DataKey::ReentrancyGuard, &true);
+        Pausable::require_not_paused(&e);
+        caller.require_auth();
+
+        let result = Self::vote_on_claim(
+            &e,
+            &caller,
+            &commitment_id,
+            &attestation_type,
+            &data,
+            is_compliant,
+        );
+        e.storage().instance().remove(&DataKey::ReentrancyGuard);
+        result
+    }
+
+    /// Get a quorum claim. None if no vote was ever submitted for it.
+    pub fn get_claim(e: Env, claim_id: BytesN<32>) -> Option<QuorumClaim> {
+        let mut claim: QuorumClaim = e
+            .storage()
+            .persistent()
+            .get(&DataKey::QuorumClaim(claim_id))?;
+        if Self::claim_expired(&e, &claim) {
+            claim.status = ClaimStatus::Expired;
+        }
+        Some(claim)
+    }
+
+    /// Id of the claim made by these attestation fields.
+    pub fn get_claim_id(
+        e: Env,
+        commitment_id: String,
+        attestation_type: String,
+        data: Map<String, String>,
+        is_compliant: bool,
+    ) -> BytesN<32> {
+        Self::claim_id(&e, &commitment_id, &attestation_type, &data, is_compliant)
+    }
+
     // ========================================================================
     // Fee collection (protocol revenue)
     // ========================================================================
@@ -1560,7 +2227,8 @@ impl AttestationEngineContract {
         amount: i128,
         asset: Address,
     ) -> Result<(), AttestationError> {
-        caller.require_auth();
+        // Bind the admin's signature to the exact fee being configured
+        caller.require_auth_for_args((amount, asset.clone()).into_val(&e));
         let admin: Address = e
             .storage()
             .instance()
@@ -1591,7 +2259,7 @@ impl AttestationEngineContract {
         caller: Address,
         recipient: Address,
     ) -> Result<(), AttestationError> {
-        caller.require_auth();
+        caller.require_auth_for_args((recipient.clone(),).into_val(&e));
         let admin: Address = e
             .storage()
             .instance()
@@ -1610,7 +2278,8 @@ impl AttestationEngineContract {
         Ok(())
     }
 
-    /// Withdraw collected fees to the configured fee recipient. Admin only.
+    /// Withdraw collected fees to the configured fee recipient. Callable by
+    /// the fee recipient or the admin.
     pub fn withdraw_fees(
         e: Env,
         caller: Address,
@@ -1623,17 +2292,14 @@ impl AttestationEngineContract {
             .instance()
             .get(&DataKey::Admin)
             .ok_or(AttestationError::NotInitialized)?;
-        if caller != admin {
+        let recipient: Option<Address> = e.storage().instance().get(&DataKey::FeeRecipient);
+        if caller != admin && Some(&caller) != recipient.as_ref() {
             return Err(AttestationError::Unauthorized);
         }
         if amount <= 0 {
             return Err(AttestationError::InvalidFeeAmount);
         }
-        let recipient: Address = e
-            .storage()
-            .instance()
-            .get(&DataKey::FeeRecipient)
-            .ok_or(AttestationError::FeeRecipientNotSet)?;
+        let recipient = recipient.ok_or(AttestationError::FeeRecipientNotSet)?;
         let key = DataKey::CollectedFees(asset_address.clone());
         let collected: i128 = e.storage().instance().get(&key).unwrap_or(0);
         if amount > collected {
@@ -1661,6 +2327,41 @@ impl AttestationEngineContract {
         (amount, asset)
     }
 
+    /// Choose who pays the attestation fee: the verifier (default) or the
+    /// commitment owner, who must approve the engine to spend the fee asset.
+    /// Sponsors still take precedence over either. Admin only.
+    pub fn set_attestation_fee_payer(
+        e: Env,
+        caller: Address,
+        payer: AttestationFeePayer,
+    ) -> Result<(), AttestationError> {
+        caller.require_auth();
+        let admin: Address = e
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(AttestationError::NotInitialized)?;
+        if caller != admin {
+            return Err(AttestationError::Unauthorized);
+        }
+        e.storage()
+            .instance()
+            .set(&DataKey::AttestationFeePayer, &payer);
+        e.events().publish(
+            (Symbol::new(&e, "AttestationFeePayerSet"), caller),
+            (payer, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Get who pays the attestation fee. Verifier if not set.
+    pub fn get_attestation_fee_payer(e: Env) -> AttestationFeePayer {
+        e.storage()
+            .instance()
+            .get(&DataKey::AttestationFeePayer)
+            .unwrap_or(AttestationFeePayer::Verifier)
+    }
+
     /// Get fee recipient. None if not set.
     pub fn get_fee_recipient(e: Env) -> Option<Address> {
         e.storage().instance().get(&DataKey::FeeRecipient)
@@ -1673,6 +2374,514 @@ impl AttestationEngineContract {
             .get(&DataKey::CollectedFees(asset_address))
             .unwrap_or(0)
     }
+
+    // ========================================================================
+    // Fee sponsorship
+    // ========================================================================
+
+    /// Pre-fund attestation fees as a sponsor. Tokens are pulled from the
+    /// sponsor into the engine and drawn down as sponsored fees are charged.
+    pub fn fund_sponsorship(
+        e: Env,
+        sponsor: Address,
+        asset: Address,
+        amount: i128,
+    ) -> Result<(), AttestationError> {
+        sponsor.require_auth();
+        if Sunset::state(&e) != SunsetState::Live {
+            return Err(AttestationError::ProtocolSunset);
+        }
+        if amount <= 0 {
+            return Err(AttestationError::InvalidFeeAmount);
+        }
+        let contract_address = e.current_contract_address();
+        token::Client::new(&e, &asset).transfer(&sponsor, &contract_address, &amount);
+
+        let key = DataKey::SponsorBalance(sponsor.clone(), asset.clone());
+        let balance: i128 = e.storage().instance().get(&key).unwrap_or(0);
+        e.storage().instance().set(&key, &(balance + amount));
+
+        e.events().publish(
+            (Symbol::new(&e, "SponsorFunded"), sponsor),
+            (asset, amount, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Begin the protocol sunset (admin only). Attestations continue so
+    /// existing commitments run to term, but no new sponsorship funding is
+    /// accepted; sponsors may still withdraw. Cannot be undone.
+    pub fn begin_sunset(e: Env, caller: Address, final_date: u64) -> Result<(), AttestationError> {
+        require_admin(&e, &caller)?;
+        Sunset::begin(&e, final_date);
+        Ok(())
+    }
+
+    /// Get the protocol sunset state
+    pub fn get_sunset_state(e: Env) -> SunsetState {
+        Sunset::state(&e)
+    }
+
+    /// Withdraw unused sponsorship funds back to the sponsor.
+    pub fn withdraw_sponsorship(
+        e: Env,
+        sponsor: Address,
+        asset: Address,
+        amount: i128,
+    ) -> Result<(), AttestationError> {
+        sponsor.require_auth();
+        if amount <= 0 {
+            return Err(AttestationError::InvalidFeeAmount);
+        }
+        let key = DataKey::SponsorBalance(sponsor.clone(), asset.clone());
+        let balance: i128 = e.storage().instance().get(&key).unwrap_or(0);
+        if amount > balance {
+            return Err(AttestationError::InsufficientSponsorFunds);
+        }
+        e.storage().instance().set(&key, &(balance - amount));
+
+        let contract_address = e.current_contract_address();
+        token::Client::new(&e, &asset).transfer(&contract_address, &sponsor, &amount);
+
+        e.events().publish(
+            (Symbol::new(&e, "SponsorWithdrawn"), sponsor),
+            (asset, amount, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Sponsor (or stop sponsoring) the attestation fees charged to `user`.
+    ///
+    /// A sponsor can only remove a sponsorship it owns.
+    pub fn set_sponsored_user(
+        e: Env,
+        sponsor: Address,
+        user: Address,
+        enabled: bool,
+    ) -> Result<(), AttestationError> {
+        sponsor.require_auth();
+        let key = DataKey::SponsoredUser(user.clone());
+        if enabled {
+            e.storage().instance().set(&key, &sponsor);
+        } else {
+            let current: Option<Address> = e.storage().instance().get(&key);
+            if current != Some(sponsor.clone()) {
+                return Err(AttestationError::Unauthorized);
+            }
+            e.storage().instance().remove(&key);
+        }
+        e.events().publish(
+            (Symbol::new(&e, "SponsoredUserSet"), sponsor, user),
+            (enabled, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Sponsor (or stop sponsoring) attestation fees for every commitment of
+    /// the given type ("safe", "balanced", "aggressive").
+    pub fn set_sponsored_type(
+        e: Env,
+        sponsor: Address,
+        commitment_type: String,
+        enabled: bool,
+    ) -> Result<(), AttestationError> {
+        sponsor.require_auth();
+        let key = DataKey::SponsoredType(commitment_type.clone());
+        if enabled {
+            e.storage().instance().set(&key, &sponsor);
+        } else {
+            let current: Option<Address> = e.storage().instance().get(&key);
+            if current != Some(sponsor.clone()) {
+                return Err(AttestationError::Unauthorized);
+            }
+            e.storage().instance().remove(&key);
+        }
+        e.events().publish(
+            (
+                Symbol::new(&e, "SponsoredTypeSet"),
+                sponsor,
+                commitment_type,
+            ),
+            (enabled, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Get the sponsor covering fees for a user, if any.
+    pub fn get_user_sponsor(e: Env, user: Address) -> Option<Address> {
+        e.storage().instance().get(&DataKey::SponsoredUser(user))
+    }
+
+    /// Get the sponsor covering fees for a commitment type, if any.
+    pub fn get_type_sponsor(e: Env, commitment_type: String) -> Option<Address> {
+        e.storage()
+            .instance()
+            .get(&DataKey::SponsoredType(commitment_type))
+    }
+
+    /// Get a sponsor's remaining pre-funded balance for an asset.
+    pub fn get_sponsor_balance(e: Env, sponsor: Address, asset: Address) -> i128 {
+        e.storage()
+            .instance()
+            .get(&DataKey::SponsorBalance(sponsor, asset))
+            .unwrap_or(0)
+    }
+
+    /// Charge the configured attestation fee for attesting `commitment_id`,
+    /// crediting it to the collected fees of the fee asset.
+    ///
+    /// A sponsor of the payer or commitment type pays from its pre-funded
+    /// balance; otherwise the verifier pays, or the commitment owner through
+    /// its allowance when the fee payer is set to `Owner`.
+    fn charge_attestation_fee(
+        e: &Env,
+        verifier: &Address,
+        commitment_id: &String,
+    ) -> Result<(), AttestationError> {
+        let fee_amount: i128 = e
+            .storage()
+            .instance()
+            .get(&DataKey::AttestationFeeAmount)
+            .unwrap_or(0);
+        if fee_amount <= 0 {
+            return Ok(());
+        }
+        let Some(fee_asset) = e
+            .storage()
+            .instance()
+            .get::<DataKey, Address>(&DataKey::AttestationFeeAsset)
+        else {
+            return Ok(());
+        };
+
+        let payer = match Self::get_attestation_fee_payer(e.clone()) {
+            AttestationFeePayer::Verifier => verifier.clone(),
+            AttestationFeePayer::Owner => {
+                Self::fetch_commitment(e, commitment_id)
+                    .ok_or(AttestationError::CommitmentNotFound)?
+                    .owner
+            }
+        };
+        let contract_address = e.current_contract_address();
+        // Sponsors pay from their pre-funded balance; otherwise the payer does
+        match Self::resolve_fee_sponsor(e, &payer, commitment_id, &fee_asset, fee_amount) {
+            Some(sponsor) => {
+                let balance_key = DataKey::SponsorBalance(sponsor.clone(), fee_asset.clone());
+                let balance: i128 = e.storage().instance().get(&balance_key).unwrap_or(0);
+                e.storage()
+                    .instance()
+                    .set(&balance_key, &(balance - fee_amount));
+                e.events().publish(
+                    (Symbol::new(e, "FeeSponsored"), sponsor, payer),
+                    (fee_asset.clone(), fee_amount, e.ledger().timestamp()),
+                );
+            }
+            None if payer == *verifier => {
+                token::Client::new(e, &fee_asset).transfer(&payer, &contract_address, &fee_amount);
+            }
+            None => {
+                let token_client = token::Client::new(e, &fee_asset);
+                if token_client.allowance(&payer, &contract_address) < fee_amount {
+                    return Err(AttestationError::InsufficientFeeAllowance);
+                }
+                token_client.transfer_from(
+                    &contract_address,
+                    &payer,
+                    &contract_address,
+                    &fee_amount,
+                );
+            }
+        }
+        let key = DataKey::CollectedFees(fee_asset.clone());
+        let current: i128 = e.storage().instance().get(&key).unwrap_or(0);
+        e.storage().instance().set(&key, &(current + fee_amount));
+        e.events().publish(
+            (
+                Symbol::new(e, "AttestationFeeCharged"),
+                commitment_id.clone(),
+            ),
+            (fee_asset, fee_amount, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Count `caller`'s vote for the claim, confirming it once the quorum is met.
+    fn vote_on_claim(
+        e: &Env,
+        caller: &Address,
+        commitment_id: &String,
+        attestation_type: &String,
+        data: &Map<String, String>,
+        is_compliant: bool,
+    ) -> Result<BytesN<32>, AttestationError> {
+        let config: QuorumConfig = e
+            .storage()
+            .instance()
+            .get(&DataKey::QuorumConfig)
+            .ok_or(AttestationError::QuorumNotConfigured)?;
+        if !Self::is_authorized_verifier(e, caller) {
+            return Err(AttestationError::Unauthorized);
+        }
+        RateLimiter::check(e, caller, &Symbol::new(e, "attest"));
+        if commitment_id.is_empty() {
+            return Err(AttestationError::InvalidCommitmentId);
+        }
+        if !Self::commitment_exists(e, commitment_id) {
+            return Err(AttestationError::CommitmentNotFound);
+        }
+        if !Self::is_valid_attestation_type(e, attestation_type) {
+            return Err(AttestationError::InvalidAttestationType);
+        }
+        if !Self::validate_attestation_data(e, attestation_type, data) {
+            return Err(AttestationError::InvalidAttestationData);
+        }
+
+        let claim_id = Self::claim_id(e, commitment_id, attestation_type, data, is_compliant);
+        let key = DataKey::QuorumClaim(claim_id.clone());
+        let now = e.ledger().timestamp();
+        let mut claim = match e.storage().persistent().get::<_, QuorumClaim>(&key) {
+            Some(claim) if !Self::window_passed(e, &claim) => {
+                if claim.status == ClaimStatus::Confirmed {
+                    return Err(AttestationError::ClaimAlreadyConfirmed);
+                }
+                claim
+            }
+            _ => QuorumClaim {
+                commitment_id: commitment_id.clone(),
+                attestation_type: attestation_type.clone(),
+                data: data.clone(),
+                is_compliant,
+                verifiers: Vec::new(e),
+                opened_at: now,
+                status: ClaimStatus::Pending,
+            },
+        };
+        if claim.verifiers.contains(caller) {
+            return Err(AttestationError::DuplicateClaimVote);
+        }
+        Self::charge_attestation_fee(e, caller, commitment_id)?;
+
+        claim.verifiers.push_back(caller.clone());
+        if claim.verifiers.len() >= config.required {
+            claim.status = ClaimStatus::Confirmed;
+        }
+        e.storage().persistent().set(&key, &claim);
+        e.events().publish(
+            (
+                Symbol::new(e, "ClaimVote"),
+                claim_id.clone(),
+                caller.clone(),
+            ),
+            (claim.verifiers.len(), config.required, now),
+        );
+        if claim.status == ClaimStatus::Confirmed {
+            Self::record_attestation(
+                e,
+                caller,
+                commitment_id,
+                attestation_type,
+                data,
+                is_compliant,
+            );
+            e.events().publish(
+                (Symbol::new(e, "ClaimConfirmed"), claim_id.clone()),
+                (commitment_id.clone(), claim.verifiers, now),
+            );
+        }
+        Ok(claim_id)
+    }
+
+    /// Whether a pending claim's window has passed.
+    fn claim_expired(e: &Env, claim: &QuorumClaim) -> bool {
+        claim.status == ClaimStatus::Pending && Self::window_passed(e, claim)
+    }
+
+    /// Whether the window opened by a claim's first vote has passed.
+    fn window_passed(e: &Env, claim: &QuorumClaim) -> bool {
+        let window = e
+            .storage()
+            .instance()
+            .get::<_, QuorumConfig>(&DataKey::QuorumConfig)
+            .map_or(0, |config| config.window_seconds);
+        e.ledger().timestamp() > claim.opened_at.saturating_add(window)
+    }
+
+    fn claim_id(
+        e: &Env,
+        commitment_id: &String,
+        attestation_type: &String,
+        data: &Map<String, String>,
+        is_compliant: bool,
+    ) -> BytesN<32> {
+        let claim = (
+            commitment_id.clone(),
+            attestation_type.clone(),
+            data.clone(),
+            is_compliant,
+        );
+        e.crypto().sha256(&claim.to_xdr(e)).to_bytes()
+    }
+
+    /// Whether quorum mode is on and `commitment_id` is large enough to need it.
+    fn requires_quorum(e: &Env, commitment_id: &String) -> bool {
+        let Some(config) = e
+            .storage()
+            .instance()
+            .get::<_, QuorumConfig>(&DataKey::QuorumConfig)
+        else {
+            return false;
+        };
+        Self::fetch_commitment(e, commitment_id)
+            .is_some_and(|commitment| commitment.amount >= config.min_amount)
+    }
+
+    /// Store an attestation by `caller`, schedule the health metrics update,
+    /// bump the attestation counters and emit `AttestationRecorded`.
+    fn record_attestation(
+        e: &Env,
+        caller: &Address,
+        commitment_id: &String,
+        attestation_type: &String,
+        data: &Map<String, String>,
+        is_compliant: bool,
+    ) {
+        // Create attestation record
+        let timestamp = e.ledger().timestamp();
+        let attestation = Attestation {
+            commitment_id: commitment_id.clone(),
+            timestamp,
+            attestation_type: attestation_type.clone(),
+            data: data.clone(),
+            is_compliant,
+            verified_by: caller.clone(),
+        };
+
+        // Store attestation in commitment's list
+        let key = DataKey::Attestations(commitment_id.clone());
+        let mut attestations: Vec<Attestation> = e
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Vec::new(e));
+
+        // Add new attestation
+        attestations.push_back(attestation.clone());
+
+        // Store updated list
+        e.storage().persistent().set(&key, &attestations);
+        SnapshotVersion::bump(e);
+
+        // Update health metrics
+        Self::index_verifier_attestation(e, caller, commitment_id);
+        Self::schedule_health_update(e, commitment_id);
+
+        // Increment attestation counter
+        let counter_key = DataKey::AttestationCounter(commitment_id.clone());
+        let counter: u64 = e.storage().persistent().get(&counter_key).unwrap_or(0);
+        e.storage().persistent().set(&counter_key, &(counter + 1));
+
+        // Batch update all analytics counters
+        let (total_attestations, total_violations, verifier_count) = {
+            let total_att = e
+                .storage()
+                .instance()
+                .get(&DataKey::TotalAttestations)
+                .unwrap_or(0u64);
+            let total_viol = e
+                .storage()
+                .instance()
+                .get(&DataKey::TotalViolations)
+                .unwrap_or(0u64);
+            let verifier_key = DataKey::VerifierAttestationCount(caller.clone());
+            let ver_count = e.storage().instance().get(&verifier_key).unwrap_or(0u64);
+            (total_att, total_viol, ver_count)
+        };
+
+        e.storage()
+            .instance()
+            .set(&DataKey::TotalAttestations, &(total_attestations + 1));
+
+        // Track violations (explicit or non-compliant)
+        let violation_type = String::from_str(e, "violation");
+        if attestation.attestation_type == violation_type || !attestation.is_compliant {
+            e.storage()
+                .instance()
+                .set(&DataKey::TotalViolations, &(total_violations + 1));
+        }
+
+        // Track per-verifier attestation count
+        let verifier_key = DataKey::VerifierAttestationCount(caller.clone());
+        e.storage()
+            .instance()
+            .set(&verifier_key, &(verifier_count + 1));
+
+        // Emit enhanced AttestationRecorded event
+        e.events().publish(
+            (
+                Symbol::new(e, "AttestationRecorded"),
+                commitment_id.clone(),
+                caller.clone(),
+            ),
+            (attestation_type.clone(), is_compliant, timestamp),
+        );
+    }
+
+    /// Find a sponsor able to cover `fee_amount` for this payer/commitment.
+    ///
+    /// User sponsorships take precedence over commitment-type sponsorships.
+    /// Sponsors without enough balance are skipped so the payer is charged instead.
+    fn resolve_fee_sponsor(
+        e: &Env,
+        payer: &Address,
+        commitment_id: &String,
+        asset: &Address,
+        fee_amount: i128,
+    ) -> Option<Address> {
+        let has_funds = |sponsor: &Address| {
+            let balance: i128 = e
+                .storage()
+                .instance()
+                .get(&DataKey::SponsorBalance(sponsor.clone(), asset.clone()))
+                .unwrap_or(0);
+            balance >= fee_amount
+        };
+
+        if let Some(sponsor) = e
+            .storage()
+            .instance()
+            .get::<DataKey, Address>(&DataKey::SponsoredUser(payer.clone()))
+        {
+            if has_funds(&sponsor) {
+                return Some(sponsor);
+            }
+        }
+
+        let commitment = Self::fetch_commitment(e, commitment_id)?;
+        let sponsor = e
+            .storage()
+            .instance()
+            .get::<DataKey, Address>(&DataKey::SponsoredType(commitment.rules.commitment_type))?;
+        if has_funds(&sponsor) {
+            Some(sponsor)
+        } else {
+            None
+        }
+    }
+
+    /// Fetch a commitment from the core contract, `None` if unavailable.
+    fn fetch_commitment(e: &Env, commitment_id: &String) -> Option<Commitment> {
+        let commitment_core: Address = e.storage().instance().get(&DataKey::CoreContract)?;
+        let mut args = Vec::new(e);
+        args.push_back(commitment_id.clone().into_val(e));
+        match e.try_invoke_contract::<Val, soroban_sdk::Error>(
+            &commitment_core,
+            &Symbol::new(e, "get_commitment"),
+            args,
+        ) {
+            Ok(Ok(val)) => val.try_into_val(e).ok(),
+            _ => None,
+        }
+    }
 }
 
 fn read_version(e: &Env) -> u32 {
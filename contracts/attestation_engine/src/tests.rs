@@ -1,7 +1,61 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, Map, String};
+use soroban_sdk::{contract, contractimpl, testutils::Address as _, Address, Env, Map, String};
+
+/// Stand-in for commitment_core: every commitment is an active 1000-unit
+/// commitment owned by the mock itself.
+#[contract]
+pub struct MockCoreContract;
+
+#[contractimpl]
+impl MockCoreContract {
+    pub fn get_commitment(e: Env, commitment_id: String) -> Commitment {
+        Commitment {
+            commitment_id,
+            owner: e.current_contract_address(),
+            nft_token_id: 1,
+            rules: CommitmentRules {
+                duration_days: 30,
+                max_loss_percent: 10,
+                commitment_type: String::from_str(&e, "safe"),
+                early_exit_penalty: 5,
+                min_fee_threshold: 0,
+                grace_period_days: 0,
+            },
+            amount: 1000,
+            asset_address: e.current_contract_address(),
+            created_at: 0,
+            expires_at: 30 * 86400,
+            current_value: 1000,
+            status: String::from_str(&e, "active"),
+        }
+    }
+}
+
+/// An engine initialized against `MockCoreContract`, with all auths mocked.
+struct Fixture {
+    e: Env,
+    client: AttestationEngineContractClient<'static>,
+    admin: Address,
+    core_id: Address,
+}
+
+fn fixture() -> Fixture {
+    let e = Env::default();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let core_id = e.register_contract(None, MockCoreContract);
+    let contract_id = e.register_contract(None, AttestationEngineContract);
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+    client.initialize(&admin, &core_id);
+    Fixture {
+        e,
+        client,
+        admin,
+        core_id,
+    }
+}
 
 #[test]
 fn test_initialize_and_getters() {
@@ -149,3 +203,886 @@ fn test_fee_queries_not_initialized_return_defaults() {
     assert_eq!(collected_fees, 0);
 }
 
+// ============================================
+// Fee sponsorship Tests
+// ============================================
+
+#[test]
+fn test_get_latest_drawdown_percent() {
+    let e = Env::default();
+    let contract_id = e.register_contract(None, AttestationEngineContract);
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+    let commitment_id = String::from_str(&e, "c_drawdown");
+    assert_eq!(client.get_latest_drawdown_percent(&commitment_id), None);
+
+    let attestation = |att_type: &str, percent: &str| {
+        let mut data = Map::new(&e);
+        data.set(
+            String::from_str(&e, "drawdown_percent"),
+            String::from_str(&e, percent),
+        );
+        Attestation {
+            commitment_id: commitment_id.clone(),
+            timestamp: 0,
+            attestation_type: String::from_str(&e, att_type),
+            data,
+            is_compliant: true,
+            verified_by: contract_id.clone(),
+        }
+    };
+    let mut attestations = Vec::new(&e);
+    attestations.push_back(attestation("drawdown", "4"));
+    attestations.push_back(attestation("drawdown", "7"));
+    attestations.push_back(attestation("health_check", "99"));
+    e.as_contract(&contract_id, || {
+        e.storage()
+            .persistent()
+            .set(&DataKey::Attestations(commitment_id.clone()), &attestations);
+    });
+
+    assert_eq!(client.get_latest_drawdown_percent(&commitment_id), Some(7));
+}
+
+#[test]
+fn test_get_fee_summary() {
+    let e = Env::default();
+    let contract_id = e.register_contract(None, AttestationEngineContract);
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+    let commitment_id = String::from_str(&e, "c_fees");
+    assert_eq!(client.get_fee_summary(&commitment_id), (0, 0));
+
+    let attestation = |att_type: &str, fee: &str, timestamp: u64| {
+        let mut data = Map::new(&e);
+        data.set(
+            String::from_str(&e, "fee_amount"),
+            String::from_str(&e, fee),
+        );
+        Attestation {
+            commitment_id: commitment_id.clone(),
+            timestamp,
+            attestation_type: String::from_str(&e, att_type),
+            data,
+            is_compliant: true,
+            verified_by: contract_id.clone(),
+        }
+    };
+    let mut attestations = Vec::new(&e);
+    attestations.push_back(attestation("fee_generation", "40", 10));
+    attestations.push_back(attestation("fee_generation", "25", 20));
+    attestations.push_back(attestation("health_check", "99", 30));
+    e.as_contract(&contract_id, || {
+        e.storage()
+            .persistent()
+            .set(&DataKey::Attestations(commitment_id.clone()), &attestations);
+    });
+
+    assert_eq!(client.get_fee_summary(&commitment_id), (65, 30));
+}
+
+mod sponsorship {
+    use super::*;
+    use soroban_sdk::token::StellarAssetClient;
+
+    struct Setup {
+        e: Env,
+        client: AttestationEngineContractClient<'static>,
+        verifier: Address,
+        asset: Address,
+    }
+
+    fn setup_with_fee(fee: i128) -> Setup {
+        let Fixture {
+            e, client, admin, ..
+        } = fixture();
+        let verifier = Address::generate(&e);
+        client.add_verifier(&admin, &verifier);
+
+        let token_admin = Address::generate(&e);
+        let asset = e.register_stellar_asset_contract_v2(token_admin).address();
+        client.set_attestation_fee(&admin, &fee, &asset);
+
+        Setup {
+            e,
+            client,
+            verifier,
+            asset,
+        }
+    }
+
+    fn attest_health(s: &Setup) {
+        s.client.attest(
+            &s.verifier,
+            &String::from_str(&s.e, "c_0"),
+            &String::from_str(&s.e, "health_check"),
+            &Map::new(&s.e),
+            &true,
+        );
+    }
+
+    #[test]
+    fn test_sponsored_user_fee_drawn_from_sponsor_balance() {
+        let s = setup_with_fee(10);
+        let sponsor = Address::generate(&s.e);
+        StellarAssetClient::new(&s.e, &s.asset).mint(&sponsor, &100);
+
+        s.client.fund_sponsorship(&sponsor, &s.asset, &100);
+        s.client.set_sponsored_user(&sponsor, &s.verifier, &true);
+        assert_eq!(
+            s.client.get_user_sponsor(&s.verifier),
+            Some(sponsor.clone())
+        );
+
+        // Verifier holds no tokens; the sponsor covers the fee
+        attest_health(&s);
+
+        assert_eq!(s.client.get_sponsor_balance(&sponsor, &s.asset), 90);
+        assert_eq!(s.client.get_collected_fees(&s.asset), 10);
+    }
+
+    #[test]
+    fn test_sponsored_type_fee_drawn_from_sponsor_balance() {
+        let s = setup_with_fee(25);
+        let sponsor = Address::generate(&s.e);
+        StellarAssetClient::new(&s.e, &s.asset).mint(&sponsor, &100);
+
+        s.client.fund_sponsorship(&sponsor, &s.asset, &100);
+        s.client
+            .set_sponsored_type(&sponsor, &String::from_str(&s.e, "safe"), &true);
+
+        attest_health(&s);
+
+        assert_eq!(s.client.get_sponsor_balance(&sponsor, &s.asset), 75);
+        assert_eq!(s.client.get_collected_fees(&s.asset), 25);
+    }
+
+    #[test]
+    fn test_underfunded_sponsor_falls_back_to_caller() {
+        let s = setup_with_fee(10);
+        let sponsor = Address::generate(&s.e);
+        StellarAssetClient::new(&s.e, &s.asset).mint(&sponsor, &5);
+        StellarAssetClient::new(&s.e, &s.asset).mint(&s.verifier, &10);
+
+        s.client.fund_sponsorship(&sponsor, &s.asset, &5);
+        s.client.set_sponsored_user(&sponsor, &s.verifier, &true);
+
+        attest_health(&s);
+
+        assert_eq!(s.client.get_sponsor_balance(&sponsor, &s.asset), 5);
+        let token = soroban_sdk::token::Client::new(&s.e, &s.asset);
+        assert_eq!(token.balance(&s.verifier), 0);
+        assert_eq!(s.client.get_collected_fees(&s.asset), 10);
+    }
+
+    #[test]
+    fn test_withdraw_sponsorship() {
+        let s = setup_with_fee(10);
+        let sponsor = Address::generate(&s.e);
+        StellarAssetClient::new(&s.e, &s.asset).mint(&sponsor, &100);
+
+        s.client.fund_sponsorship(&sponsor, &s.asset, &100);
+        s.client.withdraw_sponsorship(&sponsor, &s.asset, &40);
+        assert_eq!(s.client.get_sponsor_balance(&sponsor, &s.asset), 60);
+
+        let result = s.client.try_withdraw_sponsorship(&sponsor, &s.asset, &61);
+        assert_eq!(result, Err(Ok(AttestationError::InsufficientSponsorFunds)));
+    }
+
+    #[test]
+    fn test_sunset_blocks_new_sponsorship_funding() {
+        let s = setup_with_fee(10);
+        let sponsor = Address::generate(&s.e);
+        StellarAssetClient::new(&s.e, &s.asset).mint(&sponsor, &200);
+        s.client.fund_sponsorship(&sponsor, &s.asset, &100);
+
+        let admin = s.client.get_admin();
+        s.client.begin_sunset(&admin, &1_000);
+        assert_eq!(s.client.get_sunset_state(), SunsetState::Sunsetting);
+
+        let result = s.client.try_fund_sponsorship(&sponsor, &s.asset, &100);
+        assert_eq!(result, Err(Ok(AttestationError::ProtocolSunset)));
+
+        // Sponsors can still withdraw
+        s.client.withdraw_sponsorship(&sponsor, &s.asset, &100);
+    }
+
+    #[test]
+    fn test_remove_sponsorship_requires_owning_sponsor() {
+        let s = setup_with_fee(10);
+        let sponsor = Address::generate(&s.e);
+        let other = Address::generate(&s.e);
+
+        s.client.set_sponsored_user(&sponsor, &s.verifier, &true);
+        let result = s.client.try_set_sponsored_user(&other, &s.verifier, &false);
+        assert_eq!(result, Err(Ok(AttestationError::Unauthorized)));
+
+        s.client.set_sponsored_user(&sponsor, &s.verifier, &false);
+        assert_eq!(s.client.get_user_sponsor(&s.verifier), None);
+    }
+
+    #[test]
+    fn test_owner_pays_attestation_fee_through_allowance() {
+        let s = setup_with_fee(10);
+        let admin = s.client.get_admin();
+        // The mock core reports itself as the commitment owner
+        let owner = s.client.get_core_contract();
+        StellarAssetClient::new(&s.e, &s.asset).mint(&owner, &30);
+        s.client
+            .set_attestation_fee_payer(&admin, &AttestationFeePayer::Owner);
+        assert_eq!(
+            s.client.get_attestation_fee_payer(),
+            AttestationFeePayer::Owner
+        );
+
+        let result = s.client.try_attest(
+            &s.verifier,
+            &String::from_str(&s.e, "c_0"),
+            &String::from_str(&s.e, "health_check"),
+            &Map::new(&s.e),
+            &true,
+        );
+        assert_eq!(result, Err(Ok(AttestationError::InsufficientFeeAllowance)));
+
+        let token = soroban_sdk::token::Client::new(&s.e, &s.asset);
+        token.approve(&owner, &s.client.address, &20, &100);
+        attest_health(&s);
+
+        assert_eq!(token.balance(&owner), 20);
+        assert_eq!(token.balance(&s.verifier), 0);
+        assert_eq!(s.client.get_collected_fees(&s.asset), 10);
+    }
+
+    #[test]
+    fn test_batch_attest_charges_fee_per_attestation() {
+        let s = setup_with_fee(10);
+        StellarAssetClient::new(&s.e, &s.asset).mint(&s.verifier, &20);
+        let params = AttestParams {
+            commitment_id: String::from_str(&s.e, "c_0"),
+            attestation_type: String::from_str(&s.e, "health_check"),
+            data: Map::new(&s.e),
+            is_compliant: true,
+        };
+        let mut params_list = Vec::new(&s.e);
+        params_list.push_back(params.clone());
+        params_list.push_back(params);
+
+        let result = s
+            .client
+            .batch_attest(&s.verifier, &params_list, &BatchMode::BestEffort);
+        assert_eq!(result.success_count, 2);
+        let token = soroban_sdk::token::Client::new(&s.e, &s.asset);
+        assert_eq!(token.balance(&s.verifier), 0);
+        assert_eq!(s.client.get_collected_fees(&s.asset), 20);
+    }
+
+    #[test]
+    fn test_fee_recipient_withdraws_collected_fees() {
+        let s = setup_with_fee(10);
+        let admin = s.client.get_admin();
+        let treasury = Address::generate(&s.e);
+        StellarAssetClient::new(&s.e, &s.asset).mint(&s.verifier, &20);
+        attest_health(&s);
+        attest_health(&s);
+
+        // Nothing is withdrawable until a recipient is configured
+        let result = s.client.try_withdraw_fees(&admin, &s.asset, &5);
+        assert_eq!(result, Err(Ok(AttestationError::FeeRecipientNotSet)));
+        s.client.set_fee_recipient(&admin, &treasury);
+
+        let result = s.client.try_withdraw_fees(&s.verifier, &s.asset, &5);
+        assert_eq!(result, Err(Ok(AttestationError::Unauthorized)));
+        let result = s.client.try_withdraw_fees(&treasury, &s.asset, &21);
+        assert_eq!(result, Err(Ok(AttestationError::InsufficientFees)));
+
+        s.client.withdraw_fees(&treasury, &s.asset, &15);
+        s.client.withdraw_fees(&admin, &s.asset, &5);
+        let token = soroban_sdk::token::Client::new(&s.e, &s.asset);
+        assert_eq!(token.balance(&treasury), 20);
+        assert_eq!(s.client.get_collected_fees(&s.asset), 0);
+    }
+}
+
+mod emergency_revocation {
+    use super::*;
+    use soroban_sdk::testutils::Ledger;
+
+    fn attest_health(
+        e: &Env,
+        client: &AttestationEngineContractClient,
+        verifier: &Address,
+        id: &str,
+    ) {
+        client.attest(
+            verifier,
+            &String::from_str(e, id),
+            &String::from_str(e, "health_check"),
+            &Map::new(e),
+            &true,
+        );
+    }
+
+    #[test]
+    fn test_emergency_revoke_invalidates_current_epoch_only() {
+        let Fixture {
+            e, client, admin, ..
+        } = fixture();
+        let council = Address::generate(&e);
+        let compromised = Address::generate(&e);
+        let honest = Address::generate(&e);
+        client.set_council_member(&admin, &council, &true);
+        client.add_verifier(&admin, &compromised);
+        client.add_verifier(&admin, &honest);
+
+        // Finalized attestation from an earlier epoch
+        attest_health(&e, &client, &compromised, "c_0");
+
+        e.ledger()
+            .with_mut(|l| l.timestamp = 2 * DEFAULT_EPOCH_SECONDS + 10);
+        attest_health(&e, &client, &compromised, "c_0");
+        attest_health(&e, &client, &compromised, "c_1");
+        attest_health(&e, &client, &honest, "c_1");
+
+        let invalidated = client.emergency_revoke_verifier(
+            &council,
+            &compromised,
+            &String::from_str(&e, "key leaked"),
+        );
+        assert_eq!(invalidated, 2);
+        assert!(!client.is_verifier(&compromised));
+
+        let c_0 = String::from_str(&e, "c_0");
+        let c_1 = String::from_str(&e, "c_1");
+        assert_eq!(client.get_attestations(&c_0).len(), 1);
+        assert_eq!(client.get_attestation_count(&c_0), 1);
+        let remaining = client.get_attestations(&c_1);
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining.get(0).unwrap().verified_by, honest);
+
+        assert!(client.is_health_frozen(&c_0));
+        assert!(client.is_health_frozen(&c_1));
+        // Health recompute is once per ledger, so re-attest in the next one
+        e.ledger().with_mut(|l| l.sequence_number += 1);
+        attest_health(&e, &client, &honest, "c_1");
+        assert!(!client.is_health_frozen(&c_1));
+
+        let log = client.get_emergency_revocations();
+        assert_eq!(log.len(), 1);
+        let entry = log.get(0).unwrap();
+        assert_eq!(entry.revoked_by, council);
+        assert_eq!(entry.epoch, 2);
+        assert_eq!(entry.frozen_commitments.len(), 2);
+    }
+
+    #[test]
+    fn test_emergency_revoke_reverts_derived_health_metrics() {
+        let Fixture {
+            e, client, admin, ..
+        } = fixture();
+        let council = Address::generate(&e);
+        let compromised = Address::generate(&e);
+        let honest = Address::generate(&e);
+        client.set_council_member(&admin, &council, &true);
+        client.add_verifier(&admin, &compromised);
+        client.add_verifier(&admin, &honest);
+        let id = String::from_str(&e, "c_0");
+
+        let mut fee = Map::new(&e);
+        fee.set(
+            String::from_str(&e, "fee_amount"),
+            String::from_str(&e, "100"),
+        );
+        client.attest(
+            &honest,
+            &id,
+            &String::from_str(&e, "fee_generation"),
+            &fee,
+            &true,
+        );
+
+        // One applied and one still pending in a later ledger
+        e.ledger().with_mut(|l| l.sequence_number += 1);
+        let mut violation = Map::new(&e);
+        violation.set(
+            String::from_str(&e, "violation_type"),
+            String::from_str(&e, "loss"),
+        );
+        violation.set(
+            String::from_str(&e, "severity"),
+            String::from_str(&e, "high"),
+        );
+        client.attest(
+            &compromised,
+            &id,
+            &String::from_str(&e, "violation"),
+            &violation,
+            &false,
+        );
+        fee.set(
+            String::from_str(&e, "fee_amount"),
+            String::from_str(&e, "500"),
+        );
+        client.attest(
+            &compromised,
+            &id,
+            &String::from_str(&e, "fee_generation"),
+            &fee,
+            &true,
+        );
+        let metrics = client.get_stored_health_metrics(&id).unwrap();
+        assert_eq!(metrics.compliance_score, 70);
+        assert_eq!(client.get_pending_health_updates(&id), 1);
+
+        client.emergency_revoke_verifier(&council, &compromised, &String::from_str(&e, "leak"));
+        let metrics = client.get_stored_health_metrics(&id).unwrap();
+        assert_eq!(metrics.compliance_score, 100);
+        assert_eq!(metrics.fees_generated, 100);
+        assert_eq!(client.get_pending_health_updates(&id), 0);
+    }
+
+    #[test]
+    fn test_attestations_page_snapshot_changes_on_write() {
+        let Fixture {
+            e, client, admin, ..
+        } = fixture();
+        let verifier = Address::generate(&e);
+        client.add_verifier(&admin, &verifier);
+        attest_health(&e, &client, &verifier, "c1");
+        attest_health(&e, &client, &verifier, "c1");
+
+        let id = String::from_str(&e, "c1");
+        let first = client.get_attestations_page(&id, &0, &1);
+        assert_eq!(first.next_offset, 1);
+        assert_eq!(first.snapshot, client.get_snapshot_version());
+
+        attest_health(&e, &client, &verifier, "c2");
+        let second = client.get_attestations_page(&id, &first.next_offset, &1);
+        assert_ne!(second.snapshot, first.snapshot);
+    }
+
+    #[test]
+    fn test_emergency_revoke_requires_council() {
+        let Fixture {
+            e, client, admin, ..
+        } = fixture();
+        let verifier = Address::generate(&e);
+        client.add_verifier(&admin, &verifier);
+
+        let result =
+            client.try_emergency_revoke_verifier(&admin, &verifier, &String::from_str(&e, "x"));
+        assert_eq!(result, Err(Ok(AttestationError::Unauthorized)));
+        assert!(client.is_verifier(&verifier));
+    }
+}
+
+mod self_report {
+    use super::*;
+
+    fn hedge_report(e: &Env) -> Map<String, String> {
+        let mut data = Map::new(e);
+        data.set(
+            String::from_str(e, "action"),
+            String::from_str(e, "hedged 50% via perp short"),
+        );
+        data
+    }
+
+    #[test]
+    fn test_self_report_stored_apart_from_attestations() {
+        // MockCoreContract reports itself as the owner of every commitment
+        let Fixture {
+            e,
+            client,
+            core_id: owner,
+            ..
+        } = fixture();
+        let id = String::from_str(&e, "c1");
+
+        client.self_report(&owner, &id, &hedge_report(&e));
+
+        let reports = client.get_self_reports(&id);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports.get(0).unwrap().owner, owner);
+        // Verifier attestations and counters are untouched
+        assert_eq!(client.get_attestations(&id).len(), 0);
+        assert_eq!(client.get_attestation_count(&id), 0);
+        assert_eq!(client.get_stored_health_metrics(&id), None);
+
+        let context = client.get_health_context(&id);
+        assert_eq!(context.self_reports, reports);
+        assert_eq!(context.metrics.compliance_score, 100);
+    }
+
+    #[test]
+    fn test_self_report_requires_owner_and_action() {
+        // MockCoreContract reports itself as the owner of every commitment
+        let Fixture {
+            e,
+            client,
+            core_id: owner,
+            ..
+        } = fixture();
+        let id = String::from_str(&e, "c1");
+
+        assert_eq!(
+            client.try_self_report(&Address::generate(&e), &id, &hedge_report(&e)),
+            Err(Ok(AttestationError::Unauthorized))
+        );
+        assert_eq!(
+            client.try_self_report(&owner, &id, &Map::new(&e)),
+            Err(Ok(AttestationError::InvalidAttestationData))
+        );
+
+        for _ in 0..MAX_SELF_REPORTS {
+            client.self_report(&owner, &id, &hedge_report(&e));
+        }
+        assert_eq!(
+            client.try_self_report(&owner, &id, &hedge_report(&e)),
+            Err(Ok(AttestationError::TooManySelfReports))
+        );
+    }
+}
+
+mod health_batching {
+    use super::*;
+    use soroban_sdk::testutils::Ledger;
+
+    fn setup() -> (Env, AttestationEngineContractClient<'static>, Address) {
+        let Fixture {
+            e, client, admin, ..
+        } = fixture();
+        let verifier = Address::generate(&e);
+        client.add_verifier(&admin, &verifier);
+        (e, client, verifier)
+    }
+
+    fn attest_violation(e: &Env, client: &AttestationEngineContractClient, verifier: &Address) {
+        let mut data = Map::new(e);
+        data.set(
+            String::from_str(e, "violation_type"),
+            String::from_str(e, "drawdown"),
+        );
+        data.set(
+            String::from_str(e, "severity"),
+            String::from_str(e, "medium"),
+        );
+        client.attest(
+            verifier,
+            &String::from_str(e, "c1"),
+            &String::from_str(e, "violation"),
+            &data,
+            &false,
+        );
+    }
+
+    fn score(e: &Env, client: &AttestationEngineContractClient) -> u32 {
+        client
+            .get_stored_health_metrics(&String::from_str(e, "c1"))
+            .unwrap()
+            .compliance_score
+    }
+
+    #[test]
+    fn test_same_ledger_attestations_defer_until_flush() {
+        let (e, client, verifier) = setup();
+        let id = String::from_str(&e, "c1");
+
+        attest_violation(&e, &client, &verifier);
+        attest_violation(&e, &client, &verifier);
+        assert_eq!(client.get_attestations(&id).len(), 2);
+        assert_eq!(client.get_pending_health_updates(&id), 1);
+        assert_eq!(score(&e, &client), 80);
+
+        assert_eq!(client.flush_health_metrics(&id), 1);
+        assert_eq!(client.get_pending_health_updates(&id), 0);
+        assert_eq!(score(&e, &client), 60);
+        assert_eq!(client.flush_health_metrics(&id), 0);
+    }
+
+    #[test]
+    fn test_next_ledger_attestation_applies_pending() {
+        let (e, client, verifier) = setup();
+        let id = String::from_str(&e, "c1");
+
+        attest_violation(&e, &client, &verifier);
+        attest_violation(&e, &client, &verifier);
+        attest_violation(&e, &client, &verifier);
+        assert_eq!(client.get_pending_health_updates(&id), 2);
+
+        e.ledger().with_mut(|l| l.sequence_number += 1);
+        attest_violation(&e, &client, &verifier);
+        assert_eq!(client.get_pending_health_updates(&id), 0);
+        assert_eq!(score(&e, &client), 20);
+    }
+}
+
+mod auth_contexts {
+    use super::*;
+    use soroban_sdk::testutils::{MockAuth, MockAuthInvoke};
+
+    /// The shared fixture with blanket auth mocking switched off, so each
+    /// test mocks exactly the authorizations it means to.
+    fn setup() -> (Env, AttestationEngineContractClient<'static>, Address) {
+        let Fixture {
+            e, client, admin, ..
+        } = fixture();
+        e.set_auths(&[]);
+        (e, client, admin)
+    }
+
+    #[test]
+    fn test_add_verifier_auth_binds_to_verifier() {
+        let (e, client, admin) = setup();
+        let verifier = Address::generate(&e);
+        let other = Address::generate(&e);
+
+        // A signature for a different verifier is rejected
+        e.mock_auths(&[MockAuth {
+            address: &admin,
+            invoke: &MockAuthInvoke {
+                contract: &client.address,
+                fn_name: "add_verifier",
+                args: (other.clone(),).into_val(&e),
+                sub_invokes: &[],
+            },
+        }]);
+        assert!(client.try_add_verifier(&admin, &verifier).is_err());
+        assert!(!client.is_verifier(&verifier));
+
+        e.mock_auths(&[MockAuth {
+            address: &admin,
+            invoke: &MockAuthInvoke {
+                contract: &client.address,
+                fn_name: "add_verifier",
+                args: (verifier.clone(),).into_val(&e),
+                sub_invokes: &[],
+            },
+        }]);
+        client.add_verifier(&admin, &verifier);
+        assert!(client.is_verifier(&verifier));
+    }
+
+    #[test]
+    fn test_set_attestation_fee_auth_binds_to_amount() {
+        let (e, client, admin) = setup();
+        let asset = Address::generate(&e);
+
+        e.mock_auths(&[MockAuth {
+            address: &admin,
+            invoke: &MockAuthInvoke {
+                contract: &client.address,
+                fn_name: "set_attestation_fee",
+                args: (10i128, asset.clone()).into_val(&e),
+                sub_invokes: &[],
+            },
+        }]);
+        assert!(client
+            .try_set_attestation_fee(&admin, &1_000, &asset)
+            .is_err());
+
+        e.mock_auths(&[MockAuth {
+            address: &admin,
+            invoke: &MockAuthInvoke {
+                contract: &client.address,
+                fn_name: "set_fee_recipient",
+                args: (Address::generate(&e),).into_val(&e),
+                sub_invokes: &[],
+            },
+        }]);
+        assert!(client
+            .try_set_fee_recipient(&admin, &Address::generate(&e))
+            .is_err());
+    }
+}
+
+mod quorum {
+    use super::*;
+    use soroban_sdk::testutils::Ledger;
+
+    struct Setup {
+        e: Env,
+        client: AttestationEngineContractClient<'static>,
+        verifiers: [Address; 3],
+    }
+
+    /// 2-of-3 quorum over a 1 hour window for commitments of 1000 or more
+    /// (MockCoreContract commitments hold 1000).
+    fn setup() -> Setup {
+        let Fixture {
+            e, client, admin, ..
+        } = fixture();
+        let verifiers = [
+            Address::generate(&e),
+            Address::generate(&e),
+            Address::generate(&e),
+        ];
+        for verifier in verifiers.iter() {
+            client.add_verifier(&admin, verifier);
+        }
+        client.set_quorum_config(&admin, &2, &3_600, &1_000);
+        Setup {
+            e,
+            client,
+            verifiers,
+        }
+    }
+
+    fn drawdown_claim(s: &Setup, verifier: &Address) -> BytesN<32> {
+        let mut data = Map::new(&s.e);
+        data.set(
+            String::from_str(&s.e, "drawdown_percent"),
+            String::from_str(&s.e, "20"),
+        );
+        s.client.submit_claim(
+            verifier,
+            &String::from_str(&s.e, "c_0"),
+            &String::from_str(&s.e, "drawdown"),
+            &data,
+            &false,
+        )
+    }
+
+    #[test]
+    fn test_claim_takes_effect_once_quorum_reached() {
+        let s = setup();
+        let id = String::from_str(&s.e, "c_0");
+
+        let claim_id = drawdown_claim(&s, &s.verifiers[0]);
+        let claim = s.client.get_claim(&claim_id).unwrap();
+        assert_eq!(claim.status, ClaimStatus::Pending);
+        assert_eq!(claim.verifiers.len(), 1);
+        assert_eq!(s.client.get_latest_drawdown_percent(&id), None);
+
+        assert_eq!(drawdown_claim(&s, &s.verifiers[1]), claim_id);
+        let claim = s.client.get_claim(&claim_id).unwrap();
+        assert_eq!(claim.status, ClaimStatus::Confirmed);
+        assert_eq!(s.client.get_attestations(&id).len(), 1);
+        assert_eq!(s.client.get_latest_drawdown_percent(&id), Some(20));
+
+        let result = s.client.try_submit_claim(
+            &s.verifiers[2],
+            &id,
+            &claim.attestation_type,
+            &claim.data,
+            &false,
+        );
+        assert_eq!(result, Err(Ok(AttestationError::ClaimAlreadyConfirmed)));
+    }
+
+    #[test]
+    fn test_claim_vote_counts_each_verifier_once() {
+        let s = setup();
+        let claim_id = drawdown_claim(&s, &s.verifiers[0]);
+        let claim = s.client.get_claim(&claim_id).unwrap();
+
+        let result = s.client.try_submit_claim(
+            &s.verifiers[0],
+            &claim.commitment_id,
+            &claim.attestation_type,
+            &claim.data,
+            &false,
+        );
+        assert_eq!(result, Err(Ok(AttestationError::DuplicateClaimVote)));
+        assert_eq!(
+            s.client.get_claim(&claim_id).unwrap().status,
+            ClaimStatus::Pending
+        );
+    }
+
+    #[test]
+    fn test_claim_expires_outside_window() {
+        let s = setup();
+        let claim_id = drawdown_claim(&s, &s.verifiers[0]);
+
+        s.e.ledger().with_mut(|l| l.timestamp += 3_601);
+        assert_eq!(
+            s.client.get_claim(&claim_id).unwrap().status,
+            ClaimStatus::Expired
+        );
+
+        // A late vote restarts the claim instead of completing it
+        drawdown_claim(&s, &s.verifiers[1]);
+        let claim = s.client.get_claim(&claim_id).unwrap();
+        assert_eq!(claim.status, ClaimStatus::Pending);
+        assert_eq!(claim.verifiers.len(), 1);
+        assert_eq!(
+            s.client
+                .get_attestations(&String::from_str(&s.e, "c_0"))
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_confirmed_claim_can_be_repeated_after_window() {
+        let s = setup();
+        let claim_id = drawdown_claim(&s, &s.verifiers[0]);
+        drawdown_claim(&s, &s.verifiers[1]);
+
+        // The same reading a window later opens a new round
+        s.e.ledger().with_mut(|l| l.timestamp += 3_601);
+        assert_eq!(drawdown_claim(&s, &s.verifiers[0]), claim_id);
+        let claim = s.client.get_claim(&claim_id).unwrap();
+        assert_eq!(claim.status, ClaimStatus::Pending);
+        assert_eq!(claim.verifiers.len(), 1);
+
+        drawdown_claim(&s, &s.verifiers[2]);
+        assert_eq!(
+            s.client.get_claim(&claim_id).unwrap().status,
+            ClaimStatus::Confirmed
+        );
+        assert_eq!(
+            s.client
+                .get_attestations(&String::from_str(&s.e, "c_0"))
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_high_value_commitment_rejects_single_attestation() {
+        let s = setup();
+        let result = s.client.try_attest(
+            &s.verifiers[0],
+            &String::from_str(&s.e, "c_0"),
+            &String::from_str(&s.e, "health_check"),
+            &Map::new(&s.e),
+            &true,
+        );
+        assert_eq!(result, Err(Ok(AttestationError::QuorumRequired)));
+
+        // Below the threshold single attestations still apply
+        let admin = s.client.get_admin();
+        s.client.set_quorum_config(&admin, &2, &3_600, &1_001);
+        s.client.attest(
+            &s.verifiers[0],
+            &String::from_str(&s.e, "c_0"),
+            &String::from_str(&s.e, "health_check"),
+            &Map::new(&s.e),
+            &true,
+        );
+    }
+
+    #[test]
+    fn test_quorum_config_validated() {
+        let s = setup();
+        let admin = s.client.get_admin();
+        assert_eq!(
+            s.client.try_set_quorum_config(&admin, &1, &3_600, &0),
+            Err(Ok(AttestationError::InvalidQuorumConfig))
+        );
+        assert_eq!(
+            s.client.try_set_quorum_config(&admin, &2, &0, &0),
+            Err(Ok(AttestationError::InvalidQuorumConfig))
+        );
+
+        s.client.set_quorum_config(&admin, &0, &0, &0);
+        assert_eq!(s.client.get_quorum_config(), None);
+        let result = s.client.try_submit_claim(
+            &s.verifiers[0],
+            &String::from_str(&s.e, "c_0"),
+            &String::from_str(&s.e, "health_check"),
+            &Map::new(&s.e),
+            &true,
+        );
+        assert_eq!(result, Err(Ok(AttestationError::QuorumNotConfigured)));
+    }
+}
@@ -47,7 +47,7 @@ fn benchmark_create_commitment_storage_reads() {
     let mem_before = env.budget().memory_bytes_cost();
     
     // Execute function
-    let _commitment_id = client.create_commitment(&owner, &10000, &asset, &rules);
+    let _commitment_id = client.create_commitment(&owner, &10000, &asset, &rules, &None);
     
     // Measure after
     let cpu_after = env.budget().cpu_instruction_cost();
@@ -84,7 +84,7 @@ fn benchmark_batch_counter_updates() {
     
     for i in 0..10 {
         let amount = 1000 * (i + 1);
-        client.create_commitment(&owner, &amount, &asset, &rules);
+        client.create_commitment(&owner, &amount, &asset, &rules, &None);
     }
     
     let cpu_after = env.budget().cpu_instruction_cost();
@@ -134,7 +134,7 @@ fn benchmark_check_violations() {
         grace_period_days: 0,
     };
     
-    let commitment_id = client.create_commitment(&owner, &10000, &asset, &rules);
+    let commitment_id = client.create_commitment(&owner, &10000, &asset, &rules, &None);
     
     env.budget().reset_unlimited();
     
@@ -220,7 +220,7 @@ fn benchmark_settle_function() {
         grace_period_days: 0,
     };
     
-    let commitment_id = client.create_commitment(&owner, &10000, &asset, &rules);
+    let commitment_id = client.create_commitment(&owner, &10000, &asset, &rules, &None);
     
     // Fast forward time to expiration
     env.ledger().with_mut(|li| {
@@ -267,7 +267,7 @@ fn benchmark_memory_usage() {
     // Create 10 commitments
     for i in 0..10 {
         let amount = 1000 * (i + 1);
-        client.create_commitment(&owner, &amount, &asset, &rules);
+        client.create_commitment(&owner, &amount, &asset, &rules, &None);
     }
     
     let mem_after = env.budget().memory_bytes_cost();
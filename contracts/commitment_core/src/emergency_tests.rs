@@ -50,7 +50,7 @@ fn test_create_commitment_forbidden_in_emergency() {
     };
 
     // This should panic because of emergency mode
-    client.create_commitment(&owner, &1000, &asset, &rules);
+    client.create_commitment(&owner, &1000, &asset, &rules, &None);
 }
 
 #[test]
@@ -1,9 +1,12 @@
 #![no_std]
 
-use shared_utils::{emit_error_event, Pausable, RateLimiter, SafeMath, TimeUtils, Validation};
+use shared_utils::{
+    emit_error_event, Pausable, RateLimiter, SafeMath, SnapshotVersion, Sunset, SunsetState,
+    TimeUtils, Validation,
+};
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, log, symbol_short, token, Address, Env,
-    IntoVal, String, Symbol, Vec,
+    contract, contracterror, contractimpl, contracttype, log, symbol_short, token, xdr::ToXdr,
+    Address, BytesN, Env, IntoVal, String, Symbol, Topics, Val, Vec,
 };
 
 #[contracterror]
@@ -27,6 +30,74 @@ pub enum CommitmentError {
     NotExpired = 15,
     /// Duration would cause expires_at to overflow u64
     ExpirationOverflow = 16,
+    /// Address is on the protocol blocklist
+    BlockedAddress = 17,
+    /// Settlement receipt does not exist
+    ReceiptNotFound = 18,
+    /// Settlement receipt is soulbound and cannot be transferred
+    ReceiptNotTransferable = 19,
+    /// Asset has been delisted and no longer accepts deposits
+    AssetDelisted = 20,
+    /// Asset is not delisted or has no conversion route configured
+    NoConversionRoute = 21,
+    /// Swap returned less than the requested minimum output
+    SlippageExceeded = 22,
+    /// Notification preference flags contain unknown bits
+    InvalidNotificationPrefs = 23,
+    /// Payout policy is missing a swap route or has out-of-range slippage
+    InvalidPayoutPolicy = 24,
+    /// Protocol is sunsetting and accepts no new commitments
+    ProtocolSunset = 25,
+    /// Sunset policy has mismatched or invalid beneficiary shares
+    InvalidSunsetPolicy = 26,
+    /// Sunset has not begun or its final date has not been reached
+    SunsetNotFinal = 27,
+    /// Commitment is tracking-only and holds no escrowed funds
+    TrackingOnly = 28,
+    /// Label is not in the admin-curated label set
+    UnknownLabel = 29,
+    /// Label set already holds MAX_LABELS labels
+    TooManyLabels = 30,
+    /// Early-exit queue for the asset already holds MAX_EXIT_QUEUE entries
+    ExitQueueFull = 31,
+    /// Commitment has no queued early exit
+    NotQueued = 32,
+    /// Exit queue was already processed for the asset in this ledger
+    ExitRoundTooSoon = 33,
+    /// Stress config basis points are out of range
+    InvalidStressConfig = 34,
+    /// Fee basis points are out of range
+    InvalidFeeConfig = 35,
+    /// Commitment has not been flagged as violated
+    NotViolated = 36,
+    /// Commitment is still within its grace period
+    GracePeriodActive = 37,
+    /// Commitment value has not been updated within the stale window
+    StaleValue = 38,
+    /// Owner cannot refer their own commitment
+    SelfReferral = 39,
+    /// Amount is below the asset's minimum commitment size
+    BelowMinimumAmount = 40,
+    /// Amount is above the asset's maximum commitment size
+    AboveMaximumAmount = 41,
+    /// Asset limits have min above max or a negative bound
+    InvalidAssetLimits = 42,
+    /// Insurance balance for the asset cannot cover the requested amount
+    InsufficientInsurance = 43,
+    /// Note exceeds MAX_NOTE_LEN bytes
+    NoteTooLong = 44,
+    /// Amount exceeds the approval threshold and no approved request exists
+    ApprovalRequired = 45,
+    /// Approval request has expired
+    ApprovalExpired = 46,
+    /// Signer has already approved this request
+    AlreadyApproved = 47,
+    /// Approval policy is inconsistent (e.g. more approvals required than signers)
+    InvalidApprovalPolicy = 48,
+    /// Approval request not found
+    ApprovalRequestNotFound = 49,
+    /// Revealed parameters match no commit from an earlier ledger
+    InvalidReveal = 50,
 }
 
 impl CommitmentError {
@@ -51,14 +122,170 @@ impl CommitmentError {
             CommitmentError::ExpirationOverflow => {
                 "Duration would cause expiration timestamp overflow"
             }
+            CommitmentError::BlockedAddress => "Address is blocked",
+            CommitmentError::ReceiptNotFound => "Settlement receipt not found",
+            CommitmentError::ReceiptNotTransferable => "Settlement receipt is not transferable",
+            CommitmentError::AssetDelisted => "Asset has been delisted",
+            CommitmentError::NoConversionRoute => "No conversion route for asset",
+            CommitmentError::SlippageExceeded => "Swap output below minimum",
+            CommitmentError::InvalidNotificationPrefs => "Invalid notification preferences",
+            CommitmentError::InvalidPayoutPolicy => "Invalid payout policy",
+            CommitmentError::ProtocolSunset => "Protocol is sunsetting: no new commitments",
+            CommitmentError::InvalidSunsetPolicy => "Invalid sunset policy",
+            CommitmentError::SunsetNotFinal => "Sunset final date not reached",
+            CommitmentError::TrackingOnly => "Commitment is tracking-only: no escrowed funds",
+            CommitmentError::UnknownLabel => "Unknown commitment label",
+            CommitmentError::TooManyLabels => "Label set is full",
+            CommitmentError::ExitQueueFull => "Early-exit queue is full",
+            CommitmentError::NotQueued => "No queued early exit for commitment",
+            CommitmentError::ExitRoundTooSoon => "Exit queue already processed this ledger",
+            CommitmentError::InvalidStressConfig => "Invalid stress config",
+            CommitmentError::InvalidFeeConfig => "Invalid fee config",
+            CommitmentError::NotViolated => "Commitment is not violated",
+            CommitmentError::GracePeriodActive => "Commitment is still in its grace period",
+            CommitmentError::StaleValue => "Commitment value is stale",
+            CommitmentError::SelfReferral => "Owner cannot be their own referrer",
+            CommitmentError::BelowMinimumAmount => "Amount below asset minimum",
+            CommitmentError::AboveMaximumAmount => "Amount above asset maximum",
+            CommitmentError::InvalidAssetLimits => "Invalid asset limits",
+            CommitmentError::InsufficientInsurance => "Insufficient insurance balance",
+            CommitmentError::NoteTooLong => "Note exceeds maximum length",
+            CommitmentError::ApprovalRequired => "Multi-signature approval required",
+            CommitmentError::ApprovalExpired => "Approval request expired",
+            CommitmentError::AlreadyApproved => "Signer already approved request",
+            CommitmentError::InvalidApprovalPolicy => "Invalid approval policy",
+            CommitmentError::ApprovalRequestNotFound => "Approval request not found",
+            CommitmentError::InvalidReveal => "Reveal matches no earlier creation commit",
+        }
+    }
+}
+
+/// Rate limiter function key for commitment creation (all create variants).
+const CREATE_RATE_LIMIT_FN: Symbol = symbol_short!("create");
+
+/// Fixed-point scale of `DataKeyExt::PenaltyIndex`.
+const PENALTY_INDEX_SCALE: i128 = 1_000_000_000_000;
+
+/// Storage schema version written by this code.
+///
+/// Version 0 is storage created before versioning; version 1 adds the
+/// incrementally maintained protocol stats.
+pub const CURRENT_VERSION: u32 = 1;
+
+/// Errors added once `CommitmentError` reached the 50-variant limit of
+/// `#[contracterror]` enums. Codes continue from `CommitmentError`.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum CommitmentErrorExt {
+    /// max_loss_percent exceeds the commitment type's limit
+    LossAboveTypeLimit = 51,
+    /// duration_days is below the commitment type's minimum
+    DurationBelowTypeMinimum = 52,
+    /// duration_days is above the commitment type's maximum
+    DurationAboveTypeMaximum = 53,
+    /// Type constraints are inconsistent (e.g. min duration above max)
+    InvalidTypeConstraints = 54,
+    /// Storage is already at CURRENT_VERSION
+    AlreadyMigrated = 55,
+    /// from_version does not match the stored schema version
+    InvalidVersion = 56,
+    /// WASM hash is all zeros
+    InvalidWasmHash = 57,
+    /// Caller exceeded the configured rate limit
+    RateLimited = 58,
+    /// Expiry query spans more than MAX_EXPIRY_QUERY_DAYS
+    ExpiryRangeTooLarge = 59,
+    /// Forced settlement requires a non-empty reason
+    ReasonRequired = 60,
+    /// New commitments are paused by an operator
+    CreationPaused = 61,
+    /// Batch exceeds MAX_BATCH_SETTLE
+    BatchTooLarge = 62,
+    /// Commitment is frozen by the admin
+    CommitmentFrozen = 63,
+    /// Payer has not approved core for the deposit amount
+    InsufficientAllowance = 64,
+    /// Config value has the wrong type for its key
+    InvalidConfigValue = 65,
+    /// Owner already has the maximum number of active commitments
+    TooManyActiveCommitments = 66,
+    /// Commitment is within its loss limit
+    NoViolation = 67,
+}
+
+impl CommitmentErrorExt {
+    /// Human-readable message for debugging and error events.
+    pub fn message(&self) -> &'static str {
+        match self {
+            CommitmentErrorExt::LossAboveTypeLimit => "Max loss exceeds commitment type limit",
+            CommitmentErrorExt::DurationBelowTypeMinimum => {
+                "Duration below commitment type minimum"
+            }
+            CommitmentErrorExt::DurationAboveTypeMaximum => {
+                "Duration above commitment type maximum"
+            }
+            CommitmentErrorExt::InvalidTypeConstraints => "Invalid commitment type constraints",
+            CommitmentErrorExt::AlreadyMigrated => "Storage already at current version",
+            CommitmentErrorExt::InvalidVersion => "Invalid migration version",
+            CommitmentErrorExt::InvalidWasmHash => "Invalid WASM hash",
+            CommitmentErrorExt::RateLimited => "Rate limit exceeded",
+            CommitmentErrorExt::ExpiryRangeTooLarge => "Expiry range too large",
+            CommitmentErrorExt::ReasonRequired => "Reason required",
+            CommitmentErrorExt::CreationPaused => "Commitment creation paused",
+            CommitmentErrorExt::BatchTooLarge => "Batch too large",
+            CommitmentErrorExt::CommitmentFrozen => "Commitment is frozen",
+            CommitmentErrorExt::InsufficientAllowance => "Insufficient allowance",
+            CommitmentErrorExt::InvalidConfigValue => "Invalid config value",
+            CommitmentErrorExt::TooManyActiveCommitments => "Too many active commitments",
+            CommitmentErrorExt::NoViolation => "Commitment is within its loss limit",
         }
     }
 }
 
+/// Error enums that can be reported through `fail`.
+trait FailReason: Copy {
+    fn code(self) -> u32;
+    fn reason(&self) -> &'static str;
+}
+
+impl FailReason for CommitmentError {
+    fn code(self) -> u32 {
+        self as u32
+    }
+    fn reason(&self) -> &'static str {
+        self.message()
+    }
+}
+
+impl FailReason for CommitmentErrorExt {
+    fn code(self) -> u32 {
+        self as u32
+    }
+    fn reason(&self) -> &'static str {
+        self.message()
+    }
+}
+
 /// Emit error event and panic with standardized message (for indexers and UX).
-fn fail(e: &Env, err: CommitmentError, context: &str) -> ! {
-    emit_error_event(e, err as u32, context);
-    panic!("{}", err.message());
+fn fail(e: &Env, err: impl FailReason, context: &str) -> ! {
+    emit_error_event(e, err.code(), context);
+    panic!("{}", err.reason());
+}
+
+/// Publish a core event with the next event sequence number. The payload is
+/// `(seq, data)`; `seq` starts at 1 and increases by one per event, so
+/// indexers can spot gaps or reordering and resync from `get_event_seq`.
+fn publish_event<T: Topics, D: IntoVal<Env, Val>>(e: &Env, topics: T, data: D) {
+    let seq = e
+        .storage()
+        .instance()
+        .get::<_, u64>(&DataKeyExt::EventSeq)
+        .unwrap_or(0)
+        + 1;
+    e.storage().instance().set(&DataKeyExt::EventSeq, &seq);
+    let data: Val = data.into_val(e);
+    e.events().publish(topics, (seq, data));
 }
 
 #[contracttype]
@@ -96,686 +323,6430 @@ pub struct Commitment {
     pub created_at: u64,
     pub expires_at: u64,
     pub current_value: i128,
-    pub status: String, // "active", "settled", "violated", "early_exit"
+    pub status: String, // "active", "settled", "violated", "early_exit", "exit_queued", "liquidated"
 }
 
+/// A commitment together with its owner's note, as returned by
+/// `get_commitment_view`.
 #[contracttype]
-#[derive(Clone)]
-pub enum DataKey {
-    Admin,
-    NftContract,
-    Commitment(String),        // commitment_id -> Commitment
-    OwnerCommitments(Address), // owner -> Vec<commitment_id>
-    TotalCommitments,          // counter
-    ReentrancyGuard,           // reentrancy protection flag
-    TotalValueLocked,          // aggregate value locked across active commitments
-    /// All commitment IDs for time-range queries (analytics). Appended on create.
-    AllCommitmentIds,
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CommitmentView {
+    pub commitment: Commitment,
+    pub note: Option<String>,
 }
 
-/// Transfer assets from owner to contract
-fn transfer_assets(e: &Env, from: &Address, to: &Address, asset_address: &Address, amount: i128) {
-    let token_client = token::Client::new(e, asset_address);
+/// Outcome code recorded on a settlement receipt for a matured commitment.
+pub const OUTCOME_SETTLED: u32 = 0;
+/// Outcome code recorded on a settlement receipt for an early exit.
+pub const OUTCOME_EARLY_EXIT: u32 = 1;
+/// Outcome code recorded on a settlement receipt for a liquidated commitment.
+pub const OUTCOME_LIQUIDATED: u32 = 2;
 
-    // Check balance first
-    let balance = token_client.balance(from);
-    if balance < amount {
-        log!(e, "Insufficient balance: {} < {}", balance, amount);
-        fail(e, CommitmentError::InsufficientBalance, "transfer_assets");
-    }
+/// Settlement receipt issuance configuration (admin managed).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReceiptConfig {
+    /// Whether receipts are issued when a commitment closes
+    pub enabled: bool,
+    /// Whether newly issued receipts can be transferred (false = soulbound)
+    pub transferable: bool,
+}
 
-    // Transfer tokens (fails transaction if unsuccessful)
-    token_client.transfer(from, to, &amount);
+/// Proof-of-settlement record issued when a commitment closes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SettlementReceipt {
+    pub receipt_id: u32,
+    pub commitment_id: String,
+    pub holder: Address,
+    pub asset_address: Address,
+    pub final_payout: i128,
+    pub fees: i128,
+    pub penalty: i128,
+    pub outcome: u32,
+    pub closed_at: u64,
+    pub transferable: bool,
 }
 
-/// Helper function to call NFT contract mint function
-fn call_nft_mint(
-    e: &Env,
-    nft_contract: &Address,
-    owner: &Address,
-    commitment_id: &String,
-    duration_days: u32,
-    max_loss_percent: u32,
-    commitment_type: &String,
-    initial_amount: i128,
-    asset_address: &Address,
-    early_exit_penalty: u32,
-) -> u32 {
-    let mut args = Vec::new(e);
-    args.push_back(owner.clone().into_val(e));
-    args.push_back(commitment_id.clone().into_val(e));
-    args.push_back(duration_days.into_val(e));
-    args.push_back(max_loss_percent.into_val(e));
-    args.push_back(commitment_type.clone().into_val(e));
-    args.push_back(initial_amount.into_val(e));
-    args.push_back(asset_address.clone().into_val(e));
-    args.push_back(early_exit_penalty.into_val(e));
+/// Wind-down notice for an asset removed from the protocol.
+///
+/// Commitments already holding the asset settle at expiry in the original
+/// asset, or (if `swap_adapter` and `target_asset` are set) the owner may opt
+/// to convert proceeds via `settle_with_conversion`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DelistingNotice {
+    pub asset: Address,
+    pub reason: String,
+    pub delisted_at: u64,
+    pub swap_adapter: Option<Address>,
+    pub target_asset: Option<Address>,
+}
 
-    // In Soroban, contract calls return the value directly
-    // Failures cause the entire transaction to fail
-    e.invoke_contract::<u32>(nft_contract, &Symbol::new(e, "mint"), args)
+/// Currency a commitment type settles in.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PayoutCurrency {
+    /// Pay out in the committed asset (default)
+    SameAsset,
+    /// Swap proceeds into a stable asset
+    Stable,
+    /// Swap proceeds into the protocol token
+    ProtocolToken,
 }
 
-// Storage helpers
-fn read_commitment(e: &Env, commitment_id: &String) -> Option<Commitment> {
-    e.storage()
-        .instance()
-        .get::<_, Commitment>(&DataKey::Commitment(commitment_id.clone()))
+/// Settlement payout policy for a commitment type.
+///
+/// For `Stable` and `ProtocolToken`, proceeds are swapped through
+/// `swap_adapter` into `target_asset`; the swap must return at least the
+/// adapter's quote less `max_slippage_bps`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutPolicy {
+    pub currency: PayoutCurrency,
+    pub swap_adapter: Option<Address>,
+    pub target_asset: Option<Address>,
+    pub max_slippage_bps: u32,
 }
 
-fn set_commitment(e: &Env, commitment: &Commitment) {
-    e.storage().instance().set(
-        &DataKey::Commitment(commitment.commitment_id.clone()),
-        commitment,
-    );
+/// Published wind-down policy for a protocol sunset.
+///
+/// After `final_date`, early-exit penalties are waived and funds held by core
+/// beyond what active commitments are owed are split between `beneficiaries`
+/// according to `shares_bps` (summing to 10000).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SunsetPolicy {
+    pub final_date: u64,
+    pub beneficiaries: Vec<Address>,
+    pub shares_bps: Vec<u32>,
+    /// Reference to the full published policy (e.g. an IPFS URI)
+    pub policy_uri: String,
 }
 
-fn has_commitment(e: &Env, commitment_id: &String) -> bool {
-    e.storage()
-        .instance()
-        .has(&DataKey::Commitment(commitment_id.clone()))
+/// Maximum number of commitments returned by `get_commitments_page`.
+pub const MAX_COMMITMENTS_PAGE_SIZE: u32 = 50;
+
+/// Maximum number of days one `get_commitments_expiring_between` call may span.
+pub const MAX_EXPIRY_QUERY_DAYS: u64 = 366;
+
+/// Maximum number of commitments one `batch_settle` call may process.
+pub const MAX_BATCH_SETTLE: u32 = 20;
+
+/// Ledgers a `commit_creation` hash stays revealable (about one day).
+pub const CREATION_COMMIT_TTL_LEDGERS: u32 = 17_280;
+
+/// Page of commitments in creation order.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CommitmentsPage {
+    pub commitments: Vec<Commitment>,
+    /// Next offset to use for the following page; 0 means no more pages.
+    pub next_offset: u32,
+    /// Snapshot version at read time; if it changes between pages,
+    /// commitments were modified mid-pagination and the read should restart.
+    pub snapshot: u64,
 }
 
-/// Reentrancy protection helpers
-fn require_no_reentrancy(e: &Env) {
-    let guard: bool = e
-        .storage()
-        .instance()
-        .get::<_, bool>(&DataKey::ReentrancyGuard)
-        .unwrap_or(false);
+/// Notification preference flag: value crossed a loss threshold.
+pub const NOTIFY_THRESHOLD: u32 = 1 << 0;
+/// Notification preference flag: commitment is about to expire.
+pub const NOTIFY_EXPIRY_SOON: u32 = 1 << 1;
+/// Notification preference flag: commitment was settled or exited.
+pub const NOTIFY_SETTLEMENT: u32 = 1 << 2;
+/// All notification flags (the default for owners with no stored preference).
+pub const NOTIFY_ALL: u32 = NOTIFY_THRESHOLD | NOTIFY_EXPIRY_SOON | NOTIFY_SETTLEMENT;
 
-    if guard {
-        fail(
-            e,
-            CommitmentError::ReentrancyDetected,
-            "require_no_reentrancy",
-        );
-    }
+/// Maximum number of analytics labels, keeping label cardinality bounded for indexers.
+pub const MAX_LABELS: u32 = 16;
+
+/// Maximum length of a commitment note in bytes.
+pub const MAX_NOTE_LEN: u32 = 128;
+
+/// Default age after which a commitment's value is considered stale (1 day).
+pub const DEFAULT_STALE_VALUE_SECONDS: u64 = 86_400;
+
+/// Pool risk tier as reported by the allocation contract.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PoolRiskLevel {
+    Low,
+    Medium,
+    High,
 }
 
-fn set_reentrancy_guard(e: &Env, value: bool) {
-    e.storage()
-        .instance()
-        .set(&DataKey::ReentrancyGuard, &value);
+/// Pool record as returned by the allocation contract's `get_all_pools`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllocationPool {
+    pub pool_id: u32,
+    pub risk_level: PoolRiskLevel,
+    pub apy: u32,
+    pub total_liquidity: i128,
+    pub max_capacity: i128,
+    pub active: bool,
+    pub created_at: u64,
+    pub updated_at: u64,
 }
 
-/// Require that the caller is the admin stored in this contract.
-fn require_admin(e: &Env, caller: &Address) {
-    caller.require_auth();
-    let admin = e
-        .storage()
-        .instance()
-        .get::<_, Address>(&DataKey::Admin)
-        .unwrap_or_else(|| fail(e, CommitmentError::NotInitialized, "require_admin"));
-    if *caller != admin {
-        fail(e, CommitmentError::Unauthorized, "require_admin");
-    }
+/// Aggregate risk view for operator dashboards.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RiskDashboard {
+    /// Active commitments whose loss is within 10% of their max loss limit
+    pub near_max_loss: Vec<String>,
+    /// Active commitments whose value has not been attested within the stale window
+    pub stale_attestation: Vec<String>,
+    /// Pools above 90% utilization (empty if no allocation contract is set)
+    pub high_utilization_pools: Vec<u32>,
+    /// Insurance fund holdings relative to TVL in basis points
+    pub insurance_coverage_bps: u32,
+    /// Number of early exits waiting in stressed-mode exit queues
+    pub queued_withdrawals: u32,
+    pub total_value_locked: i128,
+    pub timestamp: u64,
 }
 
-/// Pause the contract
-///
-/// # Arguments
-/// * `e` - The environment
+/// Token conservation check for a single asset across protocol contracts.
 ///
-/// # Panics
-/// Panics if caller is not admin or if contract is already paused
-pub fn pause(e: Env) {
-    let admin = e
-        .storage()
-        .instance()
-        .get::<_, Address>(&DataKey::Admin)
-        .unwrap_or_else(|| fail(&e, CommitmentError::NotInitialized, "pause"));
-    admin.require_auth();
-    Pausable::pause(&e);
+/// `expected_total` is what the protocol owes; `actual_total` is what its
+/// contracts hold. A negative `discrepancy` means tokens have leaked.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConservationReport {
+    pub asset: Address,
+    /// Value owed to active and violated commitments held in core escrow
+    pub escrow: i128,
+    /// Outstanding payouts owed to queued early exits
+    pub queued_withdrawals: i128,
+    /// Reported yield not compounded and not yet claimed
+    pub unclaimed_yield: i128,
+    /// Referral rewards accrued and not yet claimed
+    pub referral_rewards: i128,
+    /// Settlement proceeds still streaming to their recipients
+    pub vesting: i128,
+    /// Collected fees held by the attestation engine (0 if none is set)
+    pub fees: i128,
+    /// Insurance fund holdings in core
+    pub insurance: i128,
+    /// Penalty pool plus credited shares not yet claimed
+    pub penalty_pool: i128,
+    pub expected_total: i128,
+    /// Token balance held by core plus the attestation engine
+    pub actual_total: i128,
+    /// actual_total - expected_total
+    pub discrepancy: i128,
+    /// True when holdings cover all liabilities
+    pub conserved: bool,
+    pub timestamp: u64,
 }
 
-/// Unpause the contract
-///
-/// # Arguments
-/// * `e` - The environment
+/// Maximum early exits queued per asset during stressed mode
+pub const MAX_EXIT_QUEUE: u32 = 50;
+
+/// Stressed-mode tuning.
 ///
-/// # Panics
-/// Panics if caller is not admin or if contract is already unpaused
-pub fn unpause(e: Env) {
-    let admin = e
-        .storage()
-        .instance()
-        .get::<_, Address>(&DataKey::Admin)
-        .unwrap_or_else(|| fail(&e, CommitmentError::NotInitialized, "unpause"));
-    admin.require_auth();
-    Pausable::unpause(&e);
+/// In stressed mode early exits are queued and paid over several ledgers,
+/// pro-rata to what each exit is still owed, instead of first-come-first-served.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StressConfig {
+    /// Enter stressed mode automatically when a single early exit would pay out
+    /// more than this share of the asset's escrow (basis points, 0 = disabled)
+    pub auto_trigger_bps: u32,
+    /// Share of core's asset balance paid out per queue round (basis points)
+    pub round_liquidity_bps: u32,
 }
 
-/// Check if the contract is paused
+/// Maximum settlement fee (basis points)
+pub const MAX_SETTLEMENT_FEE_BPS: u32 = 1_000;
+
+/// Protocol fee charged on settlement proceeds.
 ///
-/// # Arguments
-/// * `e` - The environment
+/// No fee is taken until a treasury is configured.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    /// Share of settlement proceeds sent to the treasury (basis points)
+    pub settlement_fee_bps: u32,
+    pub treasury: Option<Address>,
+}
+
+/// Loyalty discount for owners holding settled commitment NFTs.
 ///
-/// # Returns
-/// `true` if paused, `false` otherwise
-pub fn is_paused(e: Env) -> bool {
-    Pausable::is_paused(&e)
+/// An owner qualifies when they hold at least `min_settled` settled NFTs whose
+/// initial amounts total at least `min_settled_amount`. The best qualifying
+/// tier's `discount_bps` is taken off settlement fees and early-exit penalties.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeTier {
+    pub min_settled: u32,
+    pub min_settled_amount: i128,
+    pub discount_bps: u32,
 }
 
-#[contract]
-pub struct CommitmentCoreContract;
+/// Maximum number of loyalty fee tiers
+pub const MAX_FEE_TIERS: u32 = 10;
 
-#[contractimpl]
-impl CommitmentCoreContract {
-    /// Validate commitment rules using shared utilities
-    fn validate_rules(e: &Env, rules: &CommitmentRules) {
-        // Duration must be > 0
-        Validation::require_valid_duration(rules.duration_days);
+/// Preview of an early exit, as returned by `quote_early_exit`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EarlyExitQuote {
+    pub penalty_amount: i128,
+    /// Amount the owner would receive
+    pub net_amount: i128,
+    /// Penalty in basis points of the current value, after any sunset waiver
+    /// and loyalty discount
+    pub penalty_bps: u32,
+    /// True if stressed mode would queue the payout
+    pub queued: bool,
+}
 
-        // Max loss percent must be between 0 and 100
-        Validation::require_valid_percent(rules.max_loss_percent);
+/// Projected settlement of a commitment, as returned by `quote_settlement`.
+/// Amounts are in the commitment's asset, before any payout conversion.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SettlementQuote {
+    /// Amount the payout route would receive (including accrued yield)
+    pub payout: i128,
+    pub accrued_yield: i128,
+    /// Settlement fee to the treasury, after any loyalty discount
+    pub protocol_fee: i128,
+    /// Late fee for settling past the grace period
+    pub late_fee: i128,
+    /// True if `settle` would succeed now
+    pub eligible: bool,
+}
 
-        // Commitment type must be valid
-        let valid_types = ["safe", "balanced", "aggressive"];
-        Validation::require_valid_commitment_type(e, &rules.commitment_type, &valid_types);
-    }
+/// Admin setting reachable through `set_config` / `get_config`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigKey {
+    /// U32, at most MAX_SETTLEMENT_FEE_BPS; requires a treasury
+    SettlementFeeBps,
+    /// Address receiving settlement fees
+    Treasury,
+    /// U32, at most MAX_KEEPER_REWARD_BPS
+    KeeperRewardBps,
+    /// U32, at most 10000
+    ReferralShareBps,
+    /// U32, at most 10000; 0 disables alerts
+    DrawdownAlertBps,
+    /// U64 seconds
+    StaleValueSeconds,
+    /// Bool
+    RequireFreshValue,
+    /// U64 seconds; 0 checks loss limits against the instant value
+    TwapWindowSeconds,
+    /// Address
+    AttestationEngine,
+    /// Address
+    AllocationContract,
+    /// U32, at most MAX_SETTLEMENT_FEE_BPS
+    RenewalFeeBps,
+    /// U32; 0 leaves active commitments per owner unlimited
+    MaxActivePerOwner,
+    /// U32, at most MAX_KEEPER_REWARD_BPS
+    ViolationBountyBps,
+}
 
-    /// Generate unique commitment ID
-    /// Optimized: Uses counter to create unique ID efficiently
-    fn generate_commitment_id(e: &Env, counter: u64) -> String {
-        // OPTIMIZATION: Use counter directly as string to minimize allocations
-        // This is more gas-efficient than string concatenation
-        let mut buf = [0u8; 32];
-        let prefix = b"c_";
-        buf[0] = prefix[0];
-        buf[1] = prefix[1];
+/// Typed value of a `ConfigKey`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigValue {
+    U32(u32),
+    U64(u64),
+    Bool(bool),
+    Address(Address),
+}
 
-        // Convert counter to string representation
-        let mut n = counter;
-        let mut i = 2;
-        if n == 0 {
-            buf[i] = b'0';
-            i += 1;
-        } else {
-            let mut digits = [0u8; 20];
-            let mut digit_count = 0;
-            while n > 0 {
-                digits[digit_count] = (n % 10) as u8 + b'0';
-                n /= 10;
-                digit_count += 1;
-            }
-            // Reverse digits
-            for j in 0..digit_count {
+/// Maximum keeper reward for settling overdue commitments (basis points)
+pub const MAX_KEEPER_REWARD_BPS: u32 = 500;
+
+/// Share of penalties and settlement fees set aside in core's insurance fund.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsuranceConfig {
+    /// Share of early-exit penalties and late fees (basis points)
+    pub penalty_bps: u32,
+    /// Share of settlement fees after referral rewards (basis points)
+    pub fee_bps: u32,
+}
+
+/// Maximum entries kept in a commitment's yield ledger (oldest dropped first)
+pub const MAX_YIELD_LEDGER_ENTRIES: u32 = 50;
+
+/// N-of-M approval required for commitments and early exits above a threshold.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApprovalPolicy {
+    /// Amounts strictly above this need approval
+    pub threshold_amount: i128,
+    /// Designated signers (M)
+    pub signers: Vec<Address>,
+    /// Approvals needed before the action may execute (N)
+    pub required: u32,
+    /// Lifetime of a request from submission (seconds)
+    pub expiry_seconds: u64,
+}
+
+/// Action an approval request authorizes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ApprovalAction {
+    /// Create a commitment: (owner, asset, amount)
+    Create(Address, Address, i128),
+    /// Exit a commitment early: (commitment_id)
+    EarlyExit(String),
+    /// Top up a commitment: (commitment_id, top-up amount)
+    TopUp(String, i128),
+}
+
+/// Rule bounds a commitment type must satisfy at creation.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TypeConstraints {
+    /// Highest allowed max_loss_percent
+    pub max_loss_percent: u32,
+    /// Shortest allowed duration
+    pub min_duration_days: u32,
+    /// Longest allowed duration (0 = unbounded)
+    pub max_duration_days: u32,
+}
+
+/// Weights of the `get_health_score` components (sum to 100).
+const HEALTH_DRAWDOWN_WEIGHT: i128 = 40;
+const HEALTH_TIME_WEIGHT: i128 = 20;
+const HEALTH_FEE_WEIGHT: i128 = 20;
+const HEALTH_FRESHNESS_WEIGHT: i128 = 20;
+
+/// Per-asset figures in `ProtocolStats`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetStats {
+    pub asset: Address,
+    /// Value locked in active commitments
+    pub value_locked: i128,
+    /// Early-exit penalties, late fees and liquidation penalties collected
+    pub penalties_collected: i128,
+}
+
+/// Protocol-wide counters, maintained incrementally.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProtocolStats {
+    /// Commitments ever created
+    pub total_commitments: u64,
+    pub active_commitments: u64,
+    pub settled_commitments: u64,
+    pub total_value_locked: i128,
+    pub assets: Vec<AssetStats>,
+}
+
+/// Maximum recipients in a commitment's payout split
+pub const MAX_SPLIT_RECIPIENTS: u32 = 10;
+
+/// Settlement proceeds released linearly to `recipient` after settlement.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SettlementStream {
+    pub recipient: Address,
+    pub asset: Address,
+    pub total: i128,
+    pub claimed: i128,
+    pub start: u64,
+    pub duration_seconds: u64,
+}
+
+/// Creation parameters hidden behind a `commit_creation` hash.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreationParams {
+    pub amount: i128,
+    pub asset_address: Address,
+    pub rules: CommitmentRules,
+}
+
+/// Pending approval request, consumed when its action executes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApprovalRequest {
+    pub request_id: u64,
+    pub owner: Address,
+    pub action: ApprovalAction,
+    pub approvals: Vec<Address>,
+    pub expires_at: u64,
+}
+
+/// A yield credit recorded against a commitment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct YieldCredit {
+    pub amount: i128,
+    pub credited_by: Address,
+    /// True if folded into principal, false if accrued for payout
+    pub compounded: bool,
+    pub timestamp: u64,
+}
+
+/// A commitment's value from the moment it changed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValueSnapshot {
+    pub value: i128,
+    pub timestamp: u64,
+}
+
+/// Maximum snapshots kept in a commitment's value history (oldest dropped first)
+pub const MAX_VALUE_HISTORY_ENTRIES: u32 = 100;
+
+/// Admin-configured size bounds for commitments in one asset.
+/// A `max_amount` of 0 means no upper bound.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetLimits {
+    pub min_amount: i128,
+    pub max_amount: i128,
+}
+
+/// Late settlement fee charged once a commitment's grace period has ended.
+///
+/// `bps_per_day` accrues for every started day past the grace period, up to
+/// `max_bps`. Disabled (0) by default.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LateFeeConfig {
+    pub bps_per_day: u32,
+    pub max_bps: u32,
+}
+
+/// Where an active commitment stands relative to its maturity.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MaturityStatus {
+    /// Not yet expired
+    Locked,
+    /// Expired and within `grace_period_days`: settles penalty-free
+    InGrace,
+    /// Grace period over: a late fee accrues on settlement
+    Overdue,
+    /// No longer active (settled, exited, violated or queued)
+    Closed,
+}
+
+/// Where early-exit penalties are sent.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PenaltyRecipient {
+    /// Penalties stay in core as surplus (default)
+    Retain,
+    /// Penalties are transferred to a treasury address
+    Treasury(Address),
+    /// Penalties are burned
+    Burn,
+    /// Penalties are split across active commitments in the same asset,
+    /// pro-rata to their current value, and credited to each owner's
+    /// penalty share (claimable with `claim_penalty_share`) once the
+    /// commitment's value next changes or it closes
+    Redistribute,
+    /// Penalties accrue to a pool shared by commitments that reach maturity
+    /// compliant, claimable with `claim_penalty_share`
+    CompliancePool,
+}
+
+/// An early exit waiting for liquidity in stressed mode.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueuedExit {
+    pub commitment_id: String,
+    pub owner: Address,
+    pub asset_address: Address,
+    /// Commitment value when the exit was queued
+    pub value: i128,
+    pub penalty: i128,
+    /// Payout owed once fully processed (value - penalty)
+    pub owed: i128,
+    /// Paid out so far
+    pub paid: i128,
+    pub queued_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    NftContract,
+    Commitment(String),        // commitment_id -> Commitment
+    OwnerCommitments(Address), // owner -> Vec<commitment_id>
+    TotalCommitments,          // counter
+    ReentrancyGuard,           // reentrancy protection flag
+    TotalValueLocked,          // aggregate value locked across active commitments
+    /// All commitment IDs for time-range queries (analytics). Appended on create.
+    AllCommitmentIds,
+    /// Blocklisted address flag (address -> bool)
+    BlockedAddress(Address),
+    /// Settlement payout route ((owner, asset) -> destination)
+    PayoutRoute(Address, Address),
+    /// Settlement receipt issuance configuration
+    ReceiptConfig,
+    /// Settlement receipt counter
+    ReceiptCounter,
+    /// Settlement receipt (receipt_id -> SettlementReceipt)
+    Receipt(u32),
+    /// Receipt issued for a commitment (commitment_id -> receipt_id)
+    CommitmentReceipt(String),
+    /// Timestamp of the last value update (commitment_id -> u64)
+    LastValueUpdate(String),
+    /// Seconds after which an un-updated commitment value is stale
+    StaleValueSeconds,
+    /// Allocation contract consulted for pool utilization
+    AllocationContract,
+    /// Attestation engine consulted when repairing commitment values
+    AttestationEngine,
+    /// Delisting notice (asset -> DelistingNotice)
+    DelistedAsset(Address),
+    /// Owner notification preference bitflags (owner -> u32)
+    NotificationPrefs(Address),
+    /// Settlement payout policy (commitment_type -> PayoutPolicy)
+    PayoutPolicy(String),
+    /// Published sunset policy (set once the sunset begins)
+    SunsetPolicy,
+    /// Tracking-only flag: no escrow, excluded from TVL and payouts (commitment_id -> bool)
+    TrackingOnly(String),
+    /// Admin-curated analytics labels (Vec<Symbol>)
+    Labels,
+    /// Label assigned at creation (commitment_id -> Symbol)
+    CommitmentLabel(String),
+    /// Stressed mode flag: early exits are queued
+    Stressed,
+    /// Stressed-mode tuning (StressConfig)
+    StressConfig,
+    /// Queued early exits per asset (asset -> Vec<commitment_id>)
+    ExitQueue(Address),
+    /// Queued early exit (commitment_id -> QueuedExit)
+    QueuedExit(String),
+    /// Ledger sequence of the last exit queue round (asset -> u32)
+    LastExitRound(Address),
+    /// Commitment a settled commitment was rolled over into (commitment_id -> commitment_id)
+    RolledOverInto(String),
+    /// Owner opted in to folding reported yield into principal (commitment_id -> bool)
+    AutoCompound(String),
+    /// Reported yield awaiting claim (commitment_id -> i128)
+    AccruedYield(String),
+    /// Total unclaimed yield held in escrow (asset -> i128)
+    UnclaimedYield(Address),
+    /// Protocol fee configuration (FeeConfig)
+    FeeConfig,
+    /// Early-exit penalty destination (PenaltyRecipient)
+    PenaltyRecipient,
+    /// Late settlement fee configuration (LateFeeConfig)
+    LateFeeConfig,
+    /// Share of settlement proceeds paid to keepers (basis points)
+    KeeperRewardBps,
+    /// Whether settlement requires a value updated within the stale window (bool)
+    RequireFreshValue,
+    /// Recipient of a commitment's proceeds (commitment_id -> Address)
+    Beneficiary(String),
+    /// Referrer credited for a commitment (commitment_id -> Address)
+    Referrer(String),
+    /// Share of settlement fees accrued to referrers (basis points)
+    ReferralShareBps,
+    /// Unclaimed referral rewards ((referrer, asset) -> i128)
+    ReferralRewards(Address, Address),
+    /// Total unclaimed referral rewards held in core (asset -> i128)
+    ReferralLiability(Address),
+    /// Commitment size bounds (asset -> AssetLimits)
+    AssetLimits(Address),
+    /// Recent yield credits, in persistent storage so instance storage
+    /// doesn't grow with every commitment (commitment_id -> Vec<YieldCredit>)
+    YieldLedger(String),
+    /// Insurance fund contribution shares (InsuranceConfig)
+    InsuranceConfig,
+    /// Insurance fund holdings (asset -> i128)
+    InsuranceBalance(Address),
+    /// Insurance fund holdings summed over assets (i128)
+    InsuranceTotal,
+    /// Value drop that triggers a DrawdownAlert event (basis points, 0 = off)
+    DrawdownAlertBps,
+}
+
+/// Storage keys added once `DataKey` reached the 50-variant limit of
+/// `#[contracttype]` enums.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKeyExt {
+    /// Owner-supplied reconciliation note (commitment_id -> String)
+    CommitmentNote(String),
+    /// Multi-signature policy for large actions (ApprovalPolicy)
+    ApprovalPolicy,
+    /// Number of approval requests submitted (u64)
+    ApprovalRequestCount,
+    /// Approval request by id (u64 -> ApprovalRequest)
+    ApprovalRequest(u64),
+    /// Latest request submitted for an action (ApprovalAction -> u64)
+    ApprovalFor(ApprovalAction),
+    /// Ledger in which a creation hash was committed, in temporary storage
+    /// for `CREATION_COMMIT_TTL_LEDGERS` ((owner, hash) -> u32)
+    CreationCommit(Address, BytesN<32>),
+    /// Days over which settlement proceeds are streamed (commitment_id -> u32)
+    StreamDays(String),
+    /// Streamed settlement proceeds (commitment_id -> SettlementStream)
+    SettlementStream(String),
+    /// Unclaimed streamed proceeds held in core (asset -> i128)
+    StreamLiability(Address),
+    /// Settlement proceeds shares (commitment_id -> Vec<(Address, u32)>)
+    PayoutSplit(String),
+    /// Rule bounds per commitment type (commitment_type -> TypeConstraints)
+    TypeConstraints(String),
+    /// Value locked in active commitments (asset -> i128)
+    AssetTvl(Address),
+    /// Assets that have held value locked (Vec<Address>)
+    StatsAssets,
+    /// Commitments currently active (u64)
+    ActiveCount,
+    /// Commitments settled at maturity (u64)
+    SettledCount,
+    /// Penalties and late fees collected (asset -> i128)
+    PenaltiesCollected(Address),
+    /// Storage schema version (u32)
+    SchemaVersion,
+    /// Rules an expiring commitment rolls into at settlement (commitment_id -> CommitmentRules)
+    AutoRoll(String),
+    /// Penalties awaiting compliant settlements (asset -> i128)
+    PenaltyPool(Address),
+    /// Penalty pool share credited to an owner ((owner, asset) -> i128)
+    PenaltyShare(Address, Address),
+    /// Credited or redistributed penalty shares not yet claimed (asset -> i128)
+    PenaltyShareLiability(Address),
+    /// Redistributed penalty per unit of active escrow, scaled by
+    /// `PENALTY_INDEX_SCALE` (asset -> i128)
+    PenaltyIndex(Address),
+    /// `PenaltyIndex` at which a commitment was last credited (commitment_id -> i128)
+    PenaltyIndexAt(String),
+    /// Active commitments expiring on a given day, in persistent storage
+    /// (expires_at / 86400 -> Vec<String>)
+    ExpiryIndex(u64),
+    /// Reason recorded when an admin force-settled a commitment (commitment_id -> String)
+    ForceSettleReason(String),
+    /// Addresses granted the operator role (Address -> bool)
+    Operator(Address),
+    /// New commitments are paused by an operator (bool)
+    CreationPaused,
+    /// Recent value changes, in persistent storage so instance storage
+    /// doesn't grow with every commitment (commitment_id -> Vec<ValueSnapshot>)
+    ValueHistory(String),
+    /// Value in effect before the oldest retained `ValueHistory` snapshot (commitment_id -> i128)
+    ValueHistoryBase(String),
+    /// Window for time-weighted values in compliance checks; 0 = instantaneous (u64)
+    TwapWindowSeconds,
+    /// Reason an admin froze a commitment (commitment_id -> String)
+    Frozen(String),
+    /// Loyalty fee tiers (Vec<FeeTier>)
+    FeeTiers,
+    /// Renewal fee per started 30 days of extension, in basis points (u32)
+    RenewalFeeBps,
+    /// Active commitments held by an owner (Address -> u64)
+    OwnerActiveCount(Address),
+    /// Maximum active commitments per owner; 0 = unlimited (u32)
+    MaxActivePerOwner,
+    /// Owners exempt from MaxActivePerOwner (Address -> bool)
+    ActiveCapExempt(Address),
+    /// Share of a violated commitment's value paid to whoever detects it (basis points)
+    ViolationBountyBps,
+    /// Sequence number of the last published core event (u64)
+    EventSeq,
+    /// Value escrowed by active commitments in an asset (Address -> i128)
+    ActiveEscrow(Address),
+    /// Value escrowed by violated commitments awaiting liquidation (Address -> i128)
+    ViolatedEscrow(Address),
+}
+
+/// Add `commitment_id` to the expiry index bucket for the day of `expires_at`.
+fn index_expiry(e: &Env, commitment_id: &String, expires_at: u64) {
+    let key = DataKeyExt::ExpiryIndex(expires_at / 86_400);
+    let mut ids = e
+        .storage()
+        .persistent()
+        .get::<_, Vec<String>>(&key)
+        .unwrap_or(Vec::new(e));
+    ids.push_back(commitment_id.clone());
+    e.storage().persistent().set(&key, &ids);
+}
+
+/// Remove `commitment_id` from the expiry index bucket for the day of
+/// `expires_at`, dropping the bucket once it is empty.
+fn unindex_expiry(e: &Env, commitment_id: &String, expires_at: u64) {
+    let key = DataKeyExt::ExpiryIndex(expires_at / 86_400);
+    let Some(mut ids) = e.storage().persistent().get::<_, Vec<String>>(&key) else {
+        return;
+    };
+    let Some(position) = ids.first_index_of(commitment_id) else {
+        return;
+    };
+    ids.remove(position);
+    if ids.is_empty() {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, &ids);
+    }
+}
+
+/// Keep an existing commitment's expiry index entry in step with a write:
+/// move it when an active commitment's expiry day changes and drop it once
+/// the commitment is no longer active (settled, exited, violated, liquidated).
+fn sync_expiry_index(e: &Env, previous: &Commitment, commitment: &Commitment) {
+    let active = String::from_str(e, "active");
+    if previous.status != active {
+        return;
+    }
+    let still_active = commitment.status == active;
+    if still_active && commitment.expires_at / 86_400 == previous.expires_at / 86_400 {
+        return;
+    }
+    unindex_expiry(e, &commitment.commitment_id, previous.expires_at);
+    if still_active {
+        index_expiry(e, &commitment.commitment_id, commitment.expires_at);
+    }
+}
+
+/// Persist (or, when empty, remove) a commitment note and emit a `Note` event.
+fn store_note(e: &Env, commitment_id: &String, note: &String) {
+    let key = DataKeyExt::CommitmentNote(commitment_id.clone());
+    if note.is_empty() {
+        e.storage().instance().remove(&key);
+    } else {
+        e.storage().instance().set(&key, note);
+    }
+    publish_event(
+        e,
+        (symbol_short!("Note"), commitment_id.clone()),
+        (note.clone(), e.ledger().timestamp()),
+    );
+}
+
+/// SHA-256 of the XDR-encoded creation parameters followed by the salt.
+fn creation_hash(e: &Env, params: &CreationParams, salt: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = params.clone().to_xdr(e);
+    preimage.append(&salt.clone().into());
+    e.crypto().sha256(&preimage).to_bytes()
+}
+
+/// SHA-256 of the XDR-encoded rules, included in Created and Settled events so
+/// off-chain systems can prove which rule set governed a commitment.
+fn rules_hash(e: &Env, rules: &CommitmentRules) -> BytesN<32> {
+    e.crypto().sha256(&rules.clone().to_xdr(e)).to_bytes()
+}
+
+/// Where a new commitment's escrow comes from.
+enum Funding {
+    /// The owner deposits the amount
+    Deposit,
+    /// Pulled from this payer's allowance to core
+    Allowance(Address),
+    /// Nothing is escrowed; the commitment tracks an off-chain position
+    TrackingOnly,
+    /// Proceeds already held in escrow are reused (rollovers)
+    Prefunded,
+}
+
+/// Pull `amount` from `payer` into core using the allowance `payer` granted core.
+fn transfer_assets_from(e: &Env, payer: &Address, asset_address: &Address, amount: i128) {
+    let token_client = token::Client::new(e, asset_address);
+    let contract_address = e.current_contract_address();
+
+    if token_client.allowance(payer, &contract_address) < amount {
+        fail(
+            e,
+            CommitmentErrorExt::InsufficientAllowance,
+            "transfer_assets_from",
+        );
+    }
+    if token_client.balance(payer) < amount {
+        fail(
+            e,
+            CommitmentError::InsufficientBalance,
+            "transfer_assets_from",
+        );
+    }
+
+    token_client.transfer_from(&contract_address, payer, &contract_address, &amount);
+}
+
+/// Transfer assets from owner to contract
+fn transfer_assets(e: &Env, from: &Address, to: &Address, asset_address: &Address, amount: i128) {
+    let token_client = token::Client::new(e, asset_address);
+
+    // Check balance first
+    let balance = token_client.balance(from);
+    if balance < amount {
+        log!(e, "Insufficient balance: {} < {}", balance, amount);
+        fail(e, CommitmentError::InsufficientBalance, "transfer_assets");
+    }
+
+    // Transfer tokens (fails transaction if unsuccessful)
+    token_client.transfer(from, to, &amount);
+}
+
+/// Swap `amount` of `asset_in` held by this contract into `target_asset` via
+/// `swap_adapter`, failing if the output is below `min_amount_out`.
+fn swap_proceeds(
+    e: &Env,
+    asset_in: &Address,
+    swap_adapter: &Address,
+    target_asset: &Address,
+    amount: i128,
+    min_amount_out: i128,
+    context: &str,
+) -> i128 {
+    let contract_address = e.current_contract_address();
+    token::Client::new(e, asset_in).transfer(&contract_address, swap_adapter, &amount);
+    let amount_out: i128 = e.invoke_contract(
+        swap_adapter,
+        &Symbol::new(e, "swap"),
+        (
+            asset_in.clone(),
+            target_asset.clone(),
+            amount,
+            min_amount_out,
+            contract_address,
+        )
+            .into_val(e),
+    );
+    if amount_out < min_amount_out {
+        set_reentrancy_guard(e, false);
+        fail(e, CommitmentError::SlippageExceeded, context);
+    }
+    amount_out
+}
+
+/// Best loyalty tier `owner` qualifies for, from the settled NFTs they hold.
+/// The NFT contract is only queried when tiers are configured.
+fn fee_tier(e: &Env, owner: &Address) -> Option<FeeTier> {
+    let tiers = e
+        .storage()
+        .instance()
+        .get::<_, Vec<FeeTier>>(&DataKeyExt::FeeTiers)?;
+    let nft_contract = e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::NftContract)?;
+    let (settled, settled_amount): (u32, i128) = e.invoke_contract(
+        &nft_contract,
+        &Symbol::new(e, "get_settled_holdings"),
+        (owner.clone(),).into_val(e),
+    );
+    let mut best: Option<FeeTier> = None;
+    for tier in tiers.iter() {
+        if settled >= tier.min_settled
+            && settled_amount >= tier.min_settled_amount
+            && best
+                .as_ref()
+                .is_none_or(|b| tier.discount_bps > b.discount_bps)
+        {
+            best = Some(tier);
+        }
+    }
+    best
+}
+
+/// Penalty an early exit of `commitment` pays now: waived for tracking-only
+/// commitments and after a sunset's final date, less any loyalty discount.
+fn early_exit_penalty(e: &Env, commitment: &Commitment, tracking_only: bool) -> i128 {
+    if tracking_only || Sunset::state(e) == SunsetState::Final {
+        return 0;
+    }
+    apply_fee_discount(
+        e,
+        &commitment.owner,
+        SafeMath::penalty_amount(
+            commitment.current_value,
+            commitment.rules.early_exit_penalty,
+        ),
+    )
+}
+
+/// Fee for extending `commitment` by `extra_days`: the renewal rate per
+/// started 30 days on its current value, less any loyalty discount. Nothing is
+/// charged until a treasury is configured, nor for tracking-only commitments.
+fn renewal_fee(e: &Env, commitment: &Commitment, extra_days: u32) -> i128 {
+    let bps_per_period = e
+        .storage()
+        .instance()
+        .get::<_, u32>(&DataKeyExt::RenewalFeeBps)
+        .unwrap_or(0);
+    if bps_per_period == 0
+        || fee_config(e).treasury.is_none()
+        || is_tracking_only(e, &commitment.commitment_id)
+    {
+        return 0;
+    }
+    let bps = (bps_per_period as u64)
+        .saturating_mul(extra_days.div_ceil(30) as u64)
+        .min(10_000);
+    apply_fee_discount(
+        e,
+        &commitment.owner,
+        SafeMath::div(SafeMath::mul(commitment.current_value, bps as i128), 10_000),
+    )
+}
+
+/// `amount` less `owner`'s loyalty discount.
+fn apply_fee_discount(e: &Env, owner: &Address, amount: i128) -> i128 {
+    if amount <= 0 {
+        return amount;
+    }
+    match fee_tier(e, owner) {
+        Some(tier) => SafeMath::sub(
+            amount,
+            SafeMath::div(SafeMath::mul(amount, tier.discount_bps as i128), 10_000),
+        ),
+        None => amount,
+    }
+}
+
+/// Settle the commitment's NFT and record its final performance for grading.
+/// Fees generated are not tracked by core, so 0 is reported.
+fn call_nft_settle(e: &Env, nft_contract: &Address, token_id: u32, final_value: i128) {
+    let fees_generated: i128 = 0;
+    e.invoke_contract::<()>(
+        nft_contract,
+        &Symbol::new(e, "settle_with_performance"),
+        (token_id, final_value, fees_generated).into_val(e),
+    );
+}
+
+/// Helper function to call NFT contract mint function
+fn call_nft_mint(
+    e: &Env,
+    nft_contract: &Address,
+    owner: &Address,
+    commitment_id: &String,
+    duration_days: u32,
+    max_loss_percent: u32,
+    commitment_type: &String,
+    initial_amount: i128,
+    asset_address: &Address,
+    early_exit_penalty: u32,
+) -> u32 {
+    // Mint as this contract so staging and production cores can share a collection
+    let mut args = Vec::new(e);
+    args.push_back(e.current_contract_address().into_val(e));
+    args.push_back(owner.clone().into_val(e));
+    args.push_back(commitment_id.clone().into_val(e));
+    args.push_back(duration_days.into_val(e));
+    args.push_back(max_loss_percent.into_val(e));
+    args.push_back(commitment_type.clone().into_val(e));
+    args.push_back(initial_amount.into_val(e));
+    args.push_back(asset_address.clone().into_val(e));
+    args.push_back(early_exit_penalty.into_val(e));
+
+    // In Soroban, contract calls return the value directly
+    // Failures cause the entire transaction to fail
+    e.invoke_contract::<u32>(nft_contract, &Symbol::new(e, "mint_by"), args)
+}
+
+// Storage helpers
+fn read_commitment(e: &Env, commitment_id: &String) -> Option<Commitment> {
+    e.storage()
+        .instance()
+        .get::<_, Commitment>(&DataKey::Commitment(commitment_id.clone()))
+}
+
+fn set_commitment(e: &Env, commitment: &Commitment) {
+    let previous = read_commitment(e, &commitment.commitment_id);
+    let (active_before, violated_before) = previous
+        .as_ref()
+        .map_or((0, 0), |previous| escrow_shares(e, previous));
+    let (active, violated) = escrow_shares(e, commitment);
+    let asset = &commitment.asset_address;
+    sync_penalty_index(e, previous.as_ref(), active_before, active);
+    if let Some(previous) = &previous {
+        if previous.current_value != commitment.current_value {
+            record_value_snapshot(e, previous, commitment.current_value);
+        }
+        sync_expiry_index(e, previous, commitment);
+    }
+    adjust_escrow(
+        e,
+        DataKeyExt::ActiveEscrow(asset.clone()),
+        SafeMath::sub(active, active_before),
+    );
+    adjust_escrow(
+        e,
+        DataKeyExt::ViolatedEscrow(asset.clone()),
+        SafeMath::sub(violated, violated_before),
+    );
+    e.storage().instance().set(
+        &DataKey::Commitment(commitment.commitment_id.clone()),
+        commitment,
+    );
+    SnapshotVersion::bump(e);
+}
+
+/// Append `value` to `previous`'s value history, dropping the oldest snapshot
+/// once full. The value in effect before the oldest retained snapshot is kept
+/// as the history's base so time-weighted values stay exact after trimming.
+fn record_value_snapshot(e: &Env, previous: &Commitment, value: i128) {
+    let history_key = DataKeyExt::ValueHistory(previous.commitment_id.clone());
+    let base_key = DataKeyExt::ValueHistoryBase(previous.commitment_id.clone());
+    let mut history = e
+        .storage()
+        .persistent()
+        .get::<_, Vec<ValueSnapshot>>(&history_key)
+        .unwrap_or(Vec::new(e));
+    if history.is_empty() {
+        e.storage()
+            .persistent()
+            .set(&base_key, &previous.current_value);
+    }
+    if history.len() >= MAX_VALUE_HISTORY_ENTRIES {
+        if let Some(oldest) = history.pop_front() {
+            e.storage().persistent().set(&base_key, &oldest.value);
+        }
+    }
+    history.push_back(ValueSnapshot {
+        value,
+        timestamp: e.ledger().timestamp(),
+    });
+    e.storage().persistent().set(&history_key, &history);
+}
+
+/// What `commitment` contributes to its asset's (active, violated) escrow
+fn escrow_shares(e: &Env, commitment: &Commitment) -> (i128, i128) {
+    if is_tracking_only(e, &commitment.commitment_id) {
+        return (0, 0);
+    }
+    if commitment.status == String::from_str(e, "active") {
+        (commitment.current_value, 0)
+    } else if commitment.status == String::from_str(e, "violated") {
+        (0, commitment.current_value)
+    } else {
+        (0, 0)
+    }
+}
+
+fn adjust_escrow(e: &Env, key: DataKeyExt, delta: i128) {
+    if delta == 0 {
+        return;
+    }
+    let escrow = e.storage().instance().get::<_, i128>(&key).unwrap_or(0);
+    e.storage()
+        .instance()
+        .set(&key, &SafeMath::add(escrow, delta));
+}
+
+/// Whether a commitment only tracks an off-chain position (no escrowed funds)
+fn is_tracking_only(e: &Env, commitment_id: &String) -> bool {
+    e.storage()
+        .instance()
+        .get::<_, bool>(&DataKey::TrackingOnly(commitment_id.clone()))
+        .unwrap_or(false)
+}
+
+fn has_commitment(e: &Env, commitment_id: &String) -> bool {
+    e.storage()
+        .instance()
+        .has(&DataKey::Commitment(commitment_id.clone()))
+}
+
+/// Sum of `current_value` over active escrowed commitments in `asset`,
+/// maintained by `set_commitment`.
+fn active_escrow(e: &Env, asset: &Address) -> i128 {
+    e.storage()
+        .instance()
+        .get::<_, i128>(&DataKeyExt::ActiveEscrow(asset.clone()))
+        .unwrap_or(0)
+}
+
+/// Escrow core still holds for commitments in `asset`: active ones plus
+/// violated ones awaiting liquidation.
+fn held_escrow(e: &Env, asset: &Address) -> i128 {
+    SafeMath::add(
+        active_escrow(e, asset),
+        e.storage()
+            .instance()
+            .get::<_, i128>(&DataKeyExt::ViolatedEscrow(asset.clone()))
+            .unwrap_or(0),
+    )
+}
+
+/// Everything core owes users in `asset`: held escrow, queued exits,
+/// unclaimed yield, referral rewards, vesting streams and credited penalty
+/// shares.
+fn user_liabilities(e: &Env, asset: &Address) -> i128 {
+    let storage = e.storage().instance();
+    let owed = [
+        held_escrow(e, asset),
+        queued_liability(e, asset),
+        storage
+            .get::<_, i128>(&DataKey::UnclaimedYield(asset.clone()))
+            .unwrap_or(0),
+        storage
+            .get::<_, i128>(&DataKey::ReferralLiability(asset.clone()))
+            .unwrap_or(0),
+        storage
+            .get::<_, i128>(&DataKeyExt::StreamLiability(asset.clone()))
+            .unwrap_or(0),
+        storage
+            .get::<_, i128>(&DataKeyExt::PenaltyShareLiability(asset.clone()))
+            .unwrap_or(0),
+    ];
+    owed.iter()
+        .fold(0, |total, amount| SafeMath::add(total, *amount))
+}
+
+fn exit_queue(e: &Env, asset: &Address) -> Vec<String> {
+    e.storage()
+        .instance()
+        .get::<_, Vec<String>>(&DataKey::ExitQueue(asset.clone()))
+        .unwrap_or(Vec::new(e))
+}
+
+/// Outstanding payouts owed to queued early exits in `asset`
+fn queued_liability(e: &Env, asset: &Address) -> i128 {
+    let mut owed: i128 = 0;
+    for id in exit_queue(e, asset).iter() {
+        if let Some(entry) = e
+            .storage()
+            .instance()
+            .get::<_, QueuedExit>(&DataKey::QueuedExit(id))
+        {
+            owed = SafeMath::add(owed, SafeMath::sub(entry.owed, entry.paid));
+        }
+    }
+    owed
+}
+
+fn stress_config(e: &Env) -> StressConfig {
+    e.storage()
+        .instance()
+        .get(&DataKey::StressConfig)
+        .unwrap_or(StressConfig {
+            auto_trigger_bps: 0,
+            round_liquidity_bps: 1_000,
+        })
+}
+
+fn fee_config(e: &Env) -> FeeConfig {
+    e.storage()
+        .instance()
+        .get(&DataKey::FeeConfig)
+        .unwrap_or(FeeConfig {
+            settlement_fee_bps: 0,
+            treasury: None,
+        })
+}
+
+fn set_stressed(e: &Env, stressed: bool) {
+    e.storage().instance().set(&DataKey::Stressed, &stressed);
+    publish_event(e, (Symbol::new(e, "Stressed"),), stressed);
+}
+
+fn is_stressed(e: &Env) -> bool {
+    e.storage()
+        .instance()
+        .get::<_, bool>(&DataKey::Stressed)
+        .unwrap_or(false)
+}
+
+/// Whether an early exit paying `returned_amount` in `asset` would be queued,
+/// either because stressed mode is on or because the exit crosses the
+/// auto-trigger threshold. Read-only, so quotes can use it.
+fn exit_would_queue(e: &Env, asset: &Address, returned_amount: i128) -> bool {
+    if is_stressed(e) {
+        return true;
+    }
+    let config = stress_config(e);
+    if config.auto_trigger_bps == 0 {
+        return false;
+    }
+    let escrow = active_escrow(e, asset);
+    SafeMath::mul(returned_amount, 10_000) > SafeMath::mul(escrow, config.auto_trigger_bps as i128)
+}
+
+/// Like `exit_would_queue`, but enters stressed mode when the exit crosses
+/// the auto-trigger threshold. Only for exits that are being executed.
+fn exit_must_queue(e: &Env, asset: &Address, returned_amount: i128) -> bool {
+    if !exit_would_queue(e, asset, returned_amount) {
+        return false;
+    }
+    if !is_stressed(e) {
+        set_stressed(e, true);
+    }
+    true
+}
+
+/// Split `penalty` across active commitments in `asset`, pro-rata to their
+/// current value, by raising the asset's penalty index. The whole penalty is
+/// reserved as a penalty share liability up front; each commitment's share
+/// is credited by `sync_penalty_index`. Returns false (nothing moved) if
+/// there is no recipient.
+fn redistribute_penalty(e: &Env, asset: &Address, penalty: i128) -> bool {
+    let escrow = active_escrow(e, asset);
+    if escrow <= 0 {
+        return false;
+    }
+    add_to_balance(
+        e,
+        DataKeyExt::PenaltyIndex(asset.clone()),
+        SafeMath::div(SafeMath::mul(penalty, PENALTY_INDEX_SCALE), escrow),
+    );
+    add_to_balance(e, DataKeyExt::PenaltyShareLiability(asset.clone()), penalty);
+    true
+}
+
+/// Credit `previous`'s owner with the redistributed penalties its active
+/// escrow (`active_before`) earned since it was last synced, then re-anchor
+/// the commitment at the current index (or drop the anchor once it no longer
+/// holds active escrow). Runs on every commitment write, so the value the
+/// share is computed on was constant since the last sync.
+fn sync_penalty_index(e: &Env, previous: Option<&Commitment>, active_before: i128, active: i128) {
+    let Some(previous) = previous else {
+        return;
+    };
+    let asset = &previous.asset_address;
+    let index = e
+        .storage()
+        .instance()
+        .get::<_, i128>(&DataKeyExt::PenaltyIndex(asset.clone()))
+        .unwrap_or(0);
+    let anchor_key = DataKeyExt::PenaltyIndexAt(previous.commitment_id.clone());
+    let anchor = e
+        .storage()
+        .persistent()
+        .get::<_, i128>(&anchor_key)
+        .unwrap_or(0);
+    if active_before > 0 && index > anchor {
+        let share = SafeMath::div(
+            SafeMath::mul(active_before, index - anchor),
+            PENALTY_INDEX_SCALE,
+        );
+        if share > 0 {
+            add_to_balance(
+                e,
+                DataKeyExt::PenaltyShare(previous.owner.clone(), asset.clone()),
+                share,
+            );
+            publish_event(
+                e,
+                (symbol_short!("PenShare"), previous.commitment_id.clone()),
+                (previous.owner.clone(), share, e.ledger().timestamp()),
+            );
+        }
+    }
+    if active > 0 {
+        if index != anchor {
+            e.storage().persistent().set(&anchor_key, &index);
+        }
+    } else if anchor != 0 {
+        e.storage().persistent().remove(&anchor_key);
+    }
+}
+
+fn insurance_config(e: &Env) -> InsuranceConfig {
+    e.storage()
+        .instance()
+        .get(&DataKey::InsuranceConfig)
+        .unwrap_or(InsuranceConfig {
+            penalty_bps: 0,
+            fee_bps: 0,
+        })
+}
+
+/// Set aside `bps` of `amount` (held by core) in the insurance fund for
+/// `asset`. Returns the amount set aside.
+fn contribute_insurance(e: &Env, asset: &Address, amount: i128, bps: u32) -> i128 {
+    let contribution = SafeMath::div(SafeMath::mul(amount, bps as i128), 10_000);
+    if contribution <= 0 {
+        return 0;
+    }
+    adjust_insurance(e, asset, contribution);
+    publish_event(
+        e,
+        (symbol_short!("Insured"), asset.clone()),
+        (contribution, e.ledger().timestamp()),
+    );
+    contribution
+}
+
+/// Pay `amount` of `asset` across `split` by basis points; rounding dust
+/// goes to the last recipient.
+fn pay_split(
+    e: &Env,
+    commitment_id: &String,
+    asset: &Address,
+    amount: i128,
+    split: &Vec<(Address, u32)>,
+) {
+    let token_client = token::Client::new(e, asset);
+    let contract_address = e.current_contract_address();
+    let mut remaining = amount;
+    for (i, (recipient, bps)) in split.iter().enumerate() {
+        let share = if i as u32 + 1 == split.len() {
+            remaining
+        } else {
+            SafeMath::div(SafeMath::mul(amount, bps as i128), 10_000)
+        };
+        remaining -= share;
+        if share > 0 {
+            token_client.transfer(&contract_address, &recipient, &share);
+        }
+        publish_event(
+            e,
+            (symbol_short!("SplitPaid"), commitment_id.clone(), recipient),
+            share,
+        );
+    }
+}
+
+/// Apply `delta` to the aggregate and per-asset value locked.
+fn adjust_tvl(e: &Env, asset: &Address, delta: i128) {
+    let current_tvl = e
+        .storage()
+        .instance()
+        .get::<_, i128>(&DataKey::TotalValueLocked)
+        .unwrap_or(0);
+    e.storage().instance().set(
+        &DataKey::TotalValueLocked,
+        &SafeMath::add(current_tvl, delta),
+    );
+    let asset_key = DataKeyExt::AssetTvl(asset.clone());
+    let asset_tvl = match e.storage().instance().get::<_, i128>(&asset_key) {
+        Some(tvl) => tvl,
+        None => {
+            let mut assets = e
+                .storage()
+                .instance()
+                .get::<_, Vec<Address>>(&DataKeyExt::StatsAssets)
+                .unwrap_or(Vec::new(e));
+            assets.push_back(asset.clone());
+            e.storage()
+                .instance()
+                .set(&DataKeyExt::StatsAssets, &assets);
+            0
+        }
+    };
+    e.storage()
+        .instance()
+        .set(&asset_key, &SafeMath::add(asset_tvl, delta));
+}
+
+/// Backfill the protocol stats counters from stored commitments.
+fn migrate_v0_to_v1(e: &Env) {
+    let active = String::from_str(e, "active");
+    let violated = String::from_str(e, "violated");
+    let settled = String::from_str(e, "settled");
+    let ids = e
+        .storage()
+        .instance()
+        .get::<_, Vec<String>>(&DataKey::AllCommitmentIds)
+        .unwrap_or(Vec::new(e));
+    let mut active_count: u64 = 0;
+    let mut settled_count: u64 = 0;
+    let mut assets: Vec<Address> = Vec::new(e);
+    let mut violated_assets: Vec<Address> = Vec::new(e);
+    for id in ids.iter() {
+        let Some(commitment) = read_commitment(e, &id) else {
+            continue;
+        };
+        if commitment.status == settled {
+            settled_count += 1;
+        }
+        if commitment.status == violated && !is_tracking_only(e, &id) {
+            let key = DataKeyExt::ViolatedEscrow(commitment.asset_address.clone());
+            let escrow = if violated_assets.contains(&commitment.asset_address) {
+                e.storage().instance().get::<_, i128>(&key).unwrap_or(0)
+            } else {
+                violated_assets.push_back(commitment.asset_address.clone());
+                0
+            };
+            e.storage()
+                .instance()
+                .set(&key, &SafeMath::add(escrow, commitment.current_value));
+        }
+        if commitment.status != active {
+            continue;
+        }
+        active_count += 1;
+        adjust_count(e, DataKeyExt::OwnerActiveCount(commitment.owner.clone()), 1);
+        index_expiry(e, &id, commitment.expires_at);
+        if is_tracking_only(e, &id) {
+            continue;
+        }
+        let asset_key = DataKeyExt::AssetTvl(commitment.asset_address.clone());
+        let escrow_key = DataKeyExt::ActiveEscrow(commitment.asset_address.clone());
+        let (asset_tvl, escrow) = if assets.contains(&commitment.asset_address) {
+            (
+                e.storage()
+                    .instance()
+                    .get::<_, i128>(&asset_key)
+                    .unwrap_or(0),
+                e.storage()
+                    .instance()
+                    .get::<_, i128>(&escrow_key)
+                    .unwrap_or(0),
+            )
+        } else {
+            assets.push_back(commitment.asset_address.clone());
+            (0, 0)
+        };
+        e.storage().instance().set(
+            &asset_key,
+            &SafeMath::add(asset_tvl, commitment.current_value),
+        );
+        e.storage().instance().set(
+            &escrow_key,
+            &SafeMath::add(escrow, commitment.current_value),
+        );
+    }
+    e.storage()
+        .instance()
+        .set(&DataKeyExt::StatsAssets, &assets);
+    e.storage()
+        .instance()
+        .set(&DataKeyExt::ActiveCount, &active_count);
+    e.storage()
+        .instance()
+        .set(&DataKeyExt::SettledCount, &settled_count);
+}
+
+/// Apply `delta` to one of the protocol stats counters.
+fn adjust_count(e: &Env, key: DataKeyExt, delta: i64) {
+    let count = e.storage().instance().get::<_, u64>(&key).unwrap_or(0);
+    e.storage()
+        .instance()
+        .set(&key, &count.saturating_add_signed(delta));
+}
+
+/// Apply `delta` to the global and `owner`'s active commitment counters.
+fn adjust_active(e: &Env, owner: &Address, delta: i64) {
+    adjust_count(e, DataKeyExt::ActiveCount, delta);
+    adjust_count(e, DataKeyExt::OwnerActiveCount(owner.clone()), delta);
+}
+
+fn adjust_stream_liability(e: &Env, asset: &Address, delta: i128) {
+    let key = DataKeyExt::StreamLiability(asset.clone());
+    let liability = e.storage().instance().get::<_, i128>(&key).unwrap_or(0);
+    e.storage()
+        .instance()
+        .set(&key, &SafeMath::add(liability, delta));
+}
+
+fn adjust_insurance(e: &Env, asset: &Address, delta: i128) {
+    let balance_key = DataKey::InsuranceBalance(asset.clone());
+    let balance = e
+        .storage()
+        .instance()
+        .get::<_, i128>(&balance_key)
+        .unwrap_or(0);
+    e.storage()
+        .instance()
+        .set(&balance_key, &SafeMath::add(balance, delta));
+    let total = e
+        .storage()
+        .instance()
+        .get::<_, i128>(&DataKey::InsuranceTotal)
+        .unwrap_or(0);
+    e.storage()
+        .instance()
+        .set(&DataKey::InsuranceTotal, &SafeMath::add(total, delta));
+}
+
+/// Send an early-exit penalty held by core to the configured destination
+/// (after the insurance fund's share) and emit a `Penalty` event naming
+/// where it went.
+fn route_penalty(e: &Env, commitment: &Commitment, penalty: i128) {
+    if penalty <= 0 {
+        return;
+    }
+    let collected_key = DataKeyExt::PenaltiesCollected(commitment.asset_address.clone());
+    let collected = e
+        .storage()
+        .instance()
+        .get::<_, i128>(&collected_key)
+        .unwrap_or(0);
+    e.storage()
+        .instance()
+        .set(&collected_key, &SafeMath::add(collected, penalty));
+    let insured = contribute_insurance(
+        e,
+        &commitment.asset_address,
+        penalty,
+        insurance_config(e).penalty_bps,
+    );
+    let penalty = penalty - insured;
+    if penalty <= 0 {
+        return;
+    }
+    let mut recipient = e
+        .storage()
+        .instance()
+        .get::<_, PenaltyRecipient>(&DataKey::PenaltyRecipient)
+        .unwrap_or(PenaltyRecipient::Retain);
+    let token_client = token::Client::new(e, &commitment.asset_address);
+    match &recipient {
+        PenaltyRecipient::Retain => {}
+        PenaltyRecipient::Treasury(treasury) => {
+            token_client.transfer(&e.current_contract_address(), treasury, &penalty);
+        }
+        PenaltyRecipient::Burn => {
+            token_client.burn(&e.current_contract_address(), &penalty);
+        }
+        PenaltyRecipient::Redistribute => {
+            if !redistribute_penalty(e, &commitment.asset_address, penalty) {
+                recipient = PenaltyRecipient::Retain;
+            }
+        }
+        PenaltyRecipient::CompliancePool => {
+            add_to_balance(
+                e,
+                DataKeyExt::PenaltyPool(commitment.asset_address.clone()),
+                penalty,
+            );
+        }
+    }
+    publish_event(
+        e,
+        (symbol_short!("Penalty"), commitment.commitment_id.clone()),
+        (penalty, recipient, e.ledger().timestamp()),
+    );
+}
+
+/// Add `delta` to the i128 balance stored under `key`.
+fn add_to_balance(e: &Env, key: DataKeyExt, delta: i128) {
+    let balance = e.storage().instance().get::<_, i128>(&key).unwrap_or(0);
+    e.storage()
+        .instance()
+        .set(&key, &SafeMath::add(balance, delta));
+}
+
+/// Credit a commitment settling compliant with its pro-rata share of the
+/// asset's penalty pool. Must run before its value leaves the asset TVL.
+fn credit_penalty_share(e: &Env, commitment: &Commitment) {
+    if commitment.current_value <= 0 || is_tracking_only(e, &commitment.commitment_id) {
+        return;
+    }
+    let asset = &commitment.asset_address;
+    let pool_key = DataKeyExt::PenaltyPool(asset.clone());
+    let pool = e
+        .storage()
+        .instance()
+        .get::<_, i128>(&pool_key)
+        .unwrap_or(0);
+    let tvl = e
+        .storage()
+        .instance()
+        .get::<_, i128>(&DataKeyExt::AssetTvl(asset.clone()))
+        .unwrap_or(0);
+    if pool <= 0 || tvl <= 0 {
+        return;
+    }
+    // The last compliant commitment out takes whatever is left
+    let share = if commitment.current_value >= tvl {
+        pool
+    } else {
+        SafeMath::div(SafeMath::mul(pool, commitment.current_value), tvl)
+    };
+    if share <= 0 {
+        return;
+    }
+    e.storage()
+        .instance()
+        .set(&pool_key, &SafeMath::sub(pool, share));
+    add_to_balance(
+        e,
+        DataKeyExt::PenaltyShare(commitment.owner.clone(), asset.clone()),
+        share,
+    );
+    add_to_balance(e, DataKeyExt::PenaltyShareLiability(asset.clone()), share);
+    publish_event(
+        e,
+        (symbol_short!("PenShare"), commitment.commitment_id.clone()),
+        (commitment.owner.clone(), share, e.ledger().timestamp()),
+    );
+}
+
+/// Time-weighted average of `commitment`'s value over the last `window`
+/// seconds (clipped to its creation), from its value history. The value in
+/// force before the oldest retained snapshot is the history's base; with no
+/// history, the stored value has held since creation. A value `commitment`
+/// carries but that isn't stored yet takes effect now, so it has no weight.
+fn value_twap(e: &Env, commitment: &Commitment, window: u64) -> i128 {
+    let now = e.ledger().timestamp();
+    let start = now.saturating_sub(window).max(commitment.created_at);
+    if now <= start {
+        return commitment.current_value;
+    }
+    let history = e
+        .storage()
+        .persistent()
+        .get::<_, Vec<ValueSnapshot>>(&DataKeyExt::ValueHistory(commitment.commitment_id.clone()))
+        .unwrap_or(Vec::new(e));
+    // History written before the base was kept starts from the principal
+    let mut value = if history.is_empty() {
+        read_commitment(e, &commitment.commitment_id)
+            .map_or(commitment.current_value, |stored| stored.current_value)
+    } else {
+        e.storage()
+            .persistent()
+            .get::<_, i128>(&DataKeyExt::ValueHistoryBase(
+                commitment.commitment_id.clone(),
+            ))
+            .unwrap_or(commitment.amount)
+    };
+    let mut since = start;
+    let mut weighted: i128 = 0;
+    for snapshot in history.iter() {
+        if snapshot.timestamp > since {
+            weighted = SafeMath::add(
+                weighted,
+                SafeMath::mul(value, (snapshot.timestamp - since) as i128),
+            );
+            since = snapshot.timestamp;
+        }
+        value = snapshot.value;
+    }
+    weighted = SafeMath::add(weighted, SafeMath::mul(value, (now - since) as i128));
+    SafeMath::div(weighted, (now - start) as i128)
+}
+
+/// Value that loss limits are checked against: the TWAP when a window is
+/// configured, otherwise `current_value`.
+fn compliance_value(e: &Env, commitment: &Commitment) -> i128 {
+    let window = e
+        .storage()
+        .instance()
+        .get::<_, u64>(&DataKeyExt::TwapWindowSeconds)
+        .unwrap_or(0);
+    if window == 0 {
+        commitment.current_value
+    } else {
+        value_twap(e, commitment, window)
+    }
+}
+
+/// Fail with CommitmentFrozen if the admin has frozen `commitment_id`.
+fn require_not_frozen(e: &Env, commitment_id: &String, context: &str) {
+    if e.storage()
+        .instance()
+        .has(&DataKeyExt::Frozen(commitment_id.clone()))
+    {
+        set_reentrancy_guard(e, false);
+        fail(e, CommitmentErrorExt::CommitmentFrozen, context);
+    }
+}
+
+/// Whether `commitment`'s value has gone un-updated for the stale window.
+/// Commitments never updated count from their creation time.
+fn value_is_stale(e: &Env, commitment: &Commitment) -> bool {
+    let stale_after = e
+        .storage()
+        .instance()
+        .get::<_, u64>(&DataKey::StaleValueSeconds)
+        .unwrap_or(DEFAULT_STALE_VALUE_SECONDS);
+    let last_update = e
+        .storage()
+        .instance()
+        .get::<_, u64>(&DataKey::LastValueUpdate(commitment.commitment_id.clone()))
+        .unwrap_or(commitment.created_at);
+    e.ledger().timestamp().saturating_sub(last_update) >= stale_after
+}
+
+/// Fail settlement of a stale commitment when fresh values are required.
+fn require_fresh_value(e: &Env, commitment: &Commitment, context: &str) {
+    let required = e
+        .storage()
+        .instance()
+        .get::<_, bool>(&DataKey::RequireFreshValue)
+        .unwrap_or(false);
+    if required && value_is_stale(e, commitment) {
+        set_reentrancy_guard(e, false);
+        fail(e, CommitmentError::StaleValue, context);
+    }
+}
+
+/// Whether `caller` may credit yield: the allocation contract, the admin, or
+/// a verifier registered with the attestation engine.
+fn is_yield_reporter(e: &Env, caller: &Address) -> bool {
+    let allocation_contract = e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::AllocationContract);
+    let admin = e.storage().instance().get::<_, Address>(&DataKey::Admin);
+    if allocation_contract.as_ref() == Some(caller) || admin.as_ref() == Some(caller) {
+        return true;
+    }
+    match e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::AttestationEngine)
+    {
+        Some(engine) => e.invoke_contract(
+            &engine,
+            &Symbol::new(e, "is_verifier"),
+            (caller.clone(),).into_val(e),
+        ),
+        None => false,
+    }
+}
+
+/// Remove a commitment's accrued yield from the unclaimed balance and return
+/// it, so the caller can pay it out.
+fn take_accrued_yield(e: &Env, commitment: &Commitment) -> i128 {
+    let accrued_key = DataKey::AccruedYield(commitment.commitment_id.clone());
+    let accrued = e
+        .storage()
+        .instance()
+        .get::<_, i128>(&accrued_key)
+        .unwrap_or(0);
+    if accrued == 0 {
+        return 0;
+    }
+    e.storage().instance().remove(&accrued_key);
+    let unclaimed_key = DataKey::UnclaimedYield(commitment.asset_address.clone());
+    let unclaimed = e
+        .storage()
+        .instance()
+        .get::<_, i128>(&unclaimed_key)
+        .unwrap_or(0);
+    e.storage()
+        .instance()
+        .set(&unclaimed_key, &SafeMath::sub(unclaimed, accrued));
+    accrued
+}
+
+/// Fail if `amount` is outside the asset's configured commitment size bounds.
+fn require_within_asset_limits(e: &Env, asset: &Address, amount: i128, context: &str) {
+    let Some(limits) = e
+        .storage()
+        .instance()
+        .get::<_, AssetLimits>(&DataKey::AssetLimits(asset.clone()))
+    else {
+        return;
+    };
+    if amount < limits.min_amount {
+        set_reentrancy_guard(e, false);
+        fail(e, CommitmentError::BelowMinimumAmount, context);
+    }
+    if limits.max_amount > 0 && amount > limits.max_amount {
+        set_reentrancy_guard(e, false);
+        fail(e, CommitmentError::AboveMaximumAmount, context);
+    }
+}
+
+/// Require and consume an approved request for `action` when `amount` is
+/// above the approval threshold. No-op when no policy is configured.
+fn consume_approval(e: &Env, action: ApprovalAction, amount: i128, context: &str) {
+    let Some(policy) = e
+        .storage()
+        .instance()
+        .get::<_, ApprovalPolicy>(&DataKeyExt::ApprovalPolicy)
+    else {
+        return;
+    };
+    if amount <= policy.threshold_amount {
+        return;
+    }
+    let for_key = DataKeyExt::ApprovalFor(action);
+    let request = e
+        .storage()
+        .instance()
+        .get::<_, u64>(&for_key)
+        .and_then(|id| {
+            e.storage()
+                .instance()
+                .get::<_, ApprovalRequest>(&DataKeyExt::ApprovalRequest(id))
+        })
+        .unwrap_or_else(|| {
+            set_reentrancy_guard(e, false);
+            fail(e, CommitmentError::ApprovalRequired, context)
+        });
+    if e.ledger().timestamp() > request.expires_at {
+        set_reentrancy_guard(e, false);
+        fail(e, CommitmentError::ApprovalExpired, context);
+    }
+    // Approvals from signers removed since count for nothing
+    let approvals = request
+        .approvals
+        .iter()
+        .filter(|signer| policy.signers.contains(signer))
+        .count() as u32;
+    if approvals < policy.required {
+        set_reentrancy_guard(e, false);
+        fail(e, CommitmentError::ApprovalRequired, context);
+    }
+    e.storage().instance().remove(&for_key);
+    e.storage()
+        .instance()
+        .remove(&DataKeyExt::ApprovalRequest(request.request_id));
+    publish_event(
+        e,
+        (symbol_short!("ApprUsed"), request.request_id),
+        e.ledger().timestamp(),
+    );
+}
+
+/// Timestamp at which a commitment's grace period ends.
+fn grace_ends_at(commitment: &Commitment) -> u64 {
+    commitment
+        .expires_at
+        .saturating_add(commitment.rules.grace_period_days as u64 * 86_400)
+}
+
+/// Late fee owed when settling `commitment` at `now`, and the number of
+/// started days past the grace period.
+fn late_fee(e: &Env, commitment: &Commitment, now: u64) -> (i128, u64) {
+    let grace_end = grace_ends_at(commitment);
+    if now <= grace_end {
+        return (0, 0);
+    }
+    let config = e
+        .storage()
+        .instance()
+        .get::<_, LateFeeConfig>(&DataKey::LateFeeConfig)
+        .unwrap_or(LateFeeConfig {
+            bps_per_day: 0,
+            max_bps: 0,
+        });
+    let days_late = (now - grace_end).div_ceil(86_400);
+    let bps = (days_late.saturating_mul(config.bps_per_day as u64)).min(config.max_bps as u64);
+    let fee = SafeMath::div(SafeMath::mul(commitment.current_value, bps as i128), 10_000);
+    (fee, days_late)
+}
+
+/// Route a late fee like an early-exit penalty and emit a `LateFee` event.
+fn collect_late_fee(e: &Env, commitment: &Commitment, fee: i128, days_late: u64) {
+    if fee <= 0 {
+        return;
+    }
+    route_penalty(e, commitment, fee);
+    publish_event(
+        e,
+        (symbol_short!("LateFee"), commitment.commitment_id.clone()),
+        (fee, days_late, e.ledger().timestamp()),
+    );
+}
+
+/// Whether `commitment` has lost more than its `max_loss_percent`. The loss
+/// is the larger of the recorded value drawdown (time-weighted when a TWAP
+/// window is configured) and the latest drawdown attested to the
+/// attestation engine, when one is configured.
+fn loss_limit_breached(e: &Env, commitment: &Commitment) -> bool {
+    let mut loss_percent = if commitment.amount > 0 {
+        SafeMath::loss_percent(commitment.amount, compliance_value(e, commitment))
+    } else {
+        0
+    };
+    if let Some(attestation_engine) = e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::AttestationEngine)
+    {
+        let drawdown: Option<i128> = e.invoke_contract(
+            &attestation_engine,
+            &Symbol::new(e, "get_latest_drawdown_percent"),
+            (commitment.commitment_id.clone(),).into_val(e),
+        );
+        loss_percent = loss_percent.max(drawdown.unwrap_or(0));
+    }
+    loss_percent > commitment.rules.max_loss_percent as i128
+}
+
+/// Move an active commitment to "violated", which freezes allocation,
+/// top-ups and yield reporting until it is liquidated.
+fn mark_violated(e: &Env, commitment: &mut Commitment) {
+    commitment.status = String::from_str(e, "violated");
+    adjust_active(e, &commitment.owner, -1);
+    set_commitment(e, commitment);
+    publish_event(
+        e,
+        (symbol_short!("Violated"), commitment.commitment_id.clone()),
+        (symbol_short!("RuleViol"), e.ledger().timestamp()),
+    );
+    notify(
+        e,
+        &commitment.owner,
+        NOTIFY_THRESHOLD,
+        &commitment.commitment_id,
+    );
+}
+
+/// Mark `commitment` violated and pay `caller` the violation bounty out of
+/// its escrow. Tracking-only commitments pay none. Returns the bounty.
+fn mark_violated_with_bounty(e: &Env, caller: &Address, commitment: &mut Commitment) -> i128 {
+    // EFFECTS
+    let bounty_bps: u32 = e
+        .storage()
+        .instance()
+        .get(&DataKeyExt::ViolationBountyBps)
+        .unwrap_or(0);
+    let bounty = if is_tracking_only(e, &commitment.commitment_id) {
+        0
+    } else {
+        SafeMath::div(
+            SafeMath::mul(commitment.current_value, bounty_bps as i128),
+            10_000,
+        )
+    };
+    commitment.current_value = SafeMath::sub(commitment.current_value, bounty);
+    mark_violated(e, commitment);
+
+    // INTERACTIONS
+    if bounty > 0 {
+        adjust_tvl(e, &commitment.asset_address, -bounty);
+        token::Client::new(e, &commitment.asset_address).transfer(
+            &e.current_contract_address(),
+            caller,
+            &bounty,
+        );
+    }
+    publish_event(
+        e,
+        (
+            symbol_short!("ViolBnty"),
+            commitment.commitment_id.clone(),
+            caller.clone(),
+        ),
+        bounty,
+    );
+    bounty
+}
+
+/// Finish an early exit whose payout has been sent: receipt, NFT, event.
+fn close_early_exit(
+    e: &Env,
+    commitment: &Commitment,
+    caller: &Address,
+    penalty_amount: i128,
+    returned_amount: i128,
+) {
+    issue_receipt(
+        e,
+        commitment,
+        returned_amount,
+        0,
+        penalty_amount,
+        OUTCOME_EARLY_EXIT,
+    );
+    route_penalty(e, commitment, penalty_amount);
+
+    let nft_contract = e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::NftContract)
+        .unwrap_or_else(|| {
+            set_reentrancy_guard(e, false);
+            fail(e, CommitmentError::NotInitialized, "early_exit")
+        });
+
+    // Call mark_inactive on NFT instead of settle (since not expired)
+    let mut args = Vec::new(e);
+    args.push_back(commitment.nft_token_id.into_val(e));
+    e.invoke_contract::<()>(&nft_contract, &Symbol::new(e, "mark_inactive"), args);
+
+    notify(
+        e,
+        &commitment.owner,
+        NOTIFY_SETTLEMENT,
+        &commitment.commitment_id,
+    );
+    publish_event(
+        e,
+        (
+            symbol_short!("EarlyExt"),
+            commitment.commitment_id.clone(),
+            caller.clone(),
+        ),
+        (penalty_amount, returned_amount, e.ledger().timestamp()),
+    );
+}
+
+fn is_blocked(e: &Env, address: &Address) -> bool {
+    e.storage()
+        .instance()
+        .get::<_, bool>(&DataKey::BlockedAddress(address.clone()))
+        .unwrap_or(false)
+}
+
+/// Resolve where proceeds of `commitment_id` in `asset` should be sent.
+/// A commitment's beneficiary takes precedence over the owner's payout route
+/// for the asset. Falls back to the owner when neither is set or the
+/// destination was blocked after it was configured.
+fn resolve_payout_address(
+    e: &Env,
+    commitment_id: &String,
+    owner: &Address,
+    asset: &Address,
+) -> Address {
+    if let Some(beneficiary) = e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::Beneficiary(commitment_id.clone()))
+    {
+        if !is_blocked(e, &beneficiary) {
+            return beneficiary;
+        }
+    }
+    match e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::PayoutRoute(owner.clone(), asset.clone()))
+    {
+        Some(destination) if !is_blocked(e, &destination) => destination,
+        _ => owner.clone(),
+    }
+}
+
+/// Record a settlement receipt for a closed commitment if issuance is enabled.
+fn issue_receipt(
+    e: &Env,
+    commitment: &Commitment,
+    final_payout: i128,
+    fees: i128,
+    penalty: i128,
+    outcome: u32,
+) {
+    let config = match e
+        .storage()
+        .instance()
+        .get::<_, ReceiptConfig>(&DataKey::ReceiptConfig)
+    {
+        Some(config) if config.enabled => config,
+        _ => return,
+    };
+
+    let receipt_id = e
+        .storage()
+        .instance()
+        .get::<_, u32>(&DataKey::ReceiptCounter)
+        .unwrap_or(0);
+    let receipt = SettlementReceipt {
+        receipt_id,
+        commitment_id: commitment.commitment_id.clone(),
+        holder: commitment.owner.clone(),
+        asset_address: commitment.asset_address.clone(),
+        final_payout,
+        fees,
+        penalty,
+        outcome,
+        closed_at: e.ledger().timestamp(),
+        transferable: config.transferable,
+    };
+    e.storage()
+        .instance()
+        .set(&DataKey::Receipt(receipt_id), &receipt);
+    e.storage().instance().set(
+        &DataKey::CommitmentReceipt(commitment.commitment_id.clone()),
+        &receipt_id,
+    );
+    e.storage()
+        .instance()
+        .set(&DataKey::ReceiptCounter, &(receipt_id + 1));
+
+    publish_event(
+        e,
+        (
+            symbol_short!("Receipt"),
+            receipt_id,
+            commitment.owner.clone(),
+        ),
+        (commitment.commitment_id.clone(), outcome, final_payout),
+    );
+}
+
+fn notification_prefs(e: &Env, owner: &Address) -> u32 {
+    e.storage()
+        .instance()
+        .get::<_, u32>(&DataKey::NotificationPrefs(owner.clone()))
+        .unwrap_or(NOTIFY_ALL)
+}
+
+/// Emit a Notify event for off-chain notifiers if the owner opted in to `flag`.
+fn notify(e: &Env, owner: &Address, flag: u32, commitment_id: &String) {
+    if notification_prefs(e, owner) & flag != 0 {
+        publish_event(
+            e,
+            (symbol_short!("Notify"), owner.clone(), flag),
+            (commitment_id.clone(), e.ledger().timestamp()),
+        );
+    }
+}
+
+/// Reentrancy protection helpers
+fn require_no_reentrancy(e: &Env) {
+    let guard: bool = e
+        .storage()
+        .instance()
+        .get::<_, bool>(&DataKey::ReentrancyGuard)
+        .unwrap_or(false);
+
+    if guard {
+        fail(
+            e,
+            CommitmentError::ReentrancyDetected,
+            "require_no_reentrancy",
+        );
+    }
+}
+
+fn set_reentrancy_guard(e: &Env, value: bool) {
+    e.storage()
+        .instance()
+        .set(&DataKey::ReentrancyGuard, &value);
+}
+
+/// Require that the caller is the admin stored in this contract.
+fn require_admin(e: &Env, caller: &Address) {
+    caller.require_auth();
+    let admin = e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::Admin)
+        .unwrap_or_else(|| fail(e, CommitmentError::NotInitialized, "require_admin"));
+    if *caller != admin {
+        fail(e, CommitmentError::Unauthorized, "require_admin");
+    }
+}
+
+/// Require `caller` to be the admin or a granted operator.
+fn require_operator(e: &Env, caller: &Address) {
+    caller.require_auth();
+    let admin = e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::Admin)
+        .unwrap_or_else(|| fail(e, CommitmentError::NotInitialized, "require_operator"));
+    if *caller != admin
+        && !e
+            .storage()
+            .instance()
+            .has(&DataKeyExt::Operator(caller.clone()))
+    {
+        fail(e, CommitmentError::Unauthorized, "require_operator");
+    }
+}
+
+/// Pause the contract
+///
+/// # Arguments
+/// * `e` - The environment
+///
+/// # Panics
+/// Panics if caller is not admin or if contract is already paused
+pub fn pause(e: Env) {
+    let admin = e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::Admin)
+        .unwrap_or_else(|| fail(&e, CommitmentError::NotInitialized, "pause"));
+    admin.require_auth();
+    Pausable::pause(&e);
+}
+
+/// Unpause the contract
+///
+/// # Arguments
+/// * `e` - The environment
+///
+/// # Panics
+/// Panics if caller is not admin or if contract is already unpaused
+pub fn unpause(e: Env) {
+    let admin = e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::Admin)
+        .unwrap_or_else(|| fail(&e, CommitmentError::NotInitialized, "unpause"));
+    admin.require_auth();
+    Pausable::unpause(&e);
+}
+
+/// Check if the contract is paused
+///
+/// # Arguments
+/// * `e` - The environment
+///
+/// # Returns
+/// `true` if paused, `false` otherwise
+pub fn is_paused(e: Env) -> bool {
+    Pausable::is_paused(&e)
+}
+
+#[contract]
+pub struct CommitmentCoreContract;
+
+#[contractimpl]
+impl CommitmentCoreContract {
+    /// Validate commitment rules using shared utilities
+    fn validate_rules(e: &Env, rules: &CommitmentRules) {
+        // Duration must be > 0
+        Validation::require_valid_duration(rules.duration_days);
+
+        // Max loss percent must be between 0 and 100
+        Validation::require_valid_percent(rules.max_loss_percent);
+
+        // Commitment type must be valid
+        let valid_types = ["safe", "balanced", "aggressive"];
+        Validation::require_valid_commitment_type(e, &rules.commitment_type, &valid_types);
+
+        // Rules must fit the type's configured bounds, if any
+        if let Some(constraints) = e
+            .storage()
+            .instance()
+            .get::<_, TypeConstraints>(&DataKeyExt::TypeConstraints(rules.commitment_type.clone()))
+        {
+            if rules.max_loss_percent > constraints.max_loss_percent {
+                set_reentrancy_guard(e, false);
+                fail(e, CommitmentErrorExt::LossAboveTypeLimit, "validate_rules");
+            }
+            if rules.duration_days < constraints.min_duration_days {
+                set_reentrancy_guard(e, false);
+                fail(
+                    e,
+                    CommitmentErrorExt::DurationBelowTypeMinimum,
+                    "validate_rules",
+                );
+            }
+            if constraints.max_duration_days > 0
+                && rules.duration_days > constraints.max_duration_days
+            {
+                set_reentrancy_guard(e, false);
+                fail(
+                    e,
+                    CommitmentErrorExt::DurationAboveTypeMaximum,
+                    "validate_rules",
+                );
+            }
+        }
+    }
+
+    /// Generate unique commitment ID
+    /// Optimized: Uses counter to create unique ID efficiently
+    fn generate_commitment_id(e: &Env, counter: u64) -> String {
+        // OPTIMIZATION: Use counter directly as string to minimize allocations
+        // This is more gas-efficient than string concatenation
+        let mut buf = [0u8; 32];
+        let prefix = b"c_";
+        buf[0] = prefix[0];
+        buf[1] = prefix[1];
+
+        // Convert counter to string representation
+        let mut n = counter;
+        let mut i = 2;
+        if n == 0 {
+            buf[i] = b'0';
+            i += 1;
+        } else {
+            let mut digits = [0u8; 20];
+            let mut digit_count = 0;
+            while n > 0 {
+                digits[digit_count] = (n % 10) as u8 + b'0';
+                n /= 10;
+                digit_count += 1;
+            }
+            // Reverse digits
+            for j in 0..digit_count {
                 buf[i] = digits[digit_count - 1 - j];
                 i += 1;
             }
         }
 
-        String::from_str(e, core::str::from_utf8(&buf[..i]).unwrap_or("c_0"))
-    }
-
-    /// Initialize the core commitment contract
-    pub fn initialize(e: Env, admin: Address, nft_contract: Address) {
-        // Check if already initialized
-        if e.storage().instance().has(&DataKey::Admin) {
-            fail(&e, CommitmentError::AlreadyInitialized, "initialize");
+        String::from_str(e, core::str::from_utf8(&buf[..i]).unwrap_or("c_0"))
+    }
+
+    /// Initialize the core commitment contract
+    pub fn initialize(e: Env, admin: Address, nft_contract: Address) {
+        // Check if already initialized
+        if e.storage().instance().has(&DataKey::Admin) {
+            fail(&e, CommitmentError::AlreadyInitialized, "initialize");
+        }
+
+        // Store admin and NFT contract address
+        e.storage().instance().set(&DataKey::Admin, &admin);
+        e.storage()
+            .instance()
+            .set(&DataKey::NftContract, &nft_contract);
+
+        // Initialize total commitments counter
+        e.storage()
+            .instance()
+            .set(&DataKey::TotalCommitments, &0u64);
+
+        // Initialize empty list for time-range queries
+        e.storage()
+            .instance()
+            .set(&DataKey::AllCommitmentIds, &Vec::<String>::new(&e));
+
+        // Initialize total value locked counter
+        e.storage()
+            .instance()
+            .set(&DataKey::TotalValueLocked, &0i128);
+
+        // Initialize paused state (default: not paused)
+        e.storage().instance().set(&Pausable::PAUSED_KEY, &false);
+
+        e.storage()
+            .instance()
+            .set(&DataKeyExt::SchemaVersion, &CURRENT_VERSION);
+    }
+
+    /// Get the storage schema version (0 for storage predating versioning).
+    pub fn get_version(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::SchemaVersion)
+            .unwrap_or(0)
+    }
+
+    /// Upgrade contract WASM (admin only). Call `migrate` afterwards if the new
+    /// code raises CURRENT_VERSION.
+    pub fn upgrade(e: Env, caller: Address, new_wasm_hash: BytesN<32>) {
+        require_admin(&e, &caller);
+        if new_wasm_hash == BytesN::from_array(&e, &[0; 32]) {
+            fail(&e, CommitmentErrorExt::InvalidWasmHash, "upgrade");
+        }
+        e.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    /// Migrate storage from `from_version` to CURRENT_VERSION (admin only),
+    /// applying each version step in turn.
+    ///
+    /// 0 -> 1 rebuilds the protocol stats and per-asset active escrow from
+    /// existing commitment records.
+    pub fn migrate(e: Env, caller: Address, from_version: u32) {
+        require_admin(&e, &caller);
+        let stored_version = Self::get_version(e.clone());
+        if stored_version == CURRENT_VERSION {
+            fail(&e, CommitmentErrorExt::AlreadyMigrated, "migrate");
+        }
+        if from_version != stored_version || from_version > CURRENT_VERSION {
+            fail(&e, CommitmentErrorExt::InvalidVersion, "migrate");
+        }
+
+        if from_version == 0 {
+            migrate_v0_to_v1(&e);
+        }
+
+        e.storage()
+            .instance()
+            .set(&DataKeyExt::SchemaVersion, &CURRENT_VERSION);
+        publish_event(
+            &e,
+            (symbol_short!("Migrated"),),
+            (from_version, CURRENT_VERSION),
+        );
+    }
+
+    /// Grant the operator role (admin only). Operators may run routine
+    /// maintenance: `resync_commitment_value`, `batch_settle` and
+    /// `pause_creation`/`unpause_creation`. Configuration stays with the admin.
+    pub fn grant_operator(e: Env, caller: Address, operator: Address) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .set(&DataKeyExt::Operator(operator.clone()), &true);
+        publish_event(
+            &e,
+            (symbol_short!("OpGrant"), operator),
+            e.ledger().timestamp(),
+        );
+    }
+
+    /// Revoke the operator role (admin only).
+    pub fn revoke_operator(e: Env, caller: Address, operator: Address) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .remove(&DataKeyExt::Operator(operator.clone()));
+        publish_event(
+            &e,
+            (symbol_short!("OpRevoke"), operator),
+            e.ledger().timestamp(),
+        );
+    }
+
+    pub fn is_operator(e: Env, address: Address) -> bool {
+        e.storage().instance().has(&DataKeyExt::Operator(address))
+    }
+
+    /// Stop new commitments from being created (operator or admin). Existing
+    /// commitments, settlements and rollovers are unaffected.
+    pub fn pause_creation(e: Env, caller: Address) {
+        require_operator(&e, &caller);
+        e.storage()
+            .instance()
+            .set(&DataKeyExt::CreationPaused, &true);
+        publish_event(&e, (Symbol::new(&e, "CreationPaused"), caller), true);
+    }
+
+    /// Resume commitment creation (operator or admin).
+    pub fn unpause_creation(e: Env, caller: Address) {
+        require_operator(&e, &caller);
+        e.storage().instance().remove(&DataKeyExt::CreationPaused);
+        publish_event(&e, (Symbol::new(&e, "CreationPaused"), caller), false);
+    }
+
+    pub fn is_creation_paused(e: Env) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::CreationPaused)
+            .unwrap_or(false)
+    }
+
+    /// Create a new commitment, optionally attributed to `referrer`.
+    ///
+    /// A referrer accrues `get_referral_share_bps` of the settlement fee
+    /// charged on this commitment, claimable via `claim_referral_rewards`.
+    /// Owners cannot refer themselves.
+    ///
+    /// # Reentrancy Protection
+    /// This function uses checks-effects-interactions pattern:
+    /// 1. Checks: Validate inputs
+    /// 2. Effects: Update state (commitment storage, counters)
+    /// 3. Interactions: External calls (token transfer, NFT mint)
+    /// Reentrancy guard prevents recursive calls.
+    ///
+    /// # Formal Verification
+    /// **Preconditions:**
+    /// - `amount > 0`
+    /// - `rules.duration_days > 0`
+    /// - `rules.max_loss_percent <= 100`
+    /// - `rules.commitment_type ∈ {"safe", "balanced", "aggressive"}`
+    /// - Contract is initialized
+    /// - `reentrancy_guard == false`
+    ///
+    /// **Postconditions:**
+    /// - Returns unique `commitment_id`
+    /// - `get_commitment(commitment_id).owner == owner`
+    /// - `get_commitment(commitment_id).amount == amount`
+    /// - `get_commitment(commitment_id).status == "active"`
+    /// - `get_total_commitments() == old(get_total_commitments()) + 1`
+    /// - `reentrancy_guard == false`
+    ///
+    /// **Invariants Maintained:**
+    /// - INV-1: Total commitments consistency
+    /// - INV-2: Commitment balance conservation
+    /// - INV-3: Owner commitment list consistency
+    /// - INV-4: Reentrancy guard invariant
+    ///
+    /// **Security Properties:**
+    /// - SP-1: Reentrancy protection
+    /// - SP-2: Access control
+    /// - SP-4: State consistency
+    /// - SP-5: Token conservation
+    pub fn create_commitment(
+        e: Env,
+        owner: Address,
+        amount: i128,
+        asset_address: Address,
+        rules: CommitmentRules,
+        referrer: Option<Address>,
+    ) -> String {
+        if referrer.as_ref() == Some(&owner) {
+            fail(&e, CommitmentError::SelfReferral, "create_commitment");
+        }
+        let commitment_id = Self::open_commitment(
+            e.clone(),
+            owner,
+            amount,
+            asset_address,
+            rules,
+            Funding::Deposit,
+        );
+        if let Some(referrer) = referrer {
+            e.storage()
+                .instance()
+                .set(&DataKey::Referrer(commitment_id.clone()), &referrer);
+            publish_event(
+                &e,
+                (symbol_short!("Referred"), commitment_id.clone()),
+                referrer,
+            );
+        }
+        commitment_id
+    }
+
+    /// Create a commitment funded through an allowance: `owner` approves core
+    /// for `amount` beforehand and core pulls the deposit with `transfer_from`.
+    pub fn create_commitment_with_allowance(
+        e: Env,
+        owner: Address,
+        amount: i128,
+        asset_address: Address,
+        rules: CommitmentRules,
+    ) -> String {
+        owner.require_auth();
+        Self::open_commitment(
+            e,
+            owner.clone(),
+            amount,
+            asset_address,
+            rules,
+            Funding::Allowance(owner),
+        )
+    }
+
+    /// Create a commitment for `owner` funded by `payer`, so integrators can
+    /// sponsor deposits and users can gift commitments (e.g. employer matching).
+    /// `payer` must have approved core for `amount` and is the only party whose
+    /// authorization is required; the commitment, its NFT and its proceeds
+    /// belong to `owner`.
+    pub fn create_commitment_for(
+        e: Env,
+        payer: Address,
+        owner: Address,
+        amount: i128,
+        asset_address: Address,
+        rules: CommitmentRules,
+    ) -> String {
+        payer.require_auth();
+        let commitment_id = Self::open_commitment(
+            e.clone(),
+            owner,
+            amount,
+            asset_address,
+            rules,
+            Funding::Allowance(payer.clone()),
+        );
+        publish_event(
+            &e,
+            (symbol_short!("Sponsored"), commitment_id.clone()),
+            payer,
+        );
+        commitment_id
+    }
+
+    /// First step of front-running-resistant creation: record
+    /// `creation_hash(params, salt)` without revealing the parameters.
+    ///
+    /// The commit expires after `CREATION_COMMIT_TTL_LEDGERS` ledgers.
+    pub fn commit_creation(e: Env, owner: Address, hash: BytesN<32>) {
+        owner.require_auth();
+        let key = DataKeyExt::CreationCommit(owner.clone(), hash.clone());
+        e.storage().temporary().set(&key, &e.ledger().sequence());
+        e.storage().temporary().extend_ttl(
+            &key,
+            CREATION_COMMIT_TTL_LEDGERS,
+            CREATION_COMMIT_TTL_LEDGERS,
+        );
+        publish_event(&e, (symbol_short!("CrCommit"), owner), hash);
+    }
+
+    /// Second step: reveal the parameters and salt behind an earlier
+    /// `commit_creation` and create the commitment through the normal path.
+    ///
+    /// The reveal must land in a later ledger than the commit, so observers
+    /// of the commit transaction learn nothing they can act on first, and
+    /// before the commit expires.
+    pub fn reveal_creation(
+        e: Env,
+        owner: Address,
+        params: CreationParams,
+        salt: BytesN<32>,
+    ) -> String {
+        owner.require_auth();
+        let key = DataKeyExt::CreationCommit(owner.clone(), creation_hash(&e, &params, &salt));
+        let committed_in = e
+            .storage()
+            .temporary()
+            .get::<_, u32>(&key)
+            .unwrap_or_else(|| fail(&e, CommitmentError::InvalidReveal, "reveal_creation"));
+        let sequence = e.ledger().sequence();
+        if sequence <= committed_in || sequence - committed_in > CREATION_COMMIT_TTL_LEDGERS {
+            fail(&e, CommitmentError::InvalidReveal, "reveal_creation");
+        }
+        e.storage().temporary().remove(&key);
+        Self::open_commitment(
+            e,
+            owner,
+            params.amount,
+            params.asset_address,
+            params.rules,
+            Funding::Deposit,
+        )
+    }
+
+    /// Hash to pass to `commit_creation` for the given parameters and salt.
+    pub fn get_creation_hash(e: Env, params: CreationParams, salt: BytesN<32>) -> BytesN<32> {
+        creation_hash(&e, &params, &salt)
+    }
+
+    /// Create a tracking-only commitment for a position held off-chain.
+    ///
+    /// No funds are escrowed: `notional_amount` is only the baseline for
+    /// attestation-driven valuation. The commitment is excluded from TVL and
+    /// conservation checks, and settlement or early exit pays nothing out.
+    pub fn create_tracking_commitment(
+        e: Env,
+        owner: Address,
+        notional_amount: i128,
+        asset_address: Address,
+        rules: CommitmentRules,
+    ) -> String {
+        owner.require_auth();
+        Self::open_commitment(
+            e,
+            owner,
+            notional_amount,
+            asset_address,
+            rules,
+            Funding::TrackingOnly,
+        )
+    }
+
+    /// Create a commitment tagged with an analytics label from the curated set.
+    ///
+    /// The label is emitted in a `Labeled` event right after `Created`.
+    pub fn create_labeled_commitment(
+        e: Env,
+        owner: Address,
+        amount: i128,
+        asset_address: Address,
+        rules: CommitmentRules,
+        label: Symbol,
+    ) -> String {
+        if !Self::get_labels(e.clone()).contains(&label) {
+            fail(
+                &e,
+                CommitmentError::UnknownLabel,
+                "create_labeled_commitment",
+            );
+        }
+        let commitment_id = Self::open_commitment(
+            e.clone(),
+            owner,
+            amount,
+            asset_address,
+            rules,
+            Funding::Deposit,
+        );
+        e.storage()
+            .instance()
+            .set(&DataKey::CommitmentLabel(commitment_id.clone()), &label);
+        publish_event(&e, (symbol_short!("Labeled"), commitment_id.clone()), label);
+        commitment_id
+    }
+
+    /// Create a commitment carrying a free-form note for off-chain
+    /// reconciliation (e.g. "Q3 treasury reserve"), at most MAX_NOTE_LEN bytes.
+    pub fn create_noted_commitment(
+        e: Env,
+        owner: Address,
+        amount: i128,
+        asset_address: Address,
+        rules: CommitmentRules,
+        note: String,
+    ) -> String {
+        if note.len() > MAX_NOTE_LEN {
+            fail(&e, CommitmentError::NoteTooLong, "create_noted_commitment");
+        }
+        let commitment_id = Self::open_commitment(
+            e.clone(),
+            owner,
+            amount,
+            asset_address,
+            rules,
+            Funding::Deposit,
+        );
+        store_note(&e, &commitment_id, &note);
+        commitment_id
+    }
+
+    /// Replace a commitment's note (owner only). An empty note clears it.
+    ///
+    /// Notes are bookkeeping only and may be edited in any status.
+    pub fn update_note(e: Env, commitment_id: String, note: String) {
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "update_note"));
+        commitment.owner.require_auth();
+        if note.len() > MAX_NOTE_LEN {
+            fail(&e, CommitmentError::NoteTooLong, "update_note");
+        }
+        store_note(&e, &commitment_id, &note);
+    }
+
+    /// Get the note attached to a commitment, if any.
+    ///
+    /// Kept alongside `get_commitment` rather than inside `Commitment`, whose
+    /// layout is shared with the NFT and attestation contracts.
+    pub fn get_commitment_note(e: Env, commitment_id: String) -> Option<String> {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::CommitmentNote(commitment_id))
+    }
+
+    /// Get the referrer credited for a commitment, if any
+    pub fn get_referrer(e: Env, commitment_id: String) -> Option<Address> {
+        e.storage()
+            .instance()
+            .get(&DataKey::Referrer(commitment_id))
+    }
+
+    /// Set the share of settlement fees accrued to referrers (admin only).
+    pub fn set_referral_share_bps(e: Env, caller: Address, bps: u32) {
+        require_admin(&e, &caller);
+        if bps > 10_000 {
+            fail(
+                &e,
+                CommitmentError::InvalidFeeConfig,
+                "set_referral_share_bps",
+            );
+        }
+        e.storage().instance().set(&DataKey::ReferralShareBps, &bps);
+    }
+
+    pub fn get_referral_share_bps(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DataKey::ReferralShareBps)
+            .unwrap_or(0)
+    }
+
+    /// Get a referrer's unclaimed rewards in `asset`
+    pub fn get_referral_rewards(e: Env, referrer: Address, asset: Address) -> i128 {
+        e.storage()
+            .instance()
+            .get(&DataKey::ReferralRewards(referrer, asset))
+            .unwrap_or(0)
+    }
+
+    /// Pay out a referrer's accrued rewards in `asset`. Returns the amount paid.
+    pub fn claim_referral_rewards(e: Env, referrer: Address, asset: Address) -> i128 {
+        referrer.require_auth();
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+
+        // EFFECTS
+        let key = DataKey::ReferralRewards(referrer.clone(), asset.clone());
+        let rewards = e.storage().instance().get::<_, i128>(&key).unwrap_or(0);
+        if rewards == 0 {
+            set_reentrancy_guard(&e, false);
+            return 0;
+        }
+        e.storage().instance().remove(&key);
+        let liability_key = DataKey::ReferralLiability(asset.clone());
+        let liability = e
+            .storage()
+            .instance()
+            .get::<_, i128>(&liability_key)
+            .unwrap_or(0);
+        e.storage()
+            .instance()
+            .set(&liability_key, &SafeMath::sub(liability, rewards));
+
+        // INTERACTIONS
+        token::Client::new(&e, &asset).transfer(&e.current_contract_address(), &referrer, &rewards);
+
+        set_reentrancy_guard(&e, false);
+        publish_event(
+            &e,
+            (symbol_short!("RefClaim"), referrer, asset),
+            (rewards, e.ledger().timestamp()),
+        );
+        rewards
+    }
+
+    /// Claim the penalty pool shares `owner` earned by settling compliant
+    /// commitments in `asset`. Returns the amount transferred.
+    pub fn claim_penalty_share(e: Env, owner: Address, asset: Address) -> i128 {
+        owner.require_auth();
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+
+        // EFFECTS
+        let key = DataKeyExt::PenaltyShare(owner.clone(), asset.clone());
+        let share = e.storage().instance().get::<_, i128>(&key).unwrap_or(0);
+        if share == 0 {
+            set_reentrancy_guard(&e, false);
+            return 0;
+        }
+        e.storage().instance().remove(&key);
+        add_to_balance(&e, DataKeyExt::PenaltyShareLiability(asset.clone()), -share);
+
+        // INTERACTIONS
+        token::Client::new(&e, &asset).transfer(&e.current_contract_address(), &owner, &share);
+
+        set_reentrancy_guard(&e, false);
+        publish_event(
+            &e,
+            (symbol_short!("PenClaim"), owner, asset),
+            (share, e.ledger().timestamp()),
+        );
+        share
+    }
+
+    /// Penalties in `asset` not yet credited to compliant settlements.
+    pub fn get_penalty_pool(e: Env, asset: Address) -> i128 {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::PenaltyPool(asset))
+            .unwrap_or(0)
+    }
+
+    /// Penalty pool share credited to `owner` in `asset` and not yet claimed.
+    pub fn get_penalty_share(e: Env, owner: Address, asset: Address) -> i128 {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::PenaltyShare(owner, asset))
+            .unwrap_or(0)
+    }
+
+    /// Set the minimum and maximum commitment size for `asset` (admin only).
+    ///
+    /// `max_amount` of 0 leaves the size unbounded above.
+    pub fn set_asset_limits(e: Env, caller: Address, asset: Address, limits: AssetLimits) {
+        require_admin(&e, &caller);
+        if limits.min_amount < 0
+            || limits.max_amount < 0
+            || (limits.max_amount > 0 && limits.min_amount > limits.max_amount)
+        {
+            fail(&e, CommitmentError::InvalidAssetLimits, "set_asset_limits");
+        }
+        e.storage()
+            .instance()
+            .set(&DataKey::AssetLimits(asset.clone()), &limits);
+        publish_event(
+            &e,
+            (Symbol::new(&e, "AssetLimitsSet"), asset),
+            (limits.min_amount, limits.max_amount, caller),
+        );
+    }
+
+    /// Remove the size bounds for `asset`.
+    pub fn clear_asset_limits(e: Env, caller: Address, asset: Address) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .remove(&DataKey::AssetLimits(asset.clone()));
+        publish_event(&e, (Symbol::new(&e, "AssetLimitsCleared"), asset), caller);
+    }
+
+    pub fn get_asset_limits(e: Env, asset: Address) -> Option<AssetLimits> {
+        e.storage().instance().get(&DataKey::AssetLimits(asset))
+    }
+
+    /// Get the analytics label assigned to a commitment, if any
+    pub fn get_commitment_label(e: Env, commitment_id: String) -> Option<Symbol> {
+        e.storage()
+            .instance()
+            .get(&DataKey::CommitmentLabel(commitment_id))
+    }
+
+    /// Move a commitment to the holder of its NFT (NFT contract only).
+    ///
+    /// Invoked by the NFT contract on transfer so payouts and owner queries
+    /// follow the NFT holder.
+    pub fn sync_owner(e: Env, commitment_id: String, new_owner: Address) {
+        let nft_contract = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::NftContract)
+            .unwrap_or_else(|| fail(&e, CommitmentError::NotInitialized, "sync_owner"));
+        nft_contract.require_auth();
+
+        let mut commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "sync_owner"));
+        let previous_owner = commitment.owner.clone();
+        if previous_owner == new_owner {
+            return;
+        }
+
+        let previous_key = DataKey::OwnerCommitments(previous_owner.clone());
+        let mut previous_ids = e
+            .storage()
+            .instance()
+            .get::<_, Vec<String>>(&previous_key)
+            .unwrap_or(Vec::new(&e));
+        if let Some(index) = previous_ids.first_index_of(&commitment_id) {
+            previous_ids.remove(index);
+        }
+        e.storage().instance().set(&previous_key, &previous_ids);
+
+        let new_key = DataKey::OwnerCommitments(new_owner.clone());
+        let mut new_ids = e
+            .storage()
+            .instance()
+            .get::<_, Vec<String>>(&new_key)
+            .unwrap_or(Vec::new(&e));
+        new_ids.push_back(commitment_id.clone());
+        e.storage().instance().set(&new_key, &new_ids);
+
+        if commitment.status == String::from_str(&e, "active") {
+            adjust_count(&e, DataKeyExt::OwnerActiveCount(previous_owner.clone()), -1);
+            adjust_count(&e, DataKeyExt::OwnerActiveCount(new_owner.clone()), 1);
+        }
+        commitment.owner = new_owner.clone();
+        set_commitment(&e, &commitment);
+        publish_event(
+            &e,
+            (symbol_short!("OwnerSync"), commitment_id),
+            (previous_owner, new_owner, e.ledger().timestamp()),
+        );
+    }
+
+    /// Whether a commitment is tracking-only (no escrowed funds)
+    pub fn is_tracking_only(e: Env, commitment_id: String) -> bool {
+        is_tracking_only(&e, &commitment_id)
+    }
+
+    /// Add principal to an active commitment (owner only).
+    ///
+    /// `amount` is transferred into escrow and added to both `amount` and
+    /// `current_value`; the NFT's `initial_amount` is kept in sync.
+    pub fn top_up(e: Env, commitment_id: String, amount: i128) {
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+        Pausable::require_not_paused(&e);
+
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::CommitmentNotFound, "top_up")
+        });
+        commitment.owner.require_auth();
+        Validation::require_positive(amount);
+
+        if commitment.status != String::from_str(&e, "active") {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotActive, "top_up");
+        }
+        if is_tracking_only(&e, &commitment_id) {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::TrackingOnly, "top_up");
+        }
+        if Sunset::state(&e) != SunsetState::Live {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::ProtocolSunset, "top_up");
+        }
+        if e.storage()
+            .instance()
+            .has(&DataKey::DelistedAsset(commitment.asset_address.clone()))
+        {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::AssetDelisted, "top_up");
+        }
+        // Only the upper bound applies: a top-up grows an existing commitment
+        let limits = e
+            .storage()
+            .instance()
+            .get::<_, AssetLimits>(&DataKey::AssetLimits(commitment.asset_address.clone()));
+        if let Some(limits) = limits {
+            if limits.max_amount > 0 && SafeMath::add(commitment.amount, amount) > limits.max_amount
+            {
+                set_reentrancy_guard(&e, false);
+                fail(&e, CommitmentError::AboveMaximumAmount, "top_up");
+            }
+        }
+        consume_approval(
+            &e,
+            ApprovalAction::TopUp(commitment_id.clone(), amount),
+            SafeMath::add(commitment.amount, amount),
+            "top_up",
+        );
+
+        // EFFECTS
+        commitment.amount = SafeMath::add(commitment.amount, amount);
+        commitment.current_value = SafeMath::add(commitment.current_value, amount);
+        set_commitment(&e, &commitment);
+        adjust_tvl(&e, &commitment.asset_address, amount);
+
+        // INTERACTIONS
+        let contract_address = e.current_contract_address();
+        transfer_assets(
+            &e,
+            &commitment.owner,
+            &contract_address,
+            &commitment.asset_address,
+            amount,
+        );
+        let nft_contract = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::NftContract)
+            .unwrap_or_else(|| {
+                set_reentrancy_guard(&e, false);
+                fail(&e, CommitmentError::NotInitialized, "top_up")
+            });
+        e.invoke_contract::<()>(
+            &nft_contract,
+            &Symbol::new(&e, "top_up"),
+            (commitment.nft_token_id, amount).into_val(&e),
+        );
+
+        set_reentrancy_guard(&e, false);
+        publish_event(
+            &e,
+            (symbol_short!("TopUp"), commitment_id, commitment.owner),
+            (amount, commitment.current_value, e.ledger().timestamp()),
+        );
+    }
+
+    /// Extend an active commitment's term by `extra_days` (owner only).
+    ///
+    /// The owner pays the renewal fee (see `quote_renewal_fee`) to the
+    /// treasury; the escrowed value is untouched. The new duration must fit
+    /// the commitment type's constraints and the NFT's expiry is kept in sync.
+    pub fn extend_commitment(e: Env, commitment_id: String, extra_days: u32) -> i128 {
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+        Pausable::require_not_paused(&e);
+
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::CommitmentNotFound, "extend_commitment")
+        });
+        commitment.owner.require_auth();
+        if commitment.status != String::from_str(&e, "active") {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotActive, "extend_commitment");
+        }
+        require_not_frozen(&e, &commitment_id, "extend_commitment");
+        if extra_days == 0 {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::InvalidDuration, "extend_commitment");
+        }
+        let duration_days = commitment
+            .rules
+            .duration_days
+            .checked_add(extra_days)
+            .unwrap_or_else(|| {
+                set_reentrancy_guard(&e, false);
+                fail(&e, CommitmentError::InvalidDuration, "extend_commitment")
+            });
+        let expires_at = commitment
+            .expires_at
+            .checked_add(extra_days as u64 * 86_400)
+            .unwrap_or_else(|| {
+                set_reentrancy_guard(&e, false);
+                fail(&e, CommitmentError::ExpirationOverflow, "extend_commitment")
+            });
+        let mut rules = commitment.rules.clone();
+        rules.duration_days = duration_days;
+        Self::validate_rules(&e, &rules);
+
+        // EFFECTS
+        let fee = renewal_fee(&e, &commitment, extra_days);
+        commitment.rules = rules;
+        commitment.expires_at = expires_at;
+        set_commitment(&e, &commitment);
+
+        // INTERACTIONS
+        if let (Some(treasury), true) = (fee_config(&e).treasury, fee > 0) {
+            transfer_assets(
+                &e,
+                &commitment.owner,
+                &treasury,
+                &commitment.asset_address,
+                fee,
+            );
+        }
+        let nft_contract = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::NftContract)
+            .unwrap_or_else(|| {
+                set_reentrancy_guard(&e, false);
+                fail(&e, CommitmentError::NotInitialized, "extend_commitment")
+            });
+        e.invoke_contract::<()>(
+            &nft_contract,
+            &Symbol::new(&e, "extend_expiry"),
+            (commitment.nft_token_id, extra_days).into_val(&e),
+        );
+
+        set_reentrancy_guard(&e, false);
+        publish_event(
+            &e,
+            (symbol_short!("Extended"), commitment_id),
+            (extra_days, expires_at, fee, e.ledger().timestamp()),
+        );
+        fee
+    }
+
+    /// Fee `extend_commitment` would charge to extend by `extra_days` now.
+    pub fn quote_renewal_fee(e: Env, commitment_id: String, extra_days: u32) -> i128 {
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "quote_renewal_fee"));
+        renewal_fee(&e, &commitment, extra_days)
+    }
+
+    /// Set the renewal fee charged per started 30 days of extension (admin
+    /// only), at most `MAX_SETTLEMENT_FEE_BPS`. It is priced independently of
+    /// the settlement fee, so renewals can be discounted relative to new
+    /// commitments.
+    pub fn set_renewal_fee_bps(e: Env, caller: Address, bps_per_30_days: u32) {
+        require_admin(&e, &caller);
+        if bps_per_30_days > MAX_SETTLEMENT_FEE_BPS {
+            fail(&e, CommitmentError::InvalidFeeConfig, "set_renewal_fee_bps");
+        }
+        e.storage()
+            .instance()
+            .set(&DataKeyExt::RenewalFeeBps, &bps_per_30_days);
+        publish_event(
+            &e,
+            (Symbol::new(&e, "RenewalFeeSet"),),
+            (bps_per_30_days, caller, e.ledger().timestamp()),
+        );
+    }
+
+    pub fn get_renewal_fee_bps(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::RenewalFeeBps)
+            .unwrap_or(0)
+    }
+
+    fn open_commitment(
+        e: Env,
+        owner: Address,
+        amount: i128,
+        asset_address: Address,
+        rules: CommitmentRules,
+        funding: Funding,
+    ) -> String {
+        let tracking_only = matches!(funding, Funding::TrackingOnly);
+        let prefunded = matches!(funding, Funding::Prefunded);
+
+        // Reentrancy protection
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+
+        // Check if contract is paused
+        Pausable::require_not_paused(&e);
+
+        // No new commitments once a sunset has begun
+        if Sunset::state(&e) != SunsetState::Live {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::ProtocolSunset, "create_commitment");
+        }
+
+        if !prefunded && Self::is_creation_paused(e.clone()) {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentErrorExt::CreationPaused, "create_commitment");
+        }
+
+        // The cap bounds the owner's index, whoever pays
+        let max_active = Self::get_max_active_per_owner(e.clone());
+        if max_active > 0
+            && Self::get_active_commitment_count(e.clone(), owner.clone()) >= max_active as u64
+            && !e
+                .storage()
+                .instance()
+                .has(&DataKeyExt::ActiveCapExempt(owner.clone()))
+        {
+            set_reentrancy_guard(&e, false);
+            fail(
+                &e,
+                CommitmentErrorExt::TooManyActiveCommitments,
+                "create_commitment",
+            );
+        }
+
+        // Rate limit: per-creator commitment creation, so a sponsor's gifts
+        // don't use up the recipient's allowance
+        let creator = match &funding {
+            Funding::Allowance(payer) => payer.clone(),
+            _ => owner.clone(),
+        };
+        if !RateLimiter::try_check(&e, &creator, &CREATE_RATE_LIMIT_FN) {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentErrorExt::RateLimited, "create_commitment");
+        }
+
+        // Validate amount > 0 using shared utilities
+        Validation::require_positive(amount);
+
+        // Delisted assets accept no new deposits
+        if e.storage()
+            .instance()
+            .has(&DataKey::DelistedAsset(asset_address.clone()))
+        {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::AssetDelisted, "create_commitment");
+        }
+        require_within_asset_limits(&e, &asset_address, amount, "create_commitment");
+        if !tracking_only && !prefunded {
+            consume_approval(
+                &e,
+                ApprovalAction::Create(owner.clone(), asset_address.clone(), amount),
+                amount,
+                "create_commitment",
+            );
+        }
+
+        // Validate rules
+        Self::validate_rules(&e, &rules);
+
+        // Reject duration_days that would cause expires_at to overflow u64
+        let expires_at = TimeUtils::checked_calculate_expiration(&e, rules.duration_days)
+            .unwrap_or_else(|| {
+                set_reentrancy_guard(&e, false);
+                fail(&e, CommitmentError::ExpirationOverflow, "create_commitment")
+            });
+
+        // OPTIMIZATION: Read the counter and NFT contract once to minimize storage operations
+        let (current_total, nft_contract) = {
+            let total = e
+                .storage()
+                .instance()
+                .get::<_, u64>(&DataKey::TotalCommitments)
+                .unwrap_or(0);
+            let nft = e
+                .storage()
+                .instance()
+                .get::<_, Address>(&DataKey::NftContract)
+                .unwrap_or_else(|| {
+                    set_reentrancy_guard(&e, false);
+                    fail(&e, CommitmentError::NotInitialized, "create_commitment")
+                });
+            (total, nft)
+        };
+
+        // Generate unique commitment ID using counter
+        let commitment_id = Self::generate_commitment_id(&e, current_total);
+
+        // CHECKS: Validate commitment doesn't already exist
+        if has_commitment(&e, &commitment_id) {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::InvalidStatus, "create_commitment");
+        }
+
+        // EFFECTS: Update state before external calls (expires_at already computed above with overflow check)
+        let current_timestamp = TimeUtils::now(&e);
+
+        // Create commitment data
+        let commitment = Commitment {
+            commitment_id: commitment_id.clone(),
+            owner: owner.clone(),
+            nft_token_id: 0, // Will be set after NFT mint
+            rules: rules.clone(),
+            amount,
+            asset_address: asset_address.clone(),
+            created_at: current_timestamp,
+            expires_at,
+            current_value: amount, // Initially same as amount
+            status: String::from_str(&e, "active"),
+        };
+
+        // Store commitment data (before external calls)
+        if tracking_only {
+            e.storage()
+                .instance()
+                .set(&DataKey::TrackingOnly(commitment_id.clone()), &true);
+        }
+        set_commitment(&e, &commitment);
+
+        // Update owner's commitment list
+        let mut owner_commitments = e
+            .storage()
+            .instance()
+            .get::<_, Vec<String>>(&DataKey::OwnerCommitments(owner.clone()))
+            .unwrap_or(Vec::new(&e));
+        owner_commitments.push_back(commitment_id.clone());
+        e.storage().instance().set(
+            &DataKey::OwnerCommitments(owner.clone()),
+            &owner_commitments,
+        );
+
+        // OPTIMIZATION: Increment the counter using the already-read value
+        e.storage()
+            .instance()
+            .set(&DataKey::TotalCommitments, &(current_total + 1));
+        adjust_active(&e, &owner, 1);
+        if !tracking_only {
+            adjust_tvl(&e, &asset_address, amount);
+        }
+
+        // Append to AllCommitmentIds for time-range queries (#143)
+        let mut all_ids = e
+            .storage()
+            .instance()
+            .get::<_, Vec<String>>(&DataKey::AllCommitmentIds)
+            .unwrap_or(Vec::new(&e));
+        all_ids.push_back(commitment_id.clone());
+        e.storage()
+            .instance()
+            .set(&DataKey::AllCommitmentIds, &all_ids);
+        index_expiry(&e, &commitment_id, expires_at);
+
+        // INTERACTIONS: External calls (token transfer, NFT mint)
+        // Transfer assets from owner (or the payer's allowance) to contract
+        // (tracking-only commitments escrow nothing; prefunded commitments reuse
+        // proceeds already held in escrow)
+        match &funding {
+            Funding::Deposit => {
+                let contract_address = e.current_contract_address();
+                transfer_assets(&e, &owner, &contract_address, &asset_address, amount);
+            }
+            Funding::Allowance(payer) => transfer_assets_from(&e, payer, &asset_address, amount),
+            Funding::TrackingOnly | Funding::Prefunded => {}
+        }
+
+        // Mint NFT
+        let nft_token_id = call_nft_mint(
+            &e,
+            &nft_contract,
+            &owner,
+            &commitment_id,
+            rules.duration_days,
+            rules.max_loss_percent,
+            &rules.commitment_type,
+            amount,
+            &asset_address,
+            rules.early_exit_penalty,
+        );
+
+        // Update commitment with NFT token ID
+        let mut updated_commitment = commitment;
+        updated_commitment.nft_token_id = nft_token_id;
+        set_commitment(&e, &updated_commitment);
+
+        // Clear reentrancy guard
+        set_reentrancy_guard(&e, false);
+
+        // Emit creation event
+        publish_event(
+            &e,
+            (
+                symbol_short!("Created"),
+                commitment_id.clone(),
+                owner.clone(),
+            ),
+            (
+                amount,
+                rules.clone(),
+                nft_token_id,
+                e.ledger().timestamp(),
+                rules_hash(&e, &rules),
+            ),
+        );
+        if tracking_only {
+            publish_event(
+                &e,
+                (symbol_short!("Tracking"), commitment_id.clone()),
+                amount,
+            );
+        }
+        commitment_id
+    }
+
+    /// Get the hash of a commitment's rules as emitted in its Created and Settled events
+    pub fn get_rules_hash(e: Env, commitment_id: String) -> BytesN<32> {
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "get_rules_hash"));
+        rules_hash(&e, &commitment.rules)
+    }
+
+    /// Get commitment details
+    pub fn get_commitment(e: Env, commitment_id: String) -> Commitment {
+        read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "get_commitment"))
+    }
+
+    /// Get commitment details together with the owner's note.
+    ///
+    /// `get_commitment` keeps returning the bare `Commitment`, which the NFT
+    /// and attestation contracts decode into their own copy of the struct.
+    pub fn get_commitment_view(e: Env, commitment_id: String) -> CommitmentView {
+        let commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            fail(
+                &e,
+                CommitmentError::CommitmentNotFound,
+                "get_commitment_view",
+            )
+        });
+        CommitmentView {
+            commitment,
+            note: Self::get_commitment_note(e, commitment_id),
+        }
+    }
+
+    /// List all commitment IDs owned by the given address.
+    ///
+    /// This is a convenience wrapper around `get_owner_commitments` with a
+    /// name optimized for off-chain indexers and UIs.
+    pub fn list_commitments_by_owner(e: Env, owner: Address) -> Vec<String> {
+        Self::get_owner_commitments(e, owner)
+    }
+
+    /// Get all commitments for an owner
+    pub fn get_owner_commitments(e: Env, owner: Address) -> Vec<String> {
+        e.storage()
+            .instance()
+            .get::<_, Vec<String>>(&DataKey::OwnerCommitments(owner))
+            .unwrap_or(Vec::new(&e))
+    }
+
+    /// Get total number of commitments
+    pub fn get_total_commitments(e: Env) -> u64 {
+        e.storage()
+            .instance()
+            .get::<_, u64>(&DataKey::TotalCommitments)
+            .unwrap_or(0)
+    }
+
+    /// Canonical 0-100 health score for a commitment.
+    ///
+    /// Weighted sum of:
+    /// - drawdown headroom (40): loss so far relative to `max_loss_percent`
+    /// - term progress (20): share of the duration already served
+    /// - fee generation (20): attested fees relative to `min_fee_threshold`
+    /// - freshness (20): full while the latest value update or attestation is
+    ///   within the stale window, falling to 0 at twice the window
+    ///
+    /// Violated and liquidated commitments score 0.
+    pub fn get_health_score(e: Env, commitment_id: String) -> u32 {
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "get_health_score"));
+        if commitment.status == String::from_str(&e, "violated")
+            || commitment.status == String::from_str(&e, "liquidated")
+        {
+            return 0;
+        }
+        let now = e.ledger().timestamp();
+
+        let max_loss = commitment.rules.max_loss_percent as i128;
+        let loss = if commitment.amount > 0 {
+            SafeMath::loss_percent(commitment.amount, commitment.current_value).max(0)
+        } else {
+            0
+        };
+        let drawdown_score = if max_loss == 0 {
+            if loss == 0 {
+                HEALTH_DRAWDOWN_WEIGHT
+            } else {
+                0
+            }
+        } else {
+            HEALTH_DRAWDOWN_WEIGHT * (max_loss - loss.min(max_loss)) / max_loss
+        };
+
+        let term = commitment.expires_at.saturating_sub(commitment.created_at);
+        let served = now.saturating_sub(commitment.created_at).min(term);
+        let time_score = if term == 0 {
+            HEALTH_TIME_WEIGHT
+        } else {
+            HEALTH_TIME_WEIGHT * served as i128 / term as i128
+        };
+
+        let (fees, last_attestation) = match e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::AttestationEngine)
+        {
+            Some(engine) => e.invoke_contract::<(i128, u64)>(
+                &engine,
+                &Symbol::new(&e, "get_fee_summary"),
+                (commitment_id.clone(),).into_val(&e),
+            ),
+            None => (0, 0),
+        };
+        let fee_threshold = commitment.rules.min_fee_threshold;
+        let fee_score = if fee_threshold <= 0 {
+            HEALTH_FEE_WEIGHT
+        } else {
+            HEALTH_FEE_WEIGHT * fees.clamp(0, fee_threshold) / fee_threshold
+        };
+
+        let stale_after = e
+            .storage()
+            .instance()
+            .get::<_, u64>(&DataKey::StaleValueSeconds)
+            .unwrap_or(DEFAULT_STALE_VALUE_SECONDS)
+            .max(1);
+        let last_update = e
+            .storage()
+            .instance()
+            .get::<_, u64>(&DataKey::LastValueUpdate(commitment_id))
+            .unwrap_or(commitment.created_at)
+            .max(last_attestation);
+        let age = now.saturating_sub(last_update);
+        let freshness_score = if age <= stale_after {
+            HEALTH_FRESHNESS_WEIGHT
+        } else {
+            let overdue = (age - stale_after).min(stale_after);
+            HEALTH_FRESHNESS_WEIGHT * (stale_after - overdue) as i128 / stale_after as i128
+        };
+
+        (drawdown_score + time_score + fee_score + freshness_score) as u32
+    }
+
+    /// Sequence number of the last core event published (0 before any).
+    pub fn get_event_seq(e: Env) -> u64 {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::EventSeq)
+            .unwrap_or(0)
+    }
+
+    /// Protocol-wide counters: commitments created, active and settled, value
+    /// locked, and per-asset value locked and penalties collected.
+    pub fn get_protocol_stats(e: Env) -> ProtocolStats {
+        let mut assets = Vec::new(&e);
+        for asset in e
+            .storage()
+            .instance()
+            .get::<_, Vec<Address>>(&DataKeyExt::StatsAssets)
+            .unwrap_or(Vec::new(&e))
+            .iter()
+        {
+            assets.push_back(AssetStats {
+                value_locked: e
+                    .storage()
+                    .instance()
+                    .get(&DataKeyExt::AssetTvl(asset.clone()))
+                    .unwrap_or(0),
+                penalties_collected: e
+                    .storage()
+                    .instance()
+                    .get(&DataKeyExt::PenaltiesCollected(asset.clone()))
+                    .unwrap_or(0),
+                asset,
+            });
+        }
+        ProtocolStats {
+            total_commitments: Self::get_total_commitments(e.clone()),
+            active_commitments: e
+                .storage()
+                .instance()
+                .get(&DataKeyExt::ActiveCount)
+                .unwrap_or(0),
+            settled_commitments: e
+                .storage()
+                .instance()
+                .get(&DataKeyExt::SettledCount)
+                .unwrap_or(0),
+            total_value_locked: Self::get_total_value_locked(e.clone()),
+            assets,
+        }
+    }
+
+    /// Get total value locked across all active commitments.
+    pub fn get_total_value_locked(e: Env) -> i128 {
+        e.storage()
+            .instance()
+            .get::<_, i128>(&DataKey::TotalValueLocked)
+            .unwrap_or(0)
+    }
+
+    /// Set the allocation contract consulted for pool utilization.
+    ///
+    /// This function is restricted to the contract admin.
+    pub fn set_allocation_contract(e: Env, caller: Address, allocation_contract: Address) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .set(&DataKey::AllocationContract, &allocation_contract);
+    }
+
+    /// Set the attestation engine used as the authoritative value source for repairs.
+    ///
+    /// This function is restricted to the contract admin.
+    pub fn set_attestation_engine(e: Env, caller: Address, attestation_engine: Address) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .set(&DataKey::AttestationEngine, &attestation_engine);
+    }
+
+    /// Repair a commitment's current value from the attestation engine
+    /// (operator or admin).
+    ///
+    /// The latest drawdown attestation is authoritative: the value is
+    /// re-derived as `amount * (100 - drawdown_percent) / 100`. No change is
+    /// made when no drawdown has been attested. Emits a Repair event with the
+    /// before and after values.
+    pub fn resync_commitment_value(e: Env, caller: Address, commitment_id: String) {
+        require_operator(&e, &caller);
+
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            fail(
+                &e,
+                CommitmentError::CommitmentNotFound,
+                "resync_commitment_value",
+            )
+        });
+        if commitment.status != String::from_str(&e, "active") {
+            fail(&e, CommitmentError::NotActive, "resync_commitment_value");
+        }
+
+        let attestation_engine = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::AttestationEngine)
+            .unwrap_or_else(|| {
+                fail(
+                    &e,
+                    CommitmentError::NotInitialized,
+                    "resync_commitment_value",
+                )
+            });
+        let drawdown: Option<i128> = e.invoke_contract(
+            &attestation_engine,
+            &Symbol::new(&e, "get_latest_drawdown_percent"),
+            (commitment_id.clone(),).into_val(&e),
+        );
+
+        let before = commitment.current_value;
+        let after = match drawdown {
+            Some(percent) => {
+                let percent = percent.clamp(0, 100);
+                commitment.amount - SafeMath::percent(commitment.amount, percent as u32)
+            }
+            None => before,
+        };
+
+        if after != before {
+            commitment.current_value = after;
+            set_commitment(&e, &commitment);
+            e.storage().instance().set(
+                &DataKey::LastValueUpdate(commitment_id.clone()),
+                &e.ledger().timestamp(),
+            );
+        }
+
+        if after != before && !is_tracking_only(&e, &commitment_id) {
+            adjust_tvl(&e, &commitment.asset_address, after - before);
+        }
+
+        publish_event(
+            &e,
+            (symbol_short!("Repair"), commitment_id),
+            (before, after, e.ledger().timestamp()),
+        );
+    }
+
+    /// Set the age (seconds) after which a commitment value is considered stale.
+    ///
+    /// This function is restricted to the contract admin.
+    pub fn set_stale_value_seconds(e: Env, caller: Address, seconds: u64) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .set(&DataKey::StaleValueSeconds, &seconds);
+    }
+
+    /// Require settlement to see a value updated within the stale window (admin only).
+    pub fn set_require_fresh_value(e: Env, caller: Address, required: bool) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .set(&DataKey::RequireFreshValue, &required);
+    }
+
+    pub fn get_require_fresh_value(e: Env) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::RequireFreshValue)
+            .unwrap_or(false)
+    }
+
+    /// Get when a commitment's value was last updated (its creation time if never)
+    pub fn get_last_value_update(e: Env, commitment_id: String) -> u64 {
+        let commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            fail(
+                &e,
+                CommitmentError::CommitmentNotFound,
+                "get_last_value_update",
+            )
+        });
+        e.storage()
+            .instance()
+            .get(&DataKey::LastValueUpdate(commitment_id))
+            .unwrap_or(commitment.created_at)
+    }
+
+    /// Whether a commitment's value has not been updated within the stale window
+    pub fn is_value_stale(e: Env, commitment_id: String) -> bool {
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "is_value_stale"));
+        value_is_stale(&e, &commitment)
+    }
+
+    /// Aggregate risk view for operator dashboards.
+    /// Gas cost is O(n) in total commitments plus O(p) in allocation pools.
+    pub fn get_risk_dashboard(e: Env) -> RiskDashboard {
+        let all_ids = e
+            .storage()
+            .instance()
+            .get::<_, Vec<String>>(&DataKey::AllCommitmentIds)
+            .unwrap_or(Vec::new(&e));
+
+        let active_status = String::from_str(&e, "active");
+        let queued_status = String::from_str(&e, "exit_queued");
+        let mut near_max_loss = Vec::new(&e);
+        let mut stale_attestation = Vec::new(&e);
+        let mut queued_withdrawals: u32 = 0;
+        for id in all_ids.iter() {
+            let Some(c) = read_commitment(&e, &id) else {
+                continue;
+            };
+            if c.status == queued_status {
+                queued_withdrawals += 1;
+            }
+            if c.status != active_status {
+                continue;
+            }
+
+            let loss_percent = if c.amount > 0 {
+                SafeMath::loss_percent(c.amount, c.current_value)
+            } else {
+                0
+            };
+            let max_loss = c.rules.max_loss_percent as i128;
+            if loss_percent > 0 && loss_percent * 10 >= max_loss * 9 {
+                near_max_loss.push_back(id.clone());
+            }
+
+            if value_is_stale(&e, &c) {
+                stale_attestation.push_back(id.clone());
+            }
+        }
+
+        let mut high_utilization_pools = Vec::new(&e);
+        if let Some(allocation_contract) = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::AllocationContract)
+        {
+            let pools: Vec<AllocationPool> = e.invoke_contract(
+                &allocation_contract,
+                &Symbol::new(&e, "get_all_pools"),
+                Vec::new(&e),
+            );
+            for pool in pools.iter() {
+                if pool.max_capacity > 0 && pool.total_liquidity * 10 > pool.max_capacity * 9 {
+                    high_utilization_pools.push_back(pool.pool_id);
+                }
+            }
+        }
+
+        let total_value_locked = Self::get_total_value_locked(e.clone());
+        let insurance_total = e
+            .storage()
+            .instance()
+            .get::<_, i128>(&DataKey::InsuranceTotal)
+            .unwrap_or(0);
+        let insurance_coverage_bps = if total_value_locked > 0 {
+            SafeMath::div(SafeMath::mul(insurance_total, 10_000), total_value_locked)
+                .min(u32::MAX as i128) as u32
+        } else {
+            0
+        };
+
+        RiskDashboard {
+            near_max_loss,
+            stale_attestation,
+            high_utilization_pools,
+            insurance_coverage_bps,
+            queued_withdrawals,
+            total_value_locked,
+            timestamp: e.ledger().timestamp(),
+        }
+    }
+
+    /// Compare protocol liabilities in `asset` against the tokens actually held by
+    /// protocol contracts, for monitors watching for leaks.
+    /// Gas cost is O(q) in exits queued for `asset`.
+    pub fn verify_conservation(e: Env, asset: Address) -> ConservationReport {
+        let escrow = held_escrow(&e, &asset);
+
+        let token_client = token::Client::new(&e, &asset);
+        let mut actual_total = token_client.balance(&e.current_contract_address());
+        let mut fees: i128 = 0;
+        if let Some(engine) = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::AttestationEngine)
+        {
+            fees = e.invoke_contract(
+                &engine,
+                &Symbol::new(&e, "get_collected_fees"),
+                (asset.clone(),).into_val(&e),
+            );
+            actual_total = SafeMath::add(actual_total, token_client.balance(&engine));
+        }
+
+        let queued_withdrawals = queued_liability(&e, &asset);
+        let unclaimed_yield = e
+            .storage()
+            .instance()
+            .get::<_, i128>(&DataKey::UnclaimedYield(asset.clone()))
+            .unwrap_or(0);
+        let referral_rewards = e
+            .storage()
+            .instance()
+            .get::<_, i128>(&DataKey::ReferralLiability(asset.clone()))
+            .unwrap_or(0);
+        let vesting = e
+            .storage()
+            .instance()
+            .get::<_, i128>(&DataKeyExt::StreamLiability(asset.clone()))
+            .unwrap_or(0);
+        let insurance = Self::get_insurance_balance(e.clone(), asset.clone());
+        let penalty_pool = SafeMath::add(
+            Self::get_penalty_pool(e.clone(), asset.clone()),
+            e.storage()
+                .instance()
+                .get::<_, i128>(&DataKeyExt::PenaltyShareLiability(asset.clone()))
+                .unwrap_or(0),
+        );
+        let expected_total = SafeMath::add(
+            SafeMath::add(
+                SafeMath::add(escrow, queued_withdrawals),
+                SafeMath::add(unclaimed_yield, referral_rewards),
+            ),
+            SafeMath::add(
+                SafeMath::add(vesting, fees),
+                SafeMath::add(insurance, penalty_pool),
+            ),
+        );
+        let discrepancy = SafeMath::sub(actual_total, expected_total);
+
+        ConservationReport {
+            asset,
+            escrow,
+            queued_withdrawals,
+            unclaimed_yield,
+            referral_rewards,
+            vesting,
+            fees,
+            insurance,
+            penalty_pool,
+            expected_total,
+            actual_total,
+            discrepancy,
+            conserved: discrepancy >= 0,
+            timestamp: e.ledger().timestamp(),
+        }
+    }
+
+    /// Get commitment IDs created between two timestamps (inclusive).
+    /// For analytics/dashboards. Gas cost is O(n) in total commitments; consider pagination for large n.
+    pub fn get_commitments_created_between(
+        e: Env,
+        from_ts: u64,
+        to_ts: u64,
+    ) -> Vec<String> {
+        let all_ids = e
+            .storage()
+            .instance()
+            .get::<_, Vec<String>>(&DataKey::AllCommitmentIds)
+            .unwrap_or(Vec::new(&e));
+        let mut out = Vec::new(&e);
+        for id in all_ids.iter() {
+            if let Some(c) = read_commitment(&e, &id) {
+                if c.created_at >= from_ts && c.created_at <= to_ts {
+                    out.push_back(id.clone());
+                }
+            }
+        }
+        out
+    }
+
+    /// Get active commitment IDs expiring between two timestamps (inclusive),
+    /// ordered by expiry day. Reads the expiry index one day bucket at a time,
+    /// so the range may span at most `MAX_EXPIRY_QUERY_DAYS` days.
+    pub fn get_commitments_expiring_between(e: Env, from_ts: u64, to_ts: u64) -> Vec<String> {
+        let mut out = Vec::new(&e);
+        if from_ts > to_ts {
+            return out;
+        }
+        let (first_day, last_day) = (from_ts / 86_400, to_ts / 86_400);
+        if last_day - first_day >= MAX_EXPIRY_QUERY_DAYS {
+            fail(
+                &e,
+                CommitmentErrorExt::ExpiryRangeTooLarge,
+                "get_commitments_expiring_between",
+            );
+        }
+        let active = String::from_str(&e, "active");
+        for day in first_day..=last_day {
+            let Some(ids) = e
+                .storage()
+                .persistent()
+                .get::<_, Vec<String>>(&DataKeyExt::ExpiryIndex(day))
+            else {
+                continue;
+            };
+            for id in ids.iter() {
+                if let Some(c) = read_commitment(&e, &id) {
+                    if c.status == active && c.expires_at >= from_ts && c.expires_at <= to_ts {
+                        out.push_back(id);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Get a page of commitments in creation order (at most `MAX_COMMITMENTS_PAGE_SIZE`).
+    pub fn get_commitments_page(e: Env, offset: u32, limit: u32) -> CommitmentsPage {
+        let all_ids = e
+            .storage()
+            .instance()
+            .get::<_, Vec<String>>(&DataKey::AllCommitmentIds)
+            .unwrap_or(Vec::new(&e));
+        let len = all_ids.len();
+        let end = offset
+            .saturating_add(limit.min(MAX_COMMITMENTS_PAGE_SIZE))
+            .min(len);
+
+        let mut commitments = Vec::new(&e);
+        for i in offset..end {
+            if let Some(c) = read_commitment(&e, &all_ids.get_unchecked(i)) {
+                commitments.push_back(c);
+            }
+        }
+
+        CommitmentsPage {
+            commitments,
+            next_offset: if end > offset && end < len { end } else { 0 },
+            snapshot: SnapshotVersion::current(&e),
+        }
+    }
+
+    /// Current snapshot version, bumped whenever a commitment is written.
+    pub fn get_snapshot_version(e: Env) -> u64 {
+        SnapshotVersion::current(&e)
+    }
+
+    /// Get admin address
+    pub fn get_admin(e: Env) -> Address {
+        e.storage()
+            .instance()
+            .get::<_, Address>(&DataKey::Admin)
+            .unwrap_or_else(|| fail(&e, CommitmentError::NotInitialized, "get_admin"))
+    }
+
+    /// Get NFT contract address
+    pub fn get_nft_contract(e: Env) -> Address {
+        e.storage()
+            .instance()
+            .get::<_, Address>(&DataKey::NftContract)
+            .unwrap_or_else(|| fail(&e, CommitmentError::NotInitialized, "get_nft_contract"))
+    }
+
+    /// Update commitment value (admin or operator, e.g. an oracle-fed keeper).
+    /// Persists new_value to commitment.current_value and updates TotalValueLocked.
+    ///
+    /// Recorded values drive freshness checks, loss limits and violation
+    /// bounties, so only trusted updaters may write them.
+    pub fn update_value(e: Env, caller: Address, commitment_id: String, new_value: i128) {
+        require_operator(&e, &caller);
+
+        // Global per-function rate limit (per contract instance)
+        let fn_symbol = symbol_short!("upd_val");
+        let contract_address = e.current_contract_address();
+        RateLimiter::check(&e, &contract_address, &fn_symbol);
+
+        Validation::require_non_negative(new_value);
+
+        let mut commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "update_value"));
+
+        let active_status = String::from_str(&e, "active");
+        if commitment.status != active_status {
+            fail(&e, CommitmentError::NotActive, "update_value");
+        }
+        require_not_frozen(&e, &commitment_id, "update_value");
+
+        let old_value = commitment.current_value;
+        let tracking_only = is_tracking_only(&e, &commitment_id);
+        commitment.current_value = new_value;
+        e.storage().instance().set(
+            &DataKey::LastValueUpdate(commitment_id.clone()),
+            &e.ledger().timestamp(),
+        );
+
+        // Check for violations after updating value
+        let loss_percent = if commitment.amount > 0 {
+            SafeMath::loss_percent(commitment.amount, compliance_value(&e, &commitment))
+        } else {
+            0
+        };
+        let max_loss = commitment.rules.max_loss_percent as i128;
+        let loss_violated = loss_percent > max_loss;
+
+        // Signed change relative to the previous value, in basis points
+        let change_bps = if old_value > 0 {
+            SafeMath::div(SafeMath::mul(new_value - old_value, 10000), old_value)
+        } else {
+            0
+        };
+        publish_event(
+            &e,
+            (symbol_short!("ValUpd"), commitment_id.clone()),
+            (old_value, new_value, change_bps, e.ledger().timestamp()),
+        );
+        let alert_bps = Self::get_drawdown_alert_bps(e.clone());
+        if alert_bps > 0 && -change_bps >= alert_bps as i128 {
+            publish_event(
+                &e,
+                (Symbol::new(&e, "DrawdownAlert"), commitment_id.clone()),
+                (old_value, new_value, change_bps, e.ledger().timestamp()),
+            );
+        }
+
+        if loss_violated {
+            mark_violated(&e, &mut commitment);
+        } else {
+            set_commitment(&e, &commitment);
+        }
+
+        // Adjust TotalValueLocked: TVL -= old_value, TVL += new_value
+        if tracking_only {
+            return;
+        }
+        adjust_tvl(&e, &commitment.asset_address, new_value - old_value);
+    }
+
+    /// Get a page of a commitment's recorded values, oldest first (at most
+    /// `MAX_COMMITMENTS_PAGE_SIZE`). Every change to `current_value` is
+    /// recorded; only the last `MAX_VALUE_HISTORY_ENTRIES` are kept.
+    pub fn get_value_history(
+        e: Env,
+        commitment_id: String,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<ValueSnapshot> {
+        let history = e
+            .storage()
+            .persistent()
+            .get::<_, Vec<ValueSnapshot>>(&DataKeyExt::ValueHistory(commitment_id))
+            .unwrap_or(Vec::new(&e));
+        let end = offset
+            .saturating_add(limit.min(MAX_COMMITMENTS_PAGE_SIZE))
+            .min(history.len());
+        if offset >= end {
+            return Vec::new(&e);
+        }
+        history.slice(offset..end)
+    }
+
+    /// Check if commitment rules are violated
+    /// Returns true if any rule violation is detected (loss limit or duration)
+    ///
+    /// # Formal Verification
+    /// **Preconditions:**
+    /// - `commitment_id` exists
+    ///
+    /// **Postconditions:**
+    /// - Returns `true` if `loss_percent > max_loss_percent OR current_time >= expires_at`
+    /// - Returns `false` otherwise
+    /// - Pure function (no state changes)
+    ///
+    /// **Invariants Maintained:**
+    /// - INV-2: Commitment balance conservation
+    ///
+    /// **Security Properties:**
+    /// - SP-4: State consistency (read-only)
+    pub fn check_violations(e: Env, commitment_id: String) -> bool {
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "check_violations"));
+
+        // Skip check if already settled or violated
+        let active_status = String::from_str(&e, "active");
+        if commitment.status != active_status {
+            return false; // Already processed
+        }
+
+        let current_time = e.ledger().timestamp();
+
+        // Check loss limit violation
+        // Calculate loss percentage using shared utilities, but handle zero-amount
+        // commitments gracefully to avoid panics. A zero-amount commitment cannot
+        // meaningfully violate a loss limit, so we treat its loss percent as 0.
+        let loss_percent = if commitment.amount > 0 {
+            SafeMath::loss_percent(commitment.amount, compliance_value(&e, &commitment))
+        } else {
+            0
+        };
+
+        // Convert max_loss_percent (u32) to i128 for comparison
+        let max_loss = commitment.rules.max_loss_percent as i128;
+        let loss_violated = loss_percent > max_loss;
+
+        // Check duration violation (expired)
+        let duration_violated = current_time >= commitment.expires_at;
+
+        let violated = loss_violated || duration_violated;
+
+        if violated {
+            // Emit violation event
+            publish_event(
+                &e,
+                (symbol_short!("Violated"), commitment_id),
+                (symbol_short!("RuleViol"), e.ledger().timestamp()),
+            );
+        }
+
+        // Return true if any violation exists
+        violated
+    }
+
+    /// Flag an active commitment as violated if its loss exceeds `max_loss_percent`.
+    ///
+    /// Permissionless. The loss is the larger of the recorded value drawdown
+    /// (time-weighted when `set_twap_window` is configured) and the latest drawdown attested to the attestation engine
+    /// (when one is configured). No bounty is paid; watchers use
+    /// `check_and_mark_violation`. Returns true if the commitment was flagged.
+    pub fn flag_violation(e: Env, commitment_id: String) -> bool {
+        let mut commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "flag_violation"));
+        if commitment.status != String::from_str(&e, "active") {
+            fail(&e, CommitmentError::NotActive, "flag_violation");
+        }
+        require_not_frozen(&e, &commitment_id, "flag_violation");
+
+        if !loss_limit_breached(&e, &commitment) {
+            return false;
+        }
+        mark_violated(&e, &mut commitment);
+        true
+    }
+
+    /// Mark an active commitment as violated once it breaches its loss limit,
+    /// paying `caller` a bounty of `get_violation_bounty_bps` of its current
+    /// value.
+    ///
+    /// Permissionless, so violations don't wait on the admin. Health is
+    /// judged as in `flag_violation`. The bounty comes out of the violated
+    /// commitment's escrow; tracking-only commitments pay none. Fails with
+    /// NoViolation when the commitment is within its limit. Returns the bounty.
+    pub fn check_and_mark_violation(e: Env, caller: Address, commitment_id: String) -> i128 {
+        caller.require_auth();
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+        Pausable::require_not_paused(&e);
+
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(
+                &e,
+                CommitmentError::CommitmentNotFound,
+                "check_and_mark_violation",
+            )
+        });
+        if commitment.status != String::from_str(&e, "active") {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotActive, "check_and_mark_violation");
+        }
+        require_not_frozen(&e, &commitment_id, "check_and_mark_violation");
+        if !loss_limit_breached(&e, &commitment) {
+            set_reentrancy_guard(&e, false);
+            fail(
+                &e,
+                CommitmentErrorExt::NoViolation,
+                "check_and_mark_violation",
+            );
+        }
+
+        let bounty = mark_violated_with_bounty(&e, &caller, &mut commitment);
+        set_reentrancy_guard(&e, false);
+        bounty
+    }
+
+    /// Configure the bounty paid by `check_and_mark_violation` (admin only).
+    pub fn set_violation_bounty_bps(e: Env, caller: Address, bps: u32) {
+        require_admin(&e, &caller);
+        if bps > MAX_KEEPER_REWARD_BPS {
+            fail(
+                &e,
+                CommitmentError::InvalidFeeConfig,
+                "set_violation_bounty_bps",
+            );
+        }
+        e.storage()
+            .instance()
+            .set(&DataKeyExt::ViolationBountyBps, &bps);
+    }
+
+    pub fn get_violation_bounty_bps(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::ViolationBountyBps)
+            .unwrap_or(0)
+    }
+
+    /// Close a violated commitment, returning its remaining value minus the
+    /// early-exit penalty to the owner's payout route.
+    ///
+    /// Permissionless so keepers can unwind violated positions. The penalty is
+    /// routed like an early-exit penalty. Returns the amount paid out.
+    pub fn liquidate(e: Env, commitment_id: String) -> i128 {
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+        Pausable::require_not_paused(&e);
+
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::CommitmentNotFound, "liquidate")
+        });
+        if commitment.status != String::from_str(&e, "violated") {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotViolated, "liquidate");
+        }
+        require_not_frozen(&e, &commitment_id, "liquidate");
+
+        // EFFECTS
+        let tracking_only = is_tracking_only(&e, &commitment_id);
+        let remaining = commitment.current_value;
+        let penalty_amount = if tracking_only {
+            0
+        } else {
+            SafeMath::penalty_amount(remaining, commitment.rules.early_exit_penalty)
+        };
+        let returned_amount = if tracking_only {
+            0
+        } else {
+            SafeMath::sub(remaining, penalty_amount)
+        };
+        commitment.status = String::from_str(&e, "liquidated");
+        commitment.current_value = 0;
+        set_commitment(&e, &commitment);
+        if !tracking_only {
+            adjust_tvl(&e, &commitment.asset_address, -remaining);
+        }
+
+        // INTERACTIONS
+        if returned_amount > 0 {
+            let payout_address = resolve_payout_address(
+                &e,
+                &commitment.commitment_id,
+                &commitment.owner,
+                &commitment.asset_address,
+            );
+            token::Client::new(&e, &commitment.asset_address).transfer(
+                &e.current_contract_address(),
+                &payout_address,
+                &returned_amount,
+            );
+        }
+        route_penalty(&e, &commitment, penalty_amount);
+        issue_receipt(
+            &e,
+            &commitment,
+            returned_amount,
+            0,
+            penalty_amount,
+            OUTCOME_LIQUIDATED,
+        );
+        let nft_contract = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::NftContract)
+            .unwrap_or_else(|| {
+                set_reentrancy_guard(&e, false);
+                fail(&e, CommitmentError::NotInitialized, "liquidate")
+            });
+        e.invoke_contract::<()>(
+            &nft_contract,
+            &Symbol::new(&e, "mark_inactive"),
+            (commitment.nft_token_id,).into_val(&e),
+        );
+
+        set_reentrancy_guard(&e, false);
+        notify(&e, &commitment.owner, NOTIFY_SETTLEMENT, &commitment_id);
+        publish_event(
+            &e,
+            (symbol_short!("Liquid"), commitment_id),
+            (penalty_amount, returned_amount, e.ledger().timestamp()),
+        );
+        returned_amount
+    }
+
+    /// Get detailed violation information
+    /// Returns a tuple: (has_violations, loss_violated, duration_violated, loss_percent, time_remaining)
+    pub fn get_violation_details(e: Env, commitment_id: String) -> (bool, bool, bool, i128, u64) {
+        let commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            fail(
+                &e,
+                CommitmentError::CommitmentNotFound,
+                "get_violation_details",
+            )
+        });
+
+        let current_time = e.ledger().timestamp();
+
+        // Calculate loss percentage
+        let loss_amount = commitment.amount - compliance_value(&e, &commitment);
+        let loss_percent = if commitment.amount > 0 {
+            (loss_amount * 100) / commitment.amount
+        } else {
+            0
+        };
+
+        // Check loss limit violation
+        let max_loss = commitment.rules.max_loss_percent as i128;
+        let loss_violated = loss_percent > max_loss;
+
+        // Check duration violation
+        let duration_violated = current_time >= commitment.expires_at;
+
+        // Calculate time remaining (0 if expired)
+        let time_remaining = if current_time < commitment.expires_at {
+            commitment.expires_at - current_time
+        } else {
+            0
+        };
+
+        let has_violations = loss_violated || duration_violated;
+
+        (
+            has_violations,
+            loss_violated,
+            duration_violated,
+            loss_percent,
+            time_remaining,
+        )
+    }
+
+    /// Settle commitment at maturity
+    ///
+    /// Until the grace period ends only the owner may settle; afterwards anyone
+    /// can (see also `keeper_settle`, which pays the caller a reward).
+    /// Commitments opted into `set_auto_roll` roll over instead, and anyone
+    /// may trigger that from expiry.
+    ///
+    /// # Reentrancy Protection
+    /// Uses checks-effects-interactions pattern with reentrancy guard.
+    pub fn settle(e: Env, commitment_id: String) {
+        Self::settle_commitment(e, commitment_id, None, false);
+    }
+
+    /// Settle overdue commitments in one call (operator or admin).
+    ///
+    /// Commitments that are not active, still within their grace period,
+    /// blocked by a stale value or frozen are skipped. Returns the number settled.
+    pub fn batch_settle(e: Env, caller: Address, commitment_ids: Vec<String>) -> u32 {
+        require_operator(&e, &caller);
+        if commitment_ids.len() > MAX_BATCH_SETTLE {
+            fail(&e, CommitmentErrorExt::BatchTooLarge, "batch_settle");
+        }
+        let active = String::from_str(&e, "active");
+        let fresh_required = e
+            .storage()
+            .instance()
+            .get::<_, bool>(&DataKey::RequireFreshValue)
+            .unwrap_or(false);
+        let mut settled = 0;
+        for id in commitment_ids.iter() {
+            let Some(commitment) = read_commitment(&e, &id) else {
+                continue;
+            };
+            if commitment.status != active
+                || e.ledger().timestamp() <= grace_ends_at(&commitment)
+                || (fresh_required && value_is_stale(&e, &commitment))
+                || e.storage().instance().has(&DataKeyExt::Frozen(id.clone()))
+            {
+                continue;
+            }
+            Self::settle_commitment(e.clone(), id, None, false);
+            settled += 1;
+        }
+        settled
+    }
+
+    /// Project what `settle` would pay out at the current time, without
+    /// executing it. Inactive commitments quote zero and are not eligible;
+    /// commitments set to auto-roll re-commit the payout instead of paying it.
+    pub fn quote_settlement(e: Env, commitment_id: String) -> SettlementQuote {
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "quote_settlement"));
+        if commitment.status != String::from_str(&e, "active") {
+            return SettlementQuote {
+                payout: 0,
+                accrued_yield: 0,
+                protocol_fee: 0,
+                late_fee: 0,
+                eligible: false,
+            };
+        }
+
+        let now = e.ledger().timestamp();
+        let fresh_required = e
+            .storage()
+            .instance()
+            .get::<_, bool>(&DataKey::RequireFreshValue)
+            .unwrap_or(false);
+        let eligible = now >= commitment.expires_at
+            && !Pausable::is_paused(&e)
+            && (!fresh_required || !value_is_stale(&e, &commitment))
+            && !e
+                .storage()
+                .instance()
+                .has(&DataKeyExt::Frozen(commitment_id.clone()));
+
+        if is_tracking_only(&e, &commitment_id) {
+            return SettlementQuote {
+                payout: 0,
+                accrued_yield: 0,
+                protocol_fee: 0,
+                late_fee: 0,
+                eligible,
+            };
+        }
+        let settlement_amount = commitment.current_value;
+        let (late_fee_amount, _) = late_fee(&e, &commitment, now);
+        let fees = fee_config(&e);
+        let protocol_fee = match fees.treasury {
+            Some(_) => apply_fee_discount(
+                &e,
+                &commitment.owner,
+                SafeMath::div(
+                    SafeMath::mul(
+                        settlement_amount - late_fee_amount,
+                        fees.settlement_fee_bps as i128,
+                    ),
+                    10_000,
+                ),
+            ),
+            None => 0,
+        };
+        let accrued_yield = Self::get_accrued_yield(e.clone(), commitment_id);
+        SettlementQuote {
+            payout: settlement_amount - late_fee_amount - protocol_fee + accrued_yield,
+            accrued_yield,
+            protocol_fee,
+            late_fee: late_fee_amount,
+            eligible,
+        }
+    }
+
+    /// Settle a stuck commitment before expiry (admin only), e.g. after an incident.
+    ///
+    /// Skips the expiry, grace period and value freshness checks and any auto-roll;
+    /// the owner is paid as with `settle` but earns no penalty pool share.
+    /// `reason` (at most `MAX_NOTE_LEN` bytes) is stored and published in a
+    /// `ForceSettle` event for the audit trail.
+    pub fn force_settle(e: Env, caller: Address, commitment_id: String, reason: String) {
+        require_admin(&e, &caller);
+        if reason.is_empty() {
+            fail(&e, CommitmentErrorExt::ReasonRequired, "force_settle");
+        }
+        if reason.len() > MAX_NOTE_LEN {
+            fail(&e, CommitmentError::NoteTooLong, "force_settle");
+        }
+        let settlement_amount = read_commitment(&e, &commitment_id)
+            .map(|c| c.current_value)
+            .unwrap_or(0);
+        Self::settle_commitment(e.clone(), commitment_id.clone(), None, true);
+        e.storage().instance().set(
+            &DataKeyExt::ForceSettleReason(commitment_id.clone()),
+            &reason,
+        );
+        publish_event(
+            &e,
+            (Symbol::new(&e, "ForceSettle"), commitment_id),
+            (caller, reason, settlement_amount, e.ledger().timestamp()),
+        );
+    }
+
+    /// Place a legal hold on one commitment (admin only): settlement, early
+    /// exit, value updates, violation flagging and liquidation fail with
+    /// CommitmentFrozen until it is unfrozen.
+    /// `reason` (at most `MAX_NOTE_LEN` bytes) is stored and emitted.
+    pub fn freeze_commitment(e: Env, caller: Address, commitment_id: String, reason: String) {
+        require_admin(&e, &caller);
+        if reason.is_empty() {
+            fail(&e, CommitmentErrorExt::ReasonRequired, "freeze_commitment");
+        }
+        if reason.len() > MAX_NOTE_LEN {
+            fail(&e, CommitmentError::NoteTooLong, "freeze_commitment");
+        }
+        if read_commitment(&e, &commitment_id).is_none() {
+            fail(&e, CommitmentError::CommitmentNotFound, "freeze_commitment");
+        }
+        e.storage()
+            .instance()
+            .set(&DataKeyExt::Frozen(commitment_id.clone()), &reason);
+        publish_event(
+            &e,
+            (symbol_short!("Frozen"), commitment_id),
+            (reason, e.ledger().timestamp()),
+        );
+    }
+
+    /// Lift a freeze placed with `freeze_commitment` (admin only).
+    pub fn unfreeze_commitment(e: Env, caller: Address, commitment_id: String) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .remove(&DataKeyExt::Frozen(commitment_id.clone()));
+        publish_event(
+            &e,
+            (symbol_short!("Unfrozen"), commitment_id),
+            e.ledger().timestamp(),
+        );
+    }
+
+    /// Reason the commitment is frozen, or None if it is not.
+    pub fn get_freeze_reason(e: Env, commitment_id: String) -> Option<String> {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::Frozen(commitment_id))
+    }
+
+    /// Reason given when the commitment was force-settled, if it was.
+    pub fn get_force_settle_reason(e: Env, commitment_id: String) -> Option<String> {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::ForceSettleReason(commitment_id))
+    }
+
+    /// Stream this commitment's settlement proceeds over `days` instead of
+    /// paying them out at once (owner only, while active). 0 disables streaming.
+    ///
+    /// Applies to `settle` and `keeper_settle`; proceeds vest linearly from
+    /// settlement and are pulled with `claim_settled`.
+    pub fn set_settlement_stream(e: Env, commitment_id: String, days: u32) {
+        let commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            fail(
+                &e,
+                CommitmentError::CommitmentNotFound,
+                "set_settlement_stream",
+            )
+        });
+        commitment.owner.require_auth();
+        if commitment.status != String::from_str(&e, "active") {
+            fail(&e, CommitmentError::NotActive, "set_settlement_stream");
+        }
+        // A stream has a single recipient, so it cannot be combined with a split
+        if days > 0
+            && e.storage()
+                .instance()
+                .has(&DataKeyExt::PayoutSplit(commitment_id.clone()))
+        {
+            fail(
+                &e,
+                CommitmentError::InvalidPayoutPolicy,
+                "set_settlement_stream",
+            );
+        }
+        let key = DataKeyExt::StreamDays(commitment_id);
+        if days == 0 {
+            e.storage().instance().remove(&key);
+        } else {
+            e.storage().instance().set(&key, &days);
+        }
+    }
+
+    /// Distribute this commitment's settlement proceeds across several
+    /// recipients (owner only, while active). Shares are basis points and must
+    /// sum to 10000; an empty split removes it.
+    ///
+    /// A split overrides the beneficiary and payout route at settlement and
+    /// cannot be combined with streaming.
+    pub fn set_payout_split(e: Env, commitment_id: String, split: Vec<(Address, u32)>) {
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "set_payout_split"));
+        commitment.owner.require_auth();
+        if commitment.status != String::from_str(&e, "active") {
+            fail(&e, CommitmentError::NotActive, "set_payout_split");
+        }
+        let key = DataKeyExt::PayoutSplit(commitment_id.clone());
+        if split.is_empty() {
+            e.storage().instance().remove(&key);
+            return;
+        }
+        let mut total_bps: u32 = 0;
+        for (recipient, bps) in split.iter() {
+            if is_blocked(&e, &recipient) {
+                fail(&e, CommitmentError::BlockedAddress, "set_payout_split");
+            }
+            total_bps = total_bps.saturating_add(bps);
+        }
+        if total_bps != 10_000
+            || split.len() > MAX_SPLIT_RECIPIENTS
+            || e.storage()
+                .instance()
+                .has(&DataKeyExt::StreamDays(commitment_id.clone()))
+        {
+            fail(&e, CommitmentError::InvalidPayoutPolicy, "set_payout_split");
+        }
+        e.storage().instance().set(&key, &split);
+        publish_event(
+            &e,
+            (Symbol::new(&e, "PayoutSplitSet"), commitment_id),
+            split.len(),
+        );
+    }
+
+    pub fn get_payout_split(e: Env, commitment_id: String) -> Option<Vec<(Address, u32)>> {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::PayoutSplit(commitment_id))
+    }
+
+    pub fn get_settlement_stream(e: Env, commitment_id: String) -> Option<SettlementStream> {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::SettlementStream(commitment_id))
+    }
+
+    /// Pay the vested, unclaimed part of a settlement stream to its recipient.
+    /// Returns the amount paid.
+    pub fn claim_settled(e: Env, commitment_id: String) -> i128 {
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+
+        let key = DataKeyExt::SettlementStream(commitment_id.clone());
+        let mut stream = e
+            .storage()
+            .instance()
+            .get::<_, SettlementStream>(&key)
+            .unwrap_or_else(|| {
+                set_reentrancy_guard(&e, false);
+                fail(&e, CommitmentError::CommitmentNotFound, "claim_settled")
+            });
+        let elapsed = (e.ledger().timestamp() - stream.start).min(stream.duration_seconds);
+        let vested = SafeMath::div(
+            SafeMath::mul(stream.total, elapsed as i128),
+            stream.duration_seconds as i128,
+        );
+        let claimable = vested - stream.claimed;
+
+        stream.claimed = vested;
+        if stream.claimed == stream.total {
+            e.storage().instance().remove(&key);
+        } else {
+            e.storage().instance().set(&key, &stream);
+        }
+        adjust_stream_liability(&e, &stream.asset, -claimable);
+
+        if claimable > 0 {
+            token::Client::new(&e, &stream.asset).transfer(
+                &e.current_contract_address(),
+                &stream.recipient,
+                &claimable,
+            );
+        }
+        set_reentrancy_guard(&e, false);
+        publish_event(
+            &e,
+            (symbol_short!("StrmClm"), commitment_id),
+            (claimable, e.ledger().timestamp()),
+        );
+        claimable
+    }
+
+    /// Settle an overdue commitment on behalf of an inactive owner.
+    ///
+    /// Only allowed once the grace period has ended. The keeper receives
+    /// `get_keeper_reward_bps` of the settlement proceeds (after any late fee);
+    /// the owner receives the rest as with `settle`. Returns the reward paid.
+    pub fn keeper_settle(e: Env, keeper: Address, commitment_id: String) -> i128 {
+        keeper.require_auth();
+        Self::settle_commitment(e, commitment_id, Some(keeper), false)
+    }
+
+    /// Check loss limits against the time-weighted average value over the last
+    /// `window_seconds` instead of the latest value (admin only), so a single
+    /// bad oracle tick cannot mark a commitment violated. 0 restores
+    /// instantaneous checks.
+    pub fn set_twap_window(e: Env, caller: Address, window_seconds: u64) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .set(&DataKeyExt::TwapWindowSeconds, &window_seconds);
+    }
+
+    pub fn get_twap_window(e: Env) -> u64 {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::TwapWindowSeconds)
+            .unwrap_or(0)
+    }
+
+    /// Time-weighted average value of a commitment over `window_seconds`.
+    pub fn get_value_twap(e: Env, commitment_id: String, window_seconds: u64) -> i128 {
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "get_value_twap"));
+        value_twap(&e, &commitment, window_seconds)
+    }
+
+    /// Configure the drop, in basis points of the previous value, at which
+    /// `update_value` emits a DrawdownAlert event (admin only). 0 disables alerts.
+    pub fn set_drawdown_alert_bps(e: Env, caller: Address, bps: u32) {
+        require_admin(&e, &caller);
+        if bps > 10000 {
+            fail(
+                &e,
+                CommitmentError::InvalidMaxLossPercent,
+                "set_drawdown_alert_bps",
+            );
+        }
+        e.storage().instance().set(&DataKey::DrawdownAlertBps, &bps);
+    }
+
+    pub fn get_drawdown_alert_bps(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DataKey::DrawdownAlertBps)
+            .unwrap_or(0)
+    }
+
+    /// Configure the keeper reward for settling overdue commitments (admin only).
+    pub fn set_keeper_reward_bps(e: Env, caller: Address, bps: u32) {
+        require_admin(&e, &caller);
+        if bps > MAX_KEEPER_REWARD_BPS {
+            fail(
+                &e,
+                CommitmentError::InvalidFeeConfig,
+                "set_keeper_reward_bps",
+            );
+        }
+        e.storage().instance().set(&DataKey::KeeperRewardBps, &bps);
+    }
+
+    pub fn get_keeper_reward_bps(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DataKey::KeeperRewardBps)
+            .unwrap_or(0)
+    }
+
+    fn settle_commitment(
+        e: Env,
+        commitment_id: String,
+        keeper: Option<Address>,
+        forced: bool,
+    ) -> i128 {
+        if !forced {
+            if let Some(new_rules) = Self::get_auto_roll(e.clone(), commitment_id.clone()) {
+                Self::roll_over(e, commitment_id, new_rules, false);
+                return 0;
+            }
+        }
+
+        // Reentrancy protection
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+
+        // Check if contract is paused
+        Pausable::require_not_paused(&e);
+
+        // CHECKS: Get and validate commitment
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::CommitmentNotFound, "settle")
+        });
+
+        // Verify commitment is expired
+        let current_time = e.ledger().timestamp();
+        if current_time < commitment.expires_at && !forced {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotExpired, "settle");
+        }
+
+        // Verify commitment is active
+        let active_status = String::from_str(&e, "active");
+        if commitment.status != active_status {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotActive, "settle");
+        }
+        require_not_frozen(&e, &commitment_id, "settle");
+        if !forced {
+            require_fresh_value(&e, &commitment, "settle");
+        }
+
+        // Only the owner may settle during the grace period
+        let overdue = current_time > grace_ends_at(&commitment);
+        if !overdue && !forced {
+            if keeper.is_some() {
+                set_reentrancy_guard(&e, false);
+                fail(&e, CommitmentError::GracePeriodActive, "keeper_settle");
+            }
+            commitment.owner.require_auth();
+        }
+
+        // EFFECTS: Update state before external calls
+        let settlement_amount = commitment.current_value;
+        let tracking_only = is_tracking_only(&e, &commitment_id);
+        let (late_fee_amount, days_late) = if tracking_only {
+            (0, 0)
+        } else {
+            late_fee(&e, &commitment, current_time)
+        };
+        let keeper_reward = match keeper {
+            Some(_) if !tracking_only => SafeMath::div(
+                SafeMath::mul(
+                    settlement_amount - late_fee_amount,
+                    Self::get_keeper_reward_bps(e.clone()) as i128,
+                ),
+                10_000,
+            ),
+            _ => 0,
+        };
+        let fees = fee_config(&e);
+        let fee_amount = match fees.treasury {
+            Some(_) if !tracking_only => apply_fee_discount(
+                &e,
+                &commitment.owner,
+                SafeMath::div(
+                    SafeMath::mul(
+                        settlement_amount - late_fee_amount - keeper_reward,
+                        fees.settlement_fee_bps as i128,
+                    ),
+                    10_000,
+                ),
+            ),
+            _ => 0,
+        };
+        let accrued_yield = take_accrued_yield(&e, &commitment);
+        let referrer = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::Referrer(commitment_id.clone()));
+        let referral_reward = match referrer {
+            Some(_) => SafeMath::div(
+                SafeMath::mul(fee_amount, Self::get_referral_share_bps(e.clone()) as i128),
+                10_000,
+            ),
+            None => 0,
+        };
+        if !forced {
+            credit_penalty_share(&e, &commitment);
+        }
+        commitment.status = String::from_str(&e, "settled");
+        adjust_active(&e, &commitment.owner, -1);
+        adjust_count(&e, DataKeyExt::SettledCount, 1);
+        set_commitment(&e, &commitment);
+
+        // Accrue the referrer's share of the fee; it stays in core until claimed
+        if let (Some(referrer), true) = (referrer, referral_reward > 0) {
+            let reward_key =
+                DataKey::ReferralRewards(referrer.clone(), commitment.asset_address.clone());
+            let accrued = e
+                .storage()
+                .instance()
+                .get::<_, i128>(&reward_key)
+                .unwrap_or(0);
+            e.storage()
+                .instance()
+                .set(&reward_key, &SafeMath::add(accrued, referral_reward));
+            let liability_key = DataKey::ReferralLiability(commitment.asset_address.clone());
+            let liability = e
+                .storage()
+                .instance()
+                .get::<_, i128>(&liability_key)
+                .unwrap_or(0);
+            e.storage()
+                .instance()
+                .set(&liability_key, &SafeMath::add(liability, referral_reward));
+            publish_event(
+                &e,
+                (symbol_short!("RefAccr"), commitment_id.clone(), referrer),
+                referral_reward,
+            );
+        }
+        let insured = contribute_insurance(
+            &e,
+            &commitment.asset_address,
+            fee_amount - referral_reward,
+            insurance_config(&e).fee_bps,
+        );
+
+        // Decrease total value locked
+        if !tracking_only {
+            adjust_tvl(&e, &commitment.asset_address, -settlement_amount);
+        }
+
+        // INTERACTIONS: External calls (token transfer, NFT settlement)
+        // Late fee past the grace period, keeper reward, then the protocol fee
+        collect_late_fee(&e, &commitment, late_fee_amount, days_late);
+        if let (Some(keeper), true) = (keeper, keeper_reward > 0) {
+            token::Client::new(&e, &commitment.asset_address).transfer(
+                &e.current_contract_address(),
+                &keeper,
+                &keeper_reward,
+            );
+            publish_event(
+                &e,
+                (symbol_short!("KeeperRwd"), commitment_id.clone(), keeper),
+                keeper_reward,
+            );
+        }
+        // Accrued yield is paid out with the proceeds, free of fees
+        let net_amount =
+            settlement_amount - late_fee_amount - keeper_reward - fee_amount + accrued_yield;
+        let treasury_amount = fee_amount - referral_reward - insured;
+        if let (Some(treasury), true) = (fees.treasury, treasury_amount > 0) {
+            token::Client::new(&e, &commitment.asset_address).transfer(
+                &e.current_contract_address(),
+                &treasury,
+                &treasury_amount,
+            );
+            publish_event(
+                &e,
+                (symbol_short!("FeePaid"), commitment_id.clone(), treasury),
+                (treasury_amount, commitment.asset_address.clone()),
+            );
+        }
+
+        // Convert proceeds if the commitment type settles in another currency
+        let mut payout_asset = commitment.asset_address.clone();
+        let mut payout_amount = net_amount;
+        if tracking_only {
+            // Nothing is escrowed, so nothing is paid out
+            payout_amount = 0;
+        } else if let Some(PayoutPolicy {
+            swap_adapter: Some(swap_adapter),
+            target_asset: Some(target_asset),
+            max_slippage_bps,
+            ..
+        }) = Self::get_payout_policy(e.clone(), commitment.rules.commitment_type.clone())
+        {
+            if net_amount > 0 {
+                let quote: i128 = e.invoke_contract(
+                    &swap_adapter,
+                    &Symbol::new(&e, "quote"),
+                    (
+                        commitment.asset_address.clone(),
+                        target_asset.clone(),
+                        net_amount,
+                    )
+                        .into_val(&e),
+                );
+                let min_amount_out =
+                    quote - SafeMath::div(SafeMath::mul(quote, max_slippage_bps as i128), 10_000);
+                payout_amount = swap_proceeds(
+                    &e,
+                    &commitment.asset_address,
+                    &swap_adapter,
+                    &target_asset,
+                    net_amount,
+                    min_amount_out,
+                    "settle",
+                );
+                publish_event(
+                    &e,
+                    (
+                        Symbol::new(&e, "Converted"),
+                        commitment.asset_address.clone(),
+                        target_asset.clone(),
+                    ),
+                    (net_amount, payout_amount),
+                );
+            }
+            payout_asset = target_asset;
+        }
+        issue_receipt(
+            &e,
+            &commitment,
+            payout_amount,
+            fee_amount + keeper_reward,
+            late_fee_amount,
+            OUTCOME_SETTLED,
+        );
+
+        // Transfer assets to the owner's payout route for this asset (default: owner),
+        // or hold them for `claim_settled` when the owner opted into streaming
+        if !tracking_only {
+            let payout_address = resolve_payout_address(
+                &e,
+                &commitment.commitment_id,
+                &commitment.owner,
+                &payout_asset,
+            );
+            let stream_days = e
+                .storage()
+                .instance()
+                .get::<_, u32>(&DataKeyExt::StreamDays(commitment_id.clone()))
+                .unwrap_or(0);
+            let split = e
+                .storage()
+                .instance()
+                .get::<_, Vec<(Address, u32)>>(&DataKeyExt::PayoutSplit(commitment_id.clone()));
+            if let Some(split) = split {
+                pay_split(&e, &commitment_id, &payout_asset, payout_amount, &split);
+            } else if stream_days > 0 && payout_amount > 0 {
+                let stream = SettlementStream {
+                    recipient: payout_address,
+                    asset: payout_asset.clone(),
+                    total: payout_amount,
+                    claimed: 0,
+                    start: current_time,
+                    duration_seconds: stream_days as u64 * 86_400,
+                };
+                e.storage().instance().set(
+                    &DataKeyExt::SettlementStream(commitment_id.clone()),
+                    &stream,
+                );
+                adjust_stream_liability(&e, &payout_asset, payout_amount);
+                publish_event(
+                    &e,
+                    (symbol_short!("Streamed"), commitment_id.clone()),
+                    (payout_amount, stream_days, current_time),
+                );
+            } else {
+                let contract_address = e.current_contract_address();
+                let token_client = token::Client::new(&e, &payout_asset);
+                token_client.transfer(&contract_address, &payout_address, &payout_amount);
+            }
+        }
+
+        // Call NFT contract to mark NFT as settled
+        let nft_contract = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::NftContract)
+            .unwrap_or_else(|| {
+                set_reentrancy_guard(&e, false);
+                fail(&e, CommitmentError::NotInitialized, "settle")
+            });
+
+        call_nft_settle(
+            &e,
+            &nft_contract,
+            commitment.nft_token_id,
+            settlement_amount,
+        );
+
+        // Clear reentrancy guard
+        set_reentrancy_guard(&e, false);
+
+        // Emit settlement event
+        notify(&e, &commitment.owner, NOTIFY_SETTLEMENT, &commitment_id);
+        publish_event(
+            &e,
+            (symbol_short!("Settled"), commitment_id),
+            (
+                settlement_amount,
+                e.ledger().timestamp(),
+                rules_hash(&e, &commitment.rules),
+            ),
+        );
+        keeper_reward
+    }
+
+    /// Settle an expired commitment whose asset was delisted, converting the
+    /// proceeds to the notice's target asset through its swap adapter.
+    ///
+    /// Owner opt-in alternative to `settle`, which always pays out in the
+    /// original asset. The adapter receives the proceeds and must implement
+    /// `swap(asset_in, asset_out, amount_in, min_amount_out, to) -> i128`.
+    pub fn settle_with_conversion(
+        e: Env,
+        commitment_id: String,
+        owner: Address,
+        min_amount_out: i128,
+    ) -> i128 {
+        // Reentrancy protection
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+
+        // Check if contract is paused
+        Pausable::require_not_paused(&e);
+
+        // CHECKS: Get and validate commitment
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(
+                &e,
+                CommitmentError::CommitmentNotFound,
+                "settle_with_conversion",
+            )
+        });
+
+        owner.require_auth();
+        if commitment.owner != owner {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::Unauthorized, "settle_with_conversion");
+        }
+
+        let current_time = e.ledger().timestamp();
+        if current_time < commitment.expires_at {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotExpired, "settle_with_conversion");
+        }
+
+        let active_status = String::from_str(&e, "active");
+        if commitment.status != active_status {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotActive, "settle_with_conversion");
+        }
+        require_fresh_value(&e, &commitment, "settle_with_conversion");
+        require_not_frozen(&e, &commitment_id, "settle_with_conversion");
+
+        if is_tracking_only(&e, &commitment_id) {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::TrackingOnly, "settle_with_conversion");
+        }
+
+        let notice = e
+            .storage()
+            .instance()
+            .get::<_, DelistingNotice>(&DataKey::DelistedAsset(commitment.asset_address.clone()));
+        let (swap_adapter, target_asset) = match notice {
+            Some(DelistingNotice {
+                swap_adapter: Some(adapter),
+                target_asset: Some(target),
+                ..
+            }) => (adapter, target),
+            _ => {
+                set_reentrancy_guard(&e, false);
+                fail(
+                    &e,
+                    CommitmentError::NoConversionRoute,
+                    "settle_with_conversion",
+                )
+            }
+        };
+
+        // EFFECTS: Update state before external calls
+        let settlement_amount = commitment.current_value;
+        let (late_fee_amount, days_late) = late_fee(&e, &commitment, current_time);
+        let accrued_yield = take_accrued_yield(&e, &commitment);
+        credit_penalty_share(&e, &commitment);
+        commitment.status = String::from_str(&e, "settled");
+        adjust_active(&e, &commitment.owner, -1);
+        adjust_count(&e, DataKeyExt::SettledCount, 1);
+        set_commitment(&e, &commitment);
+
+        adjust_tvl(&e, &commitment.asset_address, -settlement_amount);
+
+        // INTERACTIONS: Collect late fee, swap proceeds, pay out, settle NFT
+        collect_late_fee(&e, &commitment, late_fee_amount, days_late);
+        let swap_amount = settlement_amount - late_fee_amount + accrued_yield;
+        let contract_address = e.current_contract_address();
+        let mut amount_out: i128 = 0;
+        if swap_amount > 0 {
+            amount_out = swap_proceeds(
+                &e,
+                &commitment.asset_address,
+                &swap_adapter,
+                &target_asset,
+                swap_amount,
+                min_amount_out,
+                "settle_with_conversion",
+            );
+            let payout_address = resolve_payout_address(
+                &e,
+                &commitment.commitment_id,
+                &commitment.owner,
+                &target_asset,
+            );
+            token::Client::new(&e, &target_asset).transfer(
+                &contract_address,
+                &payout_address,
+                &amount_out,
+            );
+        }
+        issue_receipt(
+            &e,
+            &commitment,
+            amount_out,
+            0,
+            late_fee_amount,
+            OUTCOME_SETTLED,
+        );
+
+        let nft_contract = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::NftContract)
+            .unwrap_or_else(|| {
+                set_reentrancy_guard(&e, false);
+                fail(
+                    &e,
+                    CommitmentError::NotInitialized,
+                    "settle_with_conversion",
+                )
+            });
+        call_nft_settle(
+            &e,
+            &nft_contract,
+            commitment.nft_token_id,
+            settlement_amount,
+        );
+
+        // Clear reentrancy guard
+        set_reentrancy_guard(&e, false);
+
+        notify(&e, &commitment.owner, NOTIFY_SETTLEMENT, &commitment_id);
+        publish_event(
+            &e,
+            (symbol_short!("Settled"), commitment_id),
+            (
+                settlement_amount,
+                current_time,
+                rules_hash(&e, &commitment.rules),
+            ),
+        );
+        publish_event(
+            &e,
+            (
+                Symbol::new(&e, "Converted"),
+                commitment.asset_address,
+                target_asset,
+            ),
+            (swap_amount, amount_out),
+        );
+
+        amount_out
+    }
+
+    /// Settle a matured commitment and re-commit its proceeds under `new_rules`.
+    ///
+    /// The settlement amount never leaves escrow: the old commitment is
+    /// settled (receipt, NFT settlement, `Settled` event) and a new commitment
+    /// in the same asset is opened for the same owner with the proceeds.
+    /// Payout routes and payout policy conversions do not apply. Returns the
+    /// new commitment id.
+    pub fn settle_and_rollover(
+        e: Env,
+        commitment_id: String,
+        new_rules: CommitmentRules,
+    ) -> String {
+        Self::roll_over(e, commitment_id, new_rules, true)
+    }
+
+    /// Opt a commitment into rolling over under `new_rules` when `settle` or
+    /// `keeper_settle` is called at expiry (owner only, while active).
+    ///
+    /// A rolled commitment behaves as with `settle_and_rollover`: no proceeds,
+    /// keeper reward, payout split or stream are paid out. Pass `enabled =
+    /// false` to settle normally again.
+    pub fn set_auto_roll(e: Env, commitment_id: String, enabled: bool, new_rules: CommitmentRules) {
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "set_auto_roll"));
+        commitment.owner.require_auth();
+        if commitment.status != String::from_str(&e, "active") {
+            fail(&e, CommitmentError::NotActive, "set_auto_roll");
+        }
+        let key = DataKeyExt::AutoRoll(commitment_id.clone());
+        if enabled {
+            if is_tracking_only(&e, &commitment_id) {
+                fail(&e, CommitmentError::TrackingOnly, "set_auto_roll");
+            }
+            Self::validate_rules(&e, &new_rules);
+            e.storage().instance().set(&key, &new_rules);
+        } else {
+            e.storage().instance().remove(&key);
+        }
+        publish_event(&e, (symbol_short!("AutoRoll"), commitment_id), enabled);
+    }
+
+    /// Get the rules a commitment will roll into at settlement, if opted in
+    pub fn get_auto_roll(e: Env, commitment_id: String) -> Option<CommitmentRules> {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::AutoRoll(commitment_id))
+    }
+
+    /// Settle a matured commitment into a new one funded by its proceeds.
+    /// `owner_auth` is false for auto-rolls, which the owner pre-authorized.
+    fn roll_over(
+        e: Env,
+        commitment_id: String,
+        new_rules: CommitmentRules,
+        owner_auth: bool,
+    ) -> String {
+        // Reentrancy protection
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+
+        // Check if contract is paused
+        Pausable::require_not_paused(&e);
+
+        // CHECKS: Get and validate commitment
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(
+                &e,
+                CommitmentError::CommitmentNotFound,
+                "settle_and_rollover",
+            )
+        });
+        if owner_auth {
+            commitment.owner.require_auth();
+        }
+
+        let current_time = e.ledger().timestamp();
+        if current_time < commitment.expires_at {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotExpired, "settle_and_rollover");
+        }
+
+        let active_status = String::from_str(&e, "active");
+        if commitment.status != active_status {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotActive, "settle_and_rollover");
+        }
+        require_fresh_value(&e, &commitment, "settle_and_rollover");
+        require_not_frozen(&e, &commitment_id, "settle_and_rollover");
+
+        if is_tracking_only(&e, &commitment_id) {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::TrackingOnly, "settle_and_rollover");
+        }
+
+        // EFFECTS: Update state before external calls
+        let settlement_amount = commitment.current_value;
+        let (late_fee_amount, days_late) = late_fee(&e, &commitment, current_time);
+        let rollover_amount =
+            settlement_amount - late_fee_amount + take_accrued_yield(&e, &commitment);
+        credit_penalty_share(&e, &commitment);
+        commitment.status = String::from_str(&e, "settled");
+        adjust_active(&e, &commitment.owner, -1);
+        adjust_count(&e, DataKeyExt::SettledCount, 1);
+        set_commitment(&e, &commitment);
+
+        adjust_tvl(&e, &commitment.asset_address, -settlement_amount);
+
+        // INTERACTIONS: Collect late fee, settle NFT (proceeds stay in escrow)
+        collect_late_fee(&e, &commitment, late_fee_amount, days_late);
+        issue_receipt(
+            &e,
+            &commitment,
+            rollover_amount,
+            0,
+            late_fee_amount,
+            OUTCOME_SETTLED,
+        );
+
+        let nft_contract = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::NftContract)
+            .unwrap_or_else(|| {
+                set_reentrancy_guard(&e, false);
+                fail(&e, CommitmentError::NotInitialized, "settle_and_rollover")
+            });
+        call_nft_settle(
+            &e,
+            &nft_contract,
+            commitment.nft_token_id,
+            settlement_amount,
+        );
+
+        // Clear reentrancy guard before re-entering the creation path
+        set_reentrancy_guard(&e, false);
+
+        notify(&e, &commitment.owner, NOTIFY_SETTLEMENT, &commitment_id);
+        publish_event(
+            &e,
+            (symbol_short!("Settled"), commitment_id.clone()),
+            (
+                settlement_amount,
+                current_time,
+                rules_hash(&e, &commitment.rules),
+            ),
+        );
+
+        // Re-commit the proceeds; creation checks (sunset, delisting, rules) still apply
+        let new_commitment_id = Self::open_commitment(
+            e.clone(),
+            commitment.owner.clone(),
+            rollover_amount,
+            commitment.asset_address.clone(),
+            new_rules,
+            Funding::Prefunded,
+        );
+        e.storage()
+            .instance()
+            .remove(&DataKeyExt::AutoRoll(commitment_id.clone()));
+        e.storage().instance().set(
+            &DataKey::RolledOverInto(commitment_id.clone()),
+            &new_commitment_id,
+        );
+        publish_event(
+            &e,
+            (Symbol::new(&e, "RolledOver"), commitment_id),
+            (new_commitment_id.clone(), rollover_amount),
+        );
+
+        new_commitment_id
+    }
+
+    /// Get the commitment a settled commitment was rolled over into, if any
+    pub fn get_rolled_over_into(e: Env, commitment_id: String) -> Option<String> {
+        e.storage()
+            .instance()
+            .get(&DataKey::RolledOverInto(commitment_id))
+    }
+
+    /// Opt a commitment in or out of auto-compounding (owner only).
+    ///
+    /// When enabled, yield reported through `report_yield` is added to both
+    /// `amount` and `current_value`; otherwise it accrues for `claim_yield`.
+    pub fn set_auto_compound(e: Env, commitment_id: String, enabled: bool) {
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "set_auto_compound"));
+        commitment.owner.require_auth();
+        e.storage()
+            .instance()
+            .set(&DataKey::AutoCompound(commitment_id.clone()), &enabled);
+        publish_event(&e, (symbol_short!("AutoCmp"), commitment_id), enabled);
+    }
+
+    /// Whether reported yield is folded into the commitment's principal
+    pub fn is_auto_compound(e: Env, commitment_id: String) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::AutoCompound(commitment_id))
+            .unwrap_or(false)
+    }
+
+    /// Report yield earned by a commitment's allocation and deposit it into escrow.
+    ///
+    /// Restricted to the allocation contract, the admin, or an attestation
+    /// engine verifier; `caller` transfers
+    /// `amount` of the commitment's asset to core. Auto-compounding
+    /// commitments grow `amount` and `current_value` and emit `Compounded`;
+    /// others accrue the yield for the owner to claim.
+    pub fn report_yield(e: Env, caller: Address, commitment_id: String, amount: i128) {
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+        Pausable::require_not_paused(&e);
+
+        caller.require_auth();
+        if !is_yield_reporter(&e, &caller) {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::Unauthorized, "report_yield");
+        }
+        if amount <= 0 {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::InvalidAmount, "report_yield");
+        }
+
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::CommitmentNotFound, "report_yield")
+        });
+        if commitment.status != String::from_str(&e, "active") {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotActive, "report_yield");
+        }
+        if is_tracking_only(&e, &commitment_id) {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::TrackingOnly, "report_yield");
+        }
+
+        // EFFECTS
+        let compound = Self::is_auto_compound(e.clone(), commitment_id.clone());
+        let mut accrued: i128 = 0;
+        if compound {
+            commitment.amount = SafeMath::add(commitment.amount, amount);
+            commitment.current_value = SafeMath::add(commitment.current_value, amount);
+            set_commitment(&e, &commitment);
+            adjust_tvl(&e, &commitment.asset_address, amount);
+        } else {
+            let accrued_key = DataKey::AccruedYield(commitment_id.clone());
+            accrued = SafeMath::add(
+                e.storage()
+                    .instance()
+                    .get::<_, i128>(&accrued_key)
+                    .unwrap_or(0),
+                amount,
+            );
+            e.storage().instance().set(&accrued_key, &accrued);
+            let unclaimed_key = DataKey::UnclaimedYield(commitment.asset_address.clone());
+            let unclaimed = e
+                .storage()
+                .instance()
+                .get::<_, i128>(&unclaimed_key)
+                .unwrap_or(0);
+            e.storage()
+                .instance()
+                .set(&unclaimed_key, &SafeMath::add(unclaimed, amount));
+        }
+
+        let ledger_key = DataKey::YieldLedger(commitment_id.clone());
+        let mut ledger = e
+            .storage()
+            .persistent()
+            .get::<_, Vec<YieldCredit>>(&ledger_key)
+            .unwrap_or(Vec::new(&e));
+        if ledger.len() >= MAX_YIELD_LEDGER_ENTRIES {
+            ledger.pop_front();
         }
+        ledger.push_back(YieldCredit {
+            amount,
+            credited_by: caller.clone(),
+            compounded: compound,
+            timestamp: e.ledger().timestamp(),
+        });
+        e.storage().persistent().set(&ledger_key, &ledger);
 
-        // Store admin and NFT contract address
-        e.storage().instance().set(&DataKey::Admin, &admin);
-        e.storage()
-            .instance()
-            .set(&DataKey::NftContract, &nft_contract);
+        // INTERACTIONS
+        let contract_address = e.current_contract_address();
+        transfer_assets(
+            &e,
+            &caller,
+            &contract_address,
+            &commitment.asset_address,
+            amount,
+        );
 
-        // Initialize total commitments counter
-        e.storage()
-            .instance()
-            .set(&DataKey::TotalCommitments, &0u64);
+        set_reentrancy_guard(&e, false);
+        if compound {
+            publish_event(
+                &e,
+                (Symbol::new(&e, "Compounded"), commitment_id),
+                (
+                    amount,
+                    commitment.amount,
+                    commitment.current_value,
+                    e.ledger().timestamp(),
+                ),
+            );
+        } else {
+            publish_event(
+                &e,
+                (symbol_short!("YieldAcc"), commitment_id),
+                (amount, accrued, e.ledger().timestamp()),
+            );
+        }
+    }
 
-        // Initialize empty list for time-range queries
+    /// Get yield reported for a commitment that has not been claimed yet
+    pub fn get_accrued_yield(e: Env, commitment_id: String) -> i128 {
         e.storage()
             .instance()
-            .set(&DataKey::AllCommitmentIds, &Vec::<String>::new(&e));
+            .get(&DataKey::AccruedYield(commitment_id))
+            .unwrap_or(0)
+    }
 
-        // Initialize total value locked counter
+    /// Get the most recent yield credits for a commitment (oldest first)
+    pub fn get_yield_ledger(e: Env, commitment_id: String) -> Vec<YieldCredit> {
         e.storage()
-            .instance()
-            .set(&DataKey::TotalValueLocked, &0i128);
-
-        // Initialize paused state (default: not paused)
-        e.storage().instance().set(&Pausable::PAUSED_KEY, &false);
+            .persistent()
+            .get(&DataKey::YieldLedger(commitment_id))
+            .unwrap_or(Vec::new(&e))
     }
 
-    /// Create a new commitment
-    ///
-    /// # Reentrancy Protection
-    /// This function uses checks-effects-interactions pattern:
-    /// 1. Checks: Validate inputs
-    /// 2. Effects: Update state (commitment storage, counters)
-    /// 3. Interactions: External calls (token transfer, NFT mint)
-    /// Reentrancy guard prevents recursive calls.
-    ///
-    /// # Formal Verification
-    /// **Preconditions:**
-    /// - `amount > 0`
-    /// - `rules.duration_days > 0`
-    /// - `rules.max_loss_percent <= 100`
-    /// - `rules.commitment_type ∈ {"safe", "balanced", "aggressive"}`
-    /// - Contract is initialized
-    /// - `reentrancy_guard == false`
-    ///
-    /// **Postconditions:**
-    /// - Returns unique `commitment_id`
-    /// - `get_commitment(commitment_id).owner == owner`
-    /// - `get_commitment(commitment_id).amount == amount`
-    /// - `get_commitment(commitment_id).status == "active"`
-    /// - `get_total_commitments() == old(get_total_commitments()) + 1`
-    /// - `reentrancy_guard == false`
-    ///
-    /// **Invariants Maintained:**
-    /// - INV-1: Total commitments consistency
-    /// - INV-2: Commitment balance conservation
-    /// - INV-3: Owner commitment list consistency
-    /// - INV-4: Reentrancy guard invariant
+    /// Pay out a commitment's accrued yield to the owner's payout route.
     ///
-    /// **Security Properties:**
-    /// - SP-1: Reentrancy protection
-    /// - SP-2: Access control
-    /// - SP-4: State consistency
-    /// - SP-5: Token conservation
-    pub fn create_commitment(
-        e: Env,
-        owner: Address,
-        amount: i128,
-        asset_address: Address,
-        rules: CommitmentRules,
-    ) -> String {
-        // Reentrancy protection
+    /// Settlement pays out accrued yield with the proceeds; this remains
+    /// available in any status so yield left after an early exit or
+    /// liquidation is never stranded. Returns the amount paid.
+    pub fn claim_yield(e: Env, commitment_id: String) -> i128 {
         require_no_reentrancy(&e);
         set_reentrancy_guard(&e, true);
-
-        // Check if contract is paused
         Pausable::require_not_paused(&e);
 
-        // Rate limit: per-owner commitment creation
-        let fn_symbol = symbol_short!("create");
-        RateLimiter::check(&e, &owner, &fn_symbol);
+        let commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::CommitmentNotFound, "claim_yield")
+        });
+        commitment.owner.require_auth();
 
-        // Validate amount > 0 using shared utilities
-        Validation::require_positive(amount);
+        // EFFECTS
+        let accrued = take_accrued_yield(&e, &commitment);
+        if accrued == 0 {
+            set_reentrancy_guard(&e, false);
+            return 0;
+        }
 
-        // Validate rules
-        Self::validate_rules(&e, &rules);
+        // INTERACTIONS
+        let payout_address = resolve_payout_address(
+            &e,
+            &commitment.commitment_id,
+            &commitment.owner,
+            &commitment.asset_address,
+        );
+        token::Client::new(&e, &commitment.asset_address).transfer(
+            &e.current_contract_address(),
+            &payout_address,
+            &accrued,
+        );
 
-        // Reject duration_days that would cause expires_at to overflow u64
-        let expires_at = TimeUtils::checked_calculate_expiration(&e, rules.duration_days)
-            .unwrap_or_else(|| {
-                set_reentrancy_guard(&e, false);
-                fail(&e, CommitmentError::ExpirationOverflow, "create_commitment")
-            });
+        set_reentrancy_guard(&e, false);
+        publish_event(
+            &e,
+            (symbol_short!("YieldClm"), commitment_id),
+            (accrued, e.ledger().timestamp()),
+        );
+        accrued
+    }
 
-        // OPTIMIZATION: Read both counters and NFT contract once to minimize storage operations
-        let (current_total, current_tvl, nft_contract) = {
-            let total = e
-                .storage()
-                .instance()
-                .get::<_, u64>(&DataKey::TotalCommitments)
-                .unwrap_or(0);
-            let tvl = e
-                .storage()
-                .instance()
-                .get::<_, i128>(&DataKey::TotalValueLocked)
-                .unwrap_or(0);
-            let nft = e
-                .storage()
-                .instance()
-                .get::<_, Address>(&DataKey::NftContract)
-                .unwrap_or_else(|| {
-                    set_reentrancy_guard(&e, false);
-                    fail(&e, CommitmentError::NotInitialized, "create_commitment")
-                });
-            (total, tvl, nft)
+    /// Preview what `early_exit` would charge and pay out right now, without
+    /// executing it.
+    pub fn quote_early_exit(e: Env, commitment_id: String) -> EarlyExitQuote {
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "quote_early_exit"));
+        if commitment.status != String::from_str(&e, "active") {
+            fail(&e, CommitmentError::NotActive, "quote_early_exit");
+        }
+
+        let tracking_only = is_tracking_only(&e, &commitment_id);
+        let penalty_amount = early_exit_penalty(&e, &commitment, tracking_only);
+        let net_amount = if tracking_only {
+            0
+        } else {
+            SafeMath::sub(commitment.current_value, penalty_amount)
+        };
+        let penalty_bps = if commitment.current_value > 0 {
+            SafeMath::div(
+                SafeMath::mul(penalty_amount, 10_000),
+                commitment.current_value,
+            ) as u32
+        } else {
+            0
         };
+        EarlyExitQuote {
+            penalty_amount,
+            net_amount,
+            penalty_bps,
+            queued: !tracking_only && exit_would_queue(&e, &commitment.asset_address, net_amount),
+        }
+    }
 
-        // Generate unique commitment ID using counter
-        let commitment_id = Self::generate_commitment_id(&e, current_total);
+    pub fn early_exit(e: Env, commitment_id: String, caller: Address) {
+        // Reentrancy protection
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
 
-        // CHECKS: Validate commitment doesn't already exist
-        if has_commitment(&e, &commitment_id) {
+        // Check if contract is paused
+        Pausable::require_not_paused(&e);
+
+        // CHECKS: Get and validate commitment
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
             set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::InvalidStatus, "create_commitment");
+            fail(&e, CommitmentError::CommitmentNotFound, "early_exit")
+        });
+
+        // Verify caller is owner
+        caller.require_auth();
+        if commitment.owner != caller {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::Unauthorized, "early_exit");
         }
 
-        // EFFECTS: Update state before external calls (expires_at already computed above with overflow check)
-        let current_timestamp = TimeUtils::now(&e);
+        // Verify commitment is active
+        let active_status = String::from_str(&e, "active");
+        if commitment.status != active_status {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotActive, "early_exit");
+        }
+        require_not_frozen(&e, &commitment_id, "early_exit");
 
-        // Create commitment data
-        let commitment = Commitment {
-            commitment_id: commitment_id.clone(),
-            owner: owner.clone(),
-            nft_token_id: 0, // Will be set after NFT mint
-            rules: rules.clone(),
-            amount,
-            asset_address: asset_address.clone(),
-            created_at: current_timestamp,
-            expires_at,
-            current_value: amount, // Initially same as amount
-            status: String::from_str(&e, "active"),
+        let tracking_only = is_tracking_only(&e, &commitment_id);
+        if !tracking_only {
+            consume_approval(
+                &e,
+                ApprovalAction::EarlyExit(commitment_id.clone()),
+                commitment.current_value,
+                "early_exit",
+            );
+        }
+
+        // EFFECTS: Calculate penalty using shared utilities (waived after a sunset's final date)
+        let penalty_amount = early_exit_penalty(&e, &commitment, tracking_only);
+        let returned_amount = if tracking_only {
+            0
+        } else {
+            SafeMath::sub(commitment.current_value, penalty_amount)
         };
+        let original_value = commitment.current_value;
 
-        // Store commitment data (before external calls)
-        set_commitment(&e, &commitment);
+        // Under market stress the payout is queued and paid pro-rata over several rounds
+        if !tracking_only && exit_must_queue(&e, &commitment.asset_address, returned_amount) {
+            let queue_key = DataKey::ExitQueue(commitment.asset_address.clone());
+            let mut queue = exit_queue(&e, &commitment.asset_address);
+            if queue.len() >= MAX_EXIT_QUEUE {
+                set_reentrancy_guard(&e, false);
+                fail(&e, CommitmentError::ExitQueueFull, "early_exit");
+            }
+            queue.push_back(commitment_id.clone());
+            e.storage().instance().set(&queue_key, &queue);
+            e.storage().instance().set(
+                &DataKey::QueuedExit(commitment_id.clone()),
+                &QueuedExit {
+                    commitment_id: commitment_id.clone(),
+                    owner: commitment.owner.clone(),
+                    asset_address: commitment.asset_address.clone(),
+                    value: original_value,
+                    penalty: penalty_amount,
+                    owed: returned_amount,
+                    paid: 0,
+                    queued_at: e.ledger().timestamp(),
+                },
+            );
 
-        // Update owner's commitment list
-        let mut owner_commitments = e
-            .storage()
-            .instance()
-            .get::<_, Vec<String>>(&DataKey::OwnerCommitments(owner.clone()))
-            .unwrap_or(Vec::new(&e));
-        owner_commitments.push_back(commitment_id.clone());
-        e.storage().instance().set(
-            &DataKey::OwnerCommitments(owner.clone()),
-            &owner_commitments,
-        );
+            commitment.status = String::from_str(&e, "exit_queued");
+            adjust_active(&e, &commitment.owner, -1);
+            commitment.current_value = 0;
+            set_commitment(&e, &commitment);
+            adjust_tvl(&e, &commitment.asset_address, -original_value);
 
-        // OPTIMIZATION: Increment both counters using already-read values
-        e.storage()
-            .instance()
-            .set(&DataKey::TotalCommitments, &(current_total + 1));
-        e.storage()
-            .instance()
-            .set(&DataKey::TotalValueLocked, &(current_tvl + amount));
+            set_reentrancy_guard(&e, false);
+            publish_event(
+                &e,
+                (symbol_short!("ExitQd"), commitment_id, caller),
+                (penalty_amount, returned_amount, e.ledger().timestamp()),
+            );
+            return;
+        }
 
-        // Append to AllCommitmentIds for time-range queries (#143)
-        let mut all_ids = e
-            .storage()
-            .instance()
-            .get::<_, Vec<String>>(&DataKey::AllCommitmentIds)
-            .unwrap_or(Vec::new(&e));
-        all_ids.push_back(commitment_id.clone());
-        e.storage()
-            .instance()
-            .set(&DataKey::AllCommitmentIds, &all_ids);
+        // Update commitment status to early_exit
+        commitment.status = String::from_str(&e, "early_exit");
+        commitment.current_value = 0; // All value has been distributed
+        adjust_active(&e, &commitment.owner, -1);
+        set_commitment(&e, &commitment);
 
-        // INTERACTIONS: External calls (token transfer, NFT mint)
-        // Transfer assets from owner to contract
+        // Decrease total value locked by full current value (no longer locked)
+        if !tracking_only {
+            adjust_tvl(&e, &commitment.asset_address, -original_value);
+        }
+
+        // INTERACTIONS: External calls (token transfer)
+        // Transfer remaining amount (after penalty) to the beneficiary or owner
         let contract_address = e.current_contract_address();
-        transfer_assets(&e, &owner, &contract_address, &asset_address, amount);
+        let token_client = token::Client::new(&e, &commitment.asset_address);
 
-        // Mint NFT
-        let nft_token_id = call_nft_mint(
-            &e,
-            &nft_contract,
-            &owner,
-            &commitment_id,
-            rules.duration_days,
-            rules.max_loss_percent,
-            &rules.commitment_type,
-            amount,
-            &asset_address,
-            rules.early_exit_penalty,
-        );
+        if returned_amount > 0 {
+            let payout_address = resolve_payout_address(
+                &e,
+                &commitment_id,
+                &commitment.owner,
+                &commitment.asset_address,
+            );
+            token_client.transfer(&contract_address, &payout_address, &returned_amount);
+        }
 
-        // Update commitment with NFT token ID
-        let mut updated_commitment = commitment;
-        updated_commitment.nft_token_id = nft_token_id;
-        set_commitment(&e, &updated_commitment);
+        // Mark the NFT inactive, issue the receipt and emit the early exit event
+        close_early_exit(&e, &commitment, &caller, penalty_amount, returned_amount);
 
         // Clear reentrancy guard
         set_reentrancy_guard(&e, false);
+    }
 
-        // Emit creation event
-        e.events().publish(
-            (
-                symbol_short!("Created"),
-                commitment_id.clone(),
-                owner.clone(),
-            ),
-            (amount, rules, nft_token_id, e.ledger().timestamp()),
+    /// Configure the settlement fee and the treasury it is paid to (admin only).
+    ///
+    /// `settlement_fee_bps` may not exceed `MAX_SETTLEMENT_FEE_BPS`.
+    pub fn set_fee_config(e: Env, caller: Address, settlement_fee_bps: u32, treasury: Address) {
+        require_admin(&e, &caller);
+        if settlement_fee_bps > MAX_SETTLEMENT_FEE_BPS {
+            fail(&e, CommitmentError::InvalidFeeConfig, "set_fee_config");
+        }
+        let config = FeeConfig {
+            settlement_fee_bps,
+            treasury: Some(treasury.clone()),
+        };
+        e.storage().instance().set(&DataKey::FeeConfig, &config);
+        publish_event(
+            &e,
+            (symbol_short!("FeeCfg"), treasury),
+            (settlement_fee_bps, caller, e.ledger().timestamp()),
         );
-        commitment_id
     }
 
-    /// Get commitment details
-    pub fn get_commitment(e: Env, commitment_id: String) -> Commitment {
-        read_commitment(&e, &commitment_id)
-            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "get_commitment"))
+    pub fn get_fee_config(e: Env) -> FeeConfig {
+        fee_config(&e)
     }
 
-    /// List all commitment IDs owned by the given address.
-    ///
-    /// This is a convenience wrapper around `get_owner_commitments` with a
-    /// name optimized for off-chain indexers and UIs.
-    pub fn list_commitments_by_owner(e: Env, owner: Address) -> Vec<String> {
-        Self::get_owner_commitments(e, owner)
+    /// Replace the loyalty fee tiers (admin only). An empty list disables
+    /// discounts. At most `MAX_FEE_TIERS`; each discount is at most 10000 bps.
+    pub fn set_fee_tiers(e: Env, caller: Address, tiers: Vec<FeeTier>) {
+        require_admin(&e, &caller);
+        if tiers.len() > MAX_FEE_TIERS
+            || tiers
+                .iter()
+                .any(|t| t.discount_bps > 10_000 || t.min_settled_amount < 0)
+        {
+            fail(&e, CommitmentError::InvalidFeeConfig, "set_fee_tiers");
+        }
+        if tiers.is_empty() {
+            e.storage().instance().remove(&DataKeyExt::FeeTiers);
+        } else {
+            e.storage().instance().set(&DataKeyExt::FeeTiers, &tiers);
+        }
+        publish_event(
+            &e,
+            (symbol_short!("FeeTiers"),),
+            (tiers.len(), caller, e.ledger().timestamp()),
+        );
     }
 
-    /// Get all commitments for an owner
-    pub fn get_owner_commitments(e: Env, owner: Address) -> Vec<String> {
+    pub fn get_fee_tiers(e: Env) -> Vec<FeeTier> {
         e.storage()
             .instance()
-            .get::<_, Vec<String>>(&DataKey::OwnerCommitments(owner))
+            .get(&DataKeyExt::FeeTiers)
             .unwrap_or(Vec::new(&e))
     }
 
-    /// Get total number of commitments
-    pub fn get_total_commitments(e: Env) -> u64 {
-        e.storage()
-            .instance()
-            .get::<_, u64>(&DataKey::TotalCommitments)
-            .unwrap_or(0)
+    /// Loyalty tier `address` currently qualifies for, if any.
+    pub fn get_fee_tier(e: Env, address: Address) -> Option<FeeTier> {
+        fee_tier(&e, &address)
     }
 
-    /// Get total value locked across all active commitments.
-    pub fn get_total_value_locked(e: Env) -> i128 {
-        e.storage()
-            .instance()
-            .get::<_, i128>(&DataKey::TotalValueLocked)
-            .unwrap_or(0)
+    /// Change one admin setting (admin only), applying the same validation as
+    /// its dedicated setter. Emits a `ConfigSet` event with the old and new value.
+    pub fn set_config(e: Env, caller: Address, key: ConfigKey, value: ConfigValue) {
+        // Each arm's setter checks admin auth, which may only be required once
+        let old = Self::get_config(e.clone(), key.clone());
+        match (&key, value.clone()) {
+            (ConfigKey::SettlementFeeBps, ConfigValue::U32(bps)) => {
+                let treasury = fee_config(&e).treasury.unwrap_or_else(|| {
+                    require_admin(&e, &caller);
+                    fail(&e, CommitmentError::InvalidFeeConfig, "set_config")
+                });
+                Self::set_fee_config(e.clone(), caller.clone(), bps, treasury);
+            }
+            (ConfigKey::Treasury, ConfigValue::Address(treasury)) => {
+                let bps = fee_config(&e).settlement_fee_bps;
+                Self::set_fee_config(e.clone(), caller.clone(), bps, treasury);
+            }
+            (ConfigKey::KeeperRewardBps, ConfigValue::U32(bps)) => {
+                Self::set_keeper_reward_bps(e.clone(), caller.clone(), bps);
+            }
+            (ConfigKey::ReferralShareBps, ConfigValue::U32(bps)) => {
+                Self::set_referral_share_bps(e.clone(), caller.clone(), bps);
+            }
+            (ConfigKey::DrawdownAlertBps, ConfigValue::U32(bps)) => {
+                Self::set_drawdown_alert_bps(e.clone(), caller.clone(), bps);
+            }
+            (ConfigKey::StaleValueSeconds, ConfigValue::U64(seconds)) => {
+                Self::set_stale_value_seconds(e.clone(), caller.clone(), seconds);
+            }
+            (ConfigKey::RequireFreshValue, ConfigValue::Bool(required)) => {
+                Self::set_require_fresh_value(e.clone(), caller.clone(), required);
+            }
+            (ConfigKey::TwapWindowSeconds, ConfigValue::U64(seconds)) => {
+                Self::set_twap_window(e.clone(), caller.clone(), seconds);
+            }
+            (ConfigKey::AttestationEngine, ConfigValue::Address(engine)) => {
+                Self::set_attestation_engine(e.clone(), caller.clone(), engine);
+            }
+            (ConfigKey::AllocationContract, ConfigValue::Address(allocation)) => {
+                Self::set_allocation_contract(e.clone(), caller.clone(), allocation);
+            }
+            (ConfigKey::RenewalFeeBps, ConfigValue::U32(bps)) => {
+                Self::set_renewal_fee_bps(e.clone(), caller.clone(), bps);
+            }
+            (ConfigKey::MaxActivePerOwner, ConfigValue::U32(max)) => {
+                Self::set_max_active_per_owner(e.clone(), caller.clone(), max);
+            }
+            (ConfigKey::ViolationBountyBps, ConfigValue::U32(bps)) => {
+                Self::set_violation_bounty_bps(e.clone(), caller.clone(), bps);
+            }
+            _ => {
+                require_admin(&e, &caller);
+                fail(&e, CommitmentErrorExt::InvalidConfigValue, "set_config")
+            }
+        }
+        publish_event(
+            &e,
+            (symbol_short!("ConfigSet"), key),
+            (old, value, caller, e.ledger().timestamp()),
+        );
     }
 
-    /// Get commitment IDs created between two timestamps (inclusive).
-    /// For analytics/dashboards. Gas cost is O(n) in total commitments; consider pagination for large n.
-    pub fn get_commitments_created_between(
-        e: Env,
-        from_ts: u64,
-        to_ts: u64,
-    ) -> Vec<String> {
-        let all_ids = e
-            .storage()
-            .instance()
-            .get::<_, Vec<String>>(&DataKey::AllCommitmentIds)
-            .unwrap_or(Vec::new(&e));
-        let mut out = Vec::new(&e);
-        for id in all_ids.iter() {
-            if let Some(c) = read_commitment(&e, &id) {
-                if c.created_at >= from_ts && c.created_at <= to_ts {
-                    out.push_back(id.clone());
-                }
+    /// Current value of an admin setting, or None for an unset address.
+    pub fn get_config(e: Env, key: ConfigKey) -> Option<ConfigValue> {
+        let address = |data_key: DataKey| {
+            e.storage()
+                .instance()
+                .get::<_, Address>(&data_key)
+                .map(ConfigValue::Address)
+        };
+        match key {
+            ConfigKey::SettlementFeeBps => {
+                Some(ConfigValue::U32(fee_config(&e).settlement_fee_bps))
+            }
+            ConfigKey::Treasury => fee_config(&e).treasury.map(ConfigValue::Address),
+            ConfigKey::KeeperRewardBps => {
+                Some(ConfigValue::U32(Self::get_keeper_reward_bps(e.clone())))
+            }
+            ConfigKey::ReferralShareBps => {
+                Some(ConfigValue::U32(Self::get_referral_share_bps(e.clone())))
+            }
+            ConfigKey::DrawdownAlertBps => {
+                Some(ConfigValue::U32(Self::get_drawdown_alert_bps(e.clone())))
+            }
+            ConfigKey::StaleValueSeconds => Some(ConfigValue::U64(
+                e.storage()
+                    .instance()
+                    .get(&DataKey::StaleValueSeconds)
+                    .unwrap_or(DEFAULT_STALE_VALUE_SECONDS),
+            )),
+            ConfigKey::RequireFreshValue => {
+                Some(ConfigValue::Bool(Self::get_require_fresh_value(e.clone())))
+            }
+            ConfigKey::TwapWindowSeconds => {
+                Some(ConfigValue::U64(Self::get_twap_window(e.clone())))
+            }
+            ConfigKey::AttestationEngine => address(DataKey::AttestationEngine),
+            ConfigKey::AllocationContract => address(DataKey::AllocationContract),
+            ConfigKey::RenewalFeeBps => {
+                Some(ConfigValue::U32(Self::get_renewal_fee_bps(e.clone())))
+            }
+            ConfigKey::MaxActivePerOwner => {
+                Some(ConfigValue::U32(Self::get_max_active_per_owner(e.clone())))
+            }
+            ConfigKey::ViolationBountyBps => {
+                Some(ConfigValue::U32(Self::get_violation_bounty_bps(e.clone())))
             }
         }
-        out
     }
 
-    /// Get admin address
-    pub fn get_admin(e: Env) -> Address {
+    /// Set where early-exit penalties go (admin only).
+    ///
+    /// Applies to exits completed after the change, including queued exits
+    /// still waiting for liquidity.
+    pub fn set_penalty_recipient(e: Env, caller: Address, recipient: PenaltyRecipient) {
+        require_admin(&e, &caller);
         e.storage()
             .instance()
-            .get::<_, Address>(&DataKey::Admin)
-            .unwrap_or_else(|| fail(&e, CommitmentError::NotInitialized, "get_admin"))
+            .set(&DataKey::PenaltyRecipient, &recipient);
+        publish_event(
+            &e,
+            (Symbol::new(&e, "PenaltyRecipientSet"),),
+            (recipient, caller, e.ledger().timestamp()),
+        );
     }
 
-    /// Get NFT contract address
-    pub fn get_nft_contract(e: Env) -> Address {
+    pub fn get_penalty_recipient(e: Env) -> PenaltyRecipient {
         e.storage()
             .instance()
-            .get::<_, Address>(&DataKey::NftContract)
-            .unwrap_or_else(|| fail(&e, CommitmentError::NotInitialized, "get_nft_contract"))
+            .get(&DataKey::PenaltyRecipient)
+            .unwrap_or(PenaltyRecipient::Retain)
     }
 
-    /// Update commitment value (called by allocation logic or oracle-fed keeper).
-    /// Persists new_value to commitment.current_value and updates TotalValueLocked.
-    pub fn update_value(e: Env, commitment_id: String, new_value: i128) {
-        // Global per-function rate limit (per contract instance)
-        let fn_symbol = symbol_short!("upd_val");
-        let contract_address = e.current_contract_address();
-        RateLimiter::check(&e, &contract_address, &fn_symbol);
-
-        Validation::require_non_negative(new_value);
+    /// Configure the late settlement fee charged after the grace period (admin only).
+    pub fn set_late_fee_config(e: Env, caller: Address, config: LateFeeConfig) {
+        require_admin(&e, &caller);
+        if config.max_bps > 10_000 {
+            fail(&e, CommitmentError::InvalidFeeConfig, "set_late_fee_config");
+        }
+        e.storage().instance().set(&DataKey::LateFeeConfig, &config);
+    }
 
-        let mut commitment = read_commitment(&e, &commitment_id)
-            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "update_value"));
+    /// Get where a commitment stands relative to its maturity and grace period
+    pub fn get_maturity_status(e: Env, commitment_id: String) -> MaturityStatus {
+        let commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            fail(
+                &e,
+                CommitmentError::CommitmentNotFound,
+                "get_maturity_status",
+            )
+        });
+        let now = e.ledger().timestamp();
+        if commitment.status != String::from_str(&e, "active") {
+            MaturityStatus::Closed
+        } else if now < commitment.expires_at {
+            MaturityStatus::Locked
+        } else if now <= grace_ends_at(&commitment) {
+            MaturityStatus::InGrace
+        } else {
+            MaturityStatus::Overdue
+        }
+    }
 
-        let active_status = String::from_str(&e, "active");
-        if commitment.status != active_status {
-            fail(&e, CommitmentError::NotActive, "update_value");
+    /// Require N-of-M signer approval for commitments, early exits and top-ups
+    /// above `policy.threshold_amount` (admin only). A top-up is measured by
+    /// the commitment's amount after it.
+    pub fn set_approval_policy(e: Env, caller: Address, policy: ApprovalPolicy) {
+        require_admin(&e, &caller);
+        if policy.threshold_amount < 0
+            || policy.required == 0
+            || policy.required > policy.signers.len()
+            || policy.expiry_seconds == 0
+        {
+            fail(
+                &e,
+                CommitmentError::InvalidApprovalPolicy,
+                "set_approval_policy",
+            );
         }
+        e.storage()
+            .instance()
+            .set(&DataKeyExt::ApprovalPolicy, &policy);
+    }
 
-        let old_value = commitment.current_value;
-        commitment.current_value = new_value;
+    /// Remove the approval policy; large actions execute without approval.
+    pub fn clear_approval_policy(e: Env, caller: Address) {
+        require_admin(&e, &caller);
+        e.storage().instance().remove(&DataKeyExt::ApprovalPolicy);
+    }
 
-        // Check for violations after updating value
-        let loss_percent = if commitment.amount > 0 {
-            SafeMath::loss_percent(commitment.amount, commitment.current_value)
-        } else {
-            0
-        };
-        let max_loss = commitment.rules.max_loss_percent as i128;
-        let loss_violated = loss_percent > max_loss;
+    pub fn get_approval_policy(e: Env) -> Option<ApprovalPolicy> {
+        e.storage().instance().get(&DataKeyExt::ApprovalPolicy)
+    }
 
-        if loss_violated {
-            commitment.status = String::from_str(&e, "violated");
-            set_commitment(&e, &commitment);
-            e.events().publish(
-                (symbol_short!("Violated"), commitment_id.clone()),
-                (symbol_short!("RuleViol"), e.ledger().timestamp()),
-            );
-        } else {
-            set_commitment(&e, &commitment);
-            e.events().publish(
-                (symbol_short!("ValUpd"), commitment_id),
-                (new_value, e.ledger().timestamp()),
-            );
+    /// Open an approval request for a large action. Returns the request id.
+    ///
+    /// For `Create`, `owner` must match the action's owner; for `EarlyExit` and
+    /// `TopUp`, it must own the commitment. A new request for the same action replaces the
+    /// previous one. The request expires after the policy's `expiry_seconds`.
+    pub fn submit_approval_request(e: Env, owner: Address, action: ApprovalAction) -> u64 {
+        owner.require_auth();
+        let policy = Self::get_approval_policy(e.clone()).unwrap_or_else(|| {
+            fail(
+                &e,
+                CommitmentError::InvalidApprovalPolicy,
+                "submit_approval_request",
+            )
+        });
+        let action_owner = match &action {
+            ApprovalAction::Create(action_owner, _, _) => action_owner.clone(),
+            ApprovalAction::EarlyExit(commitment_id) | ApprovalAction::TopUp(commitment_id, _) => {
+                read_commitment(&e, commitment_id)
+                    .unwrap_or_else(|| {
+                        fail(
+                            &e,
+                            CommitmentError::CommitmentNotFound,
+                            "submit_approval_request",
+                        )
+                    })
+                    .owner
+            }
+        };
+        if action_owner != owner {
+            fail(&e, CommitmentError::Unauthorized, "submit_approval_request");
         }
 
-        // Adjust TotalValueLocked: TVL -= old_value, TVL += new_value
-        let current_tvl = e
+        let request_id = e
             .storage()
             .instance()
-            .get::<_, i128>(&DataKey::TotalValueLocked)
+            .get::<_, u64>(&DataKeyExt::ApprovalRequestCount)
             .unwrap_or(0);
-        let new_tvl = current_tvl - old_value + new_value;
         e.storage()
             .instance()
-            .set(&DataKey::TotalValueLocked, &new_tvl);
+            .set(&DataKeyExt::ApprovalRequestCount, &(request_id + 1));
+        let for_key = DataKeyExt::ApprovalFor(action.clone());
+        if let Some(previous) = e.storage().instance().get::<_, u64>(&for_key) {
+            e.storage()
+                .instance()
+                .remove(&DataKeyExt::ApprovalRequest(previous));
+        }
+        let request = ApprovalRequest {
+            request_id,
+            owner: owner.clone(),
+            action,
+            approvals: Vec::new(&e),
+            expires_at: e.ledger().timestamp() + policy.expiry_seconds,
+        };
+        e.storage()
+            .instance()
+            .set(&DataKeyExt::ApprovalRequest(request_id), &request);
+        e.storage().instance().set(&for_key, &request_id);
+        publish_event(
+            &e,
+            (symbol_short!("ApprReq"), request_id),
+            (owner, request.expires_at),
+        );
+        request_id
     }
 
-    /// Check if commitment rules are violated
-    /// Returns true if any rule violation is detected (loss limit or duration)
-    ///
-    /// # Formal Verification
-    /// **Preconditions:**
-    /// - `commitment_id` exists
-    ///
-    /// **Postconditions:**
-    /// - Returns `true` if `loss_percent > max_loss_percent OR current_time >= expires_at`
-    /// - Returns `false` otherwise
-    /// - Pure function (no state changes)
-    ///
-    /// **Invariants Maintained:**
-    /// - INV-2: Commitment balance conservation
-    ///
-    /// **Security Properties:**
-    /// - SP-4: State consistency (read-only)
-    pub fn check_violations(e: Env, commitment_id: String) -> bool {
-        let commitment = read_commitment(&e, &commitment_id)
-            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "check_violations"));
+    /// Record `signer`'s approval of a pending request (designated signers only).
+    pub fn approve_request(e: Env, signer: Address, request_id: u64) {
+        signer.require_auth();
+        let signers = Self::get_approval_policy(e.clone())
+            .map(|policy| policy.signers)
+            .unwrap_or_else(|| Vec::new(&e));
+        if !signers.contains(&signer) {
+            fail(&e, CommitmentError::Unauthorized, "approve_request");
+        }
+        let key = DataKeyExt::ApprovalRequest(request_id);
+        let mut request = e
+            .storage()
+            .instance()
+            .get::<_, ApprovalRequest>(&key)
+            .unwrap_or_else(|| {
+                fail(
+                    &e,
+                    CommitmentError::ApprovalRequestNotFound,
+                    "approve_request",
+                )
+            });
+        if e.ledger().timestamp() > request.expires_at {
+            fail(&e, CommitmentError::ApprovalExpired, "approve_request");
+        }
+        if request.approvals.contains(&signer) {
+            fail(&e, CommitmentError::AlreadyApproved, "approve_request");
+        }
+        request.approvals.push_back(signer.clone());
+        e.storage().instance().set(&key, &request);
+        publish_event(
+            &e,
+            (symbol_short!("Approved"), request_id),
+            (signer, request.approvals.len()),
+        );
+    }
 
-        // Skip check if already settled or violated
-        let active_status = String::from_str(&e, "active");
-        if commitment.status != active_status {
-            return false; // Already processed
+    pub fn get_approval_request(e: Env, request_id: u64) -> Option<ApprovalRequest> {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::ApprovalRequest(request_id))
+    }
+
+    /// Configure the shares of penalties and fees set aside for insurance (admin only).
+    pub fn set_insurance_config(e: Env, caller: Address, config: InsuranceConfig) {
+        require_admin(&e, &caller);
+        if config.penalty_bps > 10_000 || config.fee_bps > 10_000 {
+            fail(
+                &e,
+                CommitmentError::InvalidFeeConfig,
+                "set_insurance_config",
+            );
         }
+        e.storage()
+            .instance()
+            .set(&DataKey::InsuranceConfig, &config);
+    }
 
-        let current_time = e.ledger().timestamp();
+    pub fn get_insurance_config(e: Env) -> InsuranceConfig {
+        insurance_config(&e)
+    }
 
-        // Check loss limit violation
-        // Calculate loss percentage using shared utilities, but handle zero-amount
-        // commitments gracefully to avoid panics. A zero-amount commitment cannot
-        // meaningfully violate a loss limit, so we treat its loss percent as 0.
-        let loss_percent = if commitment.amount > 0 {
-            SafeMath::loss_percent(commitment.amount, commitment.current_value)
-        } else {
-            0
-        };
+    /// Get the insurance fund's holdings in `asset`
+    pub fn get_insurance_balance(e: Env, asset: Address) -> i128 {
+        e.storage()
+            .instance()
+            .get(&DataKey::InsuranceBalance(asset))
+            .unwrap_or(0)
+    }
 
-        // Convert max_loss_percent (u32) to i128 for comparison
-        let max_loss = commitment.rules.max_loss_percent as i128;
-        let loss_violated = loss_percent > max_loss;
+    /// Restore value a commitment lost beyond its promised max loss from the
+    /// insurance fund (admin only).
+    ///
+    /// Adds `amount` to the commitment's `current_value`. A violated commitment
+    /// whose loss is back within `max_loss_percent` becomes active again.
+    pub fn cover_shortfall(e: Env, caller: Address, commitment_id: String, amount: i128) {
+        require_admin(&e, &caller);
+        if amount <= 0 {
+            fail(&e, CommitmentError::InvalidAmount, "cover_shortfall");
+        }
+        let mut commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "cover_shortfall"));
+        let violated = String::from_str(&e, "violated");
+        if commitment.status != String::from_str(&e, "active") && commitment.status != violated {
+            fail(&e, CommitmentError::NotActive, "cover_shortfall");
+        }
+        if is_tracking_only(&e, &commitment_id) {
+            fail(&e, CommitmentError::TrackingOnly, "cover_shortfall");
+        }
+        let available = Self::get_insurance_balance(e.clone(), commitment.asset_address.clone());
+        if amount > available {
+            fail(
+                &e,
+                CommitmentError::InsufficientInsurance,
+                "cover_shortfall",
+            );
+        }
+
+        adjust_insurance(&e, &commitment.asset_address, -amount);
+        commitment.current_value = SafeMath::add(commitment.current_value, amount);
+        if commitment.status == violated
+            && SafeMath::loss_percent(commitment.amount, commitment.current_value)
+                <= commitment.rules.max_loss_percent as i128
+        {
+            commitment.status = String::from_str(&e, "active");
+            adjust_active(&e, &commitment.owner, 1);
+        }
+        set_commitment(&e, &commitment);
+        adjust_tvl(&e, &commitment.asset_address, amount);
+        publish_event(
+            &e,
+            (symbol_short!("Covered"), commitment_id),
+            (amount, commitment.current_value, caller),
+        );
+    }
+
+    /// Configure stressed-mode thresholds (admin only).
+    pub fn set_stress_config(e: Env, caller: Address, config: StressConfig) {
+        require_admin(&e, &caller);
+        if config.auto_trigger_bps > 10_000
+            || config.round_liquidity_bps == 0
+            || config.round_liquidity_bps > 10_000
+        {
+            fail(
+                &e,
+                CommitmentError::InvalidStressConfig,
+                "set_stress_config",
+            );
+        }
+        e.storage().instance().set(&DataKey::StressConfig, &config);
+    }
+
+    pub fn get_stress_config(e: Env) -> StressConfig {
+        stress_config(&e)
+    }
 
-        // Check duration violation (expired)
-        let duration_violated = current_time >= commitment.expires_at;
+    /// Enter or leave stressed mode (admin only). Exits already queued keep
+    /// being processed after stressed mode ends.
+    pub fn set_stressed_mode(e: Env, caller: Address, stressed: bool) {
+        require_admin(&e, &caller);
+        set_stressed(&e, stressed);
+    }
 
-        let violated = loss_violated || duration_violated;
+    pub fn is_stressed(e: Env) -> bool {
+        is_stressed(&e)
+    }
 
-        if violated {
-            // Emit violation event
-            e.events().publish(
-                (symbol_short!("Violated"), commitment_id),
-                (symbol_short!("RuleViol"), e.ledger().timestamp()),
-            );
+    /// Queued early exits for an asset, in queue order
+    pub fn get_exit_queue(e: Env, asset: Address) -> Vec<QueuedExit> {
+        let mut entries = Vec::new(&e);
+        for id in exit_queue(&e, &asset).iter() {
+            if let Some(entry) = e.storage().instance().get(&DataKey::QueuedExit(id)) {
+                entries.push_back(entry);
+            }
         }
+        entries
+    }
 
-        // Return true if any violation exists
-        violated
+    pub fn get_queued_exit(e: Env, commitment_id: String) -> Option<QueuedExit> {
+        e.storage()
+            .instance()
+            .get(&DataKey::QueuedExit(commitment_id))
     }
 
-    /// Get detailed violation information
-    /// Returns a tuple: (has_violations, loss_violated, duration_violated, loss_percent, time_remaining)
-    pub fn get_violation_details(e: Env, commitment_id: String) -> (bool, bool, bool, i128, u64) {
-        let commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+    /// Withdraw a queued early exit (owner only).
+    ///
+    /// The commitment becomes active again, holding its queued value minus
+    /// whatever was already paid out; no penalty is charged.
+    pub fn cancel_queued_exit(e: Env, commitment_id: String, caller: Address) {
+        caller.require_auth();
+        let entry_key = DataKey::QueuedExit(commitment_id.clone());
+        let entry: QueuedExit = e
+            .storage()
+            .instance()
+            .get(&entry_key)
+            .unwrap_or_else(|| fail(&e, CommitmentError::NotQueued, "cancel_queued_exit"));
+        if entry.owner != caller {
+            fail(&e, CommitmentError::Unauthorized, "cancel_queued_exit");
+        }
+
+        let queue_key = DataKey::ExitQueue(entry.asset_address.clone());
+        let mut queue = exit_queue(&e, &entry.asset_address);
+        if let Some(index) = queue.first_index_of(&commitment_id) {
+            queue.remove(index);
+        }
+        e.storage().instance().set(&queue_key, &queue);
+        e.storage().instance().remove(&entry_key);
+
+        let restored = SafeMath::sub(entry.value, entry.paid);
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
             fail(
                 &e,
                 CommitmentError::CommitmentNotFound,
-                "get_violation_details",
+                "cancel_queued_exit",
             )
         });
+        commitment.status = String::from_str(&e, "active");
+        commitment.current_value = restored;
+        adjust_active(&e, &commitment.owner, 1);
+        set_commitment(&e, &commitment);
+        adjust_tvl(&e, &commitment.asset_address, restored);
 
-        let current_time = e.ledger().timestamp();
+        publish_event(
+            &e,
+            (symbol_short!("ExitCncl"), commitment_id, caller),
+            (entry.paid, restored),
+        );
+    }
 
-        // Calculate loss percentage
-        let loss_amount = commitment.amount - commitment.current_value;
-        let loss_percent = if commitment.amount > 0 {
-            (loss_amount * 100) / commitment.amount
-        } else {
-            0
-        };
+    /// Pay out one round of the asset's early-exit queue. Anyone may call this,
+    /// at most once per ledger per asset.
+    ///
+    /// The round's budget is `round_liquidity_bps` of core's balance in the
+    /// asset, split across queued exits pro-rata to what each is still owed.
+    /// Fully paid exits are closed like a regular early exit.
+    ///
+    /// # Returns
+    /// The total amount paid out this round.
+    pub fn process_exit_queue(e: Env, asset: Address) -> i128 {
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+        Pausable::require_not_paused(&e);
 
-        // Check loss limit violation
-        let max_loss = commitment.rules.max_loss_percent as i128;
-        let loss_violated = loss_percent > max_loss;
+        let round_key = DataKey::LastExitRound(asset.clone());
+        let sequence = e.ledger().sequence();
+        if e.storage().instance().get::<_, u32>(&round_key) == Some(sequence) {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::ExitRoundTooSoon, "process_exit_queue");
+        }
+        e.storage().instance().set(&round_key, &sequence);
 
-        // Check duration violation
-        let duration_violated = current_time >= commitment.expires_at;
+        let outstanding = queued_liability(&e, &asset);
+        let contract_address = e.current_contract_address();
+        let token_client = token::Client::new(&e, &asset);
+        let budget = SafeMath::div(
+            SafeMath::mul(
+                token_client.balance(&contract_address),
+                stress_config(&e).round_liquidity_bps as i128,
+            ),
+            10_000,
+        )
+        .min(outstanding);
+
+        // EFFECTS: split the budget pro-rata (rounded up so small exits progress)
+        let mut remaining_budget = budget;
+        let mut payments: Vec<(Address, i128)> = Vec::new(&e);
+        let mut completed: Vec<QueuedExit> = Vec::new(&e);
+        let mut queue = Vec::new(&e);
+        for id in exit_queue(&e, &asset).iter() {
+            let entry_key = DataKey::QueuedExit(id.clone());
+            let Some(mut entry) = e.storage().instance().get::<_, QueuedExit>(&entry_key) else {
+                continue;
+            };
+            let owed = SafeMath::sub(entry.owed, entry.paid);
+            let share = if outstanding > 0 {
+                SafeMath::div(
+                    SafeMath::add(SafeMath::mul(budget, owed), outstanding - 1),
+                    outstanding,
+                )
+            } else {
+                0
+            }
+            .min(owed)
+            .min(remaining_budget);
+            if share > 0 {
+                entry.paid = SafeMath::add(entry.paid, share);
+                remaining_budget = SafeMath::sub(remaining_budget, share);
+                payments.push_back((resolve_payout_address(&e, &id, &entry.owner, &asset), share));
+            }
 
-        // Calculate time remaining (0 if expired)
-        let time_remaining = if current_time < commitment.expires_at {
-            commitment.expires_at - current_time
-        } else {
-            0
-        };
+            if entry.paid >= entry.owed {
+                e.storage().instance().remove(&entry_key);
+                let mut commitment = read_commitment(&e, &id).unwrap_or_else(|| {
+                    set_reentrancy_guard(&e, false);
+                    fail(
+                        &e,
+                        CommitmentError::CommitmentNotFound,
+                        "process_exit_queue",
+                    )
+                });
+                commitment.status = String::from_str(&e, "early_exit");
+                set_commitment(&e, &commitment);
+                completed.push_back(entry);
+            } else {
+                e.storage().instance().set(&entry_key, &entry);
+                queue.push_back(id);
+            }
+        }
+        e.storage()
+            .instance()
+            .set(&DataKey::ExitQueue(asset.clone()), &queue);
 
-        let has_violations = loss_violated || duration_violated;
+        // INTERACTIONS: payouts, then close completed exits
+        for (recipient, amount) in payments.iter() {
+            token_client.transfer(&contract_address, &recipient, &amount);
+        }
+        for entry in completed.iter() {
+            if let Some(commitment) = read_commitment(&e, &entry.commitment_id) {
+                close_early_exit(&e, &commitment, &entry.owner, entry.penalty, entry.owed);
+            }
+        }
 
-        (
-            has_violations,
-            loss_violated,
-            duration_violated,
-            loss_percent,
-            time_remaining,
-        )
+        set_reentrancy_guard(&e, false);
+        let paid = SafeMath::sub(budget, remaining_budget);
+        publish_event(
+            &e,
+            (symbol_short!("ExitRnd"), asset),
+            (paid, queue.len(), completed.len()),
+        );
+        paid
     }
 
-    /// Settle commitment at maturity
+    /// Allocate liquidity (called by allocation strategy)
     ///
     /// # Reentrancy Protection
     /// Uses checks-effects-interactions pattern with reentrancy guard.
-    pub fn settle(e: Env, commitment_id: String) {
+    pub fn allocate(e: Env, commitment_id: String, target_pool: Address, amount: i128) {
         // Reentrancy protection
         require_no_reentrancy(&e);
         set_reentrancy_guard(&e, true);
@@ -783,243 +6754,647 @@ impl CommitmentCoreContract {
         // Check if contract is paused
         Pausable::require_not_paused(&e);
 
-        // CHECKS: Get and validate commitment
-        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
-            set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::CommitmentNotFound, "settle")
-        });
+        // Rate limit allocations per target pool address
+        let fn_symbol = symbol_short!("alloc");
+        RateLimiter::check(&e, &target_pool, &fn_symbol);
 
-        // Verify commitment is expired
-        let current_time = e.ledger().timestamp();
-        if current_time < commitment.expires_at {
+        // CHECKS: Validate inputs and commitment
+        if amount <= 0 {
             set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::NotExpired, "settle");
+            fail(&e, CommitmentError::InvalidAmount, "allocate");
         }
 
+        let commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::CommitmentNotFound, "allocate")
+        });
+
         // Verify commitment is active
         let active_status = String::from_str(&e, "active");
         if commitment.status != active_status {
             set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::NotActive, "settle");
+            fail(&e, CommitmentError::NotActive, "allocate");
         }
 
-        // EFFECTS: Update state before external calls
-        let settlement_amount = commitment.current_value;
-        commitment.status = String::from_str(&e, "settled");
-        set_commitment(&e, &commitment);
+        if is_tracking_only(&e, &commitment_id) {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::TrackingOnly, "allocate");
+        }
 
-        // Decrease total value locked
-        let current_tvl = e
-            .storage()
+        // Verify sufficient balance
+        if commitment.current_value < amount {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::InsufficientBalance, "allocate");
+        }
+
+        // EFFECTS: Update commitment value before external call
+        let mut updated_commitment = commitment;
+        updated_commitment.current_value = updated_commitment.current_value - amount;
+        set_commitment(&e, &updated_commitment);
+
+        // INTERACTIONS: External call (token transfer)
+        // Transfer assets to target pool
+        let contract_address = e.current_contract_address();
+        let token_client = token::Client::new(&e, &updated_commitment.asset_address);
+        token_client.transfer(&contract_address, &target_pool, &amount);
+
+        // Clear reentrancy guard
+        set_reentrancy_guard(&e, false);
+
+        // Emit allocation event
+        publish_event(
+            &e,
+            (symbol_short!("Alloc"), commitment_id, target_pool),
+            (amount, e.ledger().timestamp()),
+        );
+    }
+
+    /// Configure rate limits for this contract's functions.
+    ///
+    /// This function is restricted to the contract admin.
+    pub fn set_rate_limit(
+        e: Env,
+        caller: Address,
+        function: Symbol,
+        window_seconds: u64,
+        max_calls: u32,
+    ) {
+        require_admin(&e, &caller);
+        RateLimiter::set_limit(&e, &function, window_seconds, max_calls);
+    }
+
+    /// Cap how many commitments one owner may create per `window_seconds`
+    /// (admin only). Applies to every creation entry point; sponsored
+    /// commitments count against the payer.
+    pub fn set_creation_rate_limit(
+        e: Env,
+        caller: Address,
+        window_seconds: u64,
+        max_creations: u32,
+    ) {
+        Self::set_rate_limit(
+            e,
+            caller,
+            CREATE_RATE_LIMIT_FN,
+            window_seconds,
+            max_creations,
+        );
+    }
+
+    /// Remove the commitment creation rate limit (admin only).
+    pub fn clear_creation_rate_limit(e: Env, caller: Address) {
+        require_admin(&e, &caller);
+        RateLimiter::clear_limit(&e, &CREATE_RATE_LIMIT_FN);
+    }
+
+    /// Cap the active commitments one owner may hold (admin only), keeping
+    /// owner indexes within pagination limits. 0 removes the cap. Sponsored
+    /// commitments count against the owner.
+    pub fn set_max_active_per_owner(e: Env, caller: Address, max: u32) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .set(&DataKeyExt::MaxActivePerOwner, &max);
+        publish_event(
+            &e,
+            (Symbol::new(&e, "ActiveCapSet"),),
+            (max, caller, e.ledger().timestamp()),
+        );
+    }
+
+    pub fn get_max_active_per_owner(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::MaxActivePerOwner)
+            .unwrap_or(0)
+    }
+
+    /// Exempt `address` from the active commitment cap, or remove its
+    /// exemption (admin only).
+    pub fn set_active_cap_exempt(e: Env, caller: Address, address: Address, exempt: bool) {
+        require_admin(&e, &caller);
+        let key = DataKeyExt::ActiveCapExempt(address.clone());
+        if exempt {
+            e.storage().instance().set(&key, &true);
+        } else {
+            e.storage().instance().remove(&key);
+        }
+        publish_event(&e, (Symbol::new(&e, "ActiveCapExempt"), address), exempt);
+    }
+
+    /// Number of active commitments `owner` holds.
+    pub fn get_active_commitment_count(e: Env, owner: Address) -> u64 {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::OwnerActiveCount(owner))
+            .unwrap_or(0)
+    }
+
+    /// Set or clear rate limit exemption for an address.
+    ///
+    /// This function is restricted to the contract admin.
+    pub fn set_rate_limit_exempt(e: Env, caller: Address, address: Address, exempt: bool) {
+        require_admin(&e, &caller);
+        RateLimiter::set_exempt(&e, &address, exempt);
+    }
+
+    /// Add or remove an address from the protocol blocklist.
+    ///
+    /// This function is restricted to the contract admin.
+    pub fn set_address_blocked(e: Env, caller: Address, address: Address, blocked: bool) {
+        require_admin(&e, &caller);
+        if blocked {
+            e.storage()
+                .instance()
+                .set(&DataKey::BlockedAddress(address.clone()), &true);
+        } else {
+            e.storage()
+                .instance()
+                .remove(&DataKey::BlockedAddress(address.clone()));
+        }
+        publish_event(
+            &e,
+            (Symbol::new(&e, "AddressBlocked"), address),
+            (blocked, e.ledger().timestamp()),
+        );
+    }
+
+    /// Check whether an address is on the protocol blocklist.
+    pub fn is_address_blocked(e: Env, address: Address) -> bool {
+        is_blocked(&e, &address)
+    }
+
+    /// Direct a commitment's settlement, early-exit and liquidation proceeds to
+    /// `beneficiary` (owner only). Overrides the owner's payout route.
+    ///
+    /// The beneficiary must not be blocklisted.
+    pub fn set_beneficiary(e: Env, commitment_id: String, beneficiary: Address) {
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "set_beneficiary"));
+        commitment.owner.require_auth();
+        if is_blocked(&e, &beneficiary) {
+            fail(&e, CommitmentError::BlockedAddress, "set_beneficiary");
+        }
+        e.storage()
+            .instance()
+            .set(&DataKey::Beneficiary(commitment_id.clone()), &beneficiary);
+        publish_event(
+            &e,
+            (Symbol::new(&e, "BeneficiarySet"), commitment_id),
+            (beneficiary, e.ledger().timestamp()),
+        );
+    }
+
+    /// Remove a commitment's beneficiary; proceeds follow the payout route again.
+    pub fn clear_beneficiary(e: Env, commitment_id: String) {
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "clear_beneficiary"));
+        commitment.owner.require_auth();
+        e.storage()
+            .instance()
+            .remove(&DataKey::Beneficiary(commitment_id.clone()));
+        publish_event(
+            &e,
+            (Symbol::new(&e, "BeneficiaryCleared"), commitment_id),
+            e.ledger().timestamp(),
+        );
+    }
+
+    pub fn get_beneficiary(e: Env, commitment_id: String) -> Option<Address> {
+        e.storage()
+            .instance()
+            .get(&DataKey::Beneficiary(commitment_id))
+    }
+
+    /// Route settlement proceeds for `asset` to `destination` instead of the owner.
+    ///
+    /// Applies to every commitment of `owner` denominated in `asset`.
+    /// The destination must not be blocklisted.
+    pub fn set_payout_route(e: Env, owner: Address, asset: Address, destination: Address) {
+        owner.require_auth();
+        if is_blocked(&e, &destination) {
+            fail(&e, CommitmentError::BlockedAddress, "set_payout_route");
+        }
+        e.storage().instance().set(
+            &DataKey::PayoutRoute(owner.clone(), asset.clone()),
+            &destination,
+        );
+        publish_event(
+            &e,
+            (Symbol::new(&e, "PayoutRouteSet"), owner, asset),
+            (destination, e.ledger().timestamp()),
+        );
+    }
+
+    /// Remove the payout route for `asset`; proceeds go to the owner again.
+    pub fn clear_payout_route(e: Env, owner: Address, asset: Address) {
+        owner.require_auth();
+        e.storage()
+            .instance()
+            .remove(&DataKey::PayoutRoute(owner.clone(), asset.clone()));
+        publish_event(
+            &e,
+            (Symbol::new(&e, "PayoutRouteCleared"), owner, asset),
+            e.ledger().timestamp(),
+        );
+    }
+
+    /// Configure settlement receipt issuance.
+    ///
+    /// This function is restricted to the contract admin. `transferable`
+    /// applies to receipts issued after the change; existing receipts keep
+    /// the setting they were issued with.
+    pub fn set_receipt_config(e: Env, caller: Address, enabled: bool, transferable: bool) {
+        require_admin(&e, &caller);
+        e.storage().instance().set(
+            &DataKey::ReceiptConfig,
+            &ReceiptConfig {
+                enabled,
+                transferable,
+            },
+        );
+    }
+
+    /// Get the settlement receipt configuration (disabled by default).
+    pub fn get_receipt_config(e: Env) -> ReceiptConfig {
+        e.storage()
             .instance()
-            .get::<_, i128>(&DataKey::TotalValueLocked)
-            .unwrap_or(0);
-        let new_tvl = current_tvl - settlement_amount;
+            .get::<_, ReceiptConfig>(&DataKey::ReceiptConfig)
+            .unwrap_or(ReceiptConfig {
+                enabled: false,
+                transferable: false,
+            })
+    }
+
+    /// Get a settlement receipt by id.
+    pub fn get_receipt(e: Env, receipt_id: u32) -> SettlementReceipt {
         e.storage()
             .instance()
-            .set(&DataKey::TotalValueLocked, &new_tvl);
+            .get::<_, SettlementReceipt>(&DataKey::Receipt(receipt_id))
+            .unwrap_or_else(|| fail(&e, CommitmentError::ReceiptNotFound, "get_receipt"))
+    }
 
-        // INTERACTIONS: External calls (token transfer, NFT settlement)
-        // Transfer assets back to owner
-        let contract_address = e.current_contract_address();
-        let token_client = token::Client::new(&e, &commitment.asset_address);
-        token_client.transfer(&contract_address, &commitment.owner, &settlement_amount);
+    /// Get the receipt id issued for a closed commitment, if any.
+    pub fn get_commitment_receipt(e: Env, commitment_id: String) -> Option<u32> {
+        e.storage()
+            .instance()
+            .get::<_, u32>(&DataKey::CommitmentReceipt(commitment_id))
+    }
 
-        // Call NFT contract to mark NFT as settled
-        let nft_contract = e
+    /// Transfer a settlement receipt to a new holder.
+    ///
+    /// Fails for soulbound receipts.
+    pub fn transfer_receipt(e: Env, from: Address, to: Address, receipt_id: u32) {
+        from.require_auth();
+        let mut receipt = e
             .storage()
             .instance()
-            .get::<_, Address>(&DataKey::NftContract)
-            .unwrap_or_else(|| {
-                set_reentrancy_guard(&e, false);
-                fail(&e, CommitmentError::NotInitialized, "settle")
-            });
-
-        let mut args = Vec::new(&e);
-        args.push_back(commitment.nft_token_id.into_val(&e));
-        e.invoke_contract::<()>(&nft_contract, &Symbol::new(&e, "settle"), args);
+            .get::<_, SettlementReceipt>(&DataKey::Receipt(receipt_id))
+            .unwrap_or_else(|| fail(&e, CommitmentError::ReceiptNotFound, "transfer_receipt"));
+        if receipt.holder != from {
+            fail(&e, CommitmentError::Unauthorized, "transfer_receipt");
+        }
+        if !receipt.transferable {
+            fail(
+                &e,
+                CommitmentError::ReceiptNotTransferable,
+                "transfer_receipt",
+            );
+        }
+        receipt.holder = to.clone();
+        e.storage()
+            .instance()
+            .set(&DataKey::Receipt(receipt_id), &receipt);
+        publish_event(
+            &e,
+            (Symbol::new(&e, "ReceiptTransfer"), receipt_id),
+            (from, to, e.ledger().timestamp()),
+        );
+    }
 
-        // Clear reentrancy guard
-        set_reentrancy_guard(&e, false);
+    /// Delist an asset: blocks new deposits and publishes a wind-down notice.
+    ///
+    /// Existing commitments settle at expiry in the original asset. If
+    /// `swap_adapter` and `target_asset` are provided, owners may instead opt
+    /// into conversion via `settle_with_conversion`.
+    ///
+    /// This function is restricted to the contract admin.
+    pub fn delist_asset(
+        e: Env,
+        caller: Address,
+        asset: Address,
+        reason: String,
+        swap_adapter: Option<Address>,
+        target_asset: Option<Address>,
+    ) {
+        require_admin(&e, &caller);
+        let notice = DelistingNotice {
+            asset: asset.clone(),
+            reason,
+            delisted_at: e.ledger().timestamp(),
+            swap_adapter,
+            target_asset,
+        };
+        e.storage()
+            .instance()
+            .set(&DataKey::DelistedAsset(asset.clone()), &notice);
+        publish_event(&e, (Symbol::new(&e, "AssetDelisted"), asset), notice);
+    }
 
-        // Emit settlement event
-        e.events().publish(
-            (symbol_short!("Settled"), commitment_id),
-            (settlement_amount, e.ledger().timestamp()),
+    /// Re-list a previously delisted asset.
+    ///
+    /// This function is restricted to the contract admin.
+    pub fn relist_asset(e: Env, caller: Address, asset: Address) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .remove(&DataKey::DelistedAsset(asset.clone()));
+        publish_event(
+            &e,
+            (Symbol::new(&e, "AssetRelisted"), asset),
+            e.ledger().timestamp(),
         );
     }
 
-    pub fn early_exit(e: Env, commitment_id: String, caller: Address) {
-        // Reentrancy protection
-        require_no_reentrancy(&e);
-        set_reentrancy_guard(&e, true);
+    /// Get the delisting notice for an asset, if it has been delisted.
+    pub fn get_delisting_notice(e: Env, asset: Address) -> Option<DelistingNotice> {
+        e.storage()
+            .instance()
+            .get::<_, DelistingNotice>(&DataKey::DelistedAsset(asset))
+    }
 
-        // Check if contract is paused
-        Pausable::require_not_paused(&e);
+    /// Bound the rules a commitment type accepts at creation (admin only),
+    /// e.g. "safe": max loss 5%, at least 30 days; "aggressive": max loss 50%.
+    ///
+    /// Existing commitments are not affected.
+    pub fn set_type_constraints(
+        e: Env,
+        caller: Address,
+        commitment_type: String,
+        constraints: TypeConstraints,
+    ) {
+        require_admin(&e, &caller);
+        let valid_types = ["safe", "balanced", "aggressive"];
+        Validation::require_valid_commitment_type(&e, &commitment_type, &valid_types);
+        if constraints.max_loss_percent > 100
+            || (constraints.max_duration_days > 0
+                && constraints.min_duration_days > constraints.max_duration_days)
+        {
+            fail(
+                &e,
+                CommitmentErrorExt::InvalidTypeConstraints,
+                "set_type_constraints",
+            );
+        }
+        e.storage().instance().set(
+            &DataKeyExt::TypeConstraints(commitment_type.clone()),
+            &constraints,
+        );
+        publish_event(
+            &e,
+            (Symbol::new(&e, "TypeConstraintsSet"), commitment_type),
+            constraints,
+        );
+    }
 
-        // CHECKS: Get and validate commitment
-        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
-            set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::CommitmentNotFound, "early_exit")
-        });
+    /// Remove a commitment type's rule bounds.
+    pub fn clear_type_constraints(e: Env, caller: Address, commitment_type: String) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .remove(&DataKeyExt::TypeConstraints(commitment_type));
+    }
 
-        // Verify caller is owner
-        caller.require_auth();
-        if commitment.owner != caller {
-            set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::Unauthorized, "early_exit");
-        }
+    pub fn get_type_constraints(e: Env, commitment_type: String) -> Option<TypeConstraints> {
+        e.storage()
+            .instance()
+            .get(&DataKeyExt::TypeConstraints(commitment_type))
+    }
 
-        // Verify commitment is active
-        let active_status = String::from_str(&e, "active");
-        if commitment.status != active_status {
-            set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::NotActive, "early_exit");
+    /// Set the settlement payout policy for a commitment type (admin only).
+    ///
+    /// `Stable` and `ProtocolToken` require a swap adapter and target asset;
+    /// `max_slippage_bps` must not exceed 10000.
+    pub fn set_payout_policy(
+        e: Env,
+        caller: Address,
+        commitment_type: String,
+        policy: PayoutPolicy,
+    ) {
+        require_admin(&e, &caller);
+        let needs_swap = policy.currency != PayoutCurrency::SameAsset;
+        let has_route = policy.swap_adapter.is_some() && policy.target_asset.is_some();
+        if needs_swap != has_route || policy.max_slippage_bps > 10_000 {
+            fail(
+                &e,
+                CommitmentError::InvalidPayoutPolicy,
+                "set_payout_policy",
+            );
         }
-
-        // EFFECTS: Calculate penalty using shared utilities
-        let penalty_amount = SafeMath::penalty_amount(
-            commitment.current_value,
-            commitment.rules.early_exit_penalty,
+        e.storage()
+            .instance()
+            .set(&DataKey::PayoutPolicy(commitment_type.clone()), &policy);
+        publish_event(
+            &e,
+            (Symbol::new(&e, "PayoutPolicySet"), commitment_type),
+            policy.currency,
         );
-        let returned_amount = SafeMath::sub(commitment.current_value, penalty_amount);
-        let original_value = commitment.current_value;
-
-        // Update commitment status to early_exit
-        commitment.status = String::from_str(&e, "early_exit");
-        commitment.current_value = 0; // All value has been distributed
-        set_commitment(&e, &commitment);
+    }
 
-        // Decrease total value locked by full current value (no longer locked)
-        let current_tvl = e
-            .storage()
+    /// Remove a commitment type's payout policy (settles in the committed asset again).
+    pub fn clear_payout_policy(e: Env, caller: Address, commitment_type: String) {
+        require_admin(&e, &caller);
+        e.storage()
             .instance()
-            .get::<_, i128>(&DataKey::TotalValueLocked)
-            .unwrap_or(0);
-        let new_tvl = current_tvl - original_value;
+            .remove(&DataKey::PayoutPolicy(commitment_type.clone()));
+        publish_event(
+            &e,
+            (Symbol::new(&e, "PayoutPolicySet"), commitment_type),
+            PayoutCurrency::SameAsset,
+        );
+    }
+
+    /// Get the settlement payout policy for a commitment type, if any.
+    pub fn get_payout_policy(e: Env, commitment_type: String) -> Option<PayoutPolicy> {
         e.storage()
             .instance()
-            .set(&DataKey::TotalValueLocked, &new_tvl);
+            .get::<_, PayoutPolicy>(&DataKey::PayoutPolicy(commitment_type))
+    }
 
-        // INTERACTIONS: External calls (token transfer)
-        // Transfer remaining amount (after penalty) to owner
-        let contract_address = e.current_contract_address();
-        let token_client = token::Client::new(&e, &commitment.asset_address);
+    /// Add a label to the curated analytics label set (admin only).
+    pub fn add_label(e: Env, caller: Address, label: Symbol) {
+        require_admin(&e, &caller);
+        let mut labels = Self::get_labels(e.clone());
+        if labels.contains(&label) {
+            return;
+        }
+        if labels.len() >= MAX_LABELS {
+            fail(&e, CommitmentError::TooManyLabels, "add_label");
+        }
+        labels.push_back(label.clone());
+        e.storage().instance().set(&DataKey::Labels, &labels);
+        publish_event(&e, (symbol_short!("LabelAdd"),), label);
+    }
 
-        if returned_amount > 0 {
-            token_client.transfer(&contract_address, &commitment.owner, &returned_amount);
+    /// Remove a label from the curated set (admin only).
+    /// Commitments already carrying it keep their label.
+    pub fn remove_label(e: Env, caller: Address, label: Symbol) {
+        require_admin(&e, &caller);
+        let mut labels = Self::get_labels(e.clone());
+        if let Some(index) = labels.first_index_of(&label) {
+            labels.remove(index);
+            e.storage().instance().set(&DataKey::Labels, &labels);
+            publish_event(&e, (symbol_short!("LabelDel"),), label);
         }
+    }
 
-        // Call NFT contract to mark as inactive (early exited, not settled)
-        let nft_contract = e
-            .storage()
+    /// Get the curated analytics label set
+    pub fn get_labels(e: Env) -> Vec<Symbol> {
+        e.storage()
             .instance()
-            .get::<_, Address>(&DataKey::NftContract)
-            .unwrap_or_else(|| {
-                set_reentrancy_guard(&e, false);
-                fail(&e, CommitmentError::NotInitialized, "early_exit")
-            });
-        
-        // Call mark_inactive on NFT instead of settle (since not expired)
-        let mut args = Vec::new(&e);
-        args.push_back(commitment.nft_token_id.into_val(&e));
-        e.invoke_contract::<()>(&nft_contract, &Symbol::new(&e, "mark_inactive"), args);
-
-        // Clear reentrancy guard
-        set_reentrancy_guard(&e, false);
-
-        // Emit early exit event with detailed information
-        e.events().publish(
-            (
-                symbol_short!("EarlyExt"),
-                commitment_id.clone(),
-                caller.clone(),
-            ),
-            (penalty_amount, returned_amount, e.ledger().timestamp()),
-        );
+            .get(&DataKey::Labels)
+            .unwrap_or(Vec::new(&e))
     }
 
-    /// Allocate liquidity (called by allocation strategy)
+    /// Begin a protocol sunset under a published policy (admin only).
     ///
-    /// # Reentrancy Protection
-    /// Uses checks-effects-interactions pattern with reentrancy guard.
-    pub fn allocate(e: Env, commitment_id: String, target_pool: Address, amount: i128) {
-        // Reentrancy protection
-        require_no_reentrancy(&e);
-        set_reentrancy_guard(&e, true);
+    /// From now on no new commitments are accepted; existing ones run to
+    /// term. After `policy.final_date` early-exit penalties are waived and
+    /// `distribute_sunset_funds` becomes available. A sunset cannot be undone.
+    pub fn begin_sunset(e: Env, caller: Address, policy: SunsetPolicy) {
+        require_admin(&e, &caller);
 
-        // Check if contract is paused
-        Pausable::require_not_paused(&e);
+        let mut total_bps: u32 = 0;
+        for share in policy.shares_bps.iter() {
+            total_bps = total_bps.saturating_add(share);
+        }
+        if policy.beneficiaries.is_empty()
+            || policy.beneficiaries.len() != policy.shares_bps.len()
+            || total_bps != 10_000
+        {
+            fail(&e, CommitmentError::InvalidSunsetPolicy, "begin_sunset");
+        }
 
-        // Rate limit allocations per target pool address
-        let fn_symbol = symbol_short!("alloc");
-        RateLimiter::check(&e, &target_pool, &fn_symbol);
+        Sunset::begin(&e, policy.final_date);
+        e.storage().instance().set(&DataKey::SunsetPolicy, &policy);
+        publish_event(&e, (Symbol::new(&e, "SunsetPolicy"), caller), policy);
+    }
 
-        // CHECKS: Validate inputs and commitment
-        if amount <= 0 {
-            set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::InvalidAmount, "allocate");
-        }
+    /// Get the protocol sunset state
+    pub fn get_sunset_state(e: Env) -> SunsetState {
+        Sunset::state(&e)
+    }
 
-        let commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
-            set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::CommitmentNotFound, "allocate")
-        });
+    /// Get the published sunset policy, if a sunset has begun
+    pub fn get_sunset_policy(e: Env) -> Option<SunsetPolicy> {
+        e.storage()
+            .instance()
+            .get::<_, SunsetPolicy>(&DataKey::SunsetPolicy)
+    }
 
-        // Verify commitment is active
-        let active_status = String::from_str(&e, "active");
-        if commitment.status != active_status {
-            set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::NotActive, "allocate");
-        }
+    /// Distribute the funds core holds in `asset` beyond what users are owed,
+    /// split per the sunset policy (admin only).
+    ///
+    /// Escrow, queued exits, unclaimed yield, referral rewards, vesting and
+    /// credited penalty shares stay reserved. The insurance fund and penalty
+    /// pool are distributed and reset to zero. Only callable after the
+    /// sunset's final date. Returns the amount distributed; any rounding dust
+    /// goes to the last beneficiary.
+    pub fn distribute_sunset_funds(e: Env, caller: Address, asset: Address) -> i128 {
+        require_admin(&e, &caller);
+        require_no_reentrancy(&e);
 
-        // Verify sufficient balance
-        if commitment.current_value < amount {
-            set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::InsufficientBalance, "allocate");
+        if Sunset::state(&e) != SunsetState::Final {
+            fail(
+                &e,
+                CommitmentError::SunsetNotFinal,
+                "distribute_sunset_funds",
+            );
         }
+        let policy = Self::get_sunset_policy(e.clone()).unwrap_or_else(|| {
+            fail(
+                &e,
+                CommitmentError::SunsetNotFinal,
+                "distribute_sunset_funds",
+            )
+        });
 
-        // EFFECTS: Update commitment value before external call
-        let mut updated_commitment = commitment;
-        updated_commitment.current_value = updated_commitment.current_value - amount;
-        set_commitment(&e, &updated_commitment);
-
-        // INTERACTIONS: External call (token transfer)
-        // Transfer assets to target pool
         let contract_address = e.current_contract_address();
-        let token_client = token::Client::new(&e, &updated_commitment.asset_address);
-        token_client.transfer(&contract_address, &target_pool, &amount);
+        let token_client = token::Client::new(&e, &asset);
+        let surplus = SafeMath::sub(
+            token_client.balance(&contract_address),
+            user_liabilities(&e, &asset),
+        );
+        if surplus <= 0 {
+            return 0;
+        }
+        let insurance = Self::get_insurance_balance(e.clone(), asset.clone());
+        adjust_insurance(&e, &asset, -insurance);
+        e.storage()
+            .instance()
+            .set(&DataKeyExt::PenaltyPool(asset.clone()), &0i128);
 
-        // Clear reentrancy guard
+        set_reentrancy_guard(&e, true);
+        let last = policy.beneficiaries.len() - 1;
+        let mut remaining = surplus;
+        for (i, beneficiary) in policy.beneficiaries.iter().enumerate() {
+            let share = if i as u32 == last {
+                remaining
+            } else {
+                let bps = policy.shares_bps.get(i as u32).unwrap_or(0);
+                SafeMath::div(SafeMath::mul(surplus, bps as i128), 10_000)
+            };
+            remaining = SafeMath::sub(remaining, share);
+            if share > 0 {
+                token_client.transfer(&contract_address, &beneficiary, &share);
+            }
+        }
         set_reentrancy_guard(&e, false);
 
-        // Emit allocation event
-        e.events().publish(
-            (symbol_short!("Alloc"), commitment_id, target_pool),
-            (amount, e.ledger().timestamp()),
+        publish_event(
+            &e,
+            (Symbol::new(&e, "SunsetDistributed"), asset),
+            (surplus, e.ledger().timestamp()),
         );
+        surplus
     }
 
-    /// Configure rate limits for this contract's functions.
+    /// Set notification preference flags for the caller's commitments.
     ///
-    /// This function is restricted to the contract admin.
-    pub fn set_rate_limit(
-        e: Env,
-        caller: Address,
-        function: Symbol,
-        window_seconds: u64,
-        max_calls: u32,
-    ) {
-        require_admin(&e, &caller);
-        RateLimiter::set_limit(&e, &function, window_seconds, max_calls);
+    /// `flags` is a combination of `NOTIFY_THRESHOLD`, `NOTIFY_EXPIRY_SOON`
+    /// and `NOTIFY_SETTLEMENT`. Threshold and settlement preferences gate the
+    /// on-chain Notify events; expiry-soon is time-based and read by off-chain
+    /// notifiers via `wants_notification`.
+    pub fn set_notification_prefs(e: Env, owner: Address, flags: u32) {
+        owner.require_auth();
+        if flags & !NOTIFY_ALL != 0 {
+            fail(
+                &e,
+                CommitmentError::InvalidNotificationPrefs,
+                "set_notification_prefs",
+            );
+        }
+        e.storage()
+            .instance()
+            .set(&DataKey::NotificationPrefs(owner.clone()), &flags);
+        publish_event(&e, (Symbol::new(&e, "NotifyPrefs"), owner), flags);
     }
 
-    /// Set or clear rate limit exemption for an address.
-    ///
-    /// This function is restricted to the contract admin.
-    pub fn set_rate_limit_exempt(e: Env, caller: Address, address: Address, exempt: bool) {
-        require_admin(&e, &caller);
-        RateLimiter::set_exempt(&e, &address, exempt);
+    /// Get an owner's notification preference flags (all enabled by default).
+    pub fn get_notification_prefs(e: Env, owner: Address) -> u32 {
+        notification_prefs(&e, &owner)
+    }
+
+    /// Check whether an owner wants notifications of the given kind.
+    pub fn wants_notification(e: Env, owner: Address, flag: u32) -> bool {
+        notification_prefs(&e, &owner) & flag != 0
+    }
+
+    /// Get the configured payout destination for `owner` and `asset`, if any.
+    pub fn get_payout_route(e: Env, owner: Address, asset: Address) -> Option<Address> {
+        e.storage()
+            .instance()
+            .get::<_, Address>(&DataKey::PayoutRoute(owner, asset))
     }
 }
 
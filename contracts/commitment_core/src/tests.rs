@@ -6,7 +6,7 @@ use soroban_sdk::{
     contract, contractimpl, symbol_short,
     testutils::{Address as _, Events, Ledger},
     token::StellarAssetClient,
-    vec, Address, Env, IntoVal, String,
+    vec, Address, Env, IntoVal, String, TryFromVal,
 };
 
 #[contract]
@@ -14,8 +14,10 @@ struct MockNftContract;
 
 #[contractimpl]
 impl MockNftContract {
-    pub fn mint(
+    #[allow(clippy::too_many_arguments)]
+    pub fn mint_by(
         _e: Env,
+        _minter: Address,
         _owner: Address,
         _commitment_id: String,
         _duration_days: u32,
@@ -27,6 +29,28 @@ impl MockNftContract {
     ) -> u32 {
         1
     }
+
+    pub fn settle_with_performance(
+        _e: Env,
+        _token_id: u32,
+        _final_value: i128,
+        _fees_generated: i128,
+    ) {
+    }
+
+    pub fn mark_inactive(_e: Env, _token_id: u32) {}
+
+    pub fn top_up(_e: Env, _token_id: u32, _amount: i128) {}
+
+    pub fn extend_expiry(_e: Env, _token_id: u32, _extra_days: u32) {}
+
+    pub fn set_settled_holdings(e: Env, owner: Address, holdings: (u32, i128)) {
+        e.storage().instance().set(&owner, &holdings);
+    }
+
+    pub fn get_settled_holdings(e: Env, owner: Address) -> (u32, i128) {
+        e.storage().instance().get(&owner).unwrap_or((0, 0))
+    }
 }
 
 fn test_rules(e: &Env) -> CommitmentRules {
@@ -81,6 +105,7 @@ fn test_create_commitment_i128_max() {
             amount,
             asset_address.clone(),
             rules.clone(),
+            None,
         );
     });
 }
@@ -117,6 +142,7 @@ fn test_create_commitment_amount_one() {
             amount,
             asset_address.clone(),
             rules.clone(),
+            None,
         );
     });
 }
@@ -144,7 +170,7 @@ fn test_update_value_zero_behaves() {
     });
 
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.update_value(&commitment_id, &0);
+    client.update_value(&admin, &commitment_id, &0);
     let updated = client.get_commitment(&commitment_id);
     assert_eq!(updated.current_value, 0);
 }
@@ -171,7 +197,7 @@ fn test_update_value_negative_panics() {
     });
 
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.update_value(&commitment_id, &-100);
+    client.update_value(&admin, &commitment_id, &-100);
 }
 
 #[test]
@@ -311,6 +337,7 @@ fn test_create_commitment_without_initialize_fails() {
             1000,
             asset_address.clone(),
             rules.clone(),
+            None,
         );
     });
 }
@@ -442,7 +469,14 @@ fn test_create_commitment_duration_zero() {
     };
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::create_commitment(e.clone(), owner, 1000, asset_address, rules);
+        CommitmentCoreContract::create_commitment(
+            e.clone(),
+            owner,
+            1000,
+            asset_address,
+            rules,
+            None,
+        );
     });
 }
 
@@ -472,7 +506,14 @@ fn test_create_commitment_max_loss_over_100() {
     };
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::create_commitment(e.clone(), owner, 1000, asset_address, rules);
+        CommitmentCoreContract::create_commitment(
+            e.clone(),
+            owner,
+            1000,
+            asset_address,
+            rules,
+            None,
+        );
     });
 }
 
@@ -502,7 +543,7 @@ fn test_create_commitment_amount_zero() {
     };
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::create_commitment(e.clone(), owner, 0, asset_address, rules);
+        CommitmentCoreContract::create_commitment(e.clone(), owner, 0, asset_address, rules, None);
         // Invalid amount
     });
 }
@@ -538,7 +579,14 @@ fn test_create_commitment_expiration_overflow() {
     };
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::create_commitment(e.clone(), owner, 1000, asset_address, rules);
+        CommitmentCoreContract::create_commitment(
+            e.clone(),
+            owner,
+            1000,
+            asset_address,
+            rules,
+            None,
+        );
     });
 }
 
@@ -581,7 +629,14 @@ fn test_create_commitment_amount_negative() {
     };
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::create_commitment(e.clone(), owner, -100, asset_address, rules);
+        CommitmentCoreContract::create_commitment(
+            e.clone(),
+            owner,
+            -100,
+            asset_address,
+            rules,
+            None,
+        );
         // Invalid amount
     });
 }
@@ -612,7 +667,14 @@ fn test_create_commitment_invalid_type() {
     };
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::create_commitment(e.clone(), owner, 1000, asset_address, rules);
+        CommitmentCoreContract::create_commitment(
+            e.clone(),
+            owner,
+            1000,
+            asset_address,
+            rules,
+            None,
+        );
     });
 }
 
@@ -879,6 +941,7 @@ fn test_get_commitment_returns_created_commitment_data() {
             amount,
             asset_address.clone(),
             rules.clone(),
+            None,
         )
     });
 
@@ -1324,7 +1387,7 @@ fn test_update_value_event() {
     });
 
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.update_value(&commitment_id, &1100);
+    client.update_value(&admin, &commitment_id, &1100);
 
     let updated = client.get_commitment(&commitment_id);
     assert_eq!(updated.current_value, 1100);
@@ -1370,9 +1433,9 @@ fn test_update_value_rate_limit_enforced() {
 
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
     // First call — allowed
-    client.update_value(&commitment_id, &100);
+    client.update_value(&admin, &commitment_id, &100);
     // Second call — should hit rate limit and panic
-    client.update_value(&commitment_id, &200);
+    client.update_value(&admin, &commitment_id, &200);
 }
 
 #[test]
@@ -2094,7 +2157,7 @@ fn test_early_exit_status_transition() {
     assert_eq!(before.status, String::from_str(&e, "active"));
 }
 #[test]
-fn test_update_value_updates_without_updater_param() {
+fn test_update_value_by_admin() {
     let e = Env::default();
     e.mock_all_auths();
     let contract_id = e.register_contract(None, CommitmentCoreContract);
@@ -2105,7 +2168,12 @@ fn test_update_value_updates_without_updater_param() {
         CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
         let commitment = create_test_commitment(&e, "test_id", &owner, 1000, 1000, 10, 30, 1000);
         set_commitment(&e, &commitment);
-        CommitmentCoreContract::update_value(e.clone(), String::from_str(&e, "test_id"), 900);
+        CommitmentCoreContract::update_value(
+            e.clone(),
+            admin.clone(),
+            String::from_str(&e, "test_id"),
+            900,
+        );
     });
 }
 
@@ -2126,7 +2194,7 @@ fn test_update_value_no_violation() {
             .set(&DataKey::TotalValueLocked, &1000i128);
     });
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.update_value(&String::from_str(&e, "test_id"), &950);
+    client.update_value(&admin, &String::from_str(&e, "test_id"), &950);
 
     let updated = client.get_commitment(&String::from_str(&e, "test_id"));
     assert_eq!(updated.current_value, 950);
@@ -2142,6 +2210,91 @@ fn test_update_value_no_violation() {
     assert!(has_val_upd, "ValueUpdated event should be emitted");
 }
 
+#[test]
+fn test_update_value_event_carries_delta() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let admin = Address::generate(&e);
+    let nft_contract = Address::generate(&e);
+    let owner = Address::generate(&e);
+    e.as_contract(&contract_id, || {
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        let commitment = create_test_commitment(&e, "test_id", &owner, 1000, 1000, 10, 30, 1000);
+        set_commitment(&e, &commitment);
+    });
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.update_value(&admin, &String::from_str(&e, "test_id"), &950);
+
+    let val_upd_symbol = symbol_short!("ValUpd").into_val(&e);
+    let event = e
+        .events()
+        .all()
+        .iter()
+        .find(|ev| ev.1.first().is_some_and(|t| t.shallow_eq(&val_upd_symbol)))
+        .unwrap();
+    let (_seq, data): (u64, (i128, i128, i128, u64)) = event.2.into_val(&e);
+    assert_eq!(data.0, 1000);
+    assert_eq!(data.1, 950);
+    assert_eq!(data.2, -500);
+}
+
+#[test]
+fn test_drawdown_alert_threshold() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let admin = Address::generate(&e);
+    let nft_contract = Address::generate(&e);
+    let owner = Address::generate(&e);
+    e.as_contract(&contract_id, || {
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        let commitment = create_test_commitment(&e, "test_id", &owner, 1000, 1000, 20, 30, 1000);
+        set_commitment(&e, &commitment);
+    });
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let id = String::from_str(&e, "test_id");
+    let alert_symbol = Symbol::new(&e, "DrawdownAlert");
+    let alerted = |e: &Env| {
+        e.events().all().iter().any(|ev| {
+            ev.1.first()
+                .is_some_and(|t| Symbol::try_from_val(e, &t) == Ok(alert_symbol.clone()))
+        })
+    };
+
+    // Disabled by default
+    client.update_value(&admin, &id, &900);
+    assert!(!alerted(&e));
+
+    client.set_drawdown_alert_bps(&admin, &500);
+    assert_eq!(client.get_drawdown_alert_bps(), 500);
+
+    // 900 -> 880 is a 2.2% drop: below threshold
+    client.update_value(&admin, &id, &880);
+    assert!(!alerted(&e));
+
+    // Rises never alert
+    client.update_value(&admin, &id, &1000);
+    assert!(!alerted(&e));
+
+    // 1000 -> 930 is a 7% drop: alert fires
+    client.update_value(&admin, &id, &930);
+    assert!(alerted(&e));
+}
+
+#[test]
+#[should_panic(expected = "Invalid max loss")]
+fn test_set_drawdown_alert_bps_rejects_out_of_range() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let admin = Address::generate(&e);
+    let nft_contract = Address::generate(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.initialize(&admin, &nft_contract);
+    client.set_drawdown_alert_bps(&admin, &10001);
+}
+
 #[test]
 fn test_update_value_triggers_violation() {
     let e = Env::default();
@@ -2161,7 +2314,7 @@ fn test_update_value_triggers_violation() {
 
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
     // Update to 850: loss = (1000-850)/1000 = 15% > 10% max_loss_percent
-    client.update_value(&String::from_str(&e, "test_id"), &850);
+    client.update_value(&admin, &String::from_str(&e, "test_id"), &850);
 
     let updated = client.get_commitment(&String::from_str(&e, "test_id"));
     assert_eq!(updated.current_value, 850);
@@ -2344,3 +2497,3403 @@ fn test_owner_multiple_commitments_settle_one() {
     let c3 = client.get_commitment(&String::from_str(&e, "commit_003"));
     assert_eq!(c3.status, String::from_str(&e, "active"));
 }
+
+// ============================================
+// Payout Routing Tests
+// ============================================
+
+fn setup_payout_route_test(e: &Env) -> (Address, Address, Address, Address) {
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let nft_contract = e.register_contract(None, MockNftContract);
+    let admin = Address::generate(e);
+    let owner = Address::generate(e);
+
+    let token_contract = e.register_stellar_asset_contract_v2(Address::generate(e));
+    let asset_address = token_contract.address();
+    StellarAssetClient::new(e, &asset_address).mint(&contract_id, &1_000);
+
+    e.as_contract(&contract_id, || {
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract);
+    });
+
+    let mut commitment = create_test_commitment(e, "route_1", &owner, 1_000, 1_000, 10, 1, 0);
+    commitment.asset_address = asset_address.clone();
+    store_commitment(e, &contract_id, &commitment);
+
+    (contract_id, admin, owner, asset_address)
+}
+
+#[test]
+fn test_settle_uses_payout_route() {
+    let e = Env::default();
+    let (contract_id, _admin, owner, asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let treasury = Address::generate(&e);
+
+    client.set_payout_route(&owner, &asset, &treasury);
+    assert_eq!(
+        client.get_payout_route(&owner, &asset),
+        Some(treasury.clone())
+    );
+
+    e.ledger().with_mut(|l| l.timestamp = 2 * 86400);
+    client.settle(&String::from_str(&e, "route_1"));
+
+    let token = token::Client::new(&e, &asset);
+    assert_eq!(token.balance(&treasury), 1_000);
+    assert_eq!(token.balance(&owner), 0);
+}
+
+#[test]
+fn test_settle_falls_back_to_owner_when_route_blocked() {
+    let e = Env::default();
+    let (contract_id, admin, owner, asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let treasury = Address::generate(&e);
+
+    client.set_payout_route(&owner, &asset, &treasury);
+    client.set_address_blocked(&admin, &treasury, &true);
+    assert!(client.is_address_blocked(&treasury));
+
+    e.ledger().with_mut(|l| l.timestamp = 2 * 86400);
+    client.settle(&String::from_str(&e, "route_1"));
+
+    let token = token::Client::new(&e, &asset);
+    assert_eq!(token.balance(&owner), 1_000);
+    assert_eq!(token.balance(&treasury), 0);
+}
+
+#[test]
+#[should_panic(expected = "Address is blocked")]
+fn test_set_payout_route_rejects_blocked_destination() {
+    let e = Env::default();
+    let (contract_id, admin, owner, asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let treasury = Address::generate(&e);
+
+    client.set_address_blocked(&admin, &treasury, &true);
+    client.set_payout_route(&owner, &asset, &treasury);
+}
+
+#[test]
+fn test_clear_payout_route() {
+    let e = Env::default();
+    let (contract_id, _admin, owner, asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let treasury = Address::generate(&e);
+
+    client.set_payout_route(&owner, &asset, &treasury);
+    client.clear_payout_route(&owner, &asset);
+    assert_eq!(client.get_payout_route(&owner, &asset), None);
+}
+
+// ============================================
+// Settlement Receipt Tests
+// ============================================
+
+#[test]
+fn test_no_receipt_when_disabled() {
+    let e = Env::default();
+    let (contract_id, _admin, _owner, _asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    e.ledger().with_mut(|l| l.timestamp = 2 * 86400);
+    let commitment_id = String::from_str(&e, "route_1");
+    client.settle(&commitment_id);
+
+    assert_eq!(client.get_commitment_receipt(&commitment_id), None);
+}
+
+#[test]
+fn test_settle_issues_transferable_receipt() {
+    let e = Env::default();
+    let (contract_id, admin, owner, asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.set_receipt_config(&admin, &true, &true);
+
+    e.ledger().with_mut(|l| l.timestamp = 2 * 86400);
+    let commitment_id = String::from_str(&e, "route_1");
+    client.settle(&commitment_id);
+
+    let receipt_id = client.get_commitment_receipt(&commitment_id).unwrap();
+    let receipt = client.get_receipt(&receipt_id);
+    assert_eq!(receipt.holder, owner);
+    assert_eq!(receipt.asset_address, asset);
+    assert_eq!(receipt.final_payout, 1_000);
+    assert_eq!(receipt.penalty, 0);
+    assert_eq!(receipt.outcome, OUTCOME_SETTLED);
+    assert_eq!(receipt.closed_at, 2 * 86400);
+
+    let accountant = Address::generate(&e);
+    client.transfer_receipt(&owner, &accountant, &receipt_id);
+    assert_eq!(client.get_receipt(&receipt_id).holder, accountant);
+}
+
+#[test]
+#[should_panic(expected = "Settlement receipt is not transferable")]
+fn test_early_exit_issues_soulbound_receipt() {
+    let e = Env::default();
+    let (contract_id, admin, owner, _asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.set_receipt_config(&admin, &true, &false);
+
+    let commitment_id = String::from_str(&e, "route_1");
+    client.early_exit(&commitment_id, &owner);
+
+    let receipt_id = client.get_commitment_receipt(&commitment_id).unwrap();
+    let receipt = client.get_receipt(&receipt_id);
+    assert_eq!(receipt.outcome, OUTCOME_EARLY_EXIT);
+    assert_eq!(receipt.penalty, 100);
+    assert_eq!(receipt.final_payout, 900);
+    assert!(!receipt.transferable);
+
+    client.transfer_receipt(&owner, &Address::generate(&e), &receipt_id);
+}
+
+// ============================================
+// Risk Dashboard Tests
+// ============================================
+
+#[contract]
+struct MockAllocationContract;
+
+#[contractimpl]
+impl MockAllocationContract {
+    pub fn get_all_pools(e: Env) -> Vec<AllocationPool> {
+        let pool = |pool_id: u32, total_liquidity: i128| AllocationPool {
+            pool_id,
+            risk_level: PoolRiskLevel::Medium,
+            apy: 500,
+            total_liquidity,
+            max_capacity: 1_000,
+            active: true,
+            created_at: 0,
+            updated_at: 0,
+        };
+        vec![&e, pool(1, 500), pool(2, 950)]
+    }
+}
+
+#[test]
+fn test_get_risk_dashboard() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let allocation_contract = e.register_contract(None, MockAllocationContract);
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.initialize(&admin, &Address::generate(&e));
+    client.set_allocation_contract(&admin, &allocation_contract);
+
+    // 9% loss on a 10% limit (near), 2% loss (healthy), settled with a large loss (ignored)
+    let near = create_test_commitment(&e, "near", &owner, 1_000, 910, 10, 30, 0);
+    let healthy = create_test_commitment(&e, "healthy", &owner, 1_000, 980, 10, 30, 0);
+    let mut settled = create_test_commitment(&e, "settled", &owner, 1_000, 905, 10, 30, 0);
+    settled.status = String::from_str(&e, "settled");
+    e.as_contract(&contract_id, || {
+        let mut ids = Vec::new(&e);
+        for c in [&near, &healthy, &settled] {
+            set_commitment(&e, c);
+            ids.push_back(c.commitment_id.clone());
+        }
+        e.storage().instance().set(&DataKey::AllCommitmentIds, &ids);
+    });
+
+    // Two days later only "healthy" gets a fresh value
+    e.ledger().with_mut(|l| l.timestamp = 2 * 86400);
+    client.update_value(&admin, &healthy.commitment_id, &980);
+
+    let dashboard = client.get_risk_dashboard();
+    assert_eq!(
+        dashboard.near_max_loss,
+        vec![&e, near.commitment_id.clone()]
+    );
+    assert_eq!(dashboard.stale_attestation, vec![&e, near.commitment_id]);
+    assert_eq!(dashboard.high_utilization_pools, vec![&e, 2u32]);
+    assert_eq!(dashboard.insurance_coverage_bps, 0);
+    assert_eq!(dashboard.queued_withdrawals, 0);
+    assert_eq!(dashboard.timestamp, 2 * 86400);
+}
+
+// ============================================
+// Consistency Repair Tests
+// ============================================
+
+#[contract]
+struct MockAttestationEngine;
+
+#[contractimpl]
+impl MockAttestationEngine {
+    pub fn get_latest_drawdown_percent(_e: Env, _commitment_id: String) -> Option<i128> {
+        Some(15)
+    }
+
+    pub fn get_collected_fees(_e: Env, _asset_address: Address) -> i128 {
+        40
+    }
+}
+
+#[test]
+fn test_resync_commitment_value_from_attestation_engine() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let engine = e.register_contract(None, MockAttestationEngine);
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.initialize(&admin, &Address::generate(&e));
+    client.set_attestation_engine(&admin, &engine);
+
+    let commitment = create_test_commitment(&e, "drift", &owner, 1_000, 1_000, 20, 30, 0);
+    store_commitment(&e, &contract_id, &commitment);
+    e.as_contract(&contract_id, || {
+        e.storage()
+            .instance()
+            .set(&DataKey::TotalValueLocked, &1_000i128);
+    });
+
+    client.resync_commitment_value(&admin, &commitment.commitment_id);
+
+    assert_eq!(
+        client
+            .get_commitment(&commitment.commitment_id)
+            .current_value,
+        850
+    );
+    assert_eq!(client.get_total_value_locked(), 850);
+
+    let events = e.events().all();
+    let last_event = events.last().unwrap();
+    let (_seq, data): (u64, (i128, i128, u64)) = last_event.2.into_val(&e);
+    assert_eq!((data.0, data.1), (1_000, 850));
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_resync_commitment_value_requires_admin() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.initialize(&admin, &Address::generate(&e));
+
+    client.resync_commitment_value(&owner, &String::from_str(&e, "drift"));
+}
+
+// ============================================
+// Delisted Asset Wind-down Tests
+// ============================================
+
+#[contract]
+struct MockSwapAdapter;
+
+#[contractimpl]
+impl MockSwapAdapter {
+    /// Pays out twice `amount_in` of `asset_out` (the adapter is its token admin).
+    pub fn swap(
+        e: Env,
+        _asset_in: Address,
+        asset_out: Address,
+        amount_in: i128,
+        _min_amount_out: i128,
+        to: Address,
+    ) -> i128 {
+        let amount_out = amount_in * 2;
+        StellarAssetClient::new(&e, &asset_out).mint(&to, &amount_out);
+        amount_out
+    }
+
+    pub fn quote(_e: Env, _asset_in: Address, _asset_out: Address, amount_in: i128) -> i128 {
+        amount_in * 2
+    }
+}
+
+#[test]
+#[should_panic(expected = "Asset has been delisted")]
+fn test_create_commitment_rejects_delisted_asset() {
+    let e = Env::default();
+    let (contract_id, admin, owner, asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    client.delist_asset(
+        &admin,
+        &asset,
+        &String::from_str(&e, "issuer sunset"),
+        &None,
+        &None,
+    );
+    let notice = client.get_delisting_notice(&asset).unwrap();
+    assert_eq!(notice.reason, String::from_str(&e, "issuer sunset"));
+
+    client.create_commitment(&owner, &100, &asset, &test_rules(&e), &None);
+}
+
+#[test]
+fn test_settle_with_conversion_pays_target_asset() {
+    let e = Env::default();
+    let (contract_id, admin, owner, asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let adapter = e.register_contract(None, MockSwapAdapter);
+    let target_asset = e
+        .register_stellar_asset_contract_v2(adapter.clone())
+        .address();
+
+    client.delist_asset(
+        &admin,
+        &asset,
+        &String::from_str(&e, "issuer sunset"),
+        &Some(adapter.clone()),
+        &Some(target_asset.clone()),
+    );
+
+    e.ledger().with_mut(|l| l.timestamp = 2 * 86400);
+    let amount_out =
+        client.settle_with_conversion(&String::from_str(&e, "route_1"), &owner, &1_500);
+
+    assert_eq!(amount_out, 2_000);
+    assert_eq!(token::Client::new(&e, &target_asset).balance(&owner), 2_000);
+    assert_eq!(token::Client::new(&e, &asset).balance(&adapter), 1_000);
+    assert_eq!(
+        client
+            .get_commitment(&String::from_str(&e, "route_1"))
+            .status,
+        String::from_str(&e, "settled")
+    );
+}
+
+#[test]
+#[should_panic(expected = "No conversion route for asset")]
+fn test_settle_with_conversion_requires_route() {
+    let e = Env::default();
+    let (contract_id, admin, owner, asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    client.delist_asset(
+        &admin,
+        &asset,
+        &String::from_str(&e, "issuer sunset"),
+        &None,
+        &None,
+    );
+
+    e.ledger().with_mut(|l| l.timestamp = 2 * 86400);
+    client.settle_with_conversion(&String::from_str(&e, "route_1"), &owner, &0);
+}
+
+// ============================================
+// Notification Preference Tests
+// ============================================
+
+fn count_notify_events(e: &Env) -> u32 {
+    use soroban_sdk::TryIntoVal;
+    let mut count = 0;
+    for event in e.events().all().iter() {
+        if let Some(topic) = event.1.get(0) {
+            let topic: Result<Symbol, _> = topic.try_into_val(e);
+            if topic == Ok(symbol_short!("Notify")) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+#[test]
+fn test_notification_prefs_default_and_update() {
+    let e = Env::default();
+    let (contract_id, _admin, owner, _asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    assert_eq!(client.get_notification_prefs(&owner), NOTIFY_ALL);
+
+    client.set_notification_prefs(&owner, &(NOTIFY_THRESHOLD | NOTIFY_EXPIRY_SOON));
+    assert!(client.wants_notification(&owner, &NOTIFY_EXPIRY_SOON));
+    assert!(!client.wants_notification(&owner, &NOTIFY_SETTLEMENT));
+}
+
+#[test]
+fn test_settlement_notification_respects_prefs() {
+    let e = Env::default();
+    let (contract_id, _admin, owner, _asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    e.ledger().with_mut(|l| l.timestamp = 2 * 86400);
+
+    client.set_notification_prefs(&owner, &NOTIFY_THRESHOLD);
+    client.settle(&String::from_str(&e, "route_1"));
+    assert_eq!(count_notify_events(&e), 0);
+}
+
+#[test]
+fn test_settlement_notification_emitted_by_default() {
+    let e = Env::default();
+    let (contract_id, _admin, _owner, _asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    e.ledger().with_mut(|l| l.timestamp = 2 * 86400);
+
+    client.settle(&String::from_str(&e, "route_1"));
+    assert_eq!(count_notify_events(&e), 1);
+}
+
+#[test]
+#[should_panic(expected = "Invalid notification preferences")]
+fn test_set_notification_prefs_rejects_unknown_flags() {
+    let e = Env::default();
+    let (contract_id, _admin, owner, _asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    client.set_notification_prefs(&owner, &(1 << 5));
+}
+
+// ============================================
+// Token Conservation Tests
+// ============================================
+
+fn setup_conservation_test(e: &Env) -> (CommitmentCoreContractClient<'static>, Address, Address) {
+    e.mock_all_auths_allowing_non_root_auth();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let nft_contract = e.register_contract(None, MockNftContract);
+    let admin = Address::generate(e);
+    let owner = Address::generate(e);
+    let asset = e
+        .register_stellar_asset_contract_v2(Address::generate(e))
+        .address();
+    StellarAssetClient::new(e, &asset).mint(&owner, &1_000);
+
+    let client = CommitmentCoreContractClient::new(e, &contract_id);
+    client.initialize(&admin, &nft_contract);
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(e), &None);
+    (client, admin, asset)
+}
+
+#[test]
+fn test_verify_conservation_balanced() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+
+    let report = client.verify_conservation(&asset);
+    assert_eq!(report.escrow, 1_000);
+    assert_eq!(report.fees, 0);
+    assert_eq!(report.expected_total, 1_000);
+    assert_eq!(report.actual_total, 1_000);
+    assert_eq!(report.discrepancy, 0);
+    assert!(report.conserved);
+}
+
+#[test]
+fn test_verify_conservation_reports_leak() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+
+    // Move tokens out of core without touching its accounting
+    let thief = Address::generate(&e);
+    e.as_contract(&client.address, || {
+        token::Client::new(&e, &asset).transfer(&client.address, &thief, &300);
+    });
+
+    let report = client.verify_conservation(&asset);
+    assert_eq!(report.expected_total, 1_000);
+    assert_eq!(report.actual_total, 700);
+    assert_eq!(report.discrepancy, -300);
+    assert!(!report.conserved);
+}
+
+#[test]
+fn test_verify_conservation_includes_attestation_fees() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let engine = e.register_contract(None, MockAttestationEngine);
+    client.set_attestation_engine(&admin, &engine);
+
+    // Engine reports 40 collected but only holds 25
+    StellarAssetClient::new(&e, &asset).mint(&engine, &25);
+
+    let report = client.verify_conservation(&asset);
+    assert_eq!(report.fees, 40);
+    assert_eq!(report.expected_total, 1_040);
+    assert_eq!(report.actual_total, 1_025);
+    assert_eq!(report.discrepancy, -15);
+    assert!(!report.conserved);
+}
+
+// ============================================
+// Payout Currency Policy Tests
+// ============================================
+
+/// Separate module so its contract functions don't clash with MockSwapAdapter's.
+mod overquoting {
+    use soroban_sdk::{contract, contractimpl, token::StellarAssetClient, Address, Env};
+
+    #[contract]
+    pub struct MockOverquotingSwapAdapter;
+
+    #[contractimpl]
+    impl MockOverquotingSwapAdapter {
+        /// Quotes three times `amount_in` but only pays out twice `amount_in`.
+        pub fn swap(
+            e: Env,
+            _asset_in: Address,
+            asset_out: Address,
+            amount_in: i128,
+            _min_amount_out: i128,
+            to: Address,
+        ) -> i128 {
+            let amount_out = amount_in * 2;
+            StellarAssetClient::new(&e, &asset_out).mint(&to, &amount_out);
+            amount_out
+        }
+
+        pub fn quote(_e: Env, _asset_in: Address, _asset_out: Address, amount_in: i128) -> i128 {
+            amount_in * 3
+        }
+    }
+}
+use overquoting::MockOverquotingSwapAdapter;
+
+fn stable_policy(adapter: &Address, target_asset: &Address, max_slippage_bps: u32) -> PayoutPolicy {
+    PayoutPolicy {
+        currency: PayoutCurrency::Stable,
+        swap_adapter: Some(adapter.clone()),
+        target_asset: Some(target_asset.clone()),
+        max_slippage_bps,
+    }
+}
+
+#[test]
+fn test_settle_pays_in_policy_currency() {
+    let e = Env::default();
+    let (contract_id, admin, owner, asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let adapter = e.register_contract(None, MockSwapAdapter);
+    let stable = e
+        .register_stellar_asset_contract_v2(adapter.clone())
+        .address();
+    let balanced = String::from_str(&e, "balanced");
+
+    client.set_receipt_config(&admin, &true, &false);
+    client.set_payout_policy(&admin, &balanced, &stable_policy(&adapter, &stable, 100));
+    assert_eq!(
+        client.get_payout_policy(&balanced).unwrap().currency,
+        PayoutCurrency::Stable
+    );
+
+    e.ledger().with_mut(|l| l.timestamp = 2 * 86400);
+    client.settle(&String::from_str(&e, "route_1"));
+
+    assert_eq!(token::Client::new(&e, &stable).balance(&owner), 2_000);
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 0);
+    let receipt_id = client
+        .get_commitment_receipt(&String::from_str(&e, "route_1"))
+        .unwrap();
+    assert_eq!(client.get_receipt(&receipt_id).final_payout, 2_000);
+}
+
+#[test]
+#[should_panic(expected = "Swap output below minimum")]
+fn test_settle_policy_enforces_slippage_bound() {
+    let e = Env::default();
+    let (contract_id, admin, _owner, _asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let adapter = e.register_contract(None, MockOverquotingSwapAdapter);
+    let stable = e
+        .register_stellar_asset_contract_v2(adapter.clone())
+        .address();
+
+    // Quote is 3000, output 2000: a 10% bound (min 2700) is violated
+    client.set_payout_policy(
+        &admin,
+        &String::from_str(&e, "balanced"),
+        &stable_policy(&adapter, &stable, 1_000),
+    );
+
+    e.ledger().with_mut(|l| l.timestamp = 2 * 86400);
+    client.settle(&String::from_str(&e, "route_1"));
+}
+
+#[test]
+fn test_clear_payout_policy_settles_in_same_asset() {
+    let e = Env::default();
+    let (contract_id, admin, owner, asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let adapter = e.register_contract(None, MockSwapAdapter);
+    let stable = e
+        .register_stellar_asset_contract_v2(adapter.clone())
+        .address();
+    let balanced = String::from_str(&e, "balanced");
+
+    client.set_payout_policy(&admin, &balanced, &stable_policy(&adapter, &stable, 100));
+    client.clear_payout_policy(&admin, &balanced);
+    assert_eq!(client.get_payout_policy(&balanced), None);
+
+    e.ledger().with_mut(|l| l.timestamp = 2 * 86400);
+    client.settle(&String::from_str(&e, "route_1"));
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Invalid payout policy")]
+fn test_set_payout_policy_requires_swap_route() {
+    let e = Env::default();
+    let (contract_id, admin, _owner, _asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    client.set_payout_policy(
+        &admin,
+        &String::from_str(&e, "safe"),
+        &PayoutPolicy {
+            currency: PayoutCurrency::ProtocolToken,
+            swap_adapter: None,
+            target_asset: None,
+            max_slippage_bps: 0,
+        },
+    );
+}
+
+// ============================================
+// Rules Hash Event Tests
+// ============================================
+
+/// Data (without the sequence number) of the most recent event whose first
+/// topic is `name`.
+fn last_event_data(e: &Env, name: Symbol) -> soroban_sdk::Val {
+    use soroban_sdk::TryIntoVal;
+    let mut data = None;
+    for event in e.events().all().iter() {
+        if let Some(topic) = event.1.get(0) {
+            let topic: Result<Symbol, _> = topic.try_into_val(e);
+            if topic == Ok(name.clone()) {
+                data = Some(event.2);
+            }
+        }
+    }
+    let (_seq, data): (u64, soroban_sdk::Val) = data.unwrap().into_val(e);
+    data
+}
+
+#[test]
+fn test_created_event_includes_rules_hash() {
+    use soroban_sdk::xdr::ToXdr;
+
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let nft_contract = e.register_contract(None, MockNftContract);
+    let owner = Address::generate(&e);
+    let asset = e
+        .register_stellar_asset_contract_v2(Address::generate(&e))
+        .address();
+    StellarAssetClient::new(&e, &asset).mint(&owner, &1_000);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.initialize(&Address::generate(&e), &nft_contract);
+
+    let rules = test_rules(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &rules, &None);
+
+    let data: (i128, CommitmentRules, u32, u64, BytesN<32>) =
+        last_event_data(&e, symbol_short!("Created")).into_val(&e);
+    let expected: BytesN<32> = e.crypto().sha256(&rules.to_xdr(&e)).to_bytes();
+    assert_eq!(data.4, expected);
+    assert_eq!(client.get_rules_hash(&commitment_id), expected);
+}
+
+#[test]
+fn test_settled_event_includes_rules_hash() {
+    let e = Env::default();
+    let (contract_id, _admin, _owner, _asset) = setup_payout_route_test(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let commitment_id = String::from_str(&e, "route_1");
+
+    e.ledger().with_mut(|l| l.timestamp = 2 * 86400);
+    client.settle(&commitment_id);
+
+    let data: (i128, u64, BytesN<32>) = last_event_data(&e, symbol_short!("Settled")).into_val(&e);
+    assert_eq!(data.0, 1_000);
+    assert_eq!(data.2, client.get_rules_hash(&commitment_id));
+}
+
+// ============================================
+// Sunset Tests
+// ============================================
+
+fn sunset_policy(
+    e: &Env,
+    final_date: u64,
+    beneficiaries: &[Address],
+    shares_bps: &[u32],
+) -> SunsetPolicy {
+    let mut b = Vec::new(e);
+    for a in beneficiaries {
+        b.push_back(a.clone());
+    }
+    let mut s = Vec::new(e);
+    for bps in shares_bps {
+        s.push_back(*bps);
+    }
+    SunsetPolicy {
+        final_date,
+        beneficiaries: b,
+        shares_bps: s,
+        policy_uri: String::from_str(e, "ipfs://sunset-policy"),
+    }
+}
+
+#[test]
+#[should_panic(expected = "Protocol is sunsetting: no new commitments")]
+fn test_sunset_blocks_new_commitments() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let treasury = Address::generate(&e);
+
+    assert_eq!(client.get_sunset_state(), SunsetState::Live);
+    client.begin_sunset(&admin, &sunset_policy(&e, 1_000, &[treasury], &[10_000]));
+    assert_eq!(client.get_sunset_state(), SunsetState::Sunsetting);
+    assert!(client.get_sunset_policy().is_some());
+
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &500);
+    client.create_commitment(&owner, &500, &asset, &test_rules(&e), &None);
+}
+
+#[test]
+fn test_sunset_waives_penalty_and_distributes_surplus() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let treasury = Address::generate(&e);
+    let insurance = Address::generate(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&commitment_id).owner;
+
+    client.begin_sunset(
+        &admin,
+        &sunset_policy(
+            &e,
+            1_000,
+            &[treasury.clone(), insurance.clone()],
+            &[7_000, 3_000],
+        ),
+    );
+    // Retained penalties from earlier exits sit in core as surplus
+    StellarAssetClient::new(&e, &asset).mint(&client.address, &100);
+
+    let result = client.try_distribute_sunset_funds(&admin, &asset);
+    assert!(result.is_err());
+
+    e.ledger().with_mut(|li| li.timestamp = 1_000);
+    assert_eq!(client.get_sunset_state(), SunsetState::Final);
+
+    // Active commitment funds are not distributable
+    assert_eq!(client.distribute_sunset_funds(&admin, &asset), 100);
+    let token_client = token::Client::new(&e, &asset);
+    assert_eq!(token_client.balance(&treasury), 70);
+    assert_eq!(token_client.balance(&insurance), 30);
+    assert_eq!(token_client.balance(&client.address), 1_000);
+
+    // Early exit after the final date returns the full value
+    client.early_exit(&commitment_id, &owner);
+    assert_eq!(token_client.balance(&owner), 1_000);
+}
+
+#[test]
+fn test_sunset_distribution_reserves_user_liabilities() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_stress_test(&e);
+    let c_0 = String::from_str(&e, "c_0");
+    let c_1 = String::from_str(&e, "c_1");
+    let owner = client.get_commitment(&c_0).owner;
+    let whale = client.get_commitment(&c_1).owner;
+    let treasury = Address::generate(&e);
+    let token_client = token::Client::new(&e, &asset);
+
+    // Outstanding at the final date: accrued yield and a queued exit
+    StellarAssetClient::new(&e, &asset).mint(&admin, &200);
+    client.report_yield(&admin, &c_1, &200);
+    client.set_stressed_mode(&admin, &true);
+    client.early_exit(&c_0, &owner);
+    client.begin_sunset(
+        &admin,
+        &sunset_policy(&e, 1_000, core::slice::from_ref(&treasury), &[10_000]),
+    );
+    StellarAssetClient::new(&e, &asset).mint(&client.address, &100);
+
+    e.ledger().with_mut(|li| li.timestamp = 1_000);
+    let surplus = client.distribute_sunset_funds(&admin, &asset);
+    // Only the stray 100 and the 50 exit penalty are distributable
+    assert_eq!(surplus, 150);
+    assert_eq!(token_client.balance(&treasury), surplus);
+    let report = client.verify_conservation(&asset);
+    assert_eq!(report.insurance, 0);
+    assert_eq!(report.discrepancy, 0);
+
+    assert_eq!(client.claim_yield(&c_1), 200);
+    assert_eq!(token_client.balance(&whale), 200);
+    client.set_stress_config(
+        &admin,
+        &StressConfig {
+            auto_trigger_bps: 0,
+            round_liquidity_bps: 10_000,
+        },
+    );
+    client.process_exit_queue(&asset);
+    assert_eq!(token_client.balance(&owner), 950);
+    assert!(client.verify_conservation(&asset).conserved);
+}
+
+#[test]
+#[should_panic(expected = "Invalid sunset policy")]
+fn test_begin_sunset_rejects_bad_shares() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let treasury = Address::generate(&e);
+
+    client.begin_sunset(&admin, &sunset_policy(&e, 1_000, &[treasury], &[9_000]));
+}
+
+// ============================================
+// Snapshot Pagination Tests
+// ============================================
+
+#[test]
+fn test_commitments_page_reports_snapshot_changes() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &2_000);
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+
+    let first = client.get_commitments_page(&0, &2);
+    assert_eq!(first.commitments.len(), 2);
+    assert_eq!(first.next_offset, 2);
+    assert_eq!(first.snapshot, client.get_snapshot_version());
+
+    let last = client.get_commitments_page(&first.next_offset, &2);
+    assert_eq!(last.commitments.len(), 1);
+    assert_eq!(last.next_offset, 0);
+    assert_eq!(last.snapshot, first.snapshot);
+
+    // A write between pages changes the snapshot
+    client.update_value(&admin, &String::from_str(&e, "c_1"), &900);
+    let retry = client.get_commitments_page(&first.next_offset, &2);
+    assert!(retry.snapshot > first.snapshot);
+}
+
+// ============================================
+// Tracking-Only Commitment Tests
+// ============================================
+
+#[test]
+fn test_tracking_commitment_excluded_from_tvl_and_payouts() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let pilot = Address::generate(&e);
+
+    // The pilot holds no tokens: nothing is escrowed
+    let id = client.create_tracking_commitment(&pilot, &5_000, &asset, &test_rules(&e));
+    assert!(client.is_tracking_only(&id));
+    assert!(!client.is_tracking_only(&String::from_str(&e, "c_0")));
+    assert_eq!(client.get_commitment(&id).amount, 5_000);
+    assert_eq!(client.get_total_value_locked(), 1_000);
+    assert_eq!(client.verify_conservation(&asset).escrow, 1_000);
+
+    // Attestation-driven valuation does not move TVL
+    client.update_value(&admin, &id, &4_800);
+    assert_eq!(client.get_commitment(&id).current_value, 4_800);
+    assert_eq!(client.get_total_value_locked(), 1_000);
+
+    e.ledger().with_mut(|l| l.timestamp = 31 * 86400);
+    client.settle(&id);
+    assert_eq!(
+        client.get_commitment(&id).status,
+        String::from_str(&e, "settled")
+    );
+    assert_eq!(token::Client::new(&e, &asset).balance(&pilot), 0);
+    assert_eq!(client.get_total_value_locked(), 1_000);
+    assert!(client.verify_conservation(&asset).conserved);
+}
+
+#[test]
+#[should_panic(expected = "Commitment is tracking-only")]
+fn test_tracking_commitment_cannot_be_allocated() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let pilot = Address::generate(&e);
+
+    let id = client.create_tracking_commitment(&pilot, &5_000, &asset, &test_rules(&e));
+    client.allocate(&id, &Address::generate(&e), &100);
+}
+
+// ============================================
+// Commitment Label Tests
+// ============================================
+
+#[test]
+fn test_labeled_commitment_uses_curated_set() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &1_000);
+
+    client.add_label(&admin, &symbol_short!("retail"));
+    client.add_label(&admin, &symbol_short!("pilot"));
+    client.add_label(&admin, &symbol_short!("pilot"));
+    assert_eq!(client.get_labels().len(), 2);
+
+    let id = client.create_labeled_commitment(
+        &owner,
+        &1_000,
+        &asset,
+        &test_rules(&e),
+        &symbol_short!("pilot"),
+    );
+    assert_eq!(
+        client.get_commitment_label(&id),
+        Some(symbol_short!("pilot"))
+    );
+    assert_eq!(
+        client.get_commitment_label(&String::from_str(&e, "c_0")),
+        None
+    );
+
+    // Removing a label keeps it on existing commitments
+    client.remove_label(&admin, &symbol_short!("pilot"));
+    assert_eq!(client.get_labels(), vec![&e, symbol_short!("retail")]);
+    assert_eq!(
+        client.get_commitment_label(&id),
+        Some(symbol_short!("pilot"))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Unknown commitment label")]
+fn test_labeled_commitment_rejects_unknown_label() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &1_000);
+
+    client.create_labeled_commitment(
+        &owner,
+        &1_000,
+        &asset,
+        &test_rules(&e),
+        &symbol_short!("whale"),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Label set is full")]
+fn test_label_set_is_bounded() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+
+    let names = [
+        "l0", "l1", "l2", "l3", "l4", "l5", "l6", "l7", "l8", "l9", "l10", "l11", "l12", "l13",
+        "l14", "l15", "l16",
+    ];
+    assert!(names.len() as u32 > MAX_LABELS);
+    for name in names {
+        client.add_label(&admin, &Symbol::new(&e, name));
+    }
+}
+
+// ============================================
+// Stressed-Mode Exit Queue Tests
+// ============================================
+
+fn setup_stress_test(e: &Env) -> (CommitmentCoreContractClient<'static>, Address, Address) {
+    let (client, admin, asset) = setup_conservation_test(e);
+    let whale = Address::generate(e);
+    StellarAssetClient::new(e, &asset).mint(&whale, &3_000);
+    client.create_commitment(&whale, &3_000, &asset, &test_rules(e), &None);
+    (client, admin, asset)
+}
+
+#[test]
+fn test_stressed_exits_paid_pro_rata_over_rounds() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_stress_test(&e);
+    let c_0 = String::from_str(&e, "c_0");
+    let c_1 = String::from_str(&e, "c_1");
+    let owner = client.get_commitment(&c_0).owner;
+    let whale = client.get_commitment(&c_1).owner;
+    let token_client = token::Client::new(&e, &asset);
+
+    client.set_stressed_mode(&admin, &true);
+    client.early_exit(&c_0, &owner);
+    client.early_exit(&c_1, &whale);
+    assert_eq!(
+        client.get_commitment(&c_0).status,
+        String::from_str(&e, "exit_queued")
+    );
+    assert_eq!(client.get_exit_queue(&asset).len(), 2);
+    assert_eq!(client.get_queued_exit(&c_1).unwrap().owed, 2_850);
+    assert_eq!(client.get_total_value_locked(), 0);
+    assert_eq!(client.get_risk_dashboard().queued_withdrawals, 2);
+    let report = client.verify_conservation(&asset);
+    assert_eq!(report.queued_withdrawals, 3_800);
+    assert!(report.conserved);
+
+    // 10% of the 4_000 balance, split 1:3 by what each is owed
+    assert_eq!(client.process_exit_queue(&asset), 400);
+    assert_eq!(token_client.balance(&owner), 100);
+    assert_eq!(token_client.balance(&whale), 300);
+    assert!(client.try_process_exit_queue(&asset).is_err());
+
+    e.ledger().with_mut(|li| li.sequence_number += 1);
+    client.set_stress_config(
+        &admin,
+        &StressConfig {
+            auto_trigger_bps: 0,
+            round_liquidity_bps: 10_000,
+        },
+    );
+    assert_eq!(client.process_exit_queue(&asset), 3_400);
+    assert_eq!(token_client.balance(&owner), 950);
+    assert_eq!(token_client.balance(&whale), 2_850);
+    assert_eq!(client.get_exit_queue(&asset).len(), 0);
+    assert_eq!(
+        client.get_commitment(&c_1).status,
+        String::from_str(&e, "early_exit")
+    );
+    // Penalties stay in core
+    assert_eq!(token_client.balance(&client.address), 200);
+}
+
+#[test]
+fn test_cancel_queued_exit_restores_unpaid_value() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_stress_test(&e);
+    let c_0 = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&c_0).owner;
+
+    client.set_stressed_mode(&admin, &true);
+    client.early_exit(&c_0, &owner);
+    assert_eq!(client.process_exit_queue(&asset), 400);
+
+    client.cancel_queued_exit(&c_0, &owner);
+    let commitment = client.get_commitment(&c_0);
+    assert_eq!(commitment.status, String::from_str(&e, "active"));
+    assert_eq!(commitment.current_value, 600);
+    assert_eq!(client.get_total_value_locked(), 3_600);
+    assert_eq!(client.get_queued_exit(&c_0), None);
+    assert!(client.verify_conservation(&asset).conserved);
+}
+
+#[test]
+fn test_large_exit_auto_triggers_stressed_mode() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_stress_test(&e);
+    let c_0 = String::from_str(&e, "c_0");
+    let c_1 = String::from_str(&e, "c_1");
+    client.set_stress_config(
+        &admin,
+        &StressConfig {
+            auto_trigger_bps: 5_000,
+            round_liquidity_bps: 1_000,
+        },
+    );
+
+    // 950 of 4_000 escrow is under the threshold
+    client.early_exit(&c_0, &client.get_commitment(&c_0).owner);
+    assert!(!client.is_stressed());
+    assert_eq!(
+        client.get_commitment(&c_0).status,
+        String::from_str(&e, "early_exit")
+    );
+
+    // 2_850 of the remaining 3_000 is over it
+    client.early_exit(&c_1, &client.get_commitment(&c_1).owner);
+    assert!(client.is_stressed());
+    assert_eq!(client.get_exit_queue(&asset).len(), 1);
+}
+
+#[test]
+fn test_quote_early_exit_does_not_trigger_stressed_mode() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_stress_test(&e);
+    let c_0 = String::from_str(&e, "c_0");
+    client.set_stress_config(
+        &admin,
+        &StressConfig {
+            auto_trigger_bps: 100,
+            round_liquidity_bps: 1_000,
+        },
+    );
+
+    assert!(client.quote_early_exit(&c_0).queued);
+    assert!(!client.is_stressed());
+
+    client.early_exit(&c_0, &client.get_commitment(&c_0).owner);
+    assert!(client.is_stressed());
+}
+
+// ============================================
+// Top-Up Tests
+// ============================================
+
+#[test]
+fn test_top_up_adds_principal() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&commitment_id).owner;
+    StellarAssetClient::new(&e, &asset).mint(&owner, &500);
+
+    client.top_up(&commitment_id, &500);
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.amount, 1_500);
+    assert_eq!(commitment.current_value, 1_500);
+    assert_eq!(client.get_total_value_locked(), 1_500);
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 0);
+    assert!(client.verify_conservation(&asset).conserved);
+}
+
+#[test]
+#[should_panic(expected = "Commitment is not active")]
+fn test_top_up_rejects_inactive_commitment() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&commitment_id).owner;
+    client.early_exit(&commitment_id, &owner);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &500);
+
+    client.top_up(&commitment_id, &500);
+}
+
+// ============================================
+// Rollover Tests
+// ============================================
+
+#[test]
+fn test_settle_and_rollover_keeps_funds_in_escrow() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&commitment_id).owner;
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+
+    let mut new_rules = test_rules(&e);
+    new_rules.duration_days = 60;
+    let new_id = client.settle_and_rollover(&commitment_id, &new_rules);
+
+    assert_eq!(
+        client.get_commitment(&commitment_id).status,
+        String::from_str(&e, "settled")
+    );
+    let rolled = client.get_commitment(&new_id);
+    assert_eq!(rolled.owner, owner);
+    assert_eq!(rolled.amount, 1_000);
+    assert_eq!(rolled.rules.duration_days, 60);
+    assert_eq!(client.get_rolled_over_into(&commitment_id), Some(new_id));
+    assert_eq!(client.get_total_value_locked(), 1_000);
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 0);
+    assert!(client.verify_conservation(&asset).conserved);
+}
+
+#[test]
+#[should_panic(expected = "Commitment has not expired yet")]
+fn test_settle_and_rollover_requires_expiry() {
+    let e = Env::default();
+    let (client, _admin, _asset) = setup_conservation_test(&e);
+    client.settle_and_rollover(&String::from_str(&e, "c_0"), &test_rules(&e));
+}
+
+// ============================================
+// Yield Compounding Tests
+// ============================================
+
+#[test]
+fn test_report_yield_compounds_when_opted_in() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    StellarAssetClient::new(&e, &asset).mint(&admin, &200);
+
+    client.set_auto_compound(&commitment_id, &true);
+    client.report_yield(&admin, &commitment_id, &200);
+
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.amount, 1_200);
+    assert_eq!(commitment.current_value, 1_200);
+    assert_eq!(client.get_total_value_locked(), 1_200);
+    assert_eq!(client.get_accrued_yield(&commitment_id), 0);
+    assert_eq!(client.verify_conservation(&asset).discrepancy, 0);
+}
+
+#[test]
+fn test_report_yield_accrues_until_claimed() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&commitment_id).owner;
+    StellarAssetClient::new(&e, &asset).mint(&admin, &200);
+
+    client.report_yield(&admin, &commitment_id, &200);
+    assert_eq!(client.get_commitment(&commitment_id).current_value, 1_000);
+    assert_eq!(client.get_accrued_yield(&commitment_id), 200);
+    let report = client.verify_conservation(&asset);
+    assert_eq!(report.unclaimed_yield, 200);
+    assert_eq!(report.discrepancy, 0);
+
+    assert_eq!(client.claim_yield(&commitment_id), 200);
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 200);
+    assert_eq!(client.verify_conservation(&asset).unclaimed_yield, 0);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: caller not allowed")]
+fn test_report_yield_rejects_unknown_reporter() {
+    let e = Env::default();
+    let (client, _admin, _asset) = setup_conservation_test(&e);
+    let stranger = Address::generate(&e);
+    client.report_yield(&stranger, &String::from_str(&e, "c_0"), &100);
+}
+
+// ============================================
+// Settlement Fee Tests
+// ============================================
+
+#[test]
+fn test_settle_pays_fee_to_treasury() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&commitment_id).owner;
+    let treasury = Address::generate(&e);
+    client.set_fee_config(&admin, &50, &treasury);
+    assert_eq!(
+        client.get_fee_config(),
+        FeeConfig {
+            settlement_fee_bps: 50,
+            treasury: Some(treasury.clone()),
+        }
+    );
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+
+    let token_client = token::Client::new(&e, &asset);
+    assert_eq!(token_client.balance(&treasury), 5);
+    assert_eq!(token_client.balance(&owner), 995);
+    assert_eq!(token_client.balance(&client.address), 0);
+}
+
+fn loyalty_tiers(e: &Env) -> Vec<FeeTier> {
+    vec![
+        e,
+        FeeTier {
+            min_settled: 1,
+            min_settled_amount: 0,
+            discount_bps: 2_000,
+        },
+        FeeTier {
+            min_settled: 2,
+            min_settled_amount: 5_000,
+            discount_bps: 5_000,
+        },
+    ]
+}
+
+#[test]
+fn test_fee_tier_discounts_settlement_fee() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&commitment_id).owner;
+    let treasury = Address::generate(&e);
+    client.set_fee_config(&admin, &50, &treasury);
+    client.set_fee_tiers(&admin, &loyalty_tiers(&e));
+    assert_eq!(client.get_fee_tier(&owner), None);
+
+    let nft = MockNftContractClient::new(&e, &client.get_nft_contract());
+    nft.set_settled_holdings(&owner, &(1, 1_000));
+    assert_eq!(
+        client.get_fee_tier(&owner),
+        Some(loyalty_tiers(&e).get(0).unwrap())
+    );
+    nft.set_settled_holdings(&owner, &(2, 5_000));
+    assert_eq!(
+        client.get_fee_tier(&owner),
+        Some(loyalty_tiers(&e).get(1).unwrap())
+    );
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+
+    // 5 fee less the 50% loyalty discount (rounded down)
+    let token_client = token::Client::new(&e, &asset);
+    assert_eq!(token_client.balance(&treasury), 3);
+    assert_eq!(token_client.balance(&owner), 997);
+}
+
+#[test]
+fn test_fee_tier_discounts_early_exit_penalty() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&commitment_id).owner;
+    client.set_fee_tiers(&admin, &loyalty_tiers(&e));
+    MockNftContractClient::new(&e, &client.get_nft_contract())
+        .set_settled_holdings(&owner, &(1, 1_000));
+
+    client.early_exit(&commitment_id, &owner);
+
+    // 50 penalty less the 20% loyalty discount
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 960);
+}
+
+#[test]
+#[should_panic(expected = "Invalid fee config")]
+fn test_set_fee_tiers_rejects_discount_above_100_percent() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let tiers = vec![
+        &e,
+        FeeTier {
+            min_settled: 1,
+            min_settled_amount: 0,
+            discount_bps: 10_001,
+        },
+    ];
+    client.set_fee_tiers(&admin, &tiers);
+}
+
+#[test]
+#[should_panic(expected = "Invalid fee config")]
+fn test_set_fee_config_rejects_excessive_fee() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    client.set_fee_config(
+        &admin,
+        &(MAX_SETTLEMENT_FEE_BPS + 1),
+        &Address::generate(&e),
+    );
+}
+
+// ============================================
+// Penalty Recipient Tests
+// ============================================
+
+#[test]
+fn test_early_exit_penalty_to_treasury() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&commitment_id).owner;
+    let treasury = Address::generate(&e);
+    client.set_penalty_recipient(&admin, &PenaltyRecipient::Treasury(treasury.clone()));
+    assert_eq!(
+        client.get_penalty_recipient(),
+        PenaltyRecipient::Treasury(treasury.clone())
+    );
+
+    client.early_exit(&commitment_id, &owner);
+
+    let token_client = token::Client::new(&e, &asset);
+    assert_eq!(token_client.balance(&owner), 950);
+    assert_eq!(token_client.balance(&treasury), 50);
+    assert_eq!(token_client.balance(&client.address), 0);
+}
+
+#[test]
+fn test_early_exit_penalty_redistributed() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let other = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&other, &1_000);
+    let other_id = client.create_commitment(&other, &1_000, &asset, &test_rules(&e), &None);
+    client.set_penalty_recipient(&admin, &PenaltyRecipient::Redistribute);
+
+    let commitment_id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&commitment_id).owner;
+    client.early_exit(&commitment_id, &owner);
+
+    // The share accrues to the index; values and TVL are untouched
+    assert_eq!(client.get_commitment(&other_id).current_value, 1_000);
+    assert_eq!(client.get_total_value_locked(), 1_000);
+    assert_eq!(client.get_penalty_share(&other, &asset), 0);
+    let report = client.verify_conservation(&asset);
+    assert_eq!(report.escrow, 1_000);
+    assert_eq!(report.discrepancy, 0);
+
+    // A value update credits the share earned before it
+    client.update_value(&admin, &other_id, &960);
+    assert_eq!(client.get_penalty_share(&other, &asset), 50);
+    assert_eq!(client.get_commitment(&other_id).current_value, 960);
+
+    // Settlement credits nothing further; the share is claimable
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86_400);
+    client.settle(&other_id);
+    assert_eq!(client.claim_penalty_share(&other, &asset), 50);
+    assert_eq!(token::Client::new(&e, &asset).balance(&other), 1_010);
+}
+
+#[test]
+fn test_early_exit_penalty_burned() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    client.set_penalty_recipient(&admin, &PenaltyRecipient::Burn);
+    let commitment_id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&commitment_id).owner;
+
+    client.early_exit(&commitment_id, &owner);
+
+    assert_eq!(token::Client::new(&e, &asset).balance(&client.address), 0);
+}
+
+#[test]
+fn test_penalty_pool_shared_by_compliant_settlements() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let small = Address::generate(&e);
+    let large = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&small, &1_000);
+    StellarAssetClient::new(&e, &asset).mint(&large, &3_000);
+    let small_id = client.create_commitment(&small, &1_000, &asset, &test_rules(&e), &None);
+    let large_id = client.create_commitment(&large, &3_000, &asset, &test_rules(&e), &None);
+    client.set_penalty_recipient(&admin, &PenaltyRecipient::CompliancePool);
+
+    let exited_id = String::from_str(&e, "c_0");
+    let exiting_owner = client.get_commitment(&exited_id).owner;
+    client.early_exit(&exited_id, &exiting_owner);
+    assert_eq!(client.get_penalty_pool(&asset), 50);
+    assert_eq!(client.verify_conservation(&asset).penalty_pool, 50);
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86_400);
+    client.settle(&small_id);
+    // 50 * 1000 / 4000, floored
+    assert_eq!(client.get_penalty_share(&small, &asset), 12);
+    assert_eq!(client.get_penalty_pool(&asset), 38);
+    // The last compliant commitment takes the remainder
+    client.settle(&large_id);
+    assert_eq!(client.get_penalty_share(&large, &asset), 38);
+    assert_eq!(client.get_penalty_pool(&asset), 0);
+
+    assert_eq!(client.claim_penalty_share(&small, &asset), 12);
+    assert_eq!(client.claim_penalty_share(&small, &asset), 0);
+    assert_eq!(token::Client::new(&e, &asset).balance(&small), 1_012);
+    let report = client.verify_conservation(&asset);
+    assert_eq!(report.penalty_pool, 38);
+    assert_eq!(report.discrepancy, 0);
+}
+
+// ============================================
+// Grace Period Tests
+// ============================================
+
+#[test]
+fn test_settle_within_grace_is_fee_free() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let commitment = client.get_commitment(&commitment_id);
+    client.set_late_fee_config(
+        &admin,
+        &LateFeeConfig {
+            bps_per_day: 100,
+            max_bps: 1_000,
+        },
+    );
+    assert_eq!(
+        client.get_maturity_status(&commitment_id),
+        MaturityStatus::Locked
+    );
+
+    // test_rules has no grace period, so the expiry instant is the last fee-free moment
+    e.ledger().with_mut(|l| l.timestamp = commitment.expires_at);
+    assert_eq!(
+        client.get_maturity_status(&commitment_id),
+        MaturityStatus::InGrace
+    );
+    client.settle(&commitment_id);
+
+    assert_eq!(
+        token::Client::new(&e, &asset).balance(&commitment.owner),
+        1_000
+    );
+    assert_eq!(
+        client.get_maturity_status(&commitment_id),
+        MaturityStatus::Closed
+    );
+}
+
+#[test]
+fn test_settle_after_grace_charges_late_fee() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &1_000);
+    let mut rules = test_rules(&e);
+    rules.grace_period_days = 2;
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &rules, &None);
+    let expires_at = client.get_commitment(&commitment_id).expires_at;
+    client.set_late_fee_config(
+        &admin,
+        &LateFeeConfig {
+            bps_per_day: 100,
+            max_bps: 250,
+        },
+    );
+
+    e.ledger()
+        .with_mut(|l| l.timestamp = expires_at + 2 * 86_400 + 1);
+    assert_eq!(
+        client.get_maturity_status(&commitment_id),
+        MaturityStatus::Overdue
+    );
+
+    // Five started days late, capped at 250 bps
+    e.ledger().with_mut(|l| l.timestamp += 4 * 86_400);
+    client.settle(&commitment_id);
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 975);
+    assert!(client.verify_conservation(&asset).conserved);
+}
+
+// ============================================
+// Liquidation Tests
+// ============================================
+
+#[test]
+fn test_liquidate_violated_commitment() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&commitment_id).owner;
+
+    // Drawdown of 20% breaches the 10% max loss
+    client.update_value(&admin, &commitment_id, &800);
+    assert_eq!(
+        client.get_commitment(&commitment_id).status,
+        String::from_str(&e, "violated")
+    );
+    assert!(client
+        .try_allocate(&commitment_id, &Address::generate(&e), &100)
+        .is_err());
+
+    // 5% penalty on the remaining 800
+    assert_eq!(client.liquidate(&commitment_id), 760);
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.status, String::from_str(&e, "liquidated"));
+    assert_eq!(commitment.current_value, 0);
+    assert_eq!(client.get_total_value_locked(), 0);
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 760);
+}
+
+#[test]
+#[should_panic(expected = "Commitment is not violated")]
+fn test_liquidate_rejects_active_commitment() {
+    let e = Env::default();
+    let (client, _admin, _asset) = setup_conservation_test(&e);
+    client.liquidate(&String::from_str(&e, "c_0"));
+}
+
+#[test]
+fn test_flag_violation_ignores_healthy_commitment() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    client.update_value(&admin, &commitment_id, &950);
+
+    assert!(!client.flag_violation(&commitment_id));
+    assert_eq!(
+        client.get_commitment(&commitment_id).status,
+        String::from_str(&e, "active")
+    );
+}
+
+// ============================================
+// Keeper Settlement Tests
+// ============================================
+
+#[test]
+fn test_keeper_settle_pays_reward_after_grace() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let commitment = client.get_commitment(&commitment_id);
+    let keeper = Address::generate(&e);
+    client.set_keeper_reward_bps(&admin, &100);
+
+    e.ledger()
+        .with_mut(|l| l.timestamp = commitment.expires_at + 1);
+    assert_eq!(client.keeper_settle(&keeper, &commitment_id), 10);
+
+    let token_client = token::Client::new(&e, &asset);
+    assert_eq!(token_client.balance(&keeper), 10);
+    assert_eq!(token_client.balance(&commitment.owner), 990);
+    assert_eq!(
+        client.get_commitment(&commitment_id).status,
+        String::from_str(&e, "settled")
+    );
+}
+
+#[test]
+#[should_panic(expected = "Commitment is still in its grace period")]
+fn test_keeper_settle_rejected_during_grace() {
+    let e = Env::default();
+    let (client, _admin, _asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let expires_at = client.get_commitment(&commitment_id).expires_at;
+
+    e.ledger().with_mut(|l| l.timestamp = expires_at);
+    client.keeper_settle(&Address::generate(&e), &commitment_id);
+}
+
+// ============================================
+// Owner Sync Tests
+// ============================================
+
+#[test]
+fn test_sync_owner_moves_commitment_to_new_holder() {
+    let e = Env::default();
+    let (client, _admin, _asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let old_owner = client.get_commitment(&commitment_id).owner;
+    let new_owner = Address::generate(&e);
+
+    client.sync_owner(&commitment_id, &new_owner);
+
+    assert_eq!(client.get_commitment(&commitment_id).owner, new_owner);
+    assert_eq!(client.get_owner_commitments(&old_owner).len(), 0);
+    assert_eq!(
+        client.get_owner_commitments(&new_owner),
+        Vec::from_array(&e, [commitment_id])
+    );
+}
+
+#[test]
+fn test_sync_owner_requires_nft_contract_auth() {
+    let e = Env::default();
+    let (client, _admin, _asset) = setup_conservation_test(&e);
+    e.set_auths(&[]);
+
+    let result = client.try_sync_owner(&String::from_str(&e, "c_0"), &Address::generate(&e));
+    assert!(result.is_err());
+}
+
+// ============================================
+// Value Staleness Tests
+// ============================================
+
+#[test]
+fn test_is_value_stale_tracks_last_update() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    client.set_stale_value_seconds(&admin, &3_600);
+    assert!(!client.is_value_stale(&commitment_id));
+
+    e.ledger().with_mut(|l| l.timestamp += 3_600);
+    assert!(client.is_value_stale(&commitment_id));
+
+    client.update_value(&admin, &commitment_id, &1_000);
+    assert_eq!(
+        client.get_last_value_update(&commitment_id),
+        e.ledger().timestamp()
+    );
+    assert!(!client.is_value_stale(&commitment_id));
+}
+
+#[test]
+#[should_panic(expected = "Commitment value is stale")]
+fn test_settle_requires_fresh_value_when_enabled() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    client.set_require_fresh_value(&admin, &true);
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86_400);
+    client.settle(&commitment_id);
+}
+
+#[test]
+fn test_only_operators_refresh_values() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let oracle = Address::generate(&e);
+    client.set_stale_value_seconds(&admin, &3_600);
+    e.ledger().with_mut(|l| l.timestamp += 3_600);
+
+    // An arbitrary caller cannot make a stale value look fresh
+    assert!(client
+        .try_update_value(&Address::generate(&e), &commitment_id, &1_000)
+        .is_err());
+    assert!(client.is_value_stale(&commitment_id));
+
+    client.grant_operator(&admin, &oracle);
+    client.update_value(&oracle, &commitment_id, &1_000);
+    assert!(!client.is_value_stale(&commitment_id));
+}
+
+// ============================================
+// Beneficiary Tests
+// ============================================
+
+#[test]
+fn test_settle_pays_beneficiary() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&commitment_id).owner;
+    let beneficiary = Address::generate(&e);
+    client.set_beneficiary(&commitment_id, &beneficiary);
+    assert_eq!(
+        client.get_beneficiary(&commitment_id),
+        Some(beneficiary.clone())
+    );
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86_400);
+    client.settle(&commitment_id);
+
+    let token_client = token::Client::new(&e, &asset);
+    assert_eq!(token_client.balance(&beneficiary), 1_000);
+    assert_eq!(token_client.balance(&owner), 0);
+}
+
+#[test]
+fn test_early_exit_pays_beneficiary_until_cleared() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&commitment_id).owner;
+    let beneficiary = Address::generate(&e);
+    client.set_beneficiary(&commitment_id, &beneficiary);
+    client.clear_beneficiary(&commitment_id);
+    assert_eq!(client.get_beneficiary(&commitment_id), None);
+    client.set_beneficiary(&commitment_id, &beneficiary);
+
+    client.early_exit(&commitment_id, &owner);
+    assert_eq!(token::Client::new(&e, &asset).balance(&beneficiary), 950);
+}
+
+// ============================================
+// Referral Tests
+// ============================================
+
+#[test]
+fn test_referrer_accrues_and_claims_fee_share() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    let referrer = Address::generate(&e);
+    let treasury = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &10_000);
+    client.set_fee_config(&admin, &100, &treasury);
+    client.set_referral_share_bps(&admin, &2_000);
+
+    let commitment_id = client.create_commitment(
+        &owner,
+        &10_000,
+        &asset,
+        &test_rules(&e),
+        &Some(referrer.clone()),
+    );
+    assert_eq!(client.get_referrer(&commitment_id), Some(referrer.clone()));
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86_400);
+    client.settle(&commitment_id);
+
+    // 1% fee of 10_000 = 100, of which 20% accrues to the referrer
+    let token_client = token::Client::new(&e, &asset);
+    assert_eq!(token_client.balance(&treasury), 80);
+    assert_eq!(client.get_referral_rewards(&referrer, &asset), 20);
+    let report = client.verify_conservation(&asset);
+    assert_eq!(report.referral_rewards, 20);
+    assert_eq!(report.discrepancy, 0);
+
+    assert_eq!(client.claim_referral_rewards(&referrer, &asset), 20);
+    assert_eq!(token_client.balance(&referrer), 20);
+    assert_eq!(client.get_referral_rewards(&referrer, &asset), 0);
+}
+
+#[test]
+#[should_panic(expected = "Owner cannot be their own referrer")]
+fn test_create_commitment_rejects_self_referral() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    client.create_commitment(&owner, &100, &asset, &test_rules(&e), &Some(owner.clone()));
+}
+
+// ============================================
+// Asset Limit Tests
+// ============================================
+
+fn setup_asset_limits_test(e: &Env) -> (CommitmentCoreContractClient<'static>, Address, Address) {
+    let (client, admin, asset) = setup_conservation_test(e);
+    let owner = Address::generate(e);
+    StellarAssetClient::new(e, &asset).mint(&owner, &10_000);
+    client.set_asset_limits(
+        &admin,
+        &asset,
+        &AssetLimits {
+            min_amount: 100,
+            max_amount: 5_000,
+        },
+    );
+    (client, owner, asset)
+}
+
+#[test]
+fn test_asset_limits_allow_amount_within_bounds() {
+    let e = Env::default();
+    let (client, owner, asset) = setup_asset_limits_test(&e);
+    let commitment_id = client.create_commitment(&owner, &5_000, &asset, &test_rules(&e), &None);
+    assert_eq!(client.get_commitment(&commitment_id).amount, 5_000);
+    assert!(client.try_top_up(&commitment_id, &1).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Amount below asset minimum")]
+fn test_asset_limits_reject_dust() {
+    let e = Env::default();
+    let (client, owner, asset) = setup_asset_limits_test(&e);
+    client.create_commitment(&owner, &99, &asset, &test_rules(&e), &None);
+}
+
+#[test]
+#[should_panic(expected = "Amount above asset maximum")]
+fn test_asset_limits_reject_oversized() {
+    let e = Env::default();
+    let (client, owner, asset) = setup_asset_limits_test(&e);
+    client.create_commitment(&owner, &5_001, &asset, &test_rules(&e), &None);
+}
+
+// ============================================
+// Yield Ledger Tests
+// ============================================
+
+#[test]
+fn test_settle_pays_accrued_yield_and_keeps_ledger() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&commitment_id).owner;
+    StellarAssetClient::new(&e, &asset).mint(&admin, &300);
+
+    client.report_yield(&admin, &commitment_id, &100);
+    e.ledger().with_mut(|l| l.timestamp += 86_400);
+    client.report_yield(&admin, &commitment_id, &200);
+    assert_eq!(client.get_accrued_yield(&commitment_id), 300);
+    let ledger = client.get_yield_ledger(&commitment_id);
+    assert_eq!(ledger.len(), 2);
+    assert_eq!(ledger.get(1).unwrap().amount, 200);
+    assert_eq!(ledger.get(1).unwrap().credited_by, admin);
+    assert!(!ledger.get(1).unwrap().compounded);
+
+    e.ledger().with_mut(|l| l.timestamp += 30 * 86_400);
+    client.settle(&commitment_id);
+
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 1_300);
+    assert_eq!(client.get_accrued_yield(&commitment_id), 0);
+    assert_eq!(client.verify_conservation(&asset).unclaimed_yield, 0);
+}
+
+// ============================================
+// Insurance Fund Tests
+// ============================================
+
+#[test]
+fn test_penalty_share_funds_insurance_and_covers_shortfall() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &1_000);
+    let victim_id = client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+    client.set_insurance_config(
+        &admin,
+        &InsuranceConfig {
+            penalty_bps: 10_000,
+            fee_bps: 0,
+        },
+    );
+
+    // The 5% penalty on c_0's exit goes entirely to insurance
+    let exiting = String::from_str(&e, "c_0");
+    client.early_exit(&exiting, &client.get_commitment(&exiting).owner);
+    assert_eq!(client.get_insurance_balance(&asset), 50);
+    assert_eq!(client.verify_conservation(&asset).insurance, 50);
+
+    // A 20% loss breaches max loss; insurance restores enough to be compliant
+    client.update_value(&admin, &victim_id, &800);
+    client.cover_shortfall(&admin, &victim_id, &50);
+    let victim = client.get_commitment(&victim_id);
+    assert_eq!(victim.current_value, 850);
+    assert_eq!(victim.status, String::from_str(&e, "violated"));
+    assert_eq!(client.get_insurance_balance(&asset), 0);
+    assert!(client.verify_conservation(&asset).conserved);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient insurance balance")]
+fn test_cover_shortfall_limited_to_insurance_balance() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    client.cover_shortfall(&admin, &String::from_str(&e, "c_0"), &1);
+}
+
+#[test]
+fn test_commitment_note_set_updated_and_cleared() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &1_000);
+
+    let note = String::from_str(&e, "Q3 treasury reserve");
+    let id = client.create_noted_commitment(&owner, &1_000, &asset, &test_rules(&e), &note);
+    assert_eq!(client.get_commitment_note(&id), Some(note.clone()));
+    let view = client.get_commitment_view(&id);
+    assert_eq!(view.commitment, client.get_commitment(&id));
+    assert_eq!(view.note, Some(note));
+    assert_eq!(
+        client.get_commitment_note(&String::from_str(&e, "c_0")),
+        None
+    );
+
+    let renamed = String::from_str(&e, "Q4 treasury reserve");
+    client.update_note(&id, &renamed);
+    assert_eq!(client.get_commitment_note(&id), Some(renamed));
+
+    client.update_note(&id, &String::from_str(&e, ""));
+    assert_eq!(client.get_commitment_note(&id), None);
+}
+
+#[test]
+#[should_panic(expected = "Note exceeds maximum length")]
+fn test_commitment_note_is_bounded() {
+    let e = Env::default();
+    let (client, _admin, _asset) = setup_conservation_test(&e);
+    let long = [b'x'; MAX_NOTE_LEN as usize + 1];
+    client.update_note(&String::from_str(&e, "c_0"), &String::from_bytes(&e, &long));
+}
+
+fn approval_policy(e: &Env, signers: &[Address]) -> ApprovalPolicy {
+    let mut list = Vec::new(e);
+    for signer in signers {
+        list.push_back(signer.clone());
+    }
+    ApprovalPolicy {
+        threshold_amount: 1_000,
+        signers: list,
+        required: 2,
+        expiry_seconds: 3_600,
+    }
+}
+
+#[test]
+fn test_large_commitment_requires_quorum() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let (s1, s2, s3) = (
+        Address::generate(&e),
+        Address::generate(&e),
+        Address::generate(&e),
+    );
+    client.set_approval_policy(&admin, &approval_policy(&e, &[s1.clone(), s2.clone(), s3]));
+
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &6_000);
+
+    // At the threshold no approval is needed
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+    assert!(client
+        .try_create_commitment(&owner, &5_000, &asset, &test_rules(&e), &None)
+        .is_err());
+
+    let action = ApprovalAction::Create(owner.clone(), asset.clone(), 5_000);
+    let request_id = client.submit_approval_request(&owner, &action);
+    client.approve_request(&s1, &request_id);
+    assert!(client.try_approve_request(&s1, &request_id).is_err());
+    assert!(client
+        .try_create_commitment(&owner, &5_000, &asset, &test_rules(&e), &None)
+        .is_err());
+
+    client.approve_request(&s2, &request_id);
+    let id = client.create_commitment(&owner, &5_000, &asset, &test_rules(&e), &None);
+    assert_eq!(client.get_commitment(&id).amount, 5_000);
+
+    // The request is consumed by execution
+    assert_eq!(client.get_approval_request(&request_id), None);
+}
+
+#[test]
+fn test_large_early_exit_requires_quorum() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let (s1, s2) = (Address::generate(&e), Address::generate(&e));
+    let mut policy = approval_policy(&e, &[s1.clone(), s2.clone()]);
+    policy.threshold_amount = 500;
+    client.set_approval_policy(&admin, &policy);
+
+    let id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&id).owner;
+    assert!(client.try_early_exit(&id, &owner).is_err());
+
+    let request_id = client.submit_approval_request(&owner, &ApprovalAction::EarlyExit(id.clone()));
+    client.approve_request(&s1, &request_id);
+    client.approve_request(&s2, &request_id);
+    client.early_exit(&id, &owner);
+    assert_eq!(
+        client.get_commitment(&id).status,
+        String::from_str(&e, "early_exit")
+    );
+}
+
+#[test]
+fn test_top_up_past_threshold_requires_quorum() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let (s1, s2) = (Address::generate(&e), Address::generate(&e));
+    let mut policy = approval_policy(&e, &[s1.clone(), s2.clone()]);
+    policy.threshold_amount = 1_200;
+    client.set_approval_policy(&admin, &policy);
+
+    let id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&id).owner;
+    StellarAssetClient::new(&e, &asset).mint(&owner, &500);
+
+    // 1_000 + 200 stays at the threshold; another 300 crosses it
+    client.top_up(&id, &200);
+    assert!(client.try_top_up(&id, &300).is_err());
+
+    let request_id =
+        client.submit_approval_request(&owner, &ApprovalAction::TopUp(id.clone(), 300));
+    client.approve_request(&s1, &request_id);
+    client.approve_request(&s2, &request_id);
+    client.top_up(&id, &300);
+    assert_eq!(client.get_commitment(&id).amount, 1_500);
+}
+
+#[test]
+#[should_panic(expected = "Approval request expired")]
+fn test_approval_request_expires() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let (s1, s2) = (Address::generate(&e), Address::generate(&e));
+    client.set_approval_policy(&admin, &approval_policy(&e, &[s1.clone(), s2.clone()]));
+    let outsider = Address::generate(&e);
+
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &5_000);
+    let request_id = client.submit_approval_request(
+        &owner,
+        &ApprovalAction::Create(owner.clone(), asset.clone(), 5_000),
+    );
+    assert!(client.try_approve_request(&outsider, &request_id).is_err());
+    client.approve_request(&s1, &request_id);
+
+    e.ledger().with_mut(|l| l.timestamp += 3_601);
+    client.approve_request(&s2, &request_id);
+}
+
+#[test]
+fn test_approval_policy_validation() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let mut policy = approval_policy(&e, &[Address::generate(&e)]);
+    assert!(client.try_set_approval_policy(&admin, &policy).is_err());
+    policy.required = 1;
+    client.set_approval_policy(&admin, &policy);
+    assert_eq!(client.get_approval_policy(), Some(policy));
+    client.clear_approval_policy(&admin);
+    assert_eq!(client.get_approval_policy(), None);
+}
+
+#[test]
+fn test_commit_reveal_creation() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &2_000);
+
+    let params = CreationParams {
+        amount: 2_000,
+        asset_address: asset.clone(),
+        rules: test_rules(&e),
+    };
+    let salt = BytesN::from_array(&e, &[7; 32]);
+    let hash = client.get_creation_hash(&params, &salt);
+    client.commit_creation(&owner, &hash);
+
+    // Same ledger as the commit
+    assert!(client.try_reveal_creation(&owner, &params, &salt).is_err());
+
+    e.ledger().with_mut(|l| l.sequence_number += 1);
+    // Wrong salt or a different owner does not match the commit
+    let other_salt = BytesN::from_array(&e, &[8; 32]);
+    assert!(client
+        .try_reveal_creation(&owner, &params, &other_salt)
+        .is_err());
+    assert!(client
+        .try_reveal_creation(&Address::generate(&e), &params, &salt)
+        .is_err());
+
+    let id = client.reveal_creation(&owner, &params, &salt);
+    let commitment = client.get_commitment(&id);
+    assert_eq!(commitment.owner, owner);
+    assert_eq!(commitment.amount, 2_000);
+
+    // A commit can be revealed only once
+    assert!(client.try_reveal_creation(&owner, &params, &salt).is_err());
+
+    // Commits expire
+    let late_salt = BytesN::from_array(&e, &[9; 32]);
+    client.commit_creation(&owner, &client.get_creation_hash(&params, &late_salt));
+    e.as_contract(&client.address, || {
+        e.storage().instance().extend_ttl(
+            CREATION_COMMIT_TTL_LEDGERS * 2,
+            CREATION_COMMIT_TTL_LEDGERS * 2,
+        )
+    });
+    e.ledger()
+        .with_mut(|l| l.sequence_number += CREATION_COMMIT_TTL_LEDGERS + 1);
+    assert!(client
+        .try_reveal_creation(&owner, &params, &late_salt)
+        .is_err());
+}
+
+#[test]
+fn test_settlement_streams_linearly() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&commitment_id).owner;
+    client.set_settlement_stream(&commitment_id, &10);
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86_400);
+    client.settle(&commitment_id);
+    let token_client = token::Client::new(&e, &asset);
+    assert_eq!(token_client.balance(&owner), 0);
+    let report = client.verify_conservation(&asset);
+    assert_eq!(report.vesting, 1_000);
+    assert!(report.conserved);
+
+    // A quarter of the way through
+    e.ledger().with_mut(|l| l.timestamp += 60 * 60 * 60);
+    assert_eq!(client.claim_settled(&commitment_id), 250);
+    assert_eq!(client.claim_settled(&commitment_id), 0);
+    assert_eq!(token_client.balance(&owner), 250);
+    assert_eq!(
+        client
+            .get_settlement_stream(&commitment_id)
+            .unwrap()
+            .claimed,
+        250
+    );
+
+    // Past the end the remainder is released and the stream closes
+    e.ledger().with_mut(|l| l.timestamp += 30 * 86_400);
+    assert_eq!(client.claim_settled(&commitment_id), 750);
+    assert_eq!(token_client.balance(&owner), 1_000);
+    assert_eq!(client.get_settlement_stream(&commitment_id), None);
+    assert_eq!(client.verify_conservation(&asset).vesting, 0);
+}
+
+#[test]
+fn test_settlement_split_across_recipients() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let manager = Address::generate(&e);
+    let investor_a = Address::generate(&e);
+    let investor_b = Address::generate(&e);
+
+    // Shares must sum to 100%
+    assert!(client
+        .try_set_payout_split(
+            &commitment_id,
+            &vec![&e, (manager.clone(), 1_000), (investor_a.clone(), 8_000)],
+        )
+        .is_err());
+    let split = vec![
+        &e,
+        (manager.clone(), 1_000),
+        (investor_a.clone(), 4_501),
+        (investor_b.clone(), 4_499),
+    ];
+    client.set_payout_split(&commitment_id, &split);
+    assert_eq!(client.get_payout_split(&commitment_id), Some(split));
+    // Streaming cannot be combined with a split
+    assert!(client
+        .try_set_settlement_stream(&commitment_id, &10)
+        .is_err());
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86_400);
+    client.settle(&commitment_id);
+
+    let token_client = token::Client::new(&e, &asset);
+    assert_eq!(token_client.balance(&manager), 100);
+    assert_eq!(token_client.balance(&investor_a), 450);
+    // Rounding dust goes to the last recipient
+    assert_eq!(token_client.balance(&investor_b), 450);
+    assert!(client.verify_conservation(&asset).conserved);
+}
+
+#[test]
+fn test_type_constraints_enforced_at_creation() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &3_000);
+    let safe = String::from_str(&e, "safe");
+    let constraints = TypeConstraints {
+        max_loss_percent: 5,
+        min_duration_days: 30,
+        max_duration_days: 365,
+    };
+    client.set_type_constraints(&admin, &safe, &constraints);
+    assert_eq!(client.get_type_constraints(&safe), Some(constraints));
+
+    let mut rules = test_rules(&e);
+    rules.commitment_type = safe.clone();
+    rules.max_loss_percent = 10;
+    assert!(client
+        .try_create_commitment(&owner, &1_000, &asset, &rules, &None)
+        .is_err());
+    rules.max_loss_percent = 5;
+    rules.duration_days = 7;
+    assert!(client
+        .try_create_commitment(&owner, &1_000, &asset, &rules, &None)
+        .is_err());
+    rules.duration_days = 400;
+    assert!(client
+        .try_create_commitment(&owner, &1_000, &asset, &rules, &None)
+        .is_err());
+    rules.duration_days = 30;
+    client.create_commitment(&owner, &1_000, &asset, &rules, &None);
+
+    // Other types are unaffected
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+
+    client.clear_type_constraints(&admin, &safe);
+    rules.max_loss_percent = 10;
+    client.create_commitment(&owner, &1_000, &asset, &rules, &None);
+}
+
+#[test]
+#[should_panic(expected = "Max loss exceeds commitment type limit")]
+fn test_type_constraints_reject_excess_loss() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &1_000);
+    client.set_type_constraints(
+        &admin,
+        &String::from_str(&e, "balanced"),
+        &TypeConstraints {
+            max_loss_percent: 5,
+            min_duration_days: 0,
+            max_duration_days: 0,
+        },
+    );
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+}
+
+#[test]
+#[should_panic(expected = "Invalid commitment type constraints")]
+fn test_type_constraints_validated() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    client.set_type_constraints(
+        &admin,
+        &String::from_str(&e, "aggressive"),
+        &TypeConstraints {
+            max_loss_percent: 50,
+            min_duration_days: 90,
+            max_duration_days: 30,
+        },
+    );
+}
+
+#[test]
+fn test_protocol_stats_track_lifecycle() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &3_000);
+    let exiting = client.create_commitment(&owner, &2_000, &asset, &test_rules(&e), &None);
+    let violating = client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+
+    let stats = client.get_protocol_stats();
+    assert_eq!(stats.total_commitments, 3);
+    assert_eq!(stats.active_commitments, 3);
+    assert_eq!(stats.total_value_locked, 4_000);
+    assert_eq!(stats.assets.len(), 1);
+    assert_eq!(stats.assets.get(0).unwrap().value_locked, 4_000);
+
+    // Early exit collects a 5% penalty
+    client.early_exit(&exiting, &owner);
+    client.update_value(&admin, &violating, &500);
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86_400);
+    client.settle(&String::from_str(&e, "c_0"));
+
+    let stats = client.get_protocol_stats();
+    assert_eq!(stats.active_commitments, 0);
+    assert_eq!(stats.settled_commitments, 1);
+    assert_eq!(stats.total_value_locked, 500);
+    let asset_stats = stats.assets.get(0).unwrap();
+    assert_eq!(asset_stats.asset, asset);
+    assert_eq!(asset_stats.value_locked, 500);
+    assert_eq!(asset_stats.penalties_collected, 100);
+}
+
+#[test]
+fn test_health_score_components() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+
+    // Fresh, no loss, no time served, no fees: 40 + 0 + 0 + 20
+    assert_eq!(client.get_health_score(&id), 60);
+
+    // Half the loss budget used, half the term served: 20 + 10 + 0 + 20
+    e.ledger().with_mut(|l| l.timestamp += 15 * 86_400);
+    client.update_value(&admin, &id, &950);
+    assert_eq!(client.get_health_score(&id), 50);
+
+    // Value not updated for over twice the stale window: freshness drops out
+    e.ledger().with_mut(|l| l.timestamp += 3 * 86_400);
+    assert_eq!(client.get_health_score(&id), 32);
+
+    e.ledger().with_mut(|l| l.sequence_number += 1);
+    client.update_value(&admin, &id, &800);
+    assert_eq!(client.get_health_score(&id), 0);
+}
+
+#[test]
+fn test_migrate_backfills_protocol_stats() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    assert_eq!(client.get_version(), CURRENT_VERSION);
+    assert!(client.try_migrate(&admin, &0).is_err());
+
+    // Simulate storage written before versioning and stats existed
+    e.as_contract(&client.address, || {
+        let storage = e.storage().instance();
+        storage.remove(&DataKeyExt::SchemaVersion);
+        storage.remove(&DataKeyExt::ActiveCount);
+        storage.remove(&DataKeyExt::StatsAssets);
+        storage.remove(&DataKeyExt::AssetTvl(asset.clone()));
+        storage.remove(&DataKeyExt::ActiveEscrow(asset.clone()));
+    });
+    assert_eq!(client.get_version(), 0);
+    assert_eq!(client.get_protocol_stats().active_commitments, 0);
+    assert!(client.try_migrate(&admin, &1).is_err());
+
+    client.migrate(&admin, &0);
+    assert_eq!(client.get_version(), CURRENT_VERSION);
+    let stats = client.get_protocol_stats();
+    assert_eq!(stats.active_commitments, 1);
+    assert_eq!(stats.assets.get(0).unwrap().value_locked, 1_000);
+    assert_eq!(client.verify_conservation(&asset).escrow, 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Invalid WASM hash")]
+fn test_upgrade_rejects_zero_wasm_hash() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    client.upgrade(&admin, &BytesN::from_array(&e, &[0; 32]));
+}
+
+#[test]
+fn test_creation_rate_limit_per_owner() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    let other = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &4_000);
+    StellarAssetClient::new(&e, &asset).mint(&other, &1_000);
+    client.set_creation_rate_limit(&admin, &3_600, &2);
+
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+    assert!(client
+        .try_create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None)
+        .is_err());
+    // Limits are per owner
+    client.create_commitment(&other, &1_000, &asset, &test_rules(&e), &None);
+
+    // A new window allows creation again
+    e.ledger().with_mut(|l| l.timestamp += 3_600);
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+    client.clear_creation_rate_limit(&admin);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &1_000);
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+}
+
+#[test]
+#[should_panic(expected = "Rate limit exceeded")]
+fn test_creation_rate_limit_error() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &2_000);
+    client.set_creation_rate_limit(&admin, &3_600, &1);
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+}
+
+#[test]
+fn test_auto_roll_on_keeper_settle() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&id).owner;
+    let mut new_rules = test_rules(&e);
+    new_rules.duration_days = 60;
+    client.set_auto_roll(&id, &true, &new_rules);
+    assert_eq!(client.get_auto_roll(&id), Some(new_rules.clone()));
+
+    // Anyone may trigger the roll from expiry, even within the grace period
+    e.ledger().with_mut(|l| l.timestamp += 30 * 86_400);
+    let keeper = Address::generate(&e);
+    assert_eq!(client.keeper_settle(&keeper, &id), 0);
+
+    assert_eq!(
+        client.get_commitment(&id).status,
+        String::from_str(&e, "settled")
+    );
+    let rolled = client.get_rolled_over_into(&id).unwrap();
+    let rolled_commitment = client.get_commitment(&rolled);
+    assert_eq!(rolled_commitment.owner, owner);
+    assert_eq!(rolled_commitment.amount, 1_000);
+    assert_eq!(rolled_commitment.rules, new_rules);
+    assert_eq!(client.get_auto_roll(&id), None);
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 0);
+    assert!(client.verify_conservation(&asset).conserved);
+}
+
+#[test]
+fn test_auto_roll_disabled_settles_normally() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&id).owner;
+    client.set_auto_roll(&id, &true, &test_rules(&e));
+    client.set_auto_roll(&id, &false, &test_rules(&e));
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86_400);
+    client.settle(&id);
+    assert_eq!(client.get_rolled_over_into(&id), None);
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 1_000);
+}
+
+// ============================================
+// Expiry Index Tests
+// ============================================
+
+#[test]
+fn test_get_commitments_expiring_between() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &1_000);
+    let mut rules = test_rules(&e);
+    rules.duration_days = 60;
+    let later_id = client.create_commitment(&owner, &1_000, &asset, &rules, &None);
+    let first_id = String::from_str(&e, "c_0");
+
+    let day = 86_400;
+    assert_eq!(
+        client.get_commitments_expiring_between(&(29 * day), &(31 * day)),
+        vec![&e, first_id.clone()]
+    );
+    assert_eq!(
+        client.get_commitments_expiring_between(&0, &(60 * day)),
+        vec![&e, first_id.clone(), later_id.clone()]
+    );
+    // Bounds are exact timestamps, not whole days
+    assert_eq!(
+        client
+            .get_commitments_expiring_between(&(30 * day + 1), &(31 * day))
+            .len(),
+        0
+    );
+    assert_eq!(
+        client
+            .get_commitments_expiring_between(&(31 * day), &(29 * day))
+            .len(),
+        0
+    );
+
+    // Closed commitments are pruned from the index
+    let first_owner = client.get_commitment(&first_id).owner;
+    client.early_exit(&first_id, &first_owner);
+    assert_eq!(
+        client.get_commitments_expiring_between(&0, &(60 * day)),
+        vec![&e, later_id]
+    );
+    e.as_contract(&client.address, || {
+        assert!(!e.storage().persistent().has(&DataKeyExt::ExpiryIndex(30)));
+    });
+}
+
+#[test]
+#[should_panic(expected = "Expiry range too large")]
+fn test_get_commitments_expiring_between_rejects_wide_range() {
+    let e = Env::default();
+    let (client, _admin, _asset) = setup_conservation_test(&e);
+    client.get_commitments_expiring_between(&0, &(MAX_EXPIRY_QUERY_DAYS * 86_400));
+}
+
+// ============================================
+// Force Settle Tests
+// ============================================
+
+#[test]
+fn test_force_settle_before_expiry_records_reason() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&id).owner;
+    let reason = String::from_str(&e, "oracle incident 2024-03");
+
+    client.force_settle(&admin, &id, &reason);
+
+    let event = e.events().all().last().unwrap();
+    assert_eq!(
+        Symbol::try_from_val(&e, &event.1.get(0).unwrap()),
+        Ok(Symbol::new(&e, "ForceSettle"))
+    );
+    let (_seq, data): (u64, (Address, String, i128, u64)) = event.2.into_val(&e);
+    assert_eq!(data, (admin, reason.clone(), 1_000, 0));
+    assert_eq!(
+        client.get_commitment(&id).status,
+        String::from_str(&e, "settled")
+    );
+    assert_eq!(client.get_force_settle_reason(&id), Some(reason));
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Reason required")]
+fn test_force_settle_requires_reason() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    client.force_settle(
+        &admin,
+        &String::from_str(&e, "c_0"),
+        &String::from_str(&e, ""),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_force_settle_requires_admin() {
+    let e = Env::default();
+    let (client, _admin, _asset) = setup_conservation_test(&e);
+    client.force_settle(
+        &Address::generate(&e),
+        &String::from_str(&e, "c_0"),
+        &String::from_str(&e, "stuck"),
+    );
+}
+
+// ============================================
+// Operator Role Tests
+// ============================================
+
+#[test]
+fn test_operator_batch_settles_overdue_commitments() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let operator = Address::generate(&e);
+    client.grant_operator(&admin, &operator);
+    assert!(client.is_operator(&operator));
+
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &1_000);
+    let mut rules = test_rules(&e);
+    rules.duration_days = 60;
+    let later_id = client.create_commitment(&owner, &1_000, &asset, &rules, &None);
+    let due_id = String::from_str(&e, "c_0");
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86_400);
+    let ids = vec![
+        &e,
+        due_id.clone(),
+        later_id.clone(),
+        String::from_str(&e, "missing"),
+    ];
+    assert_eq!(client.batch_settle(&operator, &ids), 1);
+    assert_eq!(
+        client.get_commitment(&due_id).status,
+        String::from_str(&e, "settled")
+    );
+    assert_eq!(
+        client.get_commitment(&later_id).status,
+        String::from_str(&e, "active")
+    );
+    // Already settled commitments are skipped on a retry
+    assert_eq!(client.batch_settle(&operator, &ids), 0);
+}
+
+#[test]
+fn test_operator_pauses_creation() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let operator = Address::generate(&e);
+    client.grant_operator(&admin, &operator);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &2_000);
+
+    client.pause_creation(&operator);
+    assert!(client.is_creation_paused());
+    assert!(client
+        .try_create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None)
+        .is_err());
+
+    client.unpause_creation(&operator);
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+}
+
+#[test]
+fn test_revoked_operator_loses_access() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let operator = Address::generate(&e);
+    client.grant_operator(&admin, &operator);
+    client.revoke_operator(&admin, &operator);
+
+    assert!(!client.is_operator(&operator));
+    assert!(client.try_pause_creation(&operator).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_operator_cannot_change_fee_config() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let operator = Address::generate(&e);
+    client.grant_operator(&admin, &operator);
+    client.set_keeper_reward_bps(&operator, &100);
+}
+
+// ============================================
+// Value History Tests
+// ============================================
+
+#[test]
+fn test_value_history_records_updates() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+    assert_eq!(client.get_value_history(&id, &0, &10).len(), 0);
+
+    client.update_value(&admin, &id, &980);
+    e.ledger().with_mut(|l| l.timestamp += 3_600);
+    client.update_value(&admin, &id, &1_020);
+
+    assert_eq!(
+        client.get_value_history(&id, &0, &10),
+        vec![
+            &e,
+            ValueSnapshot {
+                value: 980,
+                timestamp: 0
+            },
+            ValueSnapshot {
+                value: 1_020,
+                timestamp: 3_600
+            }
+        ]
+    );
+    assert_eq!(client.get_value_history(&id, &1, &10).len(), 1);
+    assert_eq!(client.get_value_history(&id, &5, &10).len(), 0);
+}
+
+#[test]
+fn test_value_history_is_bounded() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+    for i in 0..MAX_VALUE_HISTORY_ENTRIES + 5 {
+        e.ledger().with_mut(|l| l.timestamp = i as u64);
+        client.update_value(&admin, &id, &(950 + i as i128));
+    }
+
+    let page = client.get_value_history(&id, &0, &MAX_COMMITMENTS_PAGE_SIZE);
+    assert_eq!(page.len(), MAX_COMMITMENTS_PAGE_SIZE);
+    // The oldest five snapshots were dropped
+    assert_eq!(page.get(0).unwrap().timestamp, 5);
+    let tail = client.get_value_history(&id, &(MAX_VALUE_HISTORY_ENTRIES - 1), &10);
+    assert_eq!(tail.len(), 1);
+    assert_eq!(
+        tail.get(0).unwrap().timestamp,
+        (MAX_VALUE_HISTORY_ENTRIES + 4) as u64
+    );
+    // Before the oldest kept snapshot the last dropped value (954) applies
+    assert_eq!(client.get_value_twap(&id, &104), 1_001);
+}
+
+// ============================================
+// TWAP Compliance Tests
+// ============================================
+
+#[test]
+fn test_twap_window_ignores_single_bad_tick() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+    let active = String::from_str(&e, "active");
+    client.set_twap_window(&admin, &86_400);
+    assert_eq!(client.get_twap_window(), 86_400);
+
+    // A 20% drop against a 10% limit is absorbed by the day-long average
+    e.ledger().with_mut(|l| l.timestamp = 43_200);
+    client.update_value(&admin, &id, &800);
+    assert_eq!(client.get_commitment(&id).status, active);
+    assert!(!client.check_violations(&id));
+
+    e.ledger().with_mut(|l| l.timestamp = 86_400);
+    assert_eq!(client.get_value_twap(&id, &86_400), 900);
+    assert_eq!(client.get_value_twap(&id, &43_200), 800);
+
+    // Once the low value persists for the whole window it counts
+    e.ledger().with_mut(|l| l.timestamp = 129_600);
+    client.update_value(&admin, &id, &800);
+    assert_eq!(
+        client.get_commitment(&id).status,
+        String::from_str(&e, "violated")
+    );
+}
+
+#[test]
+fn test_twap_weights_value_changes_outside_update_value() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&id).owner;
+    StellarAssetClient::new(&e, &asset).mint(&owner, &500);
+
+    // The top-up is recorded; the half day before it is weighted at 1_000
+    e.ledger().with_mut(|l| l.timestamp = 43_200);
+    client.top_up(&id, &500);
+    assert_eq!(client.get_value_history(&id, &0, &10).len(), 1);
+    e.ledger().with_mut(|l| l.timestamp = 86_400);
+    assert_eq!(client.get_value_twap(&id, &86_400), 1_250);
+    assert_eq!(client.get_value_twap(&id, &43_200), 1_500);
+}
+
+#[test]
+fn test_no_twap_window_uses_instant_value() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+
+    e.ledger().with_mut(|l| l.timestamp = 43_200);
+    client.update_value(&admin, &id, &800);
+    assert_eq!(
+        client.get_commitment(&id).status,
+        String::from_str(&e, "violated")
+    );
+}
+
+// ============================================
+// Commitment Freeze Tests
+// ============================================
+
+#[test]
+fn test_frozen_commitment_blocks_exit_settle_and_updates() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&id).owner;
+    let reason = String::from_str(&e, "court order 42");
+
+    client.freeze_commitment(&admin, &id, &reason);
+    assert_eq!(client.get_freeze_reason(&id), Some(reason));
+    assert!(client.try_early_exit(&id, &owner).is_err());
+    assert!(client.try_update_value(&admin, &id, &990).is_err());
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86_400);
+    assert!(client.try_settle(&id).is_err());
+    assert_eq!(client.batch_settle(&admin, &vec![&e, id.clone()]), 0);
+
+    client.unfreeze_commitment(&admin, &id);
+    assert_eq!(client.get_freeze_reason(&id), None);
+    client.settle(&id);
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 1_000);
+}
+
+#[test]
+fn test_frozen_commitment_cannot_be_flagged_or_liquidated() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+    let hold = String::from_str(&e, "hold");
+
+    // Attested 15% drawdown breaches the 10% max loss
+    let engine = e.register_contract(None, MockAttestationEngine);
+    client.set_attestation_engine(&admin, &engine);
+    client.freeze_commitment(&admin, &id, &hold);
+    assert!(client.try_flag_violation(&id).is_err());
+    client.unfreeze_commitment(&admin, &id);
+
+    assert!(client.flag_violation(&id));
+    client.freeze_commitment(&admin, &id, &hold);
+    assert!(client.try_liquidate(&id).is_err());
+    assert_eq!(client.get_commitment(&id).current_value, 1_000);
+}
+
+#[test]
+fn test_freeze_is_per_commitment() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let other = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&other, &1_000);
+    let other_id = client.create_commitment(&other, &1_000, &asset, &test_rules(&e), &None);
+
+    client.freeze_commitment(
+        &admin,
+        &String::from_str(&e, "c_0"),
+        &String::from_str(&e, "hold"),
+    );
+    client.early_exit(&other_id, &other);
+    assert_eq!(
+        client.get_commitment(&other_id).status,
+        String::from_str(&e, "early_exit")
+    );
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_freeze_commitment_requires_admin() {
+    let e = Env::default();
+    let (client, _admin, _asset) = setup_conservation_test(&e);
+    client.freeze_commitment(
+        &Address::generate(&e),
+        &String::from_str(&e, "c_0"),
+        &String::from_str(&e, "hold"),
+    );
+}
+
+// ============================================
+// Allowance Deposit Tests
+// ============================================
+
+#[test]
+fn test_create_commitment_with_allowance_pulls_funds() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &1_000);
+    let token_client = token::Client::new(&e, &asset);
+    token_client.approve(&owner, &client.address, &600, &1_000);
+
+    let id = client.create_commitment_with_allowance(&owner, &600, &asset, &test_rules(&e));
+
+    assert_eq!(client.get_commitment(&id).owner, owner);
+    assert_eq!(token_client.balance(&owner), 400);
+    assert_eq!(token_client.allowance(&owner, &client.address), 0);
+    assert!(client.verify_conservation(&asset).conserved);
+}
+
+#[test]
+fn test_create_commitment_for_is_funded_by_payer() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let payer = Address::generate(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&payer, &1_000);
+    let token_client = token::Client::new(&e, &asset);
+    token_client.approve(&payer, &client.address, &1_000, &1_000);
+
+    let id = client.create_commitment_for(&payer, &owner, &1_000, &asset, &test_rules(&e));
+
+    assert_eq!(client.get_commitment(&id).owner, owner);
+    assert_eq!(client.get_owner_commitments(&owner), vec![&e, id.clone()]);
+    assert_eq!(token_client.balance(&payer), 0);
+
+    // Proceeds go to the owner, not the sponsor
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86_400);
+    client.settle(&id);
+    assert_eq!(token_client.balance(&owner), 1_000);
+}
+
+#[test]
+fn test_gifted_commitment_needs_only_payer_auth() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let payer = Address::generate(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&payer, &1_000);
+    token::Client::new(&e, &asset).approve(&payer, &client.address, &1_000, &1_000);
+
+    client.create_commitment_for(&payer, &owner, &1_000, &asset, &test_rules(&e));
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    assert_eq!(auths[0].0, payer);
+    assert_eq!(client.get_owner_commitments(&owner).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient allowance")]
+fn test_create_commitment_for_requires_allowance() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let payer = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&payer, &1_000);
+    token::Client::new(&e, &asset).approve(&payer, &client.address, &500, &1_000);
+
+    client.create_commitment_for(
+        &payer,
+        &Address::generate(&e),
+        &1_000,
+        &asset,
+        &test_rules(&e),
+    );
+}
+
+// ============================================
+// Config Registry Tests
+// ============================================
+
+#[test]
+fn test_config_registry_sets_and_reads_values() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let treasury = Address::generate(&e);
+
+    assert_eq!(client.get_config(&ConfigKey::Treasury), None);
+    assert_eq!(
+        client.get_config(&ConfigKey::StaleValueSeconds),
+        Some(ConfigValue::U64(DEFAULT_STALE_VALUE_SECONDS))
+    );
+
+    client.set_config(
+        &admin,
+        &ConfigKey::Treasury,
+        &ConfigValue::Address(treasury.clone()),
+    );
+    client.set_config(&admin, &ConfigKey::SettlementFeeBps, &ConfigValue::U32(50));
+    client.set_config(
+        &admin,
+        &ConfigKey::TwapWindowSeconds,
+        &ConfigValue::U64(3_600),
+    );
+    client.set_config(
+        &admin,
+        &ConfigKey::RequireFreshValue,
+        &ConfigValue::Bool(true),
+    );
+
+    let event = e.events().all().last().unwrap();
+    let (_seq, data): (u64, (Option<ConfigValue>, ConfigValue, Address, u64)) =
+        event.2.into_val(&e);
+    assert_eq!(data.0, Some(ConfigValue::Bool(false)));
+    assert_eq!(data.1, ConfigValue::Bool(true));
+
+    assert_eq!(
+        client.get_fee_config(),
+        FeeConfig {
+            settlement_fee_bps: 50,
+            treasury: Some(treasury),
+        }
+    );
+    assert_eq!(client.get_twap_window(), 3_600);
+    assert!(client.get_require_fresh_value());
+}
+
+#[test]
+#[should_panic(expected = "Invalid config value")]
+fn test_config_registry_rejects_mistyped_value() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    client.set_config(
+        &admin,
+        &ConfigKey::KeeperRewardBps,
+        &ConfigValue::Bool(true),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Invalid fee config")]
+fn test_config_registry_applies_setter_validation() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    client.set_config(
+        &admin,
+        &ConfigKey::KeeperRewardBps,
+        &ConfigValue::U32(MAX_KEEPER_REWARD_BPS + 1),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_config_registry_requires_admin() {
+    let e = Env::default();
+    let (client, _admin, _asset) = setup_conservation_test(&e);
+    client.set_config(
+        &Address::generate(&e),
+        &ConfigKey::DrawdownAlertBps,
+        &ConfigValue::U32(100),
+    );
+}
+
+// ============================================
+// Early Exit Quote Tests
+// ============================================
+
+#[test]
+fn test_quote_early_exit_matches_execution() {
+    let e = Env::default();
+    let (client, _admin, asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&id).owner;
+
+    let quote = client.quote_early_exit(&id);
+    assert_eq!(
+        quote,
+        EarlyExitQuote {
+            penalty_amount: 50,
+            net_amount: 950,
+            penalty_bps: 500,
+            queued: false,
+        }
+    );
+
+    client.early_exit(&id, &owner);
+    assert_eq!(
+        token::Client::new(&e, &asset).balance(&owner),
+        quote.net_amount
+    );
+    assert!(client.try_quote_early_exit(&id).is_err());
+}
+
+#[test]
+fn test_quote_early_exit_includes_loyalty_discount() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&id).owner;
+    client.set_fee_tiers(&admin, &loyalty_tiers(&e));
+    MockNftContractClient::new(&e, &client.get_nft_contract())
+        .set_settled_holdings(&owner, &(1, 1_000));
+
+    let quote = client.quote_early_exit(&id);
+    assert_eq!(quote.penalty_amount, 40);
+    assert_eq!(quote.net_amount, 960);
+    assert_eq!(quote.penalty_bps, 400);
+}
+
+// ============================================
+// Settlement Quote Tests
+// ============================================
+
+#[test]
+fn test_quote_settlement_matches_execution() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+    let owner = client.get_commitment(&id).owner;
+    client.set_fee_config(&admin, &50, &Address::generate(&e));
+    client.set_late_fee_config(
+        &admin,
+        &LateFeeConfig {
+            bps_per_day: 100,
+            max_bps: 1_000,
+        },
+    );
+
+    let early = client.quote_settlement(&id);
+    assert!(!early.eligible);
+    assert_eq!(early.payout, 995);
+
+    // Two days past expiry: 2% late fee, then the fee on the remainder
+    e.ledger()
+        .with_mut(|l| l.timestamp = client.get_commitment(&id).expires_at + 2 * 86_400);
+    let quote = client.quote_settlement(&id);
+    assert_eq!(
+        quote,
+        SettlementQuote {
+            payout: 976,
+            accrued_yield: 0,
+            protocol_fee: 4,
+            late_fee: 20,
+            eligible: true,
+        }
+    );
+
+    client.settle(&id);
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), quote.payout);
+    assert!(!client.quote_settlement(&id).eligible);
+}
+
+#[test]
+fn test_quote_settlement_not_eligible_when_frozen() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+    client.freeze_commitment(&admin, &id, &String::from_str(&e, "hold"));
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86_400);
+    assert!(!client.quote_settlement(&id).eligible);
+}
+
+// ============================================
+// Renewal Fee Tests
+// ============================================
+
+#[test]
+fn test_extend_commitment_charges_renewal_fee() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+    let commitment = client.get_commitment(&id);
+    let treasury = Address::generate(&e);
+    client.set_fee_config(&admin, &50, &treasury);
+    client.set_renewal_fee_bps(&admin, &20);
+    assert_eq!(client.get_renewal_fee_bps(), 20);
+    StellarAssetClient::new(&e, &asset).mint(&commitment.owner, &10);
+
+    // 45 days spans two 30-day periods: 40 bps of 1000
+    assert_eq!(client.quote_renewal_fee(&id, &45), 4);
+    assert_eq!(client.extend_commitment(&id, &45), 4);
+
+    let extended = client.get_commitment(&id);
+    assert_eq!(extended.rules.duration_days, 75);
+    assert_eq!(extended.expires_at, commitment.expires_at + 45 * 86_400);
+    assert_eq!(extended.current_value, 1_000);
+    let token_client = token::Client::new(&e, &asset);
+    assert_eq!(token_client.balance(&treasury), 4);
+    assert_eq!(token_client.balance(&commitment.owner), 6);
+    assert_eq!(
+        client.get_commitments_expiring_between(&extended.expires_at, &extended.expires_at),
+        vec![&e, id.clone()]
+    );
+    assert!(client.verify_conservation(&asset).conserved);
+}
+
+#[test]
+fn test_extend_commitment_is_free_without_treasury() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+    client.set_renewal_fee_bps(&admin, &20);
+
+    assert_eq!(client.extend_commitment(&id, &30), 0);
+    assert_eq!(client.get_commitment(&id).rules.duration_days, 60);
+}
+
+#[test]
+fn test_extend_commitment_respects_type_constraints() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let id = String::from_str(&e, "c_0");
+    client.set_type_constraints(
+        &admin,
+        &String::from_str(&e, "balanced"),
+        &TypeConstraints {
+            max_loss_percent: 50,
+            min_duration_days: 1,
+            max_duration_days: 60,
+        },
+    );
+
+    assert!(client.try_extend_commitment(&id, &31).is_err());
+    assert!(client.try_extend_commitment(&id, &0).is_err());
+    client.extend_commitment(&id, &30);
+}
+
+#[test]
+fn test_active_cap_blocks_creation_until_slot_frees() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &3_000);
+    client.set_max_active_per_owner(&admin, &2);
+
+    let first = client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+    assert_eq!(client.get_active_commitment_count(&owner), 2);
+    assert!(client
+        .try_create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None)
+        .is_err());
+
+    client.early_exit(&first, &owner);
+    assert_eq!(client.get_active_commitment_count(&owner), 1);
+    client.create_commitment(&owner, &950, &asset, &test_rules(&e), &None);
+    assert_eq!(client.get_active_commitment_count(&owner), 2);
+}
+
+#[test]
+fn test_active_cap_exempt_address_bypasses_cap() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &2_000);
+    client.set_max_active_per_owner(&admin, &1);
+    client.set_active_cap_exempt(&admin, &owner, &true);
+
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+    assert_eq!(client.get_active_commitment_count(&owner), 2);
+
+    client.set_active_cap_exempt(&admin, &owner, &false);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &1_000);
+    assert!(client
+        .try_create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None)
+        .is_err());
+}
+
+#[test]
+fn test_gifts_count_against_owner_cap_and_payer_rate_limit() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let payer = Address::generate(&e);
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset).mint(&payer, &3_000);
+    StellarAssetClient::new(&e, &asset).mint(&owner, &1_000);
+    token::Client::new(&e, &asset).approve(&payer, &client.address, &3_000, &1_000);
+    client.set_max_active_per_owner(&admin, &1);
+    client.set_creation_rate_limit(&admin, &3_600, &2);
+
+    // A gift fills the recipient's cap
+    client.create_commitment_for(&payer, &owner, &1_000, &asset, &test_rules(&e));
+    assert!(client
+        .try_create_commitment_for(&payer, &owner, &1_000, &asset, &test_rules(&e))
+        .is_err());
+
+    // The payer's rate limit is spent by its gifts
+    client.create_commitment_for(
+        &payer,
+        &Address::generate(&e),
+        &1_000,
+        &asset,
+        &test_rules(&e),
+    );
+    assert!(client
+        .try_create_commitment_for(
+            &payer,
+            &Address::generate(&e),
+            &1_000,
+            &asset,
+            &test_rules(&e)
+        )
+        .is_err());
+
+    // ...but not the recipient's
+    client.set_active_cap_exempt(&admin, &owner, &true);
+    client.create_commitment(&owner, &1_000, &asset, &test_rules(&e), &None);
+    assert_eq!(client.get_active_commitment_count(&owner), 2);
+}
+
+#[test]
+fn test_active_cap_configurable_through_registry() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    assert_eq!(client.get_max_active_per_owner(), 0);
+
+    client.set_config(&admin, &ConfigKey::MaxActivePerOwner, &ConfigValue::U32(5));
+    assert_eq!(client.get_max_active_per_owner(), 5);
+    assert_eq!(
+        client.get_config(&ConfigKey::MaxActivePerOwner),
+        Some(ConfigValue::U32(5))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_set_max_active_per_owner_requires_admin() {
+    let e = Env::default();
+    let (client, _admin, _asset) = setup_conservation_test(&e);
+    client.set_max_active_per_owner(&Address::generate(&e), &1);
+}
+
+#[test]
+fn test_check_and_mark_violation_pays_bounty() {
+    let e = Env::default();
+    let (client, admin, asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let engine = e.register_contract(None, MockAttestationEngine);
+    client.set_attestation_engine(&admin, &engine);
+    client.set_violation_bounty_bps(&admin, &500);
+    let watcher = Address::generate(&e);
+
+    // Attested 15% drawdown breaches the 10% max loss
+    assert_eq!(
+        client.check_and_mark_violation(&watcher, &commitment_id),
+        50
+    );
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.status, String::from_str(&e, "violated"));
+    assert_eq!(commitment.current_value, 950);
+    assert_eq!(client.get_total_value_locked(), 950);
+    assert_eq!(token::Client::new(&e, &asset).balance(&watcher), 50);
+}
+
+#[test]
+fn test_flag_violation_pays_no_bounty() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    let engine = e.register_contract(None, MockAttestationEngine);
+    client.set_attestation_engine(&admin, &engine);
+    client.set_violation_bounty_bps(&admin, &500);
+
+    assert!(client.flag_violation(&commitment_id));
+    assert_eq!(client.get_commitment(&commitment_id).current_value, 1_000);
+    assert_eq!(client.get_total_value_locked(), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Commitment is within its loss limit")]
+fn test_check_and_mark_violation_rejects_healthy_commitment() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    client.update_value(&admin, &commitment_id, &950);
+
+    client.check_and_mark_violation(&Address::generate(&e), &commitment_id);
+}
+
+#[test]
+fn test_violation_bounty_is_capped() {
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    assert!(client
+        .try_set_violation_bounty_bps(&admin, &(MAX_KEEPER_REWARD_BPS + 1))
+        .is_err());
+    client.set_config(
+        &admin,
+        &ConfigKey::ViolationBountyBps,
+        &ConfigValue::U32(MAX_KEEPER_REWARD_BPS),
+    );
+    assert_eq!(client.get_violation_bounty_bps(), MAX_KEEPER_REWARD_BPS);
+}
+
+#[test]
+fn test_core_events_carry_monotonic_sequence() {
+    use soroban_sdk::TryIntoVal;
+
+    let e = Env::default();
+    let (client, admin, _asset) = setup_conservation_test(&e);
+    let commitment_id = String::from_str(&e, "c_0");
+    client.update_value(&admin, &commitment_id, &990);
+    client.update_value(&admin, &commitment_id, &980);
+
+    let mut expected = 1u64;
+    for event in e.events().all().iter() {
+        if event.0 != client.address {
+            continue;
+        }
+        let payload: Result<(u64, soroban_sdk::Val), _> = event.2.try_into_val(&e);
+        if let Ok((seq, _)) = payload {
+            assert_eq!(seq, expected);
+            expected += 1;
+        }
+    }
+    assert!(expected > 1);
+    assert_eq!(client.get_event_seq(), expected - 1);
+}
@@ -1,8 +1,11 @@
 #![no_std]
-use shared_utils::{EmergencyControl, Pausable};
+use shared_utils::{
+    BatchError, BatchProcessor, BatchResultVoid, EmergencyControl, Pausable, SnapshotVersion,
+    Sunset, SunsetState,
+};
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env,
-    String, Symbol, Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN, Env,
+    IntoVal, Map, String, Symbol, Val, Vec,
 };
 
 // Current storage version for migration checks.
@@ -11,6 +14,43 @@ pub const CURRENT_VERSION: u32 = 1;
 // Issue #139: String parameter constraints
 const MAX_COMMITMENT_ID_LENGTH: u32 = 256;
 
+/// Bounds on the extra key/value attributes a token may carry
+pub const MAX_ATTRIBUTES: u32 = 16;
+pub const MAX_ATTRIBUTE_KEY_LENGTH: u32 = 32;
+pub const MAX_ATTRIBUTE_VALUE_LENGTH: u32 = 256;
+
+/// Collection name/symbol reported when none were set at initialization
+pub const DEFAULT_COLLECTION_NAME: &str = "Commitlabs Commitment";
+pub const DEFAULT_COLLECTION_SYMBOL: &str = "COMMIT";
+const MAX_COLLECTION_NAME_LENGTH: u32 = 64;
+const MAX_COLLECTION_SYMBOL_LENGTH: u32 = 12;
+const MAX_COLLECTION_DESCRIPTION_LENGTH: u32 = 512;
+
+/// Maximum number of tokens returned by a single `scan_tokens` call
+pub const MAX_SCAN_ITEMS: u32 = 100;
+
+/// Maximum length of the reason recorded with a clawback
+pub const MAX_CLAWBACK_REASON_LEN: u32 = 256;
+
+/// Maximum number of addresses holding the minter role
+pub const MAX_MINTERS: u32 = 10;
+
+/// Maximum number of settled tokens combined by one `merge`
+pub const MAX_MERGE_TOKENS: u32 = 20;
+
+/// Commitment type of composite tokens created by `merge`
+pub const COMPOSITE_COMMITMENT_TYPE: &str = "composite";
+
+/// Default upper bound on `duration_days` accepted by `mint` (10 years)
+pub const DEFAULT_MAX_DURATION_DAYS: u32 = 3650;
+
+// Storage TTL management (ledgers close roughly every 5 seconds)
+const DAY_IN_LEDGERS: u32 = 17_280;
+/// TTL (in ledgers) that token and instance storage is extended to
+pub const TTL_EXTEND_TO: u32 = 30 * DAY_IN_LEDGERS;
+/// Entries are only extended when their TTL falls below this threshold
+pub const TTL_THRESHOLD: u32 = TTL_EXTEND_TO - DAY_IN_LEDGERS;
+
 // ============================================================================
 // Error Types
 // ============================================================================
@@ -62,6 +102,40 @@ pub enum ContractError {
     ExpirationOverflow = 20,
     /// Invalid commitment_id (must be non-empty and <= 256 chars)
     InvalidCommitmentId = 21,
+    /// Staking program has not been approved by the admin
+    StakingProgramNotApproved = 22,
+    /// NFT is stake-locked by a staking program and cannot be transferred
+    TokenStaked = 23,
+    /// Requested lock exceeds the staking program's lock cap
+    LockCapExceeded = 24,
+    /// NFT is not stake-locked
+    NotStaked = 25,
+    /// Approval expiration ledger is in the past
+    InvalidExpirationLedger = 26,
+    /// Approval has lapsed
+    ApprovalExpired = 27,
+    /// Attribute key or value is empty or too long
+    InvalidAttribute = 28,
+    /// Token already carries MAX_ATTRIBUTES attributes
+    TooManyAttributes = 29,
+    /// Collection name, symbol or description is empty or too long
+    InvalidCollectionMetadata = 30,
+    /// Protocol is sunsetting and mints no new tokens
+    ProtocolSunset = 31,
+    /// Unbounded legacy view disabled by the admin; use `scan_tokens`
+    LegacyCallDisabled = 32,
+    /// Clawback reason is empty or too long
+    InvalidClawbackReason = 33,
+    /// MAX_MINTERS minters are already registered
+    TooManyMinters = 34,
+    /// More than MAX_SCAN_ITEMS token ids requested in one batch read
+    TooManyTokenIds = 35,
+    /// Merge needs 2..=MAX_MERGE_TOKENS distinct tokens in a single asset
+    InvalidMerge = 36,
+    /// Receiving contract's `on_nft_received` hook failed or returned false
+    ReceiverRejected = 37,
+    /// Backing commitment is still open in commitment_core, which settles it
+    CommitmentStillOpen = 38,
 }
 
 // ============================================================================
@@ -93,6 +167,271 @@ pub struct CommitmentNFT {
     pub early_exit_penalty: u32,
 }
 
+/// Performance grade assigned to a settled NFT
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PerformanceGrade {
+    /// Net gain over the initial amount
+    A,
+    /// Loss within 25% of the max loss limit
+    B,
+    /// Loss within 50% of the max loss limit
+    C,
+    /// Loss within the max loss limit
+    D,
+    /// Loss beyond the max loss limit
+    F,
+}
+
+/// Final performance of a settled NFT, supplied by commitment_core
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PerformanceRecord {
+    pub grade: PerformanceGrade,
+    pub final_value: i128,
+    pub fees_generated: i128,
+    pub graded_at: u64,
+}
+
+/// A single ownership change in a token's transfer history
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransferRecord {
+    pub from: Address,
+    pub to: Address,
+    pub timestamp: u64,
+}
+
+/// An administrative force-transfer; also the data of the `Clawback` event
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClawbackRecord {
+    pub from: Address,
+    pub to: Address,
+    pub admin: Address,
+    pub reason: String,
+    pub timestamp: u64,
+}
+
+/// Result of `mint_v2`: the new token and the timestamps callers must track
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintReceipt {
+    pub token_id: u32,
+    pub created_at: u64,
+    pub expires_at: u64,
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+/// Version of the token event schema. Every token event is published with
+/// topics `(event_name, EVENT_VERSION, token_id)` and one of the typed data
+/// structs below; new fields bump the version.
+pub const EVENT_VERSION: u32 = 1;
+
+/// Data of the `Mint` event
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintEvent {
+    pub owner: Address,
+    pub commitment_id: String,
+    pub expires_at: u64,
+    pub timestamp: u64,
+}
+
+/// Collection-wide counters, maintained incrementally as tokens change
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CollectionStats {
+    pub active_count: u32,
+    /// Inactive tokens (settled or exited), including composite tokens
+    pub settled_count: u32,
+    pub burned_count: u32,
+    /// Sum of `initial_amount` over all minted tokens
+    pub total_initial_value: i128,
+    /// Addresses holding at least one token
+    pub unique_holders: u32,
+}
+
+/// Data of the `Merge` event
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MergeEvent {
+    pub owner: Address,
+    pub merged_from: Vec<u32>,
+    pub timestamp: u64,
+}
+
+/// Data of the `Transfer` event
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransferEvent {
+    pub from: Address,
+    pub to: Address,
+    pub timestamp: u64,
+}
+
+/// Data of the `Approve` event (an expiration_ledger of 0 is a revocation)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApprovalEvent {
+    pub owner: Address,
+    pub spender: Address,
+    pub expiration_ledger: u32,
+}
+
+/// Data of the `Delegate` event (`delegate` equal to `owner` is a revocation)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DelegateEvent {
+    pub owner: Address,
+    pub delegate: Address,
+}
+
+/// Data of the `Settle` event; `settled_by` is set for keeper settlements
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SettleEvent {
+    pub settled_by: Option<Address>,
+    pub timestamp: u64,
+}
+
+/// Data of the `Inactive` and `Repair` events
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StatusChangeEvent {
+    pub was_active: bool,
+    pub is_active: bool,
+    pub timestamp: u64,
+}
+
+/// Data of the `Extended` event
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExtendedEvent {
+    pub extra_days: u32,
+    pub duration_days: u32,
+    pub expires_at: u64,
+    pub timestamp: u64,
+}
+
+/// Data of the `Staked` and `Unstaked` events
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StakeEvent {
+    pub owner: Address,
+    pub program_id: Address,
+    pub until: u64,
+    pub timestamp: u64,
+}
+
+/// Transfer approval granted by a token owner, valid through `expiration_ledger`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenApproval {
+    pub spender: Address,
+    pub expiration_ledger: u32,
+}
+
+/// Collection-level display metadata for wallets and explorers
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollectionMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub description: String,
+}
+
+/// Bounded batch of tokens returned by `scan_tokens`, in mint order
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenScan {
+    pub items: Vec<CommitmentNFT>,
+    /// Continuation to pass to the next call; 0 means the scan is complete
+    pub continuation: u32,
+    /// Snapshot version at read time; a change between batches means the
+    /// collection was modified mid-scan and the scan should be restarted
+    pub snapshot: u64,
+}
+
+/// Why `is_transferable` reports a token as not transferable
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum TransferBlockReason {
+    /// No condition blocks the transfer
+    None = 0,
+    /// Token does not exist
+    TokenNotFound = 1,
+    /// Commitment is still active (not yet settled)
+    NotSettled = 2,
+    /// Contract is paused
+    Paused = 3,
+    /// Contract is frozen in emergency mode
+    EmergencyFrozen = 4,
+    /// Token is locked by a staking program
+    CollateralLocked = 5,
+}
+
+/// Time lock placed on a settled NFT by an approved staking program
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StakeLock {
+    pub program_id: Address,
+    pub locked_at: u64,
+    pub until: u64,
+}
+
+/// Storage durability of an audited entry
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StorageClass {
+    Instance,
+    Persistent,
+}
+
+/// A raw storage entry reported by the audit views
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuditEntry {
+    pub key: DataKey,
+    pub storage: StorageClass,
+    /// Name of the stored value type
+    pub entry_type: Symbol,
+    /// Ledgers until archival as last recorded by this contract (0 if unknown or lapsed)
+    pub ttl_remaining: u32,
+}
+
+/// Commitment rules as stored by commitment_core (mirrored for cross-contract reads)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CommitmentRules {
+    pub duration_days: u32,
+    pub max_loss_percent: u32,
+    pub commitment_type: String,
+    pub early_exit_penalty: u32,
+    pub min_fee_threshold: i128,
+    pub grace_period_days: u32,
+}
+
+/// Commitment as stored by commitment_core (mirrored for cross-contract reads)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Commitment {
+    pub commitment_id: String,
+    pub owner: Address,
+    pub nft_token_id: u32,
+    pub rules: CommitmentRules,
+    pub amount: i128,
+    pub asset_address: Address,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub current_value: i128,
+    pub status: String,
+}
+
 /// Parameters for batch NFT transfer operations
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -104,6 +443,7 @@ pub struct TransferParams {
 
 /// Storage keys for the contract
 #[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
     /// Admin address (singleton)
     Admin,
@@ -127,6 +467,44 @@ pub enum DataKey {
     ReentrancyGuard,
     /// Contract version
     Version,
+    /// Append-only transfer history (token_id -> Vec<TransferRecord>)
+    TransferHistory(u32),
+    /// Ledger sequence until which token storage was last extended (token_id -> u32)
+    TokenLiveUntil(u32),
+    /// Pauser role flag (Address -> bool)
+    Pauser(Address),
+    /// Performance record of a settled token (token_id -> PerformanceRecord)
+    Performance(u32),
+    /// Token ids per performance grade (PerformanceGrade -> Vec<u32>)
+    GradeTokens(PerformanceGrade),
+    /// Maximum duration_days accepted by mint (u32)
+    MaxDurationDays,
+    /// Approved staking program and its lock cap in seconds (Address -> u64)
+    StakingProgram(Address),
+    /// Active stake lock on a token (token_id -> StakeLock)
+    StakeLock(u32),
+    /// Transfer approval (token_id -> TokenApproval)
+    Approval(u32),
+    /// Auditor role flag (Address -> bool)
+    Auditor(Address),
+    /// Ledger sequence until which instance storage was last extended (u32)
+    InstanceLiveUntil,
+    /// Holder of a token's governance/utility rights (token_id -> Address)
+    Delegate(u32),
+    /// Extra key/value attributes (token_id -> Map<String, String>)
+    Attributes(u32),
+    /// Collection name/symbol/description (CollectionMetadata)
+    CollectionMetadata,
+    /// Whether the unbounded get_all_metadata view is disabled (bool)
+    LegacyMetadataDisabled,
+    /// Administrative clawbacks of a token (token_id -> Vec<ClawbackRecord>)
+    Clawbacks(u32),
+    /// Addresses granted the minter role besides the core contract (Vec<Address>)
+    Minters,
+    /// Tokens burned into a composite token (token_id -> Vec<u32>)
+    MergedFrom(u32),
+    /// Collection-wide counters (CollectionStats)
+    Stats,
 }
 
 // Events
@@ -169,26 +547,216 @@ impl CommitmentNFTContract {
         Ok(())
     }
 
-    /// Pause the contract
-    pub fn pause(e: Env) {
-        let admin: Address = e
-            .storage()
+    /// Initialize the NFT contract with the collection's display metadata.
+    ///
+    /// Contracts initialized with `initialize` report the default name and
+    /// symbol.
+    pub fn initialize_with_metadata(
+        e: Env,
+        admin: Address,
+        name: String,
+        symbol: String,
+        description: String,
+    ) -> Result<(), ContractError> {
+        if name.is_empty()
+            || name.len() > MAX_COLLECTION_NAME_LENGTH
+            || symbol.is_empty()
+            || symbol.len() > MAX_COLLECTION_SYMBOL_LENGTH
+            || description.len() > MAX_COLLECTION_DESCRIPTION_LENGTH
+        {
+            return Err(ContractError::InvalidCollectionMetadata);
+        }
+        Self::initialize(e.clone(), admin)?;
+
+        let metadata = CollectionMetadata {
+            name,
+            symbol,
+            description,
+        };
+        e.storage()
             .instance()
-            .get(&DataKey::Admin)
-            .unwrap_or_else(|| panic!("Contract not initialized"));
-        admin.require_auth();
-        Pausable::pause(&e);
+            .set(&DataKey::CollectionMetadata, &metadata);
+        Ok(())
+    }
+
+    /// Collection name
+    pub fn name(e: Env) -> String {
+        Self::collection_metadata(e).name
+    }
+
+    /// Collection symbol
+    pub fn symbol(e: Env) -> String {
+        Self::collection_metadata(e).symbol
+    }
+
+    /// Collection name, symbol and description
+    pub fn collection_metadata(e: Env) -> CollectionMetadata {
+        e.storage()
+            .instance()
+            .get(&DataKey::CollectionMetadata)
+            .unwrap_or_else(|| CollectionMetadata {
+                name: String::from_str(&e, DEFAULT_COLLECTION_NAME),
+                symbol: String::from_str(&e, DEFAULT_COLLECTION_SYMBOL),
+                description: String::from_str(&e, ""),
+            })
+    }
+
+    /// Pause the contract
+    ///
+    /// Callable by the admin or any address holding the pauser role.
+    pub fn pause(e: Env, caller: Address) -> Result<(), ContractError> {
+        require_pauser(&e, &caller)?;
+        Pausable::pause_by(&e, &caller);
+        Ok(())
     }
 
     /// Unpause the contract
-    pub fn unpause(e: Env) {
-        let admin: Address = e
-            .storage()
+    ///
+    /// Callable by the admin or any address holding the pauser role.
+    pub fn unpause(e: Env, caller: Address) -> Result<(), ContractError> {
+        require_pauser(&e, &caller)?;
+        Pausable::unpause_by(&e, &caller);
+        Ok(())
+    }
+
+    /// Set the maximum duration_days accepted by mint (admin only)
+    pub fn set_max_duration_days(
+        e: Env,
+        caller: Address,
+        max_duration_days: u32,
+    ) -> Result<(), ContractError> {
+        require_admin(&e, &caller)?;
+        if max_duration_days == 0 {
+            return Err(ContractError::InvalidDuration);
+        }
+        let previous = Self::get_max_duration_days(e.clone());
+        e.storage()
             .instance()
-            .get(&DataKey::Admin)
-            .unwrap_or_else(|| panic!("Contract not initialized"));
-        admin.require_auth();
-        Pausable::unpause(&e);
+            .set(&DataKey::MaxDurationDays, &max_duration_days);
+        e.events().publish(
+            (Symbol::new(&e, "MaxDurationSet"),),
+            (previous, max_duration_days, caller, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Get the maximum duration_days accepted by mint
+    pub fn get_max_duration_days(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DataKey::MaxDurationDays)
+            .unwrap_or(DEFAULT_MAX_DURATION_DAYS)
+    }
+
+    /// Grant the pauser role (admin only)
+    pub fn add_pauser(e: Env, caller: Address, pauser: Address) -> Result<(), ContractError> {
+        require_admin(&e, &caller)?;
+        e.storage()
+            .instance()
+            .set(&DataKey::Pauser(pauser.clone()), &true);
+        e.events().publish(
+            (Symbol::new(&e, "PauserGranted"), pauser),
+            (caller, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Revoke the pauser role (admin only)
+    pub fn remove_pauser(e: Env, caller: Address, pauser: Address) -> Result<(), ContractError> {
+        require_admin(&e, &caller)?;
+        e.storage()
+            .instance()
+            .remove(&DataKey::Pauser(pauser.clone()));
+        e.events().publish(
+            (Symbol::new(&e, "PauserRevoked"), pauser),
+            (caller, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Grant the minter role, e.g. to a second core deployment during a
+    /// migration (admin only). The registered core contract is always a minter.
+    pub fn add_minter(e: Env, caller: Address, minter: Address) -> Result<(), ContractError> {
+        require_admin(&e, &caller)?;
+        let mut minters = Self::get_minters(e.clone());
+        if minters.contains(&minter) {
+            return Ok(());
+        }
+        if minters.len() >= MAX_MINTERS {
+            return Err(ContractError::TooManyMinters);
+        }
+        minters.push_back(minter.clone());
+        e.storage().instance().set(&DataKey::Minters, &minters);
+        e.events().publish(
+            (Symbol::new(&e, "MinterGranted"), minter),
+            (caller, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Revoke the minter role (admin only)
+    pub fn remove_minter(e: Env, caller: Address, minter: Address) -> Result<(), ContractError> {
+        require_admin(&e, &caller)?;
+        let mut minters = Self::get_minters(e.clone());
+        if let Some(index) = minters.first_index_of(&minter) {
+            minters.remove(index);
+            e.storage().instance().set(&DataKey::Minters, &minters);
+        }
+        e.events().publish(
+            (Symbol::new(&e, "MinterRevoked"), minter),
+            (caller, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Addresses granted the minter role in addition to the core contract
+    pub fn get_minters(e: Env) -> Vec<Address> {
+        e.storage()
+            .instance()
+            .get(&DataKey::Minters)
+            .unwrap_or(Vec::new(&e))
+    }
+
+    /// Check if an address holds the pauser role
+    pub fn is_pauser(e: Env, address: Address) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::Pauser(address))
+            .unwrap_or(false)
+    }
+
+    /// Grant the auditor role (admin only)
+    pub fn add_auditor(e: Env, caller: Address, auditor: Address) -> Result<(), ContractError> {
+        require_admin(&e, &caller)?;
+        e.storage()
+            .instance()
+            .set(&DataKey::Auditor(auditor.clone()), &true);
+        e.events().publish(
+            (Symbol::new(&e, "AuditorGranted"), auditor),
+            (caller, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Revoke the auditor role (admin only)
+    pub fn remove_auditor(e: Env, caller: Address, auditor: Address) -> Result<(), ContractError> {
+        require_admin(&e, &caller)?;
+        e.storage()
+            .instance()
+            .remove(&DataKey::Auditor(auditor.clone()));
+        e.events().publish(
+            (Symbol::new(&e, "AuditorRevoked"), auditor),
+            (caller, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Check if an address holds the auditor role
+    pub fn is_auditor(e: Env, address: Address) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::Auditor(address))
+            .unwrap_or(false)
     }
 
     /// Check if the contract is paused
@@ -218,15 +786,18 @@ impl CommitmentNFTContract {
             .instance()
             .get(&DataKey::Admin)
             .ok_or(ContractError::NotInitialized)?;
-        admin.require_auth();
+        // Bind the admin's signature to the exact contract being registered
+        admin.require_auth_for_args((core_contract.clone(),).into_val(&e));
 
         e.storage()
             .instance()
             .set(&DataKey::CoreContract, &core_contract);
 
         // Emit event for access control change
-        e.events()
-            .publish((Symbol::new(&e, "CoreContractSet"),), (core_contract,));
+        e.events().publish(
+            (Symbol::new(&e, "CoreContractSet"),),
+            (core_contract, admin, e.ledger().timestamp()),
+        );
 
         Ok(())
     }
@@ -256,6 +827,10 @@ impl CommitmentNFTContract {
     pub fn set_admin(e: Env, caller: Address, new_admin: Address) -> Result<(), ContractError> {
         require_admin(&e, &caller)?;
         e.storage().instance().set(&DataKey::Admin, &new_admin);
+        e.events().publish(
+            (Symbol::new(&e, "AdminSet"),),
+            (new_admin, caller, e.ledger().timestamp()),
+        );
         Ok(())
     }
 
@@ -336,14 +911,72 @@ impl CommitmentNFTContract {
         asset_address: Address,
         early_exit_penalty: u32,
     ) -> Result<u32, ContractError> {
-        // Reentrancy protection
-        let guard: bool = e
-            .storage()
-            .instance()
-            .get(&DataKey::ReentrancyGuard)
-            .unwrap_or(false);
-
-        if guard {
+        Self::mint_token(
+            e,
+            None,
+            owner,
+            commitment_id,
+            duration_days,
+            max_loss_percent,
+            commitment_type,
+            initial_amount,
+            asset_address,
+            early_exit_penalty,
+        )
+    }
+
+    /// Mint a new Commitment NFT on behalf of `minter`.
+    ///
+    /// Same arguments and checks as `mint`, but authorized by `minter`, which
+    /// must be the core contract or an address added with `add_minter`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn mint_by(
+        e: Env,
+        minter: Address,
+        owner: Address,
+        commitment_id: String,
+        duration_days: u32,
+        max_loss_percent: u32,
+        commitment_type: String,
+        initial_amount: i128,
+        asset_address: Address,
+        early_exit_penalty: u32,
+    ) -> Result<u32, ContractError> {
+        Self::mint_token(
+            e,
+            Some(minter),
+            owner,
+            commitment_id,
+            duration_days,
+            max_loss_percent,
+            commitment_type,
+            initial_amount,
+            asset_address,
+            early_exit_penalty,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn mint_token(
+        e: Env,
+        minter: Option<Address>,
+        owner: Address,
+        commitment_id: String,
+        duration_days: u32,
+        max_loss_percent: u32,
+        commitment_type: String,
+        initial_amount: i128,
+        asset_address: Address,
+        early_exit_penalty: u32,
+    ) -> Result<u32, ContractError> {
+        // Reentrancy protection
+        let guard: bool = e
+            .storage()
+            .instance()
+            .get(&DataKey::ReentrancyGuard)
+            .unwrap_or(false);
+
+        if guard {
             return Err(ContractError::ReentrancyDetected);
         }
         e.storage().instance().set(&DataKey::ReentrancyGuard, &true);
@@ -360,8 +993,22 @@ impl CommitmentNFTContract {
             return Err(ContractError::NotInitialized);
         }
 
+        // Only the core contract (or the admin before one is set) or a registered minter may mint
+        match &minter {
+            Some(minter) => require_minter(&e, minter)?,
+            None => require_core_or_admin(&e)?,
+        }
+
+        // No new tokens once a sunset has begun
+        if Sunset::state(&e) != SunsetState::Live {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+            return Err(ContractError::ProtocolSunset);
+        }
+
         // Validate inputs
-        if duration_days == 0 {
+        if duration_days == 0 || duration_days > Self::get_max_duration_days(e.clone()) {
             e.storage()
                 .instance()
                 .set(&DataKey::ReentrancyGuard, &false);
@@ -447,39 +1094,11 @@ impl CommitmentNFTContract {
             early_exit_penalty,
         };
 
-        // Store NFT data
-        e.storage().persistent().set(&DataKey::NFT(token_id), &nft);
-
-        // Update owner balance
-        let current_balance: u32 = e
-            .storage()
-            .persistent()
-            .get(&DataKey::OwnerBalance(owner.clone()))
-            .unwrap_or(0);
-        e.storage().persistent().set(
-            &DataKey::OwnerBalance(owner.clone()),
-            &(current_balance + 1),
-        );
-
-        // Update owner tokens list
-        let mut owner_tokens: Vec<u32> = e
-            .storage()
-            .persistent()
-            .get(&DataKey::OwnerTokens(owner.clone()))
-            .unwrap_or(Vec::new(&e));
-        owner_tokens.push_back(token_id);
-        e.storage()
-            .persistent()
-            .set(&DataKey::OwnerTokens(owner.clone()), &owner_tokens);
-
-        // Add token_id to the list of all tokens
-        let mut token_ids: Vec<u32> = e
-            .storage()
-            .instance()
-            .get(&DataKey::TokenIds)
-            .unwrap_or(Vec::new(&e));
-        token_ids.push_back(token_id);
-        e.storage().instance().set(&DataKey::TokenIds, &token_ids);
+        // Store NFT data and update owner balance, owner tokens and token list
+        add_token(&e, &nft);
+        update_stats(&e, |stats| {
+            stats.total_initial_value = stats.total_initial_value.saturating_add(initial_amount)
+        });
 
         // Clear reentrancy guard
         e.storage()
@@ -487,14 +1106,56 @@ impl CommitmentNFTContract {
             .set(&DataKey::ReentrancyGuard, &false);
 
         // Emit mint event
-        e.events().publish(
-            (symbol_short!("Mint"), token_id, owner.clone()),
-            (commitment_id, e.ledger().timestamp()),
+        publish_token_event(
+            &e,
+            symbol_short!("Mint"),
+            token_id,
+            MintEvent {
+                owner: owner.clone(),
+                commitment_id,
+                expires_at,
+                timestamp: e.ledger().timestamp(),
+            },
         );
 
         Ok(token_id)
     }
 
+    /// Mint a new Commitment NFT and return a receipt with its timestamps.
+    ///
+    /// Same arguments and checks as `mint`; saves callers a follow-up
+    /// `get_metadata` call to learn the expiry.
+    #[allow(clippy::too_many_arguments)]
+    pub fn mint_v2(
+        e: Env,
+        owner: Address,
+        commitment_id: String,
+        duration_days: u32,
+        max_loss_percent: u32,
+        commitment_type: String,
+        initial_amount: i128,
+        asset_address: Address,
+        early_exit_penalty: u32,
+    ) -> Result<MintReceipt, ContractError> {
+        let token_id = Self::mint(
+            e.clone(),
+            owner,
+            commitment_id,
+            duration_days,
+            max_loss_percent,
+            commitment_type,
+            initial_amount,
+            asset_address,
+            early_exit_penalty,
+        )?;
+        let nft = Self::get_metadata(e, token_id)?;
+        Ok(MintReceipt {
+            token_id,
+            created_at: nft.metadata.created_at,
+            expires_at: nft.metadata.expires_at,
+        })
+    }
+
     // ========================================================================
     // NFT Query Functions
     // ========================================================================
@@ -507,6 +1168,96 @@ impl CommitmentNFTContract {
             .ok_or(ContractError::TokenNotFound)
     }
 
+    /// Get metadata for several tokens in one call.
+    ///
+    /// Results are in the order of `token_ids`, with `None` for ids that do
+    /// not exist. At most `MAX_SCAN_ITEMS` ids may be requested.
+    pub fn get_metadata_many(
+        e: Env,
+        token_ids: Vec<u32>,
+    ) -> Result<Vec<Option<CommitmentNFT>>, ContractError> {
+        if token_ids.len() > MAX_SCAN_ITEMS {
+            return Err(ContractError::TooManyTokenIds);
+        }
+        let mut nfts = Vec::new(&e);
+        for token_id in token_ids.iter() {
+            nfts.push_back(e.storage().persistent().get(&DataKey::NFT(token_id)));
+        }
+        Ok(nfts)
+    }
+
+    /// Get the extra key/value attributes attached to a token
+    pub fn get_attributes(e: Env, token_id: u32) -> Result<Map<String, String>, ContractError> {
+        if !e.storage().persistent().has(&DataKey::NFT(token_id)) {
+            return Err(ContractError::TokenNotFound);
+        }
+        Ok(e.storage()
+            .persistent()
+            .get(&DataKey::Attributes(token_id))
+            .unwrap_or(Map::new(&e)))
+    }
+
+    /// Set (or overwrite) an extra attribute on a token, e.g. "strategy_name".
+    ///
+    /// Callable by the token owner or the core contract.
+    ///
+    /// # Errors
+    /// * `NotAuthorized` - caller is neither the owner nor the core contract
+    /// * `InvalidAttribute` - empty key, or key/value over the length limits
+    /// * `TooManyAttributes` - adding a new key would exceed `MAX_ATTRIBUTES`
+    pub fn set_attribute(
+        e: Env,
+        caller: Address,
+        token_id: u32,
+        key: String,
+        value: String,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+        require_owner_or_core(&e, &caller, token_id)?;
+
+        if key.is_empty()
+            || key.len() > MAX_ATTRIBUTE_KEY_LENGTH
+            || value.len() > MAX_ATTRIBUTE_VALUE_LENGTH
+        {
+            return Err(ContractError::InvalidAttribute);
+        }
+
+        let mut attributes = Self::get_attributes(e.clone(), token_id)?;
+        if !attributes.contains_key(key.clone()) && attributes.len() >= MAX_ATTRIBUTES {
+            return Err(ContractError::TooManyAttributes);
+        }
+        attributes.set(key.clone(), value);
+        e.storage()
+            .persistent()
+            .set(&DataKey::Attributes(token_id), &attributes);
+
+        publish_token_event(&e, symbol_short!("AttrSet"), token_id, key);
+        Ok(())
+    }
+
+    /// Remove an extra attribute from a token (owner or core contract)
+    pub fn remove_attribute(
+        e: Env,
+        caller: Address,
+        token_id: u32,
+        key: String,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+        require_owner_or_core(&e, &caller, token_id)?;
+
+        let mut attributes = Self::get_attributes(e.clone(), token_id)?;
+        if attributes.remove(key.clone()).is_some() {
+            let storage_key = DataKey::Attributes(token_id);
+            if attributes.is_empty() {
+                e.storage().persistent().remove(&storage_key);
+            } else {
+                e.storage().persistent().set(&storage_key, &attributes);
+            }
+            publish_token_event(&e, symbol_short!("AttrDel"), token_id, key);
+        }
+        Ok(())
+    }
+
     /// Get owner of NFT
     pub fn owner_of(e: Env, token_id: u32) -> Result<Address, ContractError> {
         let nft: CommitmentNFT = e
@@ -529,128 +1280,153 @@ impl CommitmentNFTContract {
         to: Address,
         token_id: u32,
     ) -> Result<(), ContractError> {
-        // Reentrancy protection
-        let guard: bool = e
-            .storage()
-            .instance()
-            .get(&DataKey::ReentrancyGuard)
-            .unwrap_or(false);
-
-        if guard {
-            return Err(ContractError::ReentrancyDetected);
-        }
-        e.storage().instance().set(&DataKey::ReentrancyGuard, &true);
-        EmergencyControl::require_not_emergency(&e);
-
-        // Check if contract is paused
-        Pausable::require_not_paused(&e);
-
         // CHECKS: Require authorization from the sender
         from.require_auth();
+        transfer_token(e, from, to, token_id)
+    }
 
-        // Validate 'to' address is not the same as 'from' (prevent self-transfer)
-        if to == from {
-            e.storage()
-                .instance()
-                .set(&DataKey::ReentrancyGuard, &false);
-            return Err(ContractError::TransferToZeroAddress);
+    /// Transfer NFT to new owner, checking that a contract recipient can handle it.
+    ///
+    /// When `to` is a contract, its `on_nft_received(nft_contract, from,
+    /// token_id, data) -> bool` hook is invoked after the transfer; the whole
+    /// transfer reverts unless the hook exists and returns true.
+    pub fn safe_transfer(
+        e: Env,
+        from: Address,
+        to: Address,
+        token_id: u32,
+        data: Bytes,
+    ) -> Result<(), ContractError> {
+        from.require_auth();
+        transfer_token(e.clone(), from.clone(), to.clone(), token_id)?;
+
+        if is_contract_address(&to) {
+            let accepted = e.try_invoke_contract::<bool, soroban_sdk::Error>(
+                &to,
+                &Symbol::new(&e, "on_nft_received"),
+                (e.current_contract_address(), from, token_id, data).into_val(&e),
+            );
+            if !matches!(accepted, Ok(Ok(true))) {
+                return Err(ContractError::ReceiverRejected);
+            }
         }
+        Ok(())
+    }
 
-        // Get the NFT
-        let mut nft: CommitmentNFT = e
-            .storage()
-            .persistent()
-            .get(&DataKey::NFT(token_id))
-            .ok_or_else(|| {
-                e.storage()
-                    .instance()
-                    .set(&DataKey::ReentrancyGuard, &false);
-                ContractError::TokenNotFound
-            })?;
-
-        // Verify ownership
-        if nft.owner != from {
-            e.storage()
-                .instance()
-                .set(&DataKey::ReentrancyGuard, &false);
+    /// Approve `spender` to transfer a token on the owner's behalf until
+    /// `expiration_ledger` (inclusive), replacing any existing approval.
+    ///
+    /// Passing `expiration_ledger` of 0 revokes the approval. The approval is
+    /// cleared whenever the token changes hands.
+    pub fn approve(
+        e: Env,
+        owner: Address,
+        spender: Address,
+        token_id: u32,
+        expiration_ledger: u32,
+    ) -> Result<(), ContractError> {
+        owner.require_auth();
+        if Self::owner_of(e.clone(), token_id)? != owner {
             return Err(ContractError::NotOwner);
         }
 
-        // Active (locked) commitment NFTs cannot be transferred (#145)
-        if nft.is_active {
-            e.storage()
-                .instance()
-                .set(&DataKey::ReentrancyGuard, &false);
-            return Err(ContractError::NFTLocked);
+        let key = DataKey::Approval(token_id);
+        if expiration_ledger == 0 {
+            e.storage().persistent().remove(&key);
+        } else {
+            if expiration_ledger < e.ledger().sequence() {
+                return Err(ContractError::InvalidExpirationLedger);
+            }
+            let approval = TokenApproval {
+                spender: spender.clone(),
+                expiration_ledger,
+            };
+            e.storage().persistent().set(&key, &approval);
         }
 
-        // EFFECTS: Update state
-        // Update owner
-        nft.owner = to.clone();
-        e.storage().persistent().set(&DataKey::NFT(token_id), &nft);
-
-        // OPTIMIZATION: Batch read balances before updating
-        let (from_balance, to_balance) = {
-            let from_bal = e
-                .storage()
-                .persistent()
-                .get(&DataKey::OwnerBalance(from.clone()))
-                .unwrap_or(0u32);
-            let to_bal = e
-                .storage()
-                .persistent()
-                .get(&DataKey::OwnerBalance(to.clone()))
-                .unwrap_or(0u32);
-            (from_bal, to_bal)
-        };
+        publish_token_event(
+            &e,
+            symbol_short!("Approve"),
+            token_id,
+            ApprovalEvent {
+                owner,
+                spender,
+                expiration_ledger,
+            },
+        );
+        Ok(())
+    }
 
-        // Update balance counts
-        if from_balance > 0 {
-            e.storage()
-                .persistent()
-                .set(&DataKey::OwnerBalance(from.clone()), &(from_balance - 1));
-        }
+    /// Get the approved spender of a token, if the approval has not lapsed
+    pub fn get_approved(e: Env, token_id: u32) -> Option<Address> {
         e.storage()
             .persistent()
-            .set(&DataKey::OwnerBalance(to.clone()), &(to_balance + 1));
+            .get::<_, TokenApproval>(&DataKey::Approval(token_id))
+            .filter(|approval| approval.expiration_ledger >= e.ledger().sequence())
+            .map(|approval| approval.spender)
+    }
 
-        // Update owner tokens lists
-        let mut from_tokens: Vec<u32> = e
+    /// Transfer a token as its approved spender.
+    ///
+    /// # Errors
+    /// * `NotAuthorized` - `spender` holds no approval for the token
+    /// * `ApprovalExpired` - the approval's expiration ledger has passed
+    pub fn transfer_from(
+        e: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        token_id: u32,
+    ) -> Result<(), ContractError> {
+        spender.require_auth();
+        let approval: TokenApproval = e
             .storage()
             .persistent()
-            .get(&DataKey::OwnerTokens(from.clone()))
-            .unwrap_or(Vec::new(&e));
-        if let Some(index) = from_tokens.iter().position(|id| id == token_id) {
-            from_tokens.remove(index as u32);
+            .get(&DataKey::Approval(token_id))
+            .ok_or(ContractError::NotAuthorized)?;
+        if approval.spender != spender {
+            return Err(ContractError::NotAuthorized);
         }
-        e.storage()
-            .persistent()
-            .set(&DataKey::OwnerTokens(from.clone()), &from_tokens);
-
-        let mut to_tokens: Vec<u32> = e
-            .storage()
-            .persistent()
-            .get(&DataKey::OwnerTokens(to.clone()))
-            .unwrap_or(Vec::new(&e));
-        to_tokens.push_back(token_id);
-        e.storage()
-            .persistent()
-            .set(&DataKey::OwnerTokens(to.clone()), &to_tokens);
+        if approval.expiration_ledger < e.ledger().sequence() {
+            return Err(ContractError::ApprovalExpired);
+        }
+        transfer_token(e, from, to, token_id)
+    }
 
-        // Clear reentrancy guard
-        e.storage()
-            .instance()
-            .set(&DataKey::ReentrancyGuard, &false);
+    /// Delegate a token's governance/utility rights (voting weight, fee
+    /// discounts, ...) to another address without transferring it.
+    ///
+    /// Delegating to the owner itself clears the delegation. Delegation is
+    /// cleared whenever the token changes hands.
+    pub fn delegate(e: Env, token_id: u32, delegate: Address) -> Result<(), ContractError> {
+        let owner = Self::owner_of(e.clone(), token_id)?;
+        owner.require_auth();
+
+        let key = DataKey::Delegate(token_id);
+        if delegate == owner {
+            e.storage().persistent().remove(&key);
+        } else {
+            e.storage().persistent().set(&key, &delegate);
+        }
 
-        // Emit transfer event
-        e.events().publish(
-            (symbol_short!("Transfer"), from, to),
-            (token_id, e.ledger().timestamp()),
+        publish_token_event(
+            &e,
+            symbol_short!("Delegate"),
+            token_id,
+            DelegateEvent { owner, delegate },
         );
-
         Ok(())
     }
 
+    /// Get the address holding a token's utility rights: its delegate if set,
+    /// otherwise its owner.
+    pub fn get_delegate(e: Env, token_id: u32) -> Result<Address, ContractError> {
+        match e.storage().persistent().get(&DataKey::Delegate(token_id)) {
+            Some(delegate) => Ok(delegate),
+            None => Self::owner_of(e, token_id),
+        }
+    }
+
     /// Check if NFT is active
     pub fn is_active(e: Env, token_id: u32) -> Result<bool, ContractError> {
         let nft: CommitmentNFT = e
@@ -662,6 +1438,15 @@ impl CommitmentNFTContract {
         Ok(nft.is_active)
     }
 
+    /// Collection statistics: token counts by status, total committed value
+    /// and number of holders
+    pub fn get_stats(e: Env) -> CollectionStats {
+        e.storage()
+            .instance()
+            .get(&DataKey::Stats)
+            .unwrap_or_default()
+    }
+
     /// Get total supply of NFTs minted
     pub fn total_supply(e: Env) -> u32 {
         e.storage()
@@ -679,7 +1464,19 @@ impl CommitmentNFTContract {
     }
 
     /// Get all NFTs metadata (for frontend)
-    pub fn get_all_metadata(e: Env) -> Vec<CommitmentNFT> {
+    ///
+    /// Deprecated: unbounded, and reverts once the collection exceeds the
+    /// read budget. Use `scan_tokens`. Fails with `LegacyCallDisabled` once
+    /// the admin disables it.
+    pub fn get_all_metadata(e: Env) -> Result<Vec<CommitmentNFT>, ContractError> {
+        if e.storage()
+            .instance()
+            .get(&DataKey::LegacyMetadataDisabled)
+            .unwrap_or(false)
+        {
+            return Err(ContractError::LegacyCallDisabled);
+        }
+
         let token_ids: Vec<u32> = e
             .storage()
             .instance()
@@ -698,41 +1495,387 @@ impl CommitmentNFTContract {
             }
         }
 
-        nfts
+        Ok(nfts)
     }
 
-    /// Get all NFTs owned by a specific address
-    pub fn get_nfts_by_owner(e: Env, owner: Address) -> Vec<CommitmentNFT> {
+    /// Scan tokens in mint order in bounded batches.
+    ///
+    /// Pass 0 as `continuation` to start and the returned `continuation` to
+    /// resume; a returned `continuation` of 0 means the scan is complete.
+    /// `max_items` is capped at `MAX_SCAN_ITEMS`.
+    pub fn scan_tokens(e: Env, continuation: u32, max_items: u32) -> TokenScan {
         let token_ids: Vec<u32> = e
             .storage()
-            .persistent()
-            .get(&DataKey::OwnerTokens(owner))
+            .instance()
+            .get(&DataKey::TokenIds)
             .unwrap_or(Vec::new(&e));
+        let max_items = max_items.clamp(1, MAX_SCAN_ITEMS);
 
-        let mut owned_nfts: Vec<CommitmentNFT> = Vec::new(&e);
-
-        for token_id in token_ids.iter() {
+        let mut items: Vec<CommitmentNFT> = Vec::new(&e);
+        let mut position = continuation;
+        while position < token_ids.len() && items.len() < max_items {
             if let Some(nft) = e
                 .storage()
                 .persistent()
-                .get::<DataKey, CommitmentNFT>(&DataKey::NFT(token_id))
+                .get::<DataKey, CommitmentNFT>(&DataKey::NFT(token_ids.get_unchecked(position)))
             {
-                owned_nfts.push_back(nft);
+                items.push_back(nft);
             }
+            position += 1;
         }
 
-        owned_nfts
+        TokenScan {
+            items,
+            continuation: if position < token_ids.len() {
+                position
+            } else {
+                0
+            },
+            snapshot: SnapshotVersion::current(&e),
+        }
     }
 
-    // ========================================================================
-    // Settlement (Issue #5 - Main Implementation)
-    // ========================================================================
+    /// Current snapshot version, bumped whenever token state changes
+    pub fn get_snapshot_version(e: Env) -> u64 {
+        SnapshotVersion::current(&e)
+    }
 
-    /// Mark NFT as inactive (for early exit or other non-expiry scenarios)
+    /// Enable or disable the unbounded `get_all_metadata` view (admin only)
+    pub fn set_legacy_metadata_enabled(
+        e: Env,
+        caller: Address,
+        enabled: bool,
+    ) -> Result<(), ContractError> {
+        require_admin(&e, &caller)?;
+        e.storage()
+            .instance()
+            .set(&DataKey::LegacyMetadataDisabled, &!enabled);
+        e.events().publish(
+            (Symbol::new(&e, "LegacyMetadata"),),
+            (enabled, caller, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Get a page of the transfer history for a token (oldest first)
+    pub fn get_transfer_history(
+        e: Env,
+        token_id: u32,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<TransferRecord>, ContractError> {
+        if !e.storage().persistent().has(&DataKey::NFT(token_id)) {
+            return Err(ContractError::TokenNotFound);
+        }
+
+        let history: Vec<TransferRecord> = e
+            .storage()
+            .persistent()
+            .get(&DataKey::TransferHistory(token_id))
+            .unwrap_or(Vec::new(&e));
+
+        let start = offset.min(history.len());
+        let end = offset.saturating_add(limit).min(history.len());
+        Ok(history.slice(start..end))
+    }
+
+    /// Force-transfer a token on administrative grounds (e.g. court order,
+    /// compromised key recovery).
+    ///
+    /// Bypasses owner authorization, approvals and stake locks; any stake lock
+    /// is released. Active tokens are rejected, as in `transfer`. The core
+    /// commitment's owner follows the new holder. The reason is kept in the
+    /// token's clawback log.
+    pub fn clawback(
+        e: Env,
+        caller: Address,
+        token_id: u32,
+        to: Address,
+        reason: String,
+    ) -> Result<(), ContractError> {
+        require_admin(&e, &caller)?;
+
+        if reason.is_empty() || reason.len() > MAX_CLAWBACK_REASON_LEN {
+            return Err(ContractError::InvalidClawbackReason);
+        }
+        let nft: CommitmentNFT = e
+            .storage()
+            .persistent()
+            .get(&DataKey::NFT(token_id))
+            .ok_or(ContractError::TokenNotFound)?;
+        if nft.owner == to {
+            return Err(ContractError::TransferToZeroAddress);
+        }
+        if nft.is_active {
+            return Err(ContractError::NFTLocked);
+        }
+
+        let record = ClawbackRecord {
+            from: nft.owner.clone(),
+            to: to.clone(),
+            admin: caller,
+            reason,
+            timestamp: e.ledger().timestamp(),
+        };
+
+        e.storage()
+            .persistent()
+            .remove(&DataKey::StakeLock(token_id));
+        let commitment_id = nft.metadata.commitment_id.clone();
+        move_token(&e, nft, &to);
+        sync_core_owner(&e, &commitment_id, &to);
+
+        let key = DataKey::Clawbacks(token_id);
+        let mut log: Vec<ClawbackRecord> =
+            e.storage().persistent().get(&key).unwrap_or(Vec::new(&e));
+        log.push_back(record.clone());
+        e.storage().persistent().set(&key, &log);
+
+        publish_token_event(&e, symbol_short!("Clawback"), token_id, record);
+        Ok(())
+    }
+
+    /// Get the administrative clawbacks of a token (oldest first)
+    pub fn get_clawbacks(e: Env, token_id: u32) -> Vec<ClawbackRecord> {
+        e.storage()
+            .persistent()
+            .get(&DataKey::Clawbacks(token_id))
+            .unwrap_or(Vec::new(&e))
+    }
+
+    /// Burn several settled tokens of one owner into a composite token.
+    ///
+    /// The composite token is inactive and aggregates the burned tokens'
+    /// durations and initial amounts; all of them must be in the same asset.
+    /// Requires the owner's signature. Burned tokens still count toward
+    /// `total_supply`, which counts tokens ever minted.
+    ///
+    /// # Returns
+    /// The composite token id.
+    pub fn merge(e: Env, token_ids: Vec<u32>) -> Result<u32, ContractError> {
+        EmergencyControl::require_not_emergency(&e);
+        Pausable::require_not_paused(&e);
+        if token_ids.len() < 2 || token_ids.len() > MAX_MERGE_TOKENS {
+            return Err(ContractError::InvalidMerge);
+        }
+
+        let mut nfts: Vec<CommitmentNFT> = Vec::new(&e);
+        for token_id in token_ids.iter() {
+            let nft: CommitmentNFT = e
+                .storage()
+                .persistent()
+                .get(&DataKey::NFT(token_id))
+                .ok_or(ContractError::TokenNotFound)?;
+            nfts.push_back(nft);
+        }
+        let first = nfts.get_unchecked(0);
+        let owner = first.owner.clone();
+        owner.require_auth();
+
+        let mut duration_days: u32 = 0;
+        let mut initial_amount: i128 = 0;
+        let mut max_loss_percent: u32 = 0;
+        for (index, nft) in nfts.iter().enumerate() {
+            if nft.owner != owner {
+                return Err(ContractError::NotOwner);
+            }
+            if nft.is_active {
+                return Err(ContractError::NFTLocked);
+            }
+            if e.storage()
+                .persistent()
+                .has(&DataKey::StakeLock(nft.token_id))
+            {
+                return Err(ContractError::TokenStaked);
+            }
+            if nft.metadata.asset_address != first.metadata.asset_address
+                || token_ids.first_index_of(nft.token_id) != Some(index as u32)
+            {
+                return Err(ContractError::InvalidMerge);
+            }
+            duration_days = duration_days
+                .checked_add(nft.metadata.duration_days)
+                .ok_or(ContractError::InvalidMerge)?;
+            initial_amount = initial_amount
+                .checked_add(nft.metadata.initial_amount)
+                .ok_or(ContractError::InvalidMerge)?;
+            max_loss_percent = max_loss_percent.max(nft.metadata.max_loss_percent);
+        }
+
+        for nft in nfts.iter() {
+            burn_token(&e, &nft);
+        }
+
+        let token_id: u32 = e
+            .storage()
+            .instance()
+            .get(&DataKey::TokenCounter)
+            .unwrap_or(0);
+        e.storage()
+            .instance()
+            .set(&DataKey::TokenCounter, &(token_id + 1));
+        let now = e.ledger().timestamp();
+        let composite = CommitmentNFT {
+            owner: owner.clone(),
+            token_id,
+            metadata: CommitmentMetadata {
+                commitment_id: String::from_str(&e, COMPOSITE_COMMITMENT_TYPE),
+                duration_days,
+                max_loss_percent,
+                commitment_type: String::from_str(&e, COMPOSITE_COMMITMENT_TYPE),
+                created_at: now,
+                expires_at: now,
+                initial_amount,
+                asset_address: first.metadata.asset_address.clone(),
+            },
+            is_active: false,
+            early_exit_penalty: 0,
+        };
+        add_token(&e, &composite);
+        e.storage()
+            .persistent()
+            .set(&DataKey::MergedFrom(token_id), &token_ids);
+
+        publish_token_event(
+            &e,
+            symbol_short!("Merge"),
+            token_id,
+            MergeEvent {
+                owner,
+                merged_from: token_ids,
+                timestamp: now,
+            },
+        );
+        Ok(token_id)
+    }
+
+    /// Token ids burned into a composite token (empty for regular tokens)
+    pub fn get_merged_from(e: Env, token_id: u32) -> Vec<u32> {
+        e.storage()
+            .persistent()
+            .get(&DataKey::MergedFrom(token_id))
+            .unwrap_or(Vec::new(&e))
+    }
+
+    /// Get all NFTs owned by a specific address
+    pub fn get_nfts_by_owner(e: Env, owner: Address) -> Vec<CommitmentNFT> {
+        let token_ids: Vec<u32> = e
+            .storage()
+            .persistent()
+            .get(&DataKey::OwnerTokens(owner))
+            .unwrap_or(Vec::new(&e));
+
+        let mut owned_nfts: Vec<CommitmentNFT> = Vec::new(&e);
+
+        for token_id in token_ids.iter() {
+            if let Some(nft) = e
+                .storage()
+                .persistent()
+                .get::<DataKey, CommitmentNFT>(&DataKey::NFT(token_id))
+            {
+                owned_nfts.push_back(nft);
+            }
+        }
+
+        owned_nfts
+    }
+
+    /// Get a page of an owner's NFTs filtered by status.
+    ///
+    /// `active_only` selects active commitments; otherwise only inactive
+    /// (settled or exited) ones are returned. `offset` and `limit` apply to the
+    /// filtered list and `limit` is capped at `MAX_SCAN_ITEMS`.
+    pub fn get_nfts_by_owner_filtered(
+        e: Env,
+        owner: Address,
+        active_only: bool,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<CommitmentNFT> {
+        let token_ids: Vec<u32> = e
+            .storage()
+            .persistent()
+            .get(&DataKey::OwnerTokens(owner))
+            .unwrap_or(Vec::new(&e));
+        let limit = limit.min(MAX_SCAN_ITEMS);
+
+        let mut nfts: Vec<CommitmentNFT> = Vec::new(&e);
+        let mut skipped: u32 = 0;
+        for token_id in token_ids.iter() {
+            if nfts.len() >= limit {
+                break;
+            }
+            let Some(nft) = e
+                .storage()
+                .persistent()
+                .get::<DataKey, CommitmentNFT>(&DataKey::NFT(token_id))
+            else {
+                continue;
+            };
+            if nft.is_active != active_only {
+                continue;
+            }
+            if skipped < offset {
+                skipped += 1;
+                continue;
+            }
+            nfts.push_back(nft);
+        }
+        nfts
+    }
+
+    /// Count and total initial amount of the settled NFTs `owner` holds.
+    ///
+    /// Only tokens settled through the core contract (those with a performance
+    /// record) count; early exits and plain `settle` calls do not.
+    pub fn get_settled_holdings(e: Env, owner: Address) -> (u32, i128) {
+        let token_ids: Vec<u32> = e
+            .storage()
+            .persistent()
+            .get(&DataKey::OwnerTokens(owner))
+            .unwrap_or(Vec::new(&e));
+
+        let mut count: u32 = 0;
+        let mut total: i128 = 0;
+        for token_id in token_ids.iter() {
+            if !e
+                .storage()
+                .persistent()
+                .has(&DataKey::Performance(token_id))
+            {
+                continue;
+            }
+            if let Some(nft) = e
+                .storage()
+                .persistent()
+                .get::<DataKey, CommitmentNFT>(&DataKey::NFT(token_id))
+            {
+                count += 1;
+                total = total.saturating_add(nft.metadata.initial_amount);
+            }
+        }
+        (count, total)
+    }
+
+    /// Get the token ids owned by an address, without loading metadata
+    pub fn token_ids_of_owner(e: Env, owner: Address) -> Vec<u32> {
+        e.storage()
+            .persistent()
+            .get(&DataKey::OwnerTokens(owner))
+            .unwrap_or(Vec::new(&e))
+    }
+
+    // ========================================================================
+    // Settlement (Issue #5 - Main Implementation)
+    // ========================================================================
+
+    /// Mark NFT as inactive (for early exit or other non-expiry scenarios)
     ///
     /// # Reentrancy Protection
     /// Uses checks-effects-interactions pattern.
     pub fn mark_inactive(e: Env, token_id: u32) -> Result<(), ContractError> {
+        require_core_or_admin(&e)?;
+
         // Reentrancy protection
         let guard: bool = e
             .storage()
@@ -773,6 +1916,8 @@ impl CommitmentNFTContract {
         // Mark as inactive
         nft.is_active = false;
         e.storage().persistent().set(&DataKey::NFT(token_id), &nft);
+        SnapshotVersion::bump(&e);
+        record_status_change(&e, false);
 
         // Clear reentrancy guard
         e.storage()
@@ -780,11 +1925,107 @@ impl CommitmentNFTContract {
             .set(&DataKey::ReentrancyGuard, &false);
 
         // Emit event
-        e.events().publish(
-            (symbol_short!("Inactive"), token_id),
-            e.ledger().timestamp(),
+        publish_token_event(
+            &e,
+            symbol_short!("Inactive"),
+            token_id,
+            StatusChangeEvent {
+                was_active: true,
+                is_active: false,
+                timestamp: e.ledger().timestamp(),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Push an active token's expiry forward by `extra_days` (core contract only).
+    ///
+    /// Keeps the NFT in sync when the core contract extends a commitment.
+    /// The resulting duration may not exceed the configured max duration.
+    pub fn extend_expiry(e: Env, token_id: u32, extra_days: u32) -> Result<(), ContractError> {
+        require_core_or_admin(&e)?;
+        EmergencyControl::require_not_emergency(&e);
+        Pausable::require_not_paused(&e);
+
+        let mut nft: CommitmentNFT = e
+            .storage()
+            .persistent()
+            .get(&DataKey::NFT(token_id))
+            .ok_or(ContractError::TokenNotFound)?;
+        if !nft.is_active {
+            return Err(ContractError::AlreadySettled);
+        }
+
+        let duration_days = nft
+            .metadata
+            .duration_days
+            .checked_add(extra_days)
+            .ok_or(ContractError::InvalidDuration)?;
+        if extra_days == 0 || duration_days > Self::get_max_duration_days(e.clone()) {
+            return Err(ContractError::InvalidDuration);
+        }
+        let expires_at = nft
+            .metadata
+            .expires_at
+            .checked_add(extra_days as u64 * 86400)
+            .ok_or(ContractError::ExpirationOverflow)?;
+
+        nft.metadata.duration_days = duration_days;
+        nft.metadata.expires_at = expires_at;
+        e.storage().persistent().set(&DataKey::NFT(token_id), &nft);
+        SnapshotVersion::bump(&e);
+
+        publish_token_event(
+            &e,
+            symbol_short!("Extended"),
+            token_id,
+            ExtendedEvent {
+                extra_days,
+                duration_days,
+                expires_at,
+                timestamp: e.ledger().timestamp(),
+            },
         );
+        Ok(())
+    }
 
+    /// Add `amount` to an active token's `initial_amount` (core contract only).
+    ///
+    /// Keeps the NFT in sync when the owner tops up the backing commitment.
+    pub fn top_up(e: Env, token_id: u32, amount: i128) -> Result<(), ContractError> {
+        require_core_or_admin(&e)?;
+        EmergencyControl::require_not_emergency(&e);
+        Pausable::require_not_paused(&e);
+        if amount <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        let mut nft: CommitmentNFT = e
+            .storage()
+            .persistent()
+            .get(&DataKey::NFT(token_id))
+            .ok_or(ContractError::TokenNotFound)?;
+        if !nft.is_active {
+            return Err(ContractError::AlreadySettled);
+        }
+        nft.metadata.initial_amount = nft
+            .metadata
+            .initial_amount
+            .checked_add(amount)
+            .ok_or(ContractError::InvalidAmount)?;
+        e.storage().persistent().set(&DataKey::NFT(token_id), &nft);
+        SnapshotVersion::bump(&e);
+        update_stats(&e, |stats| {
+            stats.total_initial_value = stats.total_initial_value.saturating_add(amount)
+        });
+
+        publish_token_event(
+            &e,
+            symbol_short!("TopUp"),
+            token_id,
+            (amount, nft.metadata.initial_amount, e.ledger().timestamp()),
+        );
         Ok(())
     }
 
@@ -810,64 +2051,686 @@ impl CommitmentNFTContract {
         // Check if contract is paused
         Pausable::require_not_paused(&e);
 
-        // CHECKS: Get the NFT
-        let mut nft: CommitmentNFT = e
-            .storage()
-            .persistent()
-            .get(&DataKey::NFT(token_id))
-            .ok_or_else(|| {
-                e.storage()
-                    .instance()
-                    .set(&DataKey::ReentrancyGuard, &false);
-                ContractError::TokenNotFound
-            })?;
-
-        // Check if already settled
-        if !nft.is_active {
-            e.storage()
-                .instance()
-                .set(&DataKey::ReentrancyGuard, &false);
-            return Err(ContractError::AlreadySettled);
-        }
-
-        // Verify expiration
-        let current_time = e.ledger().timestamp();
-        if current_time < nft.metadata.expires_at {
-            e.storage()
-                .instance()
-                .set(&DataKey::ReentrancyGuard, &false);
-            return Err(ContractError::NotExpired);
-        }
-
-        // EFFECTS: Update state
-        // Mark as inactive (settled)
-        nft.is_active = false;
-        e.storage().persistent().set(&DataKey::NFT(token_id), &nft);
+        let result = settle_token(&e, token_id);
 
         // Clear reentrancy guard
         e.storage()
             .instance()
             .set(&DataKey::ReentrancyGuard, &false);
+        result?;
 
         // Emit settle event
-        e.events()
-            .publish((symbol_short!("Settle"), token_id), e.ledger().timestamp());
+        publish_settle_event(&e, token_id, None);
 
         Ok(())
     }
 
-    /// Check if an NFT has expired (based on time)
-    pub fn is_expired(e: Env, token_id: u32) -> Result<bool, ContractError> {
-        let nft: CommitmentNFT = e
+    /// Settle an NFT and record its final performance grade.
+    ///
+    /// Called by the core contract at settlement with the final value and the
+    /// fees generated over the commitment's life. The grade is derived from
+    /// the net result against `initial_amount` and `max_loss_percent`.
+    ///
+    /// # Errors
+    /// * `NotInitialized` - core contract not set
+    /// * `TokenNotFound` / `AlreadySettled` / `NotExpired` - as for `settle`
+    pub fn settle_with_performance(
+        e: Env,
+        token_id: u32,
+        final_value: i128,
+        fees_generated: i128,
+    ) -> Result<(), ContractError> {
+        let core_contract: Address = e
             .storage()
-            .persistent()
-            .get(&DataKey::NFT(token_id))
-            .ok_or(ContractError::TokenNotFound)?;
+            .instance()
+            .get(&DataKey::CoreContract)
+            .ok_or(ContractError::NotInitialized)?;
+        core_contract.require_auth();
+
+        // Reentrancy protection
+        let guard: bool = e
+            .storage()
+            .instance()
+            .get(&DataKey::ReentrancyGuard)
+            .unwrap_or(false);
+
+        if guard {
+            return Err(ContractError::ReentrancyDetected);
+        }
+        e.storage().instance().set(&DataKey::ReentrancyGuard, &true);
+        EmergencyControl::require_not_emergency(&e);
+
+        // Check if contract is paused
+        Pausable::require_not_paused(&e);
+
+        let result = settle_token(&e, token_id);
+
+        // Clear reentrancy guard
+        e.storage()
+            .instance()
+            .set(&DataKey::ReentrancyGuard, &false);
+        result?;
+
+        let nft: CommitmentNFT = e
+            .storage()
+            .persistent()
+            .get(&DataKey::NFT(token_id))
+            .ok_or(ContractError::TokenNotFound)?;
+        let grade = performance_grade(
+            nft.metadata.initial_amount,
+            nft.metadata.max_loss_percent,
+            final_value,
+            fees_generated,
+        );
+        let record = PerformanceRecord {
+            grade,
+            final_value,
+            fees_generated,
+            graded_at: e.ledger().timestamp(),
+        };
+        e.storage()
+            .persistent()
+            .set(&DataKey::Performance(token_id), &record);
+
+        let grade_key = DataKey::GradeTokens(grade);
+        let mut graded: Vec<u32> = e
+            .storage()
+            .persistent()
+            .get(&grade_key)
+            .unwrap_or(Vec::new(&e));
+        graded.push_back(token_id);
+        e.storage().persistent().set(&grade_key, &graded);
+
+        // Emit settle event
+        publish_settle_event(&e, token_id, None);
+        publish_token_event(&e, symbol_short!("Graded"), token_id, record);
+
+        Ok(())
+    }
+
+    /// Get the performance record of a settled token, if it was graded
+    pub fn get_performance(e: Env, token_id: u32) -> Option<PerformanceRecord> {
+        e.storage()
+            .persistent()
+            .get(&DataKey::Performance(token_id))
+    }
+
+    /// Get all token ids that settled with the given grade
+    pub fn get_tokens_by_grade(e: Env, grade: PerformanceGrade) -> Vec<u32> {
+        e.storage()
+            .persistent()
+            .get(&DataKey::GradeTokens(grade))
+            .unwrap_or(Vec::new(&e))
+    }
+
+    /// Permissionless settlement of an expired NFT.
+    ///
+    /// Anyone (e.g. a keeper bot) may flip a token to inactive once its
+    /// `expires_at` has passed and commitment_core no longer holds its
+    /// commitment open. The caller must sign and is recorded in the Settle
+    /// event for accountability.
+    ///
+    /// # Errors
+    /// * `TokenNotFound` - token does not exist
+    /// * `AlreadySettled` - token is already inactive
+    /// * `NotExpired` - `expires_at` has not been reached yet
+    /// * `CommitmentStillOpen` - core has yet to settle, exit or liquidate it
+    pub fn settle_expired(e: Env, caller: Address, token_id: u32) -> Result<(), ContractError> {
+        // Reentrancy protection
+        let guard: bool = e
+            .storage()
+            .instance()
+            .get(&DataKey::ReentrancyGuard)
+            .unwrap_or(false);
+
+        if guard {
+            return Err(ContractError::ReentrancyDetected);
+        }
+        e.storage().instance().set(&DataKey::ReentrancyGuard, &true);
+        EmergencyControl::require_not_emergency(&e);
+
+        // Check if contract is paused
+        Pausable::require_not_paused(&e);
+
+        // CHECKS: The keeper must sign so it can be held accountable
+        caller.require_auth();
+
+        let result = settle_expired_token(&e, token_id);
+
+        // Clear reentrancy guard
+        e.storage()
+            .instance()
+            .set(&DataKey::ReentrancyGuard, &false);
+        result?;
+
+        // Emit settle event including the settling caller
+        publish_settle_event(&e, token_id, Some(caller));
+
+        Ok(())
+    }
+
+    /// Settle a list of expired NFTs in a single call.
+    ///
+    /// Tokens that cannot be settled (not found, already settled, not yet
+    /// expired, or still open in commitment_core) are skipped and reported in
+    /// the returned errors instead of aborting the whole batch.
+    pub fn batch_settle(e: Env, token_ids: Vec<u32>) -> BatchResultVoid {
+        // Reentrancy protection
+        let guard: bool = e
+            .storage()
+            .instance()
+            .get(&DataKey::ReentrancyGuard)
+            .unwrap_or(false);
+
+        if guard {
+            let mut errors = Vec::new(&e);
+            errors.push_back(BatchError {
+                index: 0,
+                error_code: ContractError::ReentrancyDetected as u32,
+                context: String::from_str(&e, "reentrancy"),
+            });
+            return BatchResultVoid::failure(&e, errors);
+        }
+        EmergencyControl::require_not_emergency(&e);
+
+        // Check if contract is paused
+        Pausable::require_not_paused(&e);
+
+        // Validate batch size
+        let batch_size = token_ids.len();
+        let contract_name = String::from_str(&e, "commitment_nft");
+        if let Err(error_code) =
+            BatchProcessor::enforce_batch_limits(&e, batch_size, Some(contract_name))
+        {
+            let mut errors = Vec::new(&e);
+            errors.push_back(BatchError {
+                index: 0,
+                error_code,
+                context: String::from_str(&e, "batch_size_validation"),
+            });
+            return BatchResultVoid::failure(&e, errors);
+        }
+
+        e.storage().instance().set(&DataKey::ReentrancyGuard, &true);
+
+        let timestamp = e.ledger().timestamp();
+        let mut errors = Vec::new(&e);
+        let mut settled_count: u32 = 0;
+
+        for i in 0..batch_size {
+            let token_id = token_ids.get(i).unwrap();
+            match settle_expired_token(&e, token_id) {
+                Ok(()) => {
+                    settled_count += 1;
+                    publish_settle_event(&e, token_id, None);
+                }
+                Err(err) => {
+                    let context = match err {
+                        ContractError::TokenNotFound => "token_not_found",
+                        ContractError::AlreadySettled => "already_settled",
+                        ContractError::NotExpired => "not_expired",
+                        ContractError::CommitmentStillOpen => "commitment_open",
+                        _ => "settle_failed",
+                    };
+                    errors.push_back(BatchError {
+                        index: i,
+                        error_code: err as u32,
+                        context: String::from_str(&e, context),
+                    });
+                }
+            }
+        }
+
+        // Clear reentrancy guard
+        e.storage()
+            .instance()
+            .set(&DataKey::ReentrancyGuard, &false);
+
+        // Emit batch summary event
+        e.events().publish(
+            (Symbol::new(&e, "BatchSettle"), batch_size),
+            (settled_count, errors.len(), timestamp),
+        );
+
+        BatchResultVoid::partial(settled_count, errors)
+    }
+
+    /// Repair an NFT whose active flag diverged from commitment_core (admin only).
+    ///
+    /// The core contract is authoritative: the NFT is active iff the backing
+    /// commitment's status is "active". Emits a Repair event with the before
+    /// and after values.
+    pub fn resync_nft_status(e: Env, caller: Address, token_id: u32) -> Result<(), ContractError> {
+        require_admin(&e, &caller)?;
+
+        let core_contract: Address = e
+            .storage()
+            .instance()
+            .get(&DataKey::CoreContract)
+            .ok_or(ContractError::NotInitialized)?;
+        let mut nft: CommitmentNFT = e
+            .storage()
+            .persistent()
+            .get(&DataKey::NFT(token_id))
+            .ok_or(ContractError::TokenNotFound)?;
+
+        let commitment: Commitment = e.invoke_contract(
+            &core_contract,
+            &Symbol::new(&e, "get_commitment"),
+            (nft.metadata.commitment_id.clone(),).into_val(&e),
+        );
+
+        let before = nft.is_active;
+        let after = commitment.status == String::from_str(&e, "active");
+        if before != after {
+            nft.is_active = after;
+            e.storage().persistent().set(&DataKey::NFT(token_id), &nft);
+            SnapshotVersion::bump(&e);
+            record_status_change(&e, after);
+        }
+
+        publish_token_event(
+            &e,
+            symbol_short!("Repair"),
+            token_id,
+            StatusChangeEvent {
+                was_active: before,
+                is_active: after,
+                timestamp: e.ledger().timestamp(),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Check if an NFT has expired (based on time)
+    pub fn is_expired(e: Env, token_id: u32) -> Result<bool, ContractError> {
+        let nft: CommitmentNFT = e
+            .storage()
+            .persistent()
+            .get(&DataKey::NFT(token_id))
+            .ok_or(ContractError::TokenNotFound)?;
 
         let current_time = e.ledger().timestamp();
         Ok(current_time >= nft.metadata.expires_at)
     }
 
+    /// Get token ids that have expired but are still active (awaiting settlement).
+    ///
+    /// Results are paginated: `offset` matching tokens are skipped and at most
+    /// `limit` ids are returned, in mint order.
+    pub fn get_expired_unsettled_tokens(e: Env, offset: u32, limit: u32) -> Vec<u32> {
+        let token_ids: Vec<u32> = e
+            .storage()
+            .instance()
+            .get(&DataKey::TokenIds)
+            .unwrap_or(Vec::new(&e));
+
+        let current_time = e.ledger().timestamp();
+        let mut result: Vec<u32> = Vec::new(&e);
+        let mut skipped: u32 = 0;
+
+        for token_id in token_ids.iter() {
+            if result.len() >= limit {
+                break;
+            }
+            if let Some(nft) = e
+                .storage()
+                .persistent()
+                .get::<DataKey, CommitmentNFT>(&DataKey::NFT(token_id))
+            {
+                if nft.is_active && current_time >= nft.metadata.expires_at {
+                    if skipped < offset {
+                        skipped += 1;
+                    } else {
+                        result.push_back(token_id);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Get active token ids that have not expired yet but will within `seconds`.
+    ///
+    /// Paginated like `get_expired_unsettled_tokens`, in mint order.
+    pub fn get_tokens_expiring_within(e: Env, seconds: u64, offset: u32, limit: u32) -> Vec<u32> {
+        let token_ids: Vec<u32> = e
+            .storage()
+            .instance()
+            .get(&DataKey::TokenIds)
+            .unwrap_or(Vec::new(&e));
+
+        let current_time = e.ledger().timestamp();
+        let window_end = current_time.saturating_add(seconds);
+        let mut result: Vec<u32> = Vec::new(&e);
+        let mut skipped: u32 = 0;
+
+        for token_id in token_ids.iter() {
+            if result.len() >= limit {
+                break;
+            }
+            if let Some(nft) = e
+                .storage()
+                .persistent()
+                .get::<DataKey, CommitmentNFT>(&DataKey::NFT(token_id))
+            {
+                let expires_at = nft.metadata.expires_at;
+                if nft.is_active && expires_at > current_time && expires_at <= window_end {
+                    if skipped < offset {
+                        skipped += 1;
+                    } else {
+                        result.push_back(token_id);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Extend the storage TTL of the given tokens and of contract instance storage.
+    ///
+    /// Permissionless: anyone may pay to keep long-lived NFTs from being archived.
+    pub fn extend_ttl(e: Env, token_ids: Vec<u32>) -> Result<(), ContractError> {
+        for token_id in token_ids.iter() {
+            if !e.storage().persistent().has(&DataKey::NFT(token_id)) {
+                return Err(ContractError::TokenNotFound);
+            }
+            extend_token_ttl(&e, token_id);
+        }
+        Self::extend_instance_ttl(e);
+        Ok(())
+    }
+
+    /// Extend the TTL of contract instance storage (admin, counters, token index).
+    pub fn extend_instance_ttl(e: Env) {
+        let storage = e.storage().instance();
+        storage.set(
+            &DataKey::InstanceLiveUntil,
+            &(e.ledger().sequence() + TTL_EXTEND_TO),
+        );
+        storage.extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    /// Remaining ledgers before a token's storage may be archived.
+    ///
+    /// Based on the last extension performed by this contract (at mint or via
+    /// `extend_ttl`), so it may overstate the live TTL by up to
+    /// `TTL_EXTEND_TO - TTL_THRESHOLD` ledgers; returns 0 once that point has passed.
+    pub fn get_ttl_remaining(e: Env, token_id: u32) -> Result<u32, ContractError> {
+        let live_until: u32 = e
+            .storage()
+            .persistent()
+            .get(&DataKey::TokenLiveUntil(token_id))
+            .ok_or(ContractError::TokenNotFound)?;
+        Ok(live_until.saturating_sub(e.ledger().sequence()))
+    }
+
+    // ========================================================================
+    // Staking Locks
+    // ========================================================================
+
+    /// Approve a staking program and cap how long it may lock a token (admin only).
+    /// A cap of 0 revokes the approval; existing locks are unaffected.
+    pub fn set_staking_program(
+        e: Env,
+        caller: Address,
+        program_id: Address,
+        max_lock_seconds: u64,
+    ) -> Result<(), ContractError> {
+        require_admin(&e, &caller)?;
+        let key = DataKey::StakingProgram(program_id.clone());
+        if max_lock_seconds == 0 {
+            e.storage().instance().remove(&key);
+        } else {
+            e.storage().instance().set(&key, &max_lock_seconds);
+        }
+        e.events().publish(
+            (Symbol::new(&e, "StakingProgramSet"), program_id),
+            (max_lock_seconds, caller, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Get a staking program's lock cap in seconds, if approved
+    pub fn get_staking_program(e: Env, program_id: Address) -> Option<u64> {
+        e.storage()
+            .instance()
+            .get(&DataKey::StakingProgram(program_id))
+    }
+
+    /// Time-lock a settled NFT for an approved staking program without moving custody.
+    ///
+    /// Requires authorization from both the token owner and the program.
+    /// Transfers fail with `TokenStaked` until the lock is released.
+    pub fn stake_lock(
+        e: Env,
+        token_id: u32,
+        program_id: Address,
+        until: u64,
+    ) -> Result<(), ContractError> {
+        Pausable::require_not_paused(&e);
+
+        let max_lock_seconds: u64 = e
+            .storage()
+            .instance()
+            .get(&DataKey::StakingProgram(program_id.clone()))
+            .ok_or(ContractError::StakingProgramNotApproved)?;
+        let nft: CommitmentNFT = e
+            .storage()
+            .persistent()
+            .get(&DataKey::NFT(token_id))
+            .ok_or(ContractError::TokenNotFound)?;
+
+        nft.owner.require_auth();
+        program_id.require_auth();
+
+        if nft.is_active {
+            return Err(ContractError::NFTLocked);
+        }
+        if e.storage().persistent().has(&DataKey::StakeLock(token_id)) {
+            return Err(ContractError::TokenStaked);
+        }
+        let now = e.ledger().timestamp();
+        if until <= now {
+            return Err(ContractError::InvalidDuration);
+        }
+        if until - now > max_lock_seconds {
+            return Err(ContractError::LockCapExceeded);
+        }
+
+        let lock = StakeLock {
+            program_id: program_id.clone(),
+            locked_at: now,
+            until,
+        };
+        e.storage()
+            .persistent()
+            .set(&DataKey::StakeLock(token_id), &lock);
+        publish_token_event(
+            &e,
+            symbol_short!("Staked"),
+            token_id,
+            StakeEvent {
+                owner: nft.owner,
+                program_id,
+                until,
+                timestamp: now,
+            },
+        );
+        Ok(())
+    }
+
+    /// Release a stake lock.
+    ///
+    /// The owner may unstake once the lock has expired; the locking program may
+    /// release it at any time.
+    pub fn unstake(e: Env, caller: Address, token_id: u32) -> Result<(), ContractError> {
+        caller.require_auth();
+
+        let lock: StakeLock = e
+            .storage()
+            .persistent()
+            .get(&DataKey::StakeLock(token_id))
+            .ok_or(ContractError::NotStaked)?;
+        let owner = Self::owner_of(e.clone(), token_id)?;
+
+        if caller != lock.program_id {
+            if caller != owner {
+                return Err(ContractError::NotAuthorized);
+            }
+            if e.ledger().timestamp() < lock.until {
+                return Err(ContractError::NotExpired);
+            }
+        }
+
+        e.storage()
+            .persistent()
+            .remove(&DataKey::StakeLock(token_id));
+        publish_token_event(
+            &e,
+            symbol_short!("Unstaked"),
+            token_id,
+            StakeEvent {
+                owner,
+                program_id: lock.program_id,
+                until: lock.until,
+                timestamp: e.ledger().timestamp(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Get the active stake lock on a token, if any
+    pub fn get_stake_lock(e: Env, token_id: u32) -> Option<StakeLock> {
+        e.storage().persistent().get(&DataKey::StakeLock(token_id))
+    }
+
+    // ========================================================================
+    // Storage Audit (admin or auditor)
+    // ========================================================================
+
+    /// List the singleton instance storage entries with their remaining TTL.
+    ///
+    /// Address-keyed role entries (pausers, auditors, staking programs)
+    /// are not indexed and are omitted. The TTL reflects the last
+    /// `extend_instance_ttl` call and is 0 if it was never extended.
+    pub fn audit_instance_entries(
+        e: Env,
+        caller: Address,
+    ) -> Result<Vec<AuditEntry>, ContractError> {
+        require_auditor(&e, &caller)?;
+
+        let storage = e.storage().instance();
+        let ttl_remaining = storage
+            .get::<_, u32>(&DataKey::InstanceLiveUntil)
+            .unwrap_or(0)
+            .saturating_sub(e.ledger().sequence());
+        let candidates = [
+            (DataKey::Admin, "Address"),
+            (DataKey::TokenCounter, "u32"),
+            (DataKey::TokenIds, "Vec_u32"),
+            (DataKey::CoreContract, "Address"),
+            (DataKey::ReentrancyGuard, "bool"),
+            (DataKey::Version, "u32"),
+            (DataKey::MaxDurationDays, "u32"),
+            (DataKey::CollectionMetadata, "CollectionMetadata"),
+            (DataKey::LegacyMetadataDisabled, "bool"),
+            (DataKey::Minters, "Vec_Address"),
+            (DataKey::Stats, "CollectionStats"),
+            (DataKey::InstanceLiveUntil, "u32"),
+        ];
+
+        let mut entries = Vec::new(&e);
+        for (key, entry_type) in candidates {
+            if storage.has(&key) {
+                entries.push_back(AuditEntry {
+                    key,
+                    storage: StorageClass::Instance,
+                    entry_type: Symbol::new(&e, entry_type),
+                    ttl_remaining,
+                });
+            }
+        }
+        Ok(entries)
+    }
+
+    /// List the persistent storage entries of tokens in mint order, paginated
+    /// by token, with the TTL recorded at their last extension.
+    pub fn audit_token_entries(
+        e: Env,
+        caller: Address,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<AuditEntry>, ContractError> {
+        require_auditor(&e, &caller)?;
+
+        let token_ids: Vec<u32> = e
+            .storage()
+            .instance()
+            .get(&DataKey::TokenIds)
+            .unwrap_or(Vec::new(&e));
+        let storage = e.storage().persistent();
+        let sequence = e.ledger().sequence();
+
+        let mut entries = Vec::new(&e);
+        for token_id in token_ids.iter().skip(offset as usize).take(limit as usize) {
+            let ttl_remaining = storage
+                .get::<_, u32>(&DataKey::TokenLiveUntil(token_id))
+                .unwrap_or(0)
+                .saturating_sub(sequence);
+            let candidates = [
+                (DataKey::NFT(token_id), "CommitmentNFT"),
+                (DataKey::TransferHistory(token_id), "Vec_TransferRecord"),
+                (DataKey::Performance(token_id), "PerformanceRecord"),
+                (DataKey::StakeLock(token_id), "StakeLock"),
+                (DataKey::Approval(token_id), "TokenApproval"),
+                (DataKey::Delegate(token_id), "Address"),
+                (DataKey::Attributes(token_id), "Map_String_String"),
+                (DataKey::Clawbacks(token_id), "Vec_ClawbackRecord"),
+                (DataKey::MergedFrom(token_id), "Vec_u32"),
+                (DataKey::TokenLiveUntil(token_id), "u32"),
+            ];
+            for (key, entry_type) in candidates {
+                if storage.has(&key) {
+                    entries.push_back(AuditEntry {
+                        key,
+                        storage: StorageClass::Persistent,
+                        entry_type: Symbol::new(&e, entry_type),
+                        ttl_remaining,
+                    });
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Check whether a token could be transferred right now.
+    ///
+    /// Evaluates the same gates as `transfer` and returns the first one that
+    /// blocks it, so frontends can explain a failure before submitting.
+    pub fn is_transferable(e: Env, token_id: u32) -> (bool, TransferBlockReason) {
+        let reason = match e
+            .storage()
+            .persistent()
+            .get::<_, CommitmentNFT>(&DataKey::NFT(token_id))
+        {
+            None => TransferBlockReason::TokenNotFound,
+            Some(_) if EmergencyControl::is_emergency_mode(&e) => {
+                TransferBlockReason::EmergencyFrozen
+            }
+            Some(_) if Pausable::is_paused(&e) => TransferBlockReason::Paused,
+            Some(nft) if nft.is_active => TransferBlockReason::NotSettled,
+            Some(_) if e.storage().persistent().has(&DataKey::StakeLock(token_id)) => {
+                TransferBlockReason::CollateralLocked
+            }
+            Some(_) => TransferBlockReason::None,
+        };
+        (reason == TransferBlockReason::None, reason)
+    }
+
     /// Check if a token exists
     pub fn token_exists(e: Env, token_id: u32) -> bool {
         e.storage().persistent().has(&DataKey::NFT(token_id))
@@ -889,6 +2752,19 @@ impl CommitmentNFTContract {
         EmergencyControl::set_emergency_mode(&e, enabled);
         Ok(())
     }
+
+    /// Begin the protocol sunset (admin only): minting stops while existing
+    /// tokens remain transferable and settleable. Cannot be undone.
+    pub fn begin_sunset(e: Env, caller: Address, final_date: u64) -> Result<(), ContractError> {
+        require_admin(&e, &caller)?;
+        Sunset::begin(&e, final_date);
+        Ok(())
+    }
+
+    /// Get the protocol sunset state
+    pub fn get_sunset_state(e: Env) -> SunsetState {
+        Sunset::state(&e)
+    }
 }
 
 fn read_version(e: &Env) -> u32 {
@@ -898,6 +2774,484 @@ fn read_version(e: &Env) -> u32 {
         .unwrap_or(0)
 }
 
+/// Move a token between owners; callers are responsible for authorization.
+fn transfer_token(e: Env, from: Address, to: Address, token_id: u32) -> Result<(), ContractError> {
+    // Reentrancy protection
+    let guard: bool = e
+        .storage()
+        .instance()
+        .get(&DataKey::ReentrancyGuard)
+        .unwrap_or(false);
+
+    if guard {
+        return Err(ContractError::ReentrancyDetected);
+    }
+    e.storage().instance().set(&DataKey::ReentrancyGuard, &true);
+    EmergencyControl::require_not_emergency(&e);
+
+    // Check if contract is paused
+    Pausable::require_not_paused(&e);
+
+    // Validate 'to' address is not the same as 'from' (prevent self-transfer)
+    if to == from {
+        e.storage()
+            .instance()
+            .set(&DataKey::ReentrancyGuard, &false);
+        return Err(ContractError::TransferToZeroAddress);
+    }
+
+    // Get the NFT
+    let nft: CommitmentNFT = e
+        .storage()
+        .persistent()
+        .get(&DataKey::NFT(token_id))
+        .ok_or_else(|| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+            ContractError::TokenNotFound
+        })?;
+
+    // Verify ownership
+    if nft.owner != from {
+        e.storage()
+            .instance()
+            .set(&DataKey::ReentrancyGuard, &false);
+        return Err(ContractError::NotOwner);
+    }
+
+    // Active (locked) commitment NFTs cannot be transferred (#145)
+    if nft.is_active {
+        e.storage()
+            .instance()
+            .set(&DataKey::ReentrancyGuard, &false);
+        return Err(ContractError::NFTLocked);
+    }
+
+    // Stake-locked NFTs stay with their owner until unstaked
+    if e.storage().persistent().has(&DataKey::StakeLock(token_id)) {
+        e.storage()
+            .instance()
+            .set(&DataKey::ReentrancyGuard, &false);
+        return Err(ContractError::TokenStaked);
+    }
+
+    // EFFECTS: Update state
+    let commitment_id = nft.metadata.commitment_id.clone();
+    move_token(&e, nft, &to);
+
+    // Clear reentrancy guard
+    e.storage()
+        .instance()
+        .set(&DataKey::ReentrancyGuard, &false);
+
+    // INTERACTIONS: Keep the core commitment's owner in step with the holder
+    sync_core_owner(&e, &commitment_id, &to);
+
+    // Emit transfer event
+    publish_token_event(
+        &e,
+        symbol_short!("Transfer"),
+        token_id,
+        TransferEvent {
+            from,
+            to,
+            timestamp: e.ledger().timestamp(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Ask the core contract to move `commitment_id` to `new_owner`.
+///
+/// Tokens minted outside core have no commitment there, so a failed sync is
+/// ignored rather than blocking the transfer.
+fn sync_core_owner(e: &Env, commitment_id: &String, new_owner: &Address) {
+    if let Some(core_contract) = e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::CoreContract)
+    {
+        let _ = e.try_invoke_contract::<(), soroban_sdk::Error>(
+            &core_contract,
+            &Symbol::new(e, "sync_owner"),
+            (commitment_id.clone(), new_owner.clone()).into_val(e),
+        );
+    }
+}
+
+/// Publish a token event with the versioned `(name, EVENT_VERSION, token_id)` topics
+fn publish_token_event<D: IntoVal<Env, Val>>(e: &Env, name: Symbol, token_id: u32, data: D) {
+    e.events().publish((name, EVENT_VERSION, token_id), data);
+}
+
+fn publish_settle_event(e: &Env, token_id: u32, settled_by: Option<Address>) {
+    publish_token_event(
+        e,
+        symbol_short!("Settle"),
+        token_id,
+        SettleEvent {
+            settled_by,
+            timestamp: e.ledger().timestamp(),
+        },
+    );
+}
+
+/// Keeper-initiated settlement of an expired token.
+///
+/// Tokens whose commitment core still holds open (active, violated or
+/// queued for exit) are left for core, which marks them inactive when it
+/// closes the commitment. Settling them first would make core's own NFT
+/// call fail with AlreadySettled, stranding the escrow, and would unlock the
+/// token for transfer while core still holds the funds.
+fn settle_expired_token(e: &Env, token_id: u32) -> Result<(), ContractError> {
+    let nft: CommitmentNFT = e
+        .storage()
+        .persistent()
+        .get(&DataKey::NFT(token_id))
+        .ok_or(ContractError::TokenNotFound)?;
+    if nft.is_active && core_commitment_open(e, &nft.metadata.commitment_id) {
+        return Err(ContractError::CommitmentStillOpen);
+    }
+    settle_token(e, token_id)
+}
+
+/// Whether commitment_core holds `commitment_id` in a non-final status.
+/// Tokens minted outside core have no commitment there and are not open.
+fn core_commitment_open(e: &Env, commitment_id: &String) -> bool {
+    let Some(core_contract) = e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::CoreContract)
+    else {
+        return false;
+    };
+    match e.try_invoke_contract::<Commitment, soroban_sdk::Error>(
+        &core_contract,
+        &Symbol::new(e, "get_commitment"),
+        (commitment_id.clone(),).into_val(e),
+    ) {
+        Ok(Ok(commitment)) => {
+            commitment.status != String::from_str(e, "settled")
+                && commitment.status != String::from_str(e, "early_exit")
+                && commitment.status != String::from_str(e, "liquidated")
+        }
+        _ => false,
+    }
+}
+
+/// Mark an expired, active NFT as settled (checks + effects only).
+///
+/// Callers are responsible for reentrancy/pause handling and event emission.
+fn settle_token(e: &Env, token_id: u32) -> Result<(), ContractError> {
+    let mut nft: CommitmentNFT = e
+        .storage()
+        .persistent()
+        .get(&DataKey::NFT(token_id))
+        .ok_or(ContractError::TokenNotFound)?;
+
+    // Check if already settled
+    if !nft.is_active {
+        return Err(ContractError::AlreadySettled);
+    }
+
+    // Verify expiration
+    if e.ledger().timestamp() < nft.metadata.expires_at {
+        return Err(ContractError::NotExpired);
+    }
+
+    // Mark as inactive (settled)
+    nft.is_active = false;
+    e.storage().persistent().set(&DataKey::NFT(token_id), &nft);
+    SnapshotVersion::bump(e);
+    record_status_change(e, false);
+
+    Ok(())
+}
+
+/// Derive a performance grade from the net result against the max loss limit
+fn performance_grade(
+    initial_amount: i128,
+    max_loss_percent: u32,
+    final_value: i128,
+    fees_generated: i128,
+) -> PerformanceGrade {
+    let net_value = final_value.saturating_add(fees_generated);
+    if net_value > initial_amount {
+        return PerformanceGrade::A;
+    }
+
+    // Compare loss against the limit in basis points to avoid rounding to zero
+    let loss_bps = if initial_amount > 0 {
+        (initial_amount - net_value).saturating_mul(10_000) / initial_amount
+    } else {
+        0
+    };
+    let max_loss_bps = max_loss_percent as i128 * 100;
+    if loss_bps * 4 <= max_loss_bps {
+        PerformanceGrade::B
+    } else if loss_bps * 2 <= max_loss_bps {
+        PerformanceGrade::C
+    } else if loss_bps <= max_loss_bps {
+        PerformanceGrade::D
+    } else {
+        PerformanceGrade::F
+    }
+}
+
+/// Extend the TTL of all persistent entries belonging to a token
+fn extend_token_ttl(e: &Env, token_id: u32) {
+    let storage = e.storage().persistent();
+    storage.extend_ttl(&DataKey::NFT(token_id), TTL_THRESHOLD, TTL_EXTEND_TO);
+
+    for key in [
+        DataKey::TransferHistory(token_id),
+        DataKey::Performance(token_id),
+        DataKey::StakeLock(token_id),
+        DataKey::Delegate(token_id),
+        DataKey::Attributes(token_id),
+        DataKey::Clawbacks(token_id),
+        DataKey::MergedFrom(token_id),
+    ] {
+        if storage.has(&key) {
+            storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        }
+    }
+
+    let live_until_key = DataKey::TokenLiveUntil(token_id);
+    storage.set(&live_until_key, &(e.ledger().sequence() + TTL_EXTEND_TO));
+    storage.extend_ttl(&live_until_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+/// Apply `update` to the stored collection statistics
+fn update_stats(e: &Env, update: impl FnOnce(&mut CollectionStats)) {
+    let mut stats: CollectionStats = e
+        .storage()
+        .instance()
+        .get(&DataKey::Stats)
+        .unwrap_or_default();
+    update(&mut stats);
+    e.storage().instance().set(&DataKey::Stats, &stats);
+}
+
+/// Move one token between the active and settled counts
+fn record_status_change(e: &Env, now_active: bool) {
+    update_stats(e, |stats| {
+        if now_active {
+            stats.settled_count = stats.settled_count.saturating_sub(1);
+            stats.active_count += 1;
+        } else {
+            stats.active_count = stats.active_count.saturating_sub(1);
+            stats.settled_count += 1;
+        }
+    });
+}
+
+/// Store a newly created token and add it to its owner's balance and the
+/// owner and global token lists
+fn add_token(e: &Env, nft: &CommitmentNFT) {
+    let token_id = nft.token_id;
+    let owner = &nft.owner;
+    e.storage().persistent().set(&DataKey::NFT(token_id), nft);
+    SnapshotVersion::bump(e);
+    extend_token_ttl(e, token_id);
+
+    let balance: u32 = e
+        .storage()
+        .persistent()
+        .get(&DataKey::OwnerBalance(owner.clone()))
+        .unwrap_or(0);
+    e.storage()
+        .persistent()
+        .set(&DataKey::OwnerBalance(owner.clone()), &(balance + 1));
+    update_stats(e, |stats| {
+        if nft.is_active {
+            stats.active_count += 1;
+        } else {
+            stats.settled_count += 1;
+        }
+        if balance == 0 {
+            stats.unique_holders += 1;
+        }
+    });
+
+    let mut owner_tokens: Vec<u32> = e
+        .storage()
+        .persistent()
+        .get(&DataKey::OwnerTokens(owner.clone()))
+        .unwrap_or(Vec::new(e));
+    owner_tokens.push_back(token_id);
+    e.storage()
+        .persistent()
+        .set(&DataKey::OwnerTokens(owner.clone()), &owner_tokens);
+
+    let mut token_ids: Vec<u32> = e
+        .storage()
+        .instance()
+        .get(&DataKey::TokenIds)
+        .unwrap_or(Vec::new(e));
+    token_ids.push_back(token_id);
+    e.storage().instance().set(&DataKey::TokenIds, &token_ids);
+}
+
+/// Remove a token and its per-token entries, and drop it from its owner's
+/// balance and the owner and global token lists. Transfer history and
+/// performance records are kept. Performs no checks.
+fn burn_token(e: &Env, nft: &CommitmentNFT) {
+    let token_id = nft.token_id;
+    let owner = &nft.owner;
+    for key in [
+        DataKey::NFT(token_id),
+        DataKey::Approval(token_id),
+        DataKey::Delegate(token_id),
+        DataKey::Attributes(token_id),
+    ] {
+        e.storage().persistent().remove(&key);
+    }
+    SnapshotVersion::bump(e);
+
+    let balance: u32 = e
+        .storage()
+        .persistent()
+        .get(&DataKey::OwnerBalance(owner.clone()))
+        .unwrap_or(0);
+    e.storage().persistent().set(
+        &DataKey::OwnerBalance(owner.clone()),
+        &balance.saturating_sub(1),
+    );
+    update_stats(e, |stats| {
+        if nft.is_active {
+            stats.active_count = stats.active_count.saturating_sub(1);
+        } else {
+            stats.settled_count = stats.settled_count.saturating_sub(1);
+        }
+        stats.burned_count += 1;
+        if balance == 1 {
+            stats.unique_holders = stats.unique_holders.saturating_sub(1);
+        }
+    });
+
+    let mut owner_tokens: Vec<u32> = e
+        .storage()
+        .persistent()
+        .get(&DataKey::OwnerTokens(owner.clone()))
+        .unwrap_or(Vec::new(e));
+    if let Some(index) = owner_tokens.first_index_of(token_id) {
+        owner_tokens.remove(index);
+    }
+    e.storage()
+        .persistent()
+        .set(&DataKey::OwnerTokens(owner.clone()), &owner_tokens);
+
+    let mut token_ids: Vec<u32> = e
+        .storage()
+        .instance()
+        .get(&DataKey::TokenIds)
+        .unwrap_or(Vec::new(e));
+    if let Some(index) = token_ids.first_index_of(token_id) {
+        token_ids.remove(index);
+    }
+    e.storage().instance().set(&DataKey::TokenIds, &token_ids);
+
+    publish_token_event(e, symbol_short!("Burn"), token_id, owner.clone());
+}
+
+/// Move a token to `to`, updating balances, owner indexes and transfer
+/// history, and clearing any approval or delegation. Performs no checks.
+fn move_token(e: &Env, mut nft: CommitmentNFT, to: &Address) {
+    let from = nft.owner.clone();
+    let token_id = nft.token_id;
+    nft.owner = to.clone();
+    e.storage().persistent().set(&DataKey::NFT(token_id), &nft);
+    SnapshotVersion::bump(e);
+    record_transfer(e, token_id, &from, to);
+    e.storage()
+        .persistent()
+        .remove(&DataKey::Approval(token_id));
+    e.storage()
+        .persistent()
+        .remove(&DataKey::Delegate(token_id));
+
+    // OPTIMIZATION: Batch read balances before updating
+    let (from_balance, to_balance) = {
+        let from_bal = e
+            .storage()
+            .persistent()
+            .get(&DataKey::OwnerBalance(from.clone()))
+            .unwrap_or(0u32);
+        let to_bal = e
+            .storage()
+            .persistent()
+            .get(&DataKey::OwnerBalance(to.clone()))
+            .unwrap_or(0u32);
+        (from_bal, to_bal)
+    };
+
+    // Update balance counts
+    if from_balance > 0 {
+        e.storage()
+            .persistent()
+            .set(&DataKey::OwnerBalance(from.clone()), &(from_balance - 1));
+    }
+    e.storage()
+        .persistent()
+        .set(&DataKey::OwnerBalance(to.clone()), &(to_balance + 1));
+    update_stats(e, |stats| {
+        if from_balance == 1 {
+            stats.unique_holders = stats.unique_holders.saturating_sub(1);
+        }
+        if to_balance == 0 {
+            stats.unique_holders += 1;
+        }
+    });
+
+    // Update owner tokens lists
+    let mut from_tokens: Vec<u32> = e
+        .storage()
+        .persistent()
+        .get(&DataKey::OwnerTokens(from.clone()))
+        .unwrap_or(Vec::new(e));
+    if let Some(index) = from_tokens.iter().position(|id| id == token_id) {
+        from_tokens.remove(index as u32);
+    }
+    e.storage()
+        .persistent()
+        .set(&DataKey::OwnerTokens(from.clone()), &from_tokens);
+
+    let mut to_tokens: Vec<u32> = e
+        .storage()
+        .persistent()
+        .get(&DataKey::OwnerTokens(to.clone()))
+        .unwrap_or(Vec::new(e));
+    to_tokens.push_back(token_id);
+    e.storage()
+        .persistent()
+        .set(&DataKey::OwnerTokens(to.clone()), &to_tokens);
+}
+
+/// Whether `address` is a contract (its strkey starts with 'C') rather than an account
+fn is_contract_address(address: &Address) -> bool {
+    let strkey = address.to_string();
+    let mut buf = [0u8; 56];
+    let len = (strkey.len() as usize).min(buf.len());
+    strkey.copy_into_slice(&mut buf[..len]);
+    buf[0] == b'C'
+}
+
+/// Append an ownership change to a token's transfer history
+fn record_transfer(e: &Env, token_id: u32, from: &Address, to: &Address) {
+    let key = DataKey::TransferHistory(token_id);
+    let mut history: Vec<TransferRecord> =
+        e.storage().persistent().get(&key).unwrap_or(Vec::new(e));
+    history.push_back(TransferRecord {
+        from: from.clone(),
+        to: to.clone(),
+        timestamp: e.ledger().timestamp(),
+    });
+    e.storage().persistent().set(&key, &history);
+}
+
 fn require_admin(e: &Env, caller: &Address) -> Result<(), ContractError> {
     caller.require_auth();
     let admin: Address = e
@@ -911,6 +3265,91 @@ fn require_admin(e: &Env, caller: &Address) -> Result<(), ContractError> {
     Ok(())
 }
 
+/// Require authorization from the core contract, or from the admin while no
+/// core contract is registered
+fn require_core_or_admin(e: &Env) -> Result<(), ContractError> {
+    let authority: Address = match e.storage().instance().get(&DataKey::CoreContract) {
+        Some(core_contract) => core_contract,
+        None => e
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(ContractError::NotInitialized)?,
+    };
+    authority.require_auth();
+    Ok(())
+}
+
+/// Require `minter` to authorize and be the core contract or a registered minter
+fn require_minter(e: &Env, minter: &Address) -> Result<(), ContractError> {
+    minter.require_auth();
+    let core_contract: Option<Address> = e.storage().instance().get(&DataKey::CoreContract);
+    let minters: Vec<Address> = e
+        .storage()
+        .instance()
+        .get(&DataKey::Minters)
+        .unwrap_or(Vec::new(e));
+    if core_contract.as_ref() != Some(minter) && !minters.contains(minter) {
+        return Err(ContractError::NotAuthorized);
+    }
+    Ok(())
+}
+
+/// Require the caller to be the token owner or the registered core contract
+fn require_owner_or_core(e: &Env, caller: &Address, token_id: u32) -> Result<(), ContractError> {
+    let nft: CommitmentNFT = e
+        .storage()
+        .persistent()
+        .get(&DataKey::NFT(token_id))
+        .ok_or(ContractError::TokenNotFound)?;
+    if nft.owner == *caller {
+        return Ok(());
+    }
+    let core_contract: Option<Address> = e.storage().instance().get(&DataKey::CoreContract);
+    if core_contract.as_ref() == Some(caller) {
+        return Ok(());
+    }
+    Err(ContractError::NotAuthorized)
+}
+
+/// Require the caller to be the admin or a holder of the auditor role
+fn require_auditor(e: &Env, caller: &Address) -> Result<(), ContractError> {
+    caller.require_auth();
+    let admin: Address = e
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .ok_or(ContractError::NotInitialized)?;
+    let is_auditor: bool = e
+        .storage()
+        .instance()
+        .get(&DataKey::Auditor(caller.clone()))
+        .unwrap_or(false);
+    if *caller != admin && !is_auditor {
+        return Err(ContractError::NotAuthorized);
+    }
+    Ok(())
+}
+
+/// Require the caller to be the admin or a holder of the pauser role
+fn require_pauser(e: &Env, caller: &Address) -> Result<(), ContractError> {
+    caller.require_auth();
+    let admin: Address = e
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .ok_or(ContractError::NotInitialized)?;
+    let is_pauser: bool = e
+        .storage()
+        .instance()
+        .get(&DataKey::Pauser(caller.clone()))
+        .unwrap_or(false);
+    if *caller != admin && !is_pauser {
+        return Err(ContractError::NotAuthorized);
+    }
+    Ok(())
+}
+
 fn require_valid_wasm_hash(e: &Env, wasm_hash: &BytesN<32>) -> Result<(), ContractError> {
     let zero = BytesN::from_array(e, &[0; 32]);
     if *wasm_hash == zero {
@@ -4,12 +4,13 @@ extern crate std;
 
 use crate::*;
 use soroban_sdk::{
-    symbol_short,
-    testutils::{Address as _, Events, Ledger},
+    contract, contractimpl, symbol_short,
+    testutils::{storage::Persistent as _, Address as _, Events, Ledger},
     vec, Address, Env, IntoVal, String,
 };
 
 fn setup_contract(e: &Env) -> (Address, CommitmentNFTContractClient<'_>) {
+    e.mock_all_auths();
     let contract_id = e.register_contract(None, CommitmentNFTContract);
     let client = CommitmentNFTContractClient::new(e, &contract_id);
     let admin = Address::generate(e);
@@ -118,6 +119,52 @@ fn test_initialize_twice_fails() {
     client.initialize(&admin); // Should panic
 }
 
+#[test]
+fn test_collection_metadata() {
+    let e = Env::default();
+    let (admin, client) = setup_contract(&e);
+
+    client.initialize_with_metadata(
+        &admin,
+        &String::from_str(&e, "Commitlabs Commitments"),
+        &String::from_str(&e, "CLC"),
+        &String::from_str(&e, "Liquidity commitment positions"),
+    );
+    assert_eq!(
+        client.name(),
+        String::from_str(&e, "Commitlabs Commitments")
+    );
+    assert_eq!(client.symbol(), String::from_str(&e, "CLC"));
+    assert_eq!(
+        client.collection_metadata().description,
+        String::from_str(&e, "Liquidity commitment positions")
+    );
+    assert_eq!(client.get_admin(), admin);
+}
+
+#[test]
+fn test_collection_metadata_defaults_and_validation() {
+    let e = Env::default();
+    let (admin, client) = setup_contract(&e);
+
+    assert_eq!(
+        client.try_initialize_with_metadata(
+            &admin,
+            &String::from_str(&e, "Commitlabs Commitments"),
+            &String::from_str(&e, ""),
+            &String::from_str(&e, ""),
+        ),
+        Err(Ok(ContractError::InvalidCollectionMetadata))
+    );
+
+    client.initialize(&admin);
+    assert_eq!(client.name(), String::from_str(&e, DEFAULT_COLLECTION_NAME));
+    assert_eq!(
+        client.symbol(),
+        String::from_str(&e, DEFAULT_COLLECTION_SYMBOL)
+    );
+}
+
 // ============================================
 // Mint Tests
 // ============================================
@@ -159,12 +206,17 @@ fn test_mint() {
         vec![
             &e,
             symbol_short!("Mint").into_val(&e),
-            token_id.into_val(&e),
-            owner.into_val(&e)
+            EVENT_VERSION.into_val(&e),
+            token_id.into_val(&e)
         ]
     );
-    let data: (String, u64) = last_event.2.into_val(&e);
-    assert_eq!(data.0, commitment_id);
+    let data: MintEvent = last_event.2.into_val(&e);
+    assert_eq!(data.owner, owner);
+    assert_eq!(data.commitment_id, commitment_id);
+    assert_eq!(
+        data.expires_at,
+        client.get_metadata(&token_id).metadata.expires_at
+    );
 }
 
 #[test]
@@ -217,6 +269,35 @@ fn test_mint_multiple() {
     assert_eq!(client.balance_of(&owner), 3);
 }
 
+#[test]
+fn test_mint_v2_returns_receipt() {
+    let e = Env::default();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    client.initialize(&admin);
+    e.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let receipt = client.mint_v2(
+        &owner,
+        &String::from_str(&e, "receipt_1"),
+        &30,
+        &10,
+        &String::from_str(&e, "balanced"),
+        &1000,
+        &asset_address,
+        &5,
+    );
+
+    assert_eq!(receipt.token_id, 0);
+    assert_eq!(receipt.created_at, 1_000);
+    assert_eq!(receipt.expires_at, 1_000 + 30 * 86_400);
+    let nft = client.get_metadata(&receipt.token_id);
+    assert_eq!(nft.metadata.expires_at, receipt.expires_at);
+    assert_eq!(nft.owner, owner);
+}
+
 #[test]
 #[should_panic(expected = "Error(Contract, #1)")] // NotInitialized
 fn test_mint_without_initialize_fails() {
@@ -588,6 +669,31 @@ fn test_get_metadata_nonexistent_token() {
     client.get_metadata(&999);
 }
 
+#[test]
+fn test_get_metadata_many_flags_missing_ids() {
+    let e = Env::default();
+    let (_admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+    let first = mint_to_owner(&e, &client, &owner, &asset_address, "many_1");
+    let second = mint_to_owner(&e, &client, &owner, &asset_address, "many_2");
+
+    let nfts = client.get_metadata_many(&vec![&e, second, 999, first]);
+    assert_eq!(nfts.len(), 3);
+    assert_eq!(nfts.get(0).unwrap().unwrap().token_id, second);
+    assert_eq!(nfts.get(1).unwrap(), None);
+    assert_eq!(nfts.get(2).unwrap().unwrap().token_id, first);
+
+    let mut too_many = Vec::new(&e);
+    for token_id in 0..=MAX_SCAN_ITEMS {
+        too_many.push_back(token_id);
+    }
+    assert_eq!(
+        client.try_get_metadata_many(&too_many),
+        Err(Ok(ContractError::TooManyTokenIds))
+    );
+}
+
 // ============================================
 // owner_of Tests
 // ============================================
@@ -929,6 +1035,76 @@ fn test_get_all_metadata() {
     }
 }
 
+#[test]
+fn test_scan_tokens_in_bounded_batches() {
+    let e = Env::default();
+    let (_admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let asset = Address::generate(&e);
+    let labels = ["scan_0", "scan_1", "scan_2", "scan_3", "scan_4"];
+    for label in labels {
+        mint_to_owner(&e, &client, &owner, &asset, label);
+    }
+
+    let first = client.scan_tokens(&0, &2);
+    assert_eq!(first.items.len(), 2);
+    assert_eq!(first.continuation, 2);
+
+    let second = client.scan_tokens(&first.continuation, &2);
+    assert_eq!(second.items.get(0).unwrap().token_id, 2);
+    assert_eq!(second.continuation, 4);
+
+    let last = client.scan_tokens(&second.continuation, &2);
+    assert_eq!(last.items.len(), 1);
+    assert_eq!(last.continuation, 0);
+
+    // max_items is capped and never zero
+    assert_eq!(client.scan_tokens(&0, &0).items.len(), 1);
+    assert_eq!(client.scan_tokens(&0, &1_000).items.len(), 5);
+}
+
+#[test]
+fn test_scan_tokens_snapshot_detects_mid_scan_changes() {
+    let e = Env::default();
+    let (_admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let asset = Address::generate(&e);
+    mint_to_owner(&e, &client, &owner, &asset, "snap_0");
+    mint_to_owner(&e, &client, &owner, &asset, "snap_1");
+
+    let first = client.scan_tokens(&0, &1);
+    assert_eq!(first.snapshot, client.get_snapshot_version());
+    let second = client.scan_tokens(&first.continuation, &1);
+    assert_eq!(second.snapshot, first.snapshot);
+
+    mint_to_owner(&e, &client, &owner, &asset, "snap_2");
+    let after = client.scan_tokens(&first.continuation, &1);
+    assert_ne!(after.snapshot, first.snapshot);
+}
+
+#[test]
+fn test_legacy_get_all_metadata_can_be_disabled() {
+    let e = Env::default();
+    let (admin, client, _core_id) = setup_contract_with_core(&e);
+    mint_to_owner(
+        &e,
+        &client,
+        &Address::generate(&e),
+        &Address::generate(&e),
+        "legacy",
+    );
+
+    client.set_legacy_metadata_enabled(&admin, &false);
+    assert_eq!(
+        client.try_get_all_metadata(),
+        Err(Ok(ContractError::LegacyCallDisabled))
+    );
+    assert_eq!(client.scan_tokens(&0, &10).items.len(), 1);
+
+    client.set_legacy_metadata_enabled(&admin, &true);
+    assert_eq!(client.get_all_metadata().len(), 1);
+}
+
 // ============================================
 // get_nfts_by_owner Tests
 // ============================================
@@ -1005,6 +1181,64 @@ fn test_get_nfts_by_owner() {
     }
 }
 
+#[test]
+fn test_get_nfts_by_owner_filtered() {
+    let e = Env::default();
+    let (_admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+    let first = mint_to_owner(&e, &client, &owner, &asset_address, "filter_1");
+    let second = mint_to_owner(&e, &client, &owner, &asset_address, "filter_2");
+    let third = mint_to_owner(&e, &client, &owner, &asset_address, "filter_3");
+
+    e.ledger().with_mut(|li| li.timestamp = 172_800);
+    client.settle(&second);
+
+    let active = client.get_nfts_by_owner_filtered(&owner, &true, &0, &10);
+    assert_eq!(active.len(), 2);
+    assert_eq!(active.get(0).unwrap().token_id, first);
+    assert_eq!(active.get(1).unwrap().token_id, third);
+
+    let history = client.get_nfts_by_owner_filtered(&owner, &false, &0, &10);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.get(0).unwrap().token_id, second);
+
+    // Offset and limit apply to the filtered list
+    let page = client.get_nfts_by_owner_filtered(&owner, &true, &1, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().token_id, third);
+    assert_eq!(
+        client
+            .get_nfts_by_owner_filtered(&owner, &true, &2, &10)
+            .len(),
+        0
+    );
+}
+
+#[test]
+fn test_token_ids_of_owner() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    assert_eq!(client.token_ids_of_owner(&owner).len(), 0);
+
+    let first = mint_to_owner(&e, &client, &owner, &asset_address, "ids_1");
+    let second = mint_to_owner(&e, &client, &owner, &asset_address, "ids_2");
+    let other = mint_to_owner(&e, &client, &admin, &asset_address, "ids_3");
+    assert_eq!(client.token_ids_of_owner(&owner), vec![&e, first, second]);
+    assert_eq!(client.token_ids_of_owner(&admin), vec![&e, other]);
+
+    e.ledger().with_mut(|li| li.timestamp = 172_800);
+    client.settle(&first);
+    client.transfer(&owner, &recipient, &first);
+    assert_eq!(client.token_ids_of_owner(&owner), vec![&e, second]);
+    assert_eq!(client.token_ids_of_owner(&recipient), vec![&e, first]);
+}
+
 // ============================================
 // Transfer Tests
 // ============================================
@@ -1076,12 +1310,13 @@ fn test_transfer() {
         vec![
             &e,
             symbol_short!("Transfer").into_val(&e),
-            owner1.into_val(&e),
-            owner2.into_val(&e)
+            EVENT_VERSION.into_val(&e),
+            token_id.into_val(&e)
         ]
     );
-    let data: (u32, u64) = last_event.2.into_val(&e);
-    assert_eq!(data.0, token_id);
+    let data: TransferEvent = last_event.2.into_val(&e);
+    assert_eq!(data.from, owner1);
+    assert_eq!(data.to, owner2);
 }
 
 #[test]
@@ -1604,11 +1839,13 @@ fn test_settle() {
         vec![
             &e,
             symbol_short!("Settle").into_val(&e),
+            EVENT_VERSION.into_val(&e),
             token_id.into_val(&e)
         ]
     );
-    let data: u64 = last_event.2.into_val(&e);
-    assert_eq!(data, e.ledger().timestamp());
+    let data: SettleEvent = last_event.2.into_val(&e);
+    assert_eq!(data.timestamp, e.ledger().timestamp());
+    assert_eq!(data.settled_by, None);
 }
 
 /// Mint with duration that would cause expires_at to overflow u64 (Issue #118).
@@ -2036,11 +2273,14 @@ fn test_mint_duration_days_one() {
 #[test]
 fn test_mint_duration_days_max() {
     let e = Env::default();
+    e.mock_all_auths();
     let (admin, client) = setup_contract(&e);
     let owner = Address::generate(&e);
     let asset_address = Address::generate(&e);
 
     client.initialize(&admin);
+    // Lift the configurable cap so the expiry overflow path is exercised
+    client.set_max_duration_days(&admin, &u32::MAX);
 
     let token_id = client.mint(
         &owner,
@@ -2185,7 +2425,7 @@ fn test_mint_blocked_when_paused() {
     let asset_address = Address::generate(&e);
 
     client.initialize(&admin);
-    client.pause();
+    client.pause(&admin);
 
     client.mint(
         &owner,
@@ -2223,7 +2463,7 @@ fn test_transfer_blocked_when_paused() {
         &5,
     );
 
-    client.pause();
+    client.pause(&admin);
     client.transfer(&owner1, &owner2, &token_id);
 }
 
@@ -2232,7 +2472,7 @@ fn _test_unpause_restores_transfer() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_admin, client, core_id) = setup_contract_with_core(&e);
+    let (admin, client, _core_id) = setup_contract_with_core(&e);
     let owner1 = Address::generate(&e);
     let owner2 = Address::generate(&e);
     let asset_address = Address::generate(&e);
@@ -2252,8 +2492,8 @@ fn _test_unpause_restores_transfer() {
     });
     client.settle(&token_id);
 
-    client.pause();
-    client.unpause();
+    client.pause(&admin);
+    client.unpause(&admin);
 
     // NFT is still active after unpause; settle it first to make it transferable.
     e.ledger().with_mut(|li| {
@@ -2758,3 +2998,1588 @@ fn test_owner_multiple_nfts_settle_one() {
     let nft3 = client.try_get_metadata(&token3).unwrap().unwrap();
     assert_eq!(nft3.is_active, true);
 }
+
+// ============================================
+// Permissionless settle_expired Tests
+// ============================================
+
+#[test]
+fn test_settle_expired_by_keeper() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let keeper = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+    client.initialize(&admin);
+
+    let token_id = mint_to_owner(&e, &client, &owner, &asset_address, "keeper_settle");
+    e.ledger().with_mut(|li| li.timestamp = 172800);
+
+    client.settle_expired(&keeper, &token_id);
+    assert!(!client.is_active(&token_id));
+
+    // Settle event records the keeper that performed the settlement
+    let events = e.events().all();
+    let last_event = events.last().unwrap();
+    assert_eq!(
+        last_event.1,
+        vec![
+            &e,
+            symbol_short!("Settle").into_val(&e),
+            EVENT_VERSION.into_val(&e),
+            token_id.into_val(&e)
+        ]
+    );
+    let data: SettleEvent = last_event.2.into_val(&e);
+    assert_eq!(data.timestamp, 172800);
+    assert_eq!(data.settled_by, Some(keeper));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")] // NotExpired
+fn test_settle_expired_before_expiry_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let keeper = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+    client.initialize(&admin);
+
+    let token_id = mint_to_owner(&e, &client, &owner, &asset_address, "keeper_early");
+    client.settle_expired(&keeper, &token_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")] // AlreadySettled
+fn test_settle_expired_already_settled_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let keeper = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+    client.initialize(&admin);
+
+    let token_id = mint_to_owner(&e, &client, &owner, &asset_address, "keeper_twice");
+    e.ledger().with_mut(|li| li.timestamp = 172800);
+
+    client.settle(&token_id);
+    client.settle_expired(&keeper, &token_id);
+}
+
+// ============================================================================
+// Expired Unsettled Token Query Tests
+// ============================================================================
+
+#[test]
+fn test_get_expired_unsettled_tokens() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+    client.initialize(&admin);
+
+    let t0 = mint_to_owner(&e, &client, &owner, &asset_address, "expired_0");
+    let t1 = mint_to_owner(&e, &client, &owner, &asset_address, "expired_1");
+    let t2 = mint_to_owner(&e, &client, &owner, &asset_address, "expired_2");
+
+    // Nothing has expired yet
+    assert_eq!(client.get_expired_unsettled_tokens(&0, &10).len(), 0);
+
+    e.ledger().with_mut(|li| li.timestamp = 172800);
+    client.settle(&t1);
+
+    let expired = client.get_expired_unsettled_tokens(&0, &10);
+    assert_eq!(expired, vec![&e, t0, t2]);
+
+    // Pagination
+    assert_eq!(client.get_expired_unsettled_tokens(&0, &1), vec![&e, t0]);
+    assert_eq!(client.get_expired_unsettled_tokens(&1, &1), vec![&e, t2]);
+    assert_eq!(client.get_expired_unsettled_tokens(&2, &1).len(), 0);
+}
+
+#[test]
+fn test_get_tokens_expiring_within() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+    client.initialize(&admin);
+
+    // Expires at 86400
+    let t0 = mint_to_owner(&e, &client, &owner, &asset_address, "expiring_0");
+    e.ledger().with_mut(|li| li.timestamp = 50000);
+    // Expires at 136400
+    let t1 = mint_to_owner(&e, &client, &owner, &asset_address, "expiring_1");
+
+    assert_eq!(
+        client.get_tokens_expiring_within(&40000, &0, &10),
+        vec![&e, t0]
+    );
+    assert_eq!(
+        client.get_tokens_expiring_within(&100000, &0, &10),
+        vec![&e, t0, t1]
+    );
+    assert_eq!(
+        client.get_tokens_expiring_within(&100000, &1, &10),
+        vec![&e, t1]
+    );
+    assert_eq!(
+        client.get_tokens_expiring_within(&100000, &0, &1),
+        vec![&e, t0]
+    );
+
+    // Already-expired tokens are no longer "expiring"
+    e.ledger().with_mut(|li| li.timestamp = 86400);
+    assert_eq!(
+        client.get_tokens_expiring_within(&100000, &0, &10),
+        vec![&e, t1]
+    );
+}
+
+// ============================================================================
+// Batch Settle Tests
+// ============================================================================
+
+#[test]
+fn test_batch_settle_all_expired() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+    client.initialize(&admin);
+
+    let t0 = mint_to_owner(&e, &client, &owner, &asset_address, "batch_0");
+    let t1 = mint_to_owner(&e, &client, &owner, &asset_address, "batch_1");
+    e.ledger().with_mut(|li| li.timestamp = 172800);
+
+    let result = client.batch_settle(&vec![&e, t0, t1]);
+    assert!(result.success);
+    assert_eq!(result.success_count, 2);
+    assert_eq!(result.errors.len(), 0);
+    assert!(!client.is_active(&t0));
+    assert!(!client.is_active(&t1));
+}
+
+#[test]
+fn test_batch_settle_skips_and_reports_failures() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+    client.initialize(&admin);
+
+    let settled = mint_to_owner(&e, &client, &owner, &asset_address, "batch_settled");
+    let expired = mint_to_owner(&e, &client, &owner, &asset_address, "batch_expired");
+    e.ledger().with_mut(|li| li.timestamp = 172800);
+    client.settle(&settled);
+
+    // Minted after the time jump, so not yet expired
+    let fresh = mint_to_owner(&e, &client, &owner, &asset_address, "batch_fresh");
+
+    let result = client.batch_settle(&vec![&e, settled, expired, fresh, 999]);
+    assert!(!result.success);
+    assert_eq!(result.success_count, 1);
+    assert_eq!(result.errors.len(), 3);
+
+    let e0 = result.errors.get(0).unwrap();
+    assert_eq!(e0.index, 0);
+    assert_eq!(e0.error_code, ContractError::AlreadySettled as u32);
+    let e1 = result.errors.get(1).unwrap();
+    assert_eq!(e1.index, 2);
+    assert_eq!(e1.error_code, ContractError::NotExpired as u32);
+    let e2 = result.errors.get(2).unwrap();
+    assert_eq!(e2.index, 3);
+    assert_eq!(e2.error_code, ContractError::TokenNotFound as u32);
+
+    assert!(!client.is_active(&expired));
+    assert!(client.is_active(&fresh));
+}
+
+// ============================================================================
+// Consistency Repair Tests
+// ============================================================================
+
+#[contract]
+struct MockCoreContract;
+
+#[contractimpl]
+impl MockCoreContract {
+    pub fn sync_owner(e: Env, _commitment_id: String, new_owner: Address) {
+        e.storage()
+            .instance()
+            .set(&symbol_short!("owner"), &new_owner);
+    }
+
+    pub fn synced_owner(e: Env) -> Option<Address> {
+        e.storage().instance().get(&symbol_short!("owner"))
+    }
+
+    pub fn set_status(e: Env, status: String) {
+        e.storage()
+            .instance()
+            .set(&symbol_short!("status"), &status);
+    }
+
+    pub fn get_commitment(e: Env, commitment_id: String) -> Commitment {
+        let status: String = e
+            .storage()
+            .instance()
+            .get(&symbol_short!("status"))
+            .unwrap();
+        Commitment {
+            commitment_id,
+            owner: e.current_contract_address(),
+            nft_token_id: 0,
+            rules: CommitmentRules {
+                duration_days: 1,
+                max_loss_percent: 10,
+                commitment_type: String::from_str(&e, "balanced"),
+                early_exit_penalty: 5,
+                min_fee_threshold: 0,
+                grace_period_days: 0,
+            },
+            amount: 1000,
+            asset_address: e.current_contract_address(),
+            created_at: 0,
+            expires_at: 86400,
+            current_value: 1000,
+            status,
+        }
+    }
+}
+
+#[test]
+fn test_transfer_syncs_core_owner() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    let core_id = e.register_contract(None, MockCoreContract);
+    let core = MockCoreContractClient::new(&e, &core_id);
+    client.set_core_contract(&core_id);
+
+    let owner = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+    let token_id = mint_to_owner(&e, &client, &owner, &asset_address, "sync_1");
+    client.mark_inactive(&token_id);
+
+    client.transfer(&owner, &buyer, &token_id);
+    assert_eq!(core.synced_owner(), Some(buyer));
+}
+
+#[test]
+fn test_clawback_syncs_core_owner() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    let core_id = e.register_contract(None, MockCoreContract);
+    let core = MockCoreContractClient::new(&e, &core_id);
+    client.set_core_contract(&core_id);
+
+    let owner = Address::generate(&e);
+    let recovery = Address::generate(&e);
+    let token_id = mint_to_owner(&e, &client, &owner, &Address::generate(&e), "sync_2");
+    client.mark_inactive(&token_id);
+
+    client.clawback(
+        &admin,
+        &token_id,
+        &recovery,
+        &String::from_str(&e, "court order"),
+    );
+    assert_eq!(core.synced_owner(), Some(recovery));
+}
+
+#[test]
+fn test_resync_nft_status_follows_core() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    let core_id = e.register_contract(None, MockCoreContract);
+    let core = MockCoreContractClient::new(&e, &core_id);
+    client.set_core_contract(&core_id);
+
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+    let token_id = mint_to_owner(&e, &client, &owner, &asset_address, "repair_1");
+
+    // Core settled but the NFT is still active
+    core.set_status(&String::from_str(&e, "settled"));
+    client.resync_nft_status(&admin, &token_id);
+    assert!(!client.is_active(&token_id));
+
+    let events = e.events().all();
+    let last_event = events.last().unwrap();
+    assert_eq!(
+        last_event.1,
+        vec![
+            &e,
+            symbol_short!("Repair").into_val(&e),
+            EVENT_VERSION.into_val(&e),
+            token_id.into_val(&e)
+        ]
+    );
+    let data: StatusChangeEvent = last_event.2.into_val(&e);
+    assert_eq!((data.was_active, data.is_active), (true, false));
+
+    // Core reports active again
+    core.set_status(&String::from_str(&e, "active"));
+    client.resync_nft_status(&admin, &token_id);
+    assert!(client.is_active(&token_id));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")] // NotAuthorized
+fn test_resync_nft_status_requires_admin() {
+    let e = Env::default();
+    let (_admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+    let token_id = mint_to_owner(&e, &client, &owner, &asset_address, "repair_2");
+
+    client.resync_nft_status(&owner, &token_id);
+}
+
+// ============================================================================
+// Transfer History Tests
+// ============================================================================
+
+#[test]
+fn test_get_transfer_history() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    let owner1 = Address::generate(&e);
+    let owner2 = Address::generate(&e);
+    let owner3 = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    let token_id = mint_to_owner(&e, &client, &owner1, &asset_address, "history_1");
+    assert_eq!(client.get_transfer_history(&token_id, &0, &10).len(), 0);
+
+    e.ledger().with_mut(|li| li.timestamp = 172800);
+    client.settle(&token_id);
+    client.transfer(&owner1, &owner2, &token_id);
+    e.ledger().with_mut(|li| li.timestamp = 172900);
+    client.transfer(&owner2, &owner3, &token_id);
+
+    let history = client.get_transfer_history(&token_id, &0, &10);
+    assert_eq!(history.len(), 2);
+    assert_eq!(
+        history.get(0).unwrap(),
+        TransferRecord {
+            from: owner1,
+            to: owner2.clone(),
+            timestamp: 172800,
+        }
+    );
+    assert_eq!(
+        history.get(1).unwrap(),
+        TransferRecord {
+            from: owner2,
+            to: owner3,
+            timestamp: 172900,
+        }
+    );
+
+    // Pagination
+    let page = client.get_transfer_history(&token_id, &1, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().timestamp, 172900);
+    assert_eq!(client.get_transfer_history(&token_id, &5, &1).len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")] // TokenNotFound
+fn test_get_transfer_history_unknown_token() {
+    let e = Env::default();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+
+    client.get_transfer_history(&999, &0, &10);
+}
+
+// ============================================================================
+// Storage TTL Tests
+// ============================================================================
+
+#[test]
+fn test_extend_ttl_bumps_token_storage() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    let token_id = mint_to_owner(&e, &client, &owner, &asset_address, "ttl_1");
+    assert_eq!(client.get_ttl_remaining(&token_id), TTL_EXTEND_TO);
+
+    // Time passes: the tracked TTL shrinks
+    client.extend_instance_ttl();
+    let start = e.ledger().sequence();
+    e.ledger()
+        .with_mut(|li| li.sequence_number = start + 2 * 17_280);
+    assert_eq!(
+        client.get_ttl_remaining(&token_id),
+        TTL_EXTEND_TO - 2 * 17_280
+    );
+
+    client.extend_ttl(&vec![&e, token_id]);
+    assert_eq!(client.get_ttl_remaining(&token_id), TTL_EXTEND_TO);
+
+    let live_ttl = e.as_contract(&client.address, || {
+        e.storage().persistent().get_ttl(&DataKey::NFT(token_id))
+    });
+    assert_eq!(live_ttl, TTL_EXTEND_TO);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")] // TokenNotFound
+fn test_extend_ttl_unknown_token() {
+    let e = Env::default();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+
+    client.extend_ttl(&vec![&e, 42u32]);
+}
+
+// ============================================================================
+// Pauser Role Tests
+// ============================================================================
+
+#[test]
+fn test_pauser_can_pause_and_unpause() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    let pauser = Address::generate(&e);
+
+    assert!(!client.is_pauser(&pauser));
+    client.add_pauser(&admin, &pauser);
+    assert!(client.is_pauser(&pauser));
+
+    e.ledger().with_mut(|li| li.timestamp = 500);
+    client.pause(&pauser);
+    assert!(client.is_paused());
+    // Pause events name the pauser and the time
+    let data: (Address, u64) = e.events().all().last().unwrap().2.into_val(&e);
+    assert_eq!(data, (pauser.clone(), 500));
+    client.unpause(&pauser);
+    assert!(!client.is_paused());
+
+    client.remove_pauser(&admin, &pauser);
+    assert!(!client.is_pauser(&pauser));
+    assert_eq!(
+        client.try_pause(&pauser),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")] // NotAuthorized
+fn test_pauser_cannot_grant_roles() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    let pauser = Address::generate(&e);
+    client.add_pauser(&admin, &pauser);
+
+    client.add_pauser(&pauser, &Address::generate(&e));
+}
+
+// ============================================================================
+// Performance Grade Tests
+// ============================================================================
+
+#[test]
+fn test_settle_with_performance_grades_tokens() {
+    let e = Env::default();
+    let (_admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    // mint_to_owner: initial_amount 1000, max_loss 10%
+    let gain = mint_to_owner(&e, &client, &owner, &asset_address, "grade_a");
+    let small_loss = mint_to_owner(&e, &client, &owner, &asset_address, "grade_b");
+    let near_limit = mint_to_owner(&e, &client, &owner, &asset_address, "grade_d");
+    let violated = mint_to_owner(&e, &client, &owner, &asset_address, "grade_f");
+    let with_fees = mint_to_owner(&e, &client, &owner, &asset_address, "grade_fees");
+    e.ledger().with_mut(|li| li.timestamp = 172800);
+
+    client.settle_with_performance(&gain, &1_100, &0);
+    client.settle_with_performance(&small_loss, &980, &0);
+    client.settle_with_performance(&near_limit, &920, &0);
+    client.settle_with_performance(&violated, &850, &0);
+    client.settle_with_performance(&with_fees, &950, &60);
+
+    let grade = |token_id: u32| client.get_performance(&token_id).unwrap().grade;
+    assert_eq!(grade(gain), PerformanceGrade::A);
+    assert_eq!(grade(small_loss), PerformanceGrade::B);
+    assert_eq!(grade(near_limit), PerformanceGrade::D);
+    assert_eq!(grade(violated), PerformanceGrade::F);
+    assert_eq!(grade(with_fees), PerformanceGrade::A);
+
+    assert!(!client.is_active(&gain));
+    assert_eq!(
+        client.get_tokens_by_grade(&PerformanceGrade::A),
+        vec![&e, gain, with_fees]
+    );
+    assert_eq!(client.get_tokens_by_grade(&PerformanceGrade::C).len(), 0);
+}
+
+#[test]
+fn test_plain_settle_has_no_grade() {
+    let e = Env::default();
+    let (_admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    let token_id = mint_to_owner(&e, &client, &owner, &asset_address, "ungraded");
+    e.ledger().with_mut(|li| li.timestamp = 172800);
+    client.settle(&token_id);
+
+    assert_eq!(client.get_performance(&token_id), None);
+}
+
+#[test]
+fn test_get_settled_holdings_counts_graded_tokens() {
+    let e = Env::default();
+    let (_admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    let settled = mint_to_owner(&e, &client, &owner, &asset_address, "held_1");
+    let plain = mint_to_owner(&e, &client, &owner, &asset_address, "held_2");
+    mint_to_owner(&e, &client, &owner, &asset_address, "held_3");
+    assert_eq!(client.get_settled_holdings(&owner), (0, 0));
+
+    e.ledger().with_mut(|li| li.timestamp = 172800);
+    client.settle_with_performance(&settled, &1_050, &0);
+    client.settle(&plain);
+
+    assert_eq!(client.get_settled_holdings(&owner), (1, 1_000));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1)")] // NotInitialized
+fn test_settle_with_performance_requires_core_contract() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    let token_id = mint_to_owner(&e, &client, &owner, &asset_address, "no_core");
+    client.settle_with_performance(&token_id, &1_000, &0);
+}
+
+// ============================================================================
+// Max Duration Tests
+// ============================================================================
+
+#[test]
+fn test_mint_rejects_duration_above_default_max() {
+    let e = Env::default();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    assert_eq!(client.get_max_duration_days(), DEFAULT_MAX_DURATION_DAYS);
+    let result = client.try_mint(
+        &owner,
+        &String::from_str(&e, "too_long"),
+        &(DEFAULT_MAX_DURATION_DAYS + 1),
+        &10,
+        &String::from_str(&e, "balanced"),
+        &1000,
+        &asset_address,
+        &5,
+    );
+    assert_eq!(result, Err(Ok(ContractError::InvalidDuration)));
+
+    let token_id = client.mint(
+        &owner,
+        &String::from_str(&e, "at_limit"),
+        &DEFAULT_MAX_DURATION_DAYS,
+        &10,
+        &String::from_str(&e, "balanced"),
+        &1000,
+        &asset_address,
+        &5,
+    );
+    assert_eq!(
+        client.get_metadata(&token_id).metadata.duration_days,
+        DEFAULT_MAX_DURATION_DAYS
+    );
+}
+
+#[test]
+fn test_set_max_duration_days() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    client.set_max_duration_days(&admin, &30);
+    assert_eq!(client.get_max_duration_days(), 30);
+
+    let events = e.events().all();
+    let data: (u32, u32, Address, u64) = events.last().unwrap().2.into_val(&e);
+    assert_eq!(data, (DEFAULT_MAX_DURATION_DAYS, 30, admin.clone(), 0));
+
+    let result = client.try_mint(
+        &owner,
+        &String::from_str(&e, "over_new_cap"),
+        &31,
+        &10,
+        &String::from_str(&e, "balanced"),
+        &1000,
+        &asset_address,
+        &5,
+    );
+    assert_eq!(result, Err(Ok(ContractError::InvalidDuration)));
+
+    assert_eq!(
+        client.try_set_max_duration_days(&admin, &0),
+        Err(Ok(ContractError::InvalidDuration))
+    );
+    assert_eq!(
+        client.try_set_max_duration_days(&owner, &60),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+}
+
+#[test]
+fn test_extend_expiry() {
+    let e = Env::default();
+    let (_admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let token_id = mint_to_owner(&e, &client, &owner, &Address::generate(&e), "extend_1");
+
+    client.extend_expiry(&token_id, &2);
+    let metadata = client.get_metadata(&token_id).metadata;
+    assert_eq!(metadata.duration_days, 3);
+    assert_eq!(metadata.expires_at, 3 * 86400);
+
+    let event = e.events().all().last().unwrap();
+    let data: ExtendedEvent = event.2.into_val(&e);
+    assert_eq!(data.expires_at, 3 * 86400);
+
+    assert_eq!(
+        client.try_extend_expiry(&token_id, &0),
+        Err(Ok(ContractError::InvalidDuration))
+    );
+    assert_eq!(
+        client.try_extend_expiry(&token_id, &DEFAULT_MAX_DURATION_DAYS),
+        Err(Ok(ContractError::InvalidDuration))
+    );
+
+    client.mark_inactive(&token_id);
+    assert_eq!(
+        client.try_extend_expiry(&token_id, &1),
+        Err(Ok(ContractError::AlreadySettled))
+    );
+
+    let other = mint_to_owner(&e, &client, &owner, &Address::generate(&e), "extend_2");
+    e.set_auths(&[]);
+    assert!(client.try_extend_expiry(&other, &1).is_err());
+}
+
+#[test]
+fn test_top_up_increases_initial_amount() {
+    let e = Env::default();
+    let (_admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let token_id = mint_to_owner(&e, &client, &owner, &Address::generate(&e), "top_up_1");
+
+    client.top_up(&token_id, &500);
+    assert_eq!(client.get_metadata(&token_id).metadata.initial_amount, 1500);
+    assert_eq!(client.get_stats().total_initial_value, 1500);
+    assert_eq!(
+        client.try_top_up(&token_id, &0),
+        Err(Ok(ContractError::InvalidAmount))
+    );
+
+    client.mark_inactive(&token_id);
+    assert_eq!(
+        client.try_top_up(&token_id, &500),
+        Err(Ok(ContractError::AlreadySettled))
+    );
+}
+
+// ============================================================================
+// Staking Lock Tests
+// ============================================================================
+
+/// Mint a 1-day token, settle it, and approve `program` with a 7-day lock cap.
+fn setup_staked_token(
+    e: &Env,
+) -> (
+    Address,
+    CommitmentNFTContractClient<'_>,
+    Address,
+    Address,
+    u32,
+) {
+    let (admin, client, _core_id) = setup_contract_with_core(e);
+    let owner = Address::generate(e);
+    let program = Address::generate(e);
+    let token_id = mint_to_owner(e, &client, &owner, &Address::generate(e), "stake_1");
+
+    e.ledger().with_mut(|li| li.timestamp = 172_800);
+    client.settle(&token_id);
+    client.set_staking_program(&admin, &program, &(7 * 86_400));
+    (admin, client, owner, program, token_id)
+}
+
+#[test]
+fn test_stake_lock_blocks_transfer_until_unstaked() {
+    let e = Env::default();
+    let (_admin, client, owner, program, token_id) = setup_staked_token(&e);
+    let recipient = Address::generate(&e);
+    let until = 172_800 + 86_400;
+
+    client.stake_lock(&token_id, &program, &until);
+    let lock = client.get_stake_lock(&token_id).unwrap();
+    assert_eq!(lock.program_id, program);
+    assert_eq!(lock.until, until);
+    assert_eq!(client.owner_of(&token_id), owner);
+
+    assert_eq!(
+        client.try_transfer(&owner, &recipient, &token_id),
+        Err(Ok(ContractError::TokenStaked))
+    );
+    assert_eq!(
+        client.try_unstake(&owner, &token_id),
+        Err(Ok(ContractError::NotExpired))
+    );
+
+    e.ledger().with_mut(|li| li.timestamp = until);
+    client.unstake(&owner, &token_id);
+    assert_eq!(client.get_stake_lock(&token_id), None);
+
+    client.transfer(&owner, &recipient, &token_id);
+    assert_eq!(client.owner_of(&token_id), recipient);
+}
+
+#[test]
+fn test_program_can_release_stake_lock_early() {
+    let e = Env::default();
+    let (_admin, client, owner, program, token_id) = setup_staked_token(&e);
+
+    client.stake_lock(&token_id, &program, &(172_800 + 86_400));
+    assert_eq!(
+        client.try_unstake(&Address::generate(&e), &token_id),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+    client.unstake(&program, &token_id);
+    assert_eq!(client.get_stake_lock(&token_id), None);
+    assert_eq!(
+        client.try_unstake(&owner, &token_id),
+        Err(Ok(ContractError::NotStaked))
+    );
+}
+
+#[test]
+fn test_stake_lock_enforces_program_approval_and_cap() {
+    let e = Env::default();
+    let (admin, client, _owner, program, token_id) = setup_staked_token(&e);
+    let now = 172_800;
+
+    assert_eq!(
+        client.try_stake_lock(&token_id, &Address::generate(&e), &(now + 100)),
+        Err(Ok(ContractError::StakingProgramNotApproved))
+    );
+    assert_eq!(
+        client.try_stake_lock(&token_id, &program, &(now + 7 * 86_400 + 1)),
+        Err(Ok(ContractError::LockCapExceeded))
+    );
+    assert_eq!(
+        client.try_stake_lock(&token_id, &program, &now),
+        Err(Ok(ContractError::InvalidDuration))
+    );
+
+    client.stake_lock(&token_id, &program, &(now + 7 * 86_400));
+    assert_eq!(
+        client.try_stake_lock(&token_id, &program, &(now + 100)),
+        Err(Ok(ContractError::TokenStaked))
+    );
+
+    client.set_staking_program(&admin, &program, &0);
+    assert_eq!(client.get_staking_program(&program), None);
+}
+
+#[test]
+fn test_stake_lock_rejects_active_token() {
+    let e = Env::default();
+    let (admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let program = Address::generate(&e);
+    let token_id = mint_to_owner(&e, &client, &owner, &Address::generate(&e), "active_1");
+    client.set_staking_program(&admin, &program, &86_400);
+
+    assert_eq!(
+        client.try_stake_lock(&token_id, &program, &100),
+        Err(Ok(ContractError::NFTLocked))
+    );
+}
+
+// ============================================================================
+// Storage Audit Tests
+// ============================================================================
+
+#[test]
+fn test_audit_instance_entries() {
+    let e = Env::default();
+    let (admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    mint_to_owner(&e, &client, &owner, &Address::generate(&e), "audit_1");
+    client.extend_instance_ttl();
+
+    let entries = client.audit_instance_entries(&admin);
+    let mut keys: Vec<DataKey> = Vec::new(&e);
+    for entry in entries.iter() {
+        keys.push_back(entry.key);
+    }
+    assert!(keys.contains(&DataKey::Admin));
+    assert!(keys.contains(&DataKey::TokenCounter));
+    assert!(keys.contains(&DataKey::CoreContract));
+    for entry in entries.iter() {
+        assert_eq!(entry.storage, StorageClass::Instance);
+        assert_eq!(entry.ttl_remaining, TTL_EXTEND_TO);
+    }
+}
+
+#[test]
+fn test_audit_token_entries_paginated() {
+    let e = Env::default();
+    let (admin, client, _core_id) = setup_contract_with_core(&e);
+    let auditor = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+    let first = mint_to_owner(&e, &client, &owner, &asset_address, "audit_1");
+    let second = mint_to_owner(&e, &client, &owner, &asset_address, "audit_2");
+
+    e.ledger().with_mut(|li| li.timestamp = 172_800);
+    client.settle(&second);
+    client.transfer(&owner, &admin, &second);
+
+    client.add_auditor(&admin, &auditor);
+    assert!(client.is_auditor(&auditor));
+
+    let entries = client.audit_token_entries(&auditor, &0, &1);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries.get(0).unwrap().key, DataKey::NFT(first));
+    assert_eq!(entries.get(1).unwrap().key, DataKey::TokenLiveUntil(first));
+
+    let entries = client.audit_token_entries(&auditor, &1, &10);
+    assert_eq!(entries.len(), 3);
+    assert_eq!(
+        entries.get(1).unwrap().key,
+        DataKey::TransferHistory(second)
+    );
+    assert_eq!(
+        entries.get(1).unwrap().entry_type,
+        Symbol::new(&e, "Vec_TransferRecord")
+    );
+    for entry in entries.iter() {
+        assert_eq!(entry.storage, StorageClass::Persistent);
+        assert_eq!(entry.ttl_remaining, TTL_EXTEND_TO);
+    }
+}
+
+#[test]
+fn test_audit_views_require_admin_or_auditor() {
+    let e = Env::default();
+    let (admin, client, _core_id) = setup_contract_with_core(&e);
+    let auditor = Address::generate(&e);
+
+    assert_eq!(
+        client.try_audit_instance_entries(&auditor),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+    client.add_auditor(&admin, &auditor);
+    client.remove_auditor(&admin, &auditor);
+    assert_eq!(
+        client.try_audit_token_entries(&auditor, &0, &10),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+}
+
+// ============================================================================
+// Approval Tests
+// ============================================================================
+
+/// Mint a 1-day token to `owner` and settle it so it can be transferred.
+fn setup_transferable_token(e: &Env) -> (CommitmentNFTContractClient<'_>, Address, u32) {
+    let (_admin, client, _core_id) = setup_contract_with_core(e);
+    let owner = Address::generate(e);
+    let token_id = mint_to_owner(e, &client, &owner, &Address::generate(e), "approve_1");
+    e.ledger().with_mut(|li| li.timestamp = 172_800);
+    client.settle(&token_id);
+    (client, owner, token_id)
+}
+
+#[test]
+fn test_transfer_from_with_approval() {
+    let e = Env::default();
+    let (client, owner, token_id) = setup_transferable_token(&e);
+    let marketplace = Address::generate(&e);
+    let buyer = Address::generate(&e);
+
+    client.approve(&owner, &marketplace, &token_id, &100);
+    assert_eq!(client.get_approved(&token_id), Some(marketplace.clone()));
+
+    client.transfer_from(&marketplace, &owner, &buyer, &token_id);
+    assert_eq!(client.owner_of(&token_id), buyer);
+    // Approval does not survive a change of owner
+    assert_eq!(client.get_approved(&token_id), None);
+}
+
+#[test]
+fn test_transfer_from_rejects_expired_approval() {
+    let e = Env::default();
+    let (client, owner, token_id) = setup_transferable_token(&e);
+    let marketplace = Address::generate(&e);
+    let buyer = Address::generate(&e);
+
+    e.ledger().with_mut(|li| li.sequence_number = 10);
+    client.approve(&owner, &marketplace, &token_id, &20);
+
+    e.ledger().with_mut(|li| li.sequence_number = 21);
+    assert_eq!(client.get_approved(&token_id), None);
+    assert_eq!(
+        client.try_transfer_from(&marketplace, &owner, &buyer, &token_id),
+        Err(Ok(ContractError::ApprovalExpired))
+    );
+    assert_eq!(
+        client.try_approve(&owner, &marketplace, &token_id, &20),
+        Err(Ok(ContractError::InvalidExpirationLedger))
+    );
+}
+
+#[test]
+fn test_transfer_from_requires_matching_spender() {
+    let e = Env::default();
+    let (client, owner, token_id) = setup_transferable_token(&e);
+    let marketplace = Address::generate(&e);
+    let other = Address::generate(&e);
+
+    assert_eq!(
+        client.try_transfer_from(&marketplace, &owner, &other, &token_id),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+    client.approve(&owner, &marketplace, &token_id, &100);
+    assert_eq!(
+        client.try_transfer_from(&other, &owner, &other, &token_id),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+    assert_eq!(
+        client.try_approve(&other, &other, &token_id, &100),
+        Err(Ok(ContractError::NotOwner))
+    );
+
+    // Revoke
+    client.approve(&owner, &marketplace, &token_id, &0);
+    assert_eq!(
+        client.try_transfer_from(&marketplace, &owner, &other, &token_id),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+}
+
+// ============================================================================
+// Delegation Tests
+// ============================================================================
+
+#[test]
+fn test_delegate_utility_rights() {
+    let e = Env::default();
+    let (client, owner, token_id) = setup_transferable_token(&e);
+    let voter = Address::generate(&e);
+
+    // Undelegated tokens report their owner
+    assert_eq!(client.get_delegate(&token_id), owner);
+
+    client.delegate(&token_id, &voter);
+    assert_eq!(client.get_delegate(&token_id), voter);
+    // Ownership is unchanged
+    assert_eq!(client.owner_of(&token_id), owner);
+
+    // Delegating back to the owner clears the delegation
+    client.delegate(&token_id, &owner);
+    assert_eq!(client.get_delegate(&token_id), owner);
+}
+
+#[test]
+fn test_delegation_cleared_on_transfer() {
+    let e = Env::default();
+    let (client, owner, token_id) = setup_transferable_token(&e);
+    let voter = Address::generate(&e);
+    let buyer = Address::generate(&e);
+
+    client.delegate(&token_id, &voter);
+    client.transfer(&owner, &buyer, &token_id);
+    assert_eq!(client.get_delegate(&token_id), buyer);
+
+    assert_eq!(
+        client.try_get_delegate(&999),
+        Err(Ok(ContractError::TokenNotFound))
+    );
+}
+
+// ============================================================================
+// Attribute Tests
+// ============================================================================
+
+#[test]
+fn test_set_and_remove_attributes() {
+    let e = Env::default();
+    let (_admin, client, core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let token_id = mint_to_owner(&e, &client, &owner, &Address::generate(&e), "attr_1");
+    let strategy = String::from_str(&e, "strategy_name");
+    let campaign = String::from_str(&e, "campaign");
+
+    assert_eq!(client.get_attributes(&token_id).len(), 0);
+
+    client.set_attribute(&owner, &token_id, &strategy, &String::from_str(&e, "delta"));
+    client.set_attribute(&core_id, &token_id, &campaign, &String::from_str(&e, "q3"));
+    // Overwriting an existing key
+    client.set_attribute(&owner, &token_id, &strategy, &String::from_str(&e, "gamma"));
+
+    let attributes = client.get_attributes(&token_id);
+    assert_eq!(attributes.len(), 2);
+    assert_eq!(
+        attributes.get(strategy.clone()),
+        Some(String::from_str(&e, "gamma"))
+    );
+
+    client.remove_attribute(&owner, &token_id, &strategy);
+    let attributes = client.get_attributes(&token_id);
+    assert_eq!(attributes.len(), 1);
+    assert_eq!(attributes.get(strategy), None);
+
+    let stranger = Address::generate(&e);
+    assert_eq!(
+        client.try_set_attribute(&stranger, &token_id, &campaign, &campaign),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+}
+
+#[test]
+fn test_attribute_validation() {
+    let e = Env::default();
+    let (_admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let token_id = mint_to_owner(&e, &client, &owner, &Address::generate(&e), "attr_2");
+    let value = String::from_str(&e, "v");
+
+    assert_eq!(
+        client.try_set_attribute(&owner, &token_id, &String::from_str(&e, ""), &value),
+        Err(Ok(ContractError::InvalidAttribute))
+    );
+    let long_key = String::from_str(&e, "k".repeat(33).as_str());
+    assert_eq!(
+        client.try_set_attribute(&owner, &token_id, &long_key, &value),
+        Err(Ok(ContractError::InvalidAttribute))
+    );
+
+    let keys = [
+        "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o", "p",
+    ];
+    for key in keys {
+        client.set_attribute(&owner, &token_id, &String::from_str(&e, key), &value);
+    }
+    assert_eq!(
+        client.try_set_attribute(&owner, &token_id, &String::from_str(&e, "q"), &value),
+        Err(Ok(ContractError::TooManyAttributes))
+    );
+    // Updating an existing key is still allowed at the cap
+    client.set_attribute(&owner, &token_id, &String::from_str(&e, "a"), &value);
+
+    assert_eq!(
+        client.try_get_attributes(&999),
+        Err(Ok(ContractError::TokenNotFound))
+    );
+}
+
+// ============================================================================
+// Sunset Tests
+// ============================================================================
+
+#[test]
+fn test_sunset_blocks_mint_but_not_settlement() {
+    let e = Env::default();
+    let (admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let asset = Address::generate(&e);
+    let token_id = mint_to_owner(&e, &client, &owner, &asset, "sunset_1");
+
+    assert_eq!(client.get_sunset_state(), SunsetState::Live);
+    client.begin_sunset(&admin, &500_000);
+    assert_eq!(client.get_sunset_state(), SunsetState::Sunsetting);
+
+    assert_eq!(
+        client.try_mint(
+            &owner,
+            &String::from_str(&e, "sunset_2"),
+            &1,
+            &10,
+            &String::from_str(&e, "balanced"),
+            &1000,
+            &asset,
+            &5,
+        ),
+        Err(Ok(ContractError::ProtocolSunset))
+    );
+
+    // Existing tokens still run to term
+    e.ledger().with_mut(|li| li.timestamp = 172_800);
+    client.settle(&token_id);
+    assert!(!client.is_active(&token_id));
+}
+
+// ============================================================================
+// Authorization Tests
+// ============================================================================
+
+#[test]
+fn test_mint_and_mark_inactive_require_core_auth() {
+    let e = Env::default();
+    let (_admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let token_id = mint_to_owner(&e, &client, &owner, &Address::generate(&e), "auth_1");
+
+    // Drop mocked signatures: nothing is authorized any more
+    e.set_auths(&[]);
+    assert!(client
+        .try_mint(
+            &owner,
+            &String::from_str(&e, "auth_2"),
+            &1,
+            &10,
+            &String::from_str(&e, "balanced"),
+            &1000,
+            &Address::generate(&e),
+            &5,
+        )
+        .is_err());
+    assert!(client.try_mark_inactive(&token_id).is_err());
+    assert!(client.is_active(&token_id));
+}
+
+#[test]
+fn test_transfer_and_approve_require_owner_signature() {
+    let e = Env::default();
+    let (client, owner, token_id) = setup_transferable_token(&e);
+    let buyer = Address::generate(&e);
+
+    e.set_auths(&[]);
+    assert!(client.try_transfer(&owner, &buyer, &token_id).is_err());
+    assert!(client.try_approve(&owner, &buyer, &token_id, &100).is_err());
+    assert!(client.try_delegate(&token_id, &buyer).is_err());
+    assert_eq!(client.owner_of(&token_id), owner);
+}
+
+// ============================================================================
+// Transferability Tests
+// ============================================================================
+
+#[test]
+fn test_is_transferable_reports_blocking_reason() {
+    let e = Env::default();
+    let (admin, client, owner, program, token_id) = setup_staked_token(&e);
+
+    assert_eq!(
+        client.is_transferable(&999),
+        (false, TransferBlockReason::TokenNotFound)
+    );
+    assert_eq!(
+        client.is_transferable(&token_id),
+        (true, TransferBlockReason::None)
+    );
+
+    client.stake_lock(&token_id, &program, &(172_800 + 86_400));
+    assert_eq!(
+        client.is_transferable(&token_id),
+        (false, TransferBlockReason::CollateralLocked)
+    );
+    client.unstake(&program, &token_id);
+
+    client.pause(&admin);
+    assert_eq!(
+        client.is_transferable(&token_id),
+        (false, TransferBlockReason::Paused)
+    );
+    client.unpause(&admin);
+
+    let active_id = mint_to_owner(&e, &client, &owner, &Address::generate(&e), "stake_2");
+    assert_eq!(
+        client.is_transferable(&active_id),
+        (false, TransferBlockReason::NotSettled)
+    );
+}
+
+// ============================================================================
+// Safe Transfer Tests
+// ============================================================================
+
+#[contract]
+struct NftReceiver;
+
+#[contractimpl]
+impl NftReceiver {
+    pub fn set_accept(e: Env, accept: bool) {
+        e.storage()
+            .instance()
+            .set(&symbol_short!("accept"), &accept);
+    }
+
+    pub fn on_nft_received(
+        e: Env,
+        _nft_contract: Address,
+        _from: Address,
+        token_id: u32,
+        _data: soroban_sdk::Bytes,
+    ) -> bool {
+        e.storage()
+            .instance()
+            .set(&symbol_short!("last"), &token_id);
+        e.storage()
+            .instance()
+            .get(&symbol_short!("accept"))
+            .unwrap_or(false)
+    }
+}
+
+#[test]
+fn test_safe_transfer_calls_receiver_hook() {
+    let e = Env::default();
+    let (client, owner, token_id) = setup_transferable_token(&e);
+    let receiver = e.register_contract(None, NftReceiver);
+    let receiver_client = NftReceiverClient::new(&e, &receiver);
+    let data = soroban_sdk::Bytes::new(&e);
+
+    // A rejecting receiver reverts the whole transfer
+    assert_eq!(
+        client.try_safe_transfer(&owner, &receiver, &token_id, &data),
+        Err(Ok(ContractError::ReceiverRejected))
+    );
+    assert_eq!(client.owner_of(&token_id), owner);
+
+    receiver_client.set_accept(&true);
+    client.safe_transfer(&owner, &receiver, &token_id, &data);
+    assert_eq!(client.owner_of(&token_id), receiver);
+}
+
+#[test]
+fn test_safe_transfer_to_contract_without_hook_reverts() {
+    let e = Env::default();
+    let (client, owner, token_id) = setup_transferable_token(&e);
+    // The NFT contract itself has no on_nft_received
+    let data = soroban_sdk::Bytes::new(&e);
+    assert_eq!(
+        client.try_safe_transfer(&owner, &client.address, &token_id, &data),
+        Err(Ok(ContractError::ReceiverRejected))
+    );
+
+    // Accounts need no hook
+    let account = Address::from_string(&String::from_str(
+        &e,
+        "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF",
+    ));
+    client.safe_transfer(&owner, &account, &token_id, &data);
+    assert_eq!(client.owner_of(&token_id), account);
+}
+
+// ============================================================================
+// Merge Tests
+// ============================================================================
+
+#[test]
+fn test_merge_settled_tokens_into_composite() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    let t0 = mint_to_owner(&e, &client, &owner, &asset_address, "merge_0");
+    let t1 = mint_to_owner(&e, &client, &owner, &asset_address, "merge_1");
+    let t2 = mint_to_owner(&e, &client, &owner, &asset_address, "merge_2");
+    e.ledger().with_mut(|li| li.timestamp = 172800);
+    client.settle(&t0);
+    client.settle(&t2);
+
+    let composite = client.merge(&vec![&e, t0, t2]);
+    assert_eq!(client.get_merged_from(&composite), vec![&e, t0, t2]);
+    let nft = client.get_metadata(&composite);
+    assert_eq!(nft.owner, owner);
+    assert!(!nft.is_active);
+    assert_eq!(nft.metadata.duration_days, 2);
+    assert_eq!(nft.metadata.initial_amount, 2000);
+    assert_eq!(
+        nft.metadata.commitment_type,
+        String::from_str(&e, COMPOSITE_COMMITMENT_TYPE)
+    );
+
+    assert_eq!(
+        client.try_get_metadata(&t0),
+        Err(Ok(ContractError::TokenNotFound))
+    );
+    assert_eq!(client.balance_of(&owner), 2);
+    assert_eq!(client.token_ids_of_owner(&owner), vec![&e, t1, composite]);
+}
+
+#[test]
+fn test_merge_rejects_active_duplicate_and_single_tokens() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    let t0 = mint_to_owner(&e, &client, &owner, &asset_address, "merge_0");
+    let t1 = mint_to_owner(&e, &client, &owner, &asset_address, "merge_1");
+    e.ledger().with_mut(|li| li.timestamp = 172800);
+    client.settle(&t0);
+
+    assert_eq!(
+        client.try_merge(&vec![&e, t0, t1]),
+        Err(Ok(ContractError::NFTLocked))
+    );
+    assert_eq!(
+        client.try_merge(&vec![&e, t0, t0]),
+        Err(Ok(ContractError::InvalidMerge))
+    );
+    assert_eq!(
+        client.try_merge(&vec![&e, t0]),
+        Err(Ok(ContractError::InvalidMerge))
+    );
+}
+
+// ============================================================================
+// Collection Stats Tests
+// ============================================================================
+
+#[test]
+fn test_stats_track_mint_settle_merge_and_transfer() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    let owner = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    let t0 = mint_to_owner(&e, &client, &owner, &asset_address, "stats_0");
+    let t1 = mint_to_owner(&e, &client, &owner, &asset_address, "stats_1");
+    mint_to_owner(&e, &client, &buyer, &asset_address, "stats_2");
+    let stats = client.get_stats();
+    assert_eq!(stats.active_count, 3);
+    assert_eq!(stats.total_initial_value, 3000);
+    assert_eq!(stats.unique_holders, 2);
+
+    e.ledger().with_mut(|li| li.timestamp = 172800);
+    client.settle(&t0);
+    client.settle(&t1);
+    let composite = client.merge(&vec![&e, t0, t1]);
+    let stats = client.get_stats();
+    assert_eq!(stats.active_count, 1);
+    assert_eq!(stats.settled_count, 1);
+    assert_eq!(stats.burned_count, 2);
+    assert_eq!(stats.total_initial_value, 3000);
+
+    client.transfer(&owner, &buyer, &composite);
+    assert_eq!(client.get_stats().unique_holders, 1);
+}
+
+// ============================================================================
+// Clawback Tests
+// ============================================================================
+
+#[test]
+fn test_clawback_moves_staked_token_and_records_reason() {
+    let e = Env::default();
+    let (admin, client, owner, program, token_id) = setup_staked_token(&e);
+    let recovery = Address::generate(&e);
+    let reason = String::from_str(&e, "compromised key recovery");
+
+    client.stake_lock(&token_id, &program, &(172_800 + 86_400));
+    client.clawback(&admin, &token_id, &recovery, &reason);
+
+    assert_eq!(client.owner_of(&token_id), recovery);
+    assert_eq!(client.balance_of(&owner), 0);
+    assert_eq!(client.balance_of(&recovery), 1);
+    assert_eq!(client.get_stake_lock(&token_id), None);
+
+    let clawbacks = client.get_clawbacks(&token_id);
+    assert_eq!(clawbacks.len(), 1);
+    let record = clawbacks.get(0).unwrap();
+    assert_eq!(record.from, owner);
+    assert_eq!(record.to, recovery);
+    assert_eq!(record.admin, admin);
+    assert_eq!(record.reason, reason);
+
+    let history = client.get_transfer_history(&token_id, &0, &10);
+    assert_eq!(history.len(), 1);
+}
+
+#[test]
+fn test_clawback_rejections() {
+    let e = Env::default();
+    let (admin, client, owner, _program, token_id) = setup_staked_token(&e);
+    let reason = String::from_str(&e, "court order");
+    let other = Address::generate(&e);
+
+    assert_eq!(
+        client.try_clawback(&owner, &token_id, &other, &reason),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+    assert_eq!(
+        client.try_clawback(&admin, &token_id, &other, &String::from_str(&e, "")),
+        Err(Ok(ContractError::InvalidClawbackReason))
+    );
+    assert_eq!(
+        client.try_clawback(&admin, &token_id, &owner, &reason),
+        Err(Ok(ContractError::TransferToZeroAddress))
+    );
+
+    let active_id = mint_to_owner(&e, &client, &owner, &Address::generate(&e), "claw_2");
+    assert_eq!(
+        client.try_clawback(&admin, &active_id, &other, &reason),
+        Err(Ok(ContractError::NFTLocked))
+    );
+    assert_eq!(client.get_clawbacks(&token_id).len(), 0);
+}
+
+// ============================================================================
+// Auth Context Tests
+// ============================================================================
+
+#[test]
+fn test_set_core_contract_auth_binds_to_core_address() {
+    use soroban_sdk::testutils::{MockAuth, MockAuthInvoke};
+
+    let e = Env::default();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    let core = Address::generate(&e);
+    let other = Address::generate(&e);
+
+    e.mock_auths(&[MockAuth {
+        address: &admin,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "set_core_contract",
+            args: (other,).into_val(&e),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(client.try_set_core_contract(&core).is_err());
+
+    e.mock_auths(&[MockAuth {
+        address: &admin,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "set_core_contract",
+            args: (core.clone(),).into_val(&e),
+            sub_invokes: &[],
+        },
+    }]);
+    client.set_core_contract(&core);
+    assert_eq!(client.get_core_contract(), core);
+}
+
+// ============================================================================
+// Minter Role Tests
+// ============================================================================
+
+fn mint_by_minter(
+    e: &Env,
+    client: &CommitmentNFTContractClient,
+    minter: &Address,
+    label: &str,
+) -> u32 {
+    client.mint_by(
+        minter,
+        &Address::generate(e),
+        &String::from_str(e, label),
+        &1,
+        &10,
+        &String::from_str(e, "balanced"),
+        &1000,
+        &Address::generate(e),
+        &5,
+    )
+}
+
+#[test]
+fn test_multiple_minters_share_collection() {
+    let e = Env::default();
+    let (admin, client, production_core) = setup_contract_with_core(&e);
+    let staging_core = Address::generate(&e);
+
+    client.add_minter(&admin, &staging_core);
+    client.add_minter(&admin, &staging_core);
+    assert_eq!(client.get_minters(), vec![&e, staging_core.clone()]);
+
+    let first = mint_by_minter(&e, &client, &production_core, "prod_1");
+    let second = mint_by_minter(&e, &client, &staging_core, "staging_1");
+    assert_eq!(client.total_supply(), 2);
+    assert_ne!(first, second);
+
+    client.remove_minter(&admin, &staging_core);
+    assert_eq!(client.get_minters().len(), 0);
+    let result = client.try_mint_by(
+        &staging_core,
+        &Address::generate(&e),
+        &String::from_str(&e, "staging_2"),
+        &1,
+        &10,
+        &String::from_str(&e, "balanced"),
+        &1000,
+        &Address::generate(&e),
+        &5,
+    );
+    assert_eq!(result, Err(Ok(ContractError::NotAuthorized)));
+}
+
+#[test]
+fn test_minter_management_is_admin_only_and_bounded() {
+    let e = Env::default();
+    let (admin, client, _core_id) = setup_contract_with_core(&e);
+    let outsider = Address::generate(&e);
+
+    assert_eq!(
+        client.try_add_minter(&outsider, &outsider),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+    for _ in 0..MAX_MINTERS {
+        client.add_minter(&admin, &Address::generate(&e));
+    }
+    assert_eq!(
+        client.try_add_minter(&admin, &outsider),
+        Err(Ok(ContractError::TooManyMinters))
+    );
+}
@@ -0,0 +1,171 @@
+#![no_std]
+
+//! Read Aggregator Contract
+//!
+//! Executes many read-only view calls against the protocol contracts
+//! (commitment_core, commitment_nft, attestation_engine, allocation_logic)
+//! in a single invocation so dashboards can render many commitments with
+//! one round-trip. Only (contract, function) pairs on the admin-maintained
+//! view allowlist can be called. A failing call does not abort the batch;
+//! its slot in the result reports the error instead.
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, IntoVal,
+    InvokeError, Symbol, Val, Vec,
+};
+
+/// Maximum number of calls accepted in a single multicall
+pub const MAX_CALLS: u32 = 50;
+
+/// Aggregator errors
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum AggregatorError {
+    /// Contract not initialized
+    NotInitialized = 1,
+    /// Contract already initialized
+    AlreadyInitialized = 2,
+    /// Caller is not authorized
+    Unauthorized = 3,
+    /// Batch is empty or exceeds MAX_CALLS
+    InvalidBatchSize = 4,
+    /// Call is not an allowlisted (contract, function) view
+    ViewNotAllowed = 5,
+}
+
+/// A single view call to execute
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Call {
+    pub contract: Address,
+    pub function: Symbol,
+    pub args: Vec<Val>,
+}
+
+/// A (contract, function) pair that multicall may invoke
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct View {
+    pub contract: Address,
+    pub function: Symbol,
+}
+
+/// Packed results of a multicall; entry `i` of each vector belongs to call `i`
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct MulticallResult {
+    /// Whether each call returned successfully
+    pub success: Vec<bool>,
+    /// Contract error code on failure (0 on success or for non-contract failures)
+    pub error_codes: Vec<u32>,
+    /// Return value on success, void on failure
+    pub data: Vec<Val>,
+}
+
+/// Storage keys
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    /// Allowlisted view function ((contract, function) -> bool)
+    View(Address, Symbol),
+}
+
+#[contract]
+pub struct ReadAggregatorContract;
+
+#[contractimpl]
+impl ReadAggregatorContract {
+    /// Initialize with the admin and the view functions that may be called
+    pub fn initialize(e: Env, admin: Address, views: Vec<View>) -> Result<(), AggregatorError> {
+        if e.storage().instance().has(&DataKey::Admin) {
+            return Err(AggregatorError::AlreadyInitialized);
+        }
+        e.storage().instance().set(&DataKey::Admin, &admin);
+        for view in views.iter() {
+            e.storage()
+                .instance()
+                .set(&DataKey::View(view.contract, view.function), &true);
+        }
+        Ok(())
+    }
+
+    /// Add or remove a (contract, function) pair on the view allowlist
+    /// (admin only). Only read-only functions should be allowlisted.
+    pub fn set_view(
+        e: Env,
+        caller: Address,
+        contract: Address,
+        function: Symbol,
+        enabled: bool,
+    ) -> Result<(), AggregatorError> {
+        caller.require_auth();
+        let admin: Address = e
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(AggregatorError::NotInitialized)?;
+        if caller != admin {
+            return Err(AggregatorError::Unauthorized);
+        }
+
+        let key = DataKey::View(contract.clone(), function.clone());
+        if enabled {
+            e.storage().instance().set(&key, &true);
+        } else {
+            e.storage().instance().remove(&key);
+        }
+        e.events()
+            .publish((symbol_short!("ViewSet"), contract, function), enabled);
+        Ok(())
+    }
+
+    /// Check if a (contract, function) pair is on the view allowlist
+    pub fn is_view(e: Env, contract: Address, function: Symbol) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::View(contract, function))
+            .unwrap_or(false)
+    }
+
+    /// Execute view calls in order and return one result per call
+    ///
+    /// # Errors
+    /// * `InvalidBatchSize` - no calls or more than `MAX_CALLS`
+    /// * `ViewNotAllowed` - a call is not on the view allowlist
+    pub fn multicall(e: Env, calls: Vec<Call>) -> Result<MulticallResult, AggregatorError> {
+        if calls.is_empty() || calls.len() > MAX_CALLS {
+            return Err(AggregatorError::InvalidBatchSize);
+        }
+        for call in calls.iter() {
+            if !Self::is_view(e.clone(), call.contract.clone(), call.function.clone()) {
+                return Err(AggregatorError::ViewNotAllowed);
+            }
+        }
+
+        let mut results = MulticallResult {
+            success: Vec::new(&e),
+            error_codes: Vec::new(&e),
+            data: Vec::new(&e),
+        };
+        for call in calls.iter() {
+            let (success, error_code, data) = match e.try_invoke_contract::<Val, InvokeError>(
+                &call.contract,
+                &call.function,
+                call.args,
+            ) {
+                Ok(Ok(data)) => (true, 0, data),
+                Err(Ok(InvokeError::Contract(code))) => (false, code, ().into_val(&e)),
+                _ => (false, 0, ().into_val(&e)),
+            };
+            results.success.push_back(success);
+            results.error_codes.push_back(error_code);
+            results.data.push_back(data);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests;
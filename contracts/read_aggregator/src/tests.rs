@@ -0,0 +1,140 @@
+#![cfg(test)]
+
+use super::*;
+use commitment_core::CommitmentCoreContract;
+use commitment_nft::CommitmentNFTContract;
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, IntoVal, String, TryFromVal};
+
+struct Setup<'a> {
+    e: Env,
+    admin: Address,
+    client: ReadAggregatorContractClient<'a>,
+    core_id: Address,
+    nft_id: Address,
+}
+
+fn setup() -> Setup<'static> {
+    let e = Env::default();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+
+    let nft_id = e.register_contract(None, CommitmentNFTContract);
+    let core_id = e.register_contract(None, CommitmentCoreContract);
+    commitment_nft::CommitmentNFTContractClient::new(&e, &nft_id).initialize(&admin);
+    commitment_core::CommitmentCoreContractClient::new(&e, &core_id).initialize(&admin, &nft_id);
+
+    let contract_id = e.register_contract(None, ReadAggregatorContract);
+    let client = ReadAggregatorContractClient::new(&e, &contract_id);
+    client.initialize(
+        &admin,
+        &vec![
+            &e,
+            view(&e, &core_id, "get_total_commitments"),
+            view(&e, &core_id, "get_admin"),
+            view(&e, &core_id, "get_commitment"),
+            view(&e, &nft_id, "total_supply"),
+            view(&e, &nft_id, "owner_of"),
+        ],
+    );
+
+    Setup {
+        e,
+        admin,
+        client,
+        core_id,
+        nft_id,
+    }
+}
+
+fn view(e: &Env, contract: &Address, function: &str) -> View {
+    View {
+        contract: contract.clone(),
+        function: Symbol::new(e, function),
+    }
+}
+
+fn call(e: &Env, contract: &Address, function: &str, args: Vec<Val>) -> Call {
+    Call {
+        contract: contract.clone(),
+        function: Symbol::new(e, function),
+        args,
+    }
+}
+
+#[test]
+fn test_multicall_returns_results_in_order() {
+    let s = setup();
+    let e = &s.e;
+
+    let results = s.client.multicall(&vec![
+        e,
+        call(e, &s.core_id, "get_total_commitments", Vec::new(e)),
+        call(e, &s.nft_id, "total_supply", Vec::new(e)),
+        call(e, &s.core_id, "get_admin", Vec::new(e)),
+    ]);
+
+    assert_eq!(results.data.len(), 3);
+    assert_eq!(results.success, vec![e, true, true, true]);
+    assert_eq!(u64::try_from_val(e, &results.data.get(0).unwrap()), Ok(0));
+    assert_eq!(u32::try_from_val(e, &results.data.get(1).unwrap()), Ok(0));
+    assert_eq!(
+        Address::try_from_val(e, &results.data.get(2).unwrap()),
+        Ok(s.admin.clone())
+    );
+}
+
+#[test]
+fn test_multicall_reports_failures_without_aborting() {
+    let s = setup();
+    let e = &s.e;
+
+    let results = s.client.multicall(&vec![
+        e,
+        call(e, &s.nft_id, "owner_of", vec![e, 999u32.into_val(e)]),
+        call(
+            e,
+            &s.core_id,
+            "get_commitment",
+            vec![e, String::from_str(e, "missing").into_val(e)],
+        ),
+        call(e, &s.nft_id, "total_supply", Vec::new(e)),
+    ]);
+
+    assert_eq!(results.success, vec![e, false, false, true]);
+    assert_eq!(results.error_codes.get(0), Some(3)); // commitment_nft TokenNotFound
+    assert_eq!(results.error_codes.get(2), Some(0));
+}
+
+#[test]
+fn test_multicall_rejects_calls_off_the_allowlist() {
+    let s = setup();
+    let e = &s.e;
+    let other = Address::generate(e);
+
+    let result = s
+        .client
+        .try_multicall(&vec![e, call(e, &other, "total_supply", Vec::new(e))]);
+    assert_eq!(result.err(), Some(Ok(AggregatorError::ViewNotAllowed)));
+
+    // A registered contract's state-changing functions are not callable
+    let result = s
+        .client
+        .try_multicall(&vec![e, call(e, &s.core_id, "pause", Vec::new(e))]);
+    assert_eq!(result.err(), Some(Ok(AggregatorError::ViewNotAllowed)));
+
+    let total_supply = Symbol::new(e, "total_supply");
+    s.client
+        .set_view(&s.admin, &s.nft_id, &total_supply, &false);
+    assert!(!s.client.is_view(&s.nft_id, &total_supply));
+    let result = s
+        .client
+        .try_multicall(&vec![e, call(e, &s.nft_id, "total_supply", Vec::new(e))]);
+    assert_eq!(result.err(), Some(Ok(AggregatorError::ViewNotAllowed)));
+}
+
+#[test]
+fn test_multicall_rejects_empty_batch() {
+    let s = setup();
+    let result = s.client.try_multicall(&Vec::new(&s.e));
+    assert_eq!(result.err(), Some(Ok(AggregatorError::InvalidBatchSize)));
+}
@@ -12,6 +12,8 @@
 //! - Access control patterns
 //! - Event emission patterns
 //! - Rate limiting helpers
+//! - Protocol sunset state
+//! - Snapshot versioning for paged reads
 
 pub mod access_control;
 pub mod batch;
@@ -22,7 +24,9 @@ pub mod events;
 pub mod math;
 pub mod pausable;
 pub mod rate_limiting;
+pub mod snapshot;
 pub mod storage;
+pub mod sunset;
 pub mod time;
 pub mod validation;
 
@@ -39,6 +43,8 @@ pub use events::*;
 pub use math::*;
 pub use pausable::*;
 pub use rate_limiting::*;
+pub use snapshot::SnapshotVersion;
 pub use storage::*;
+pub use sunset::{Sunset, SunsetState};
 pub use time::*;
 pub use validation::*;
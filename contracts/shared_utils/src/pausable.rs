@@ -1,7 +1,7 @@
 //! Pausable contract functionality for emergency stops
 
 use super::events::Events;
-use soroban_sdk::{symbol_short, Env, Symbol};
+use soroban_sdk::{symbol_short, Address, Env, Symbol};
 
 /// Pausable contract functionality
 pub struct Pausable;
@@ -37,17 +37,27 @@ impl Pausable {
     /// # Panics
     /// Panics if contract is already paused
     pub fn pause(e: &Env) {
-        if Self::is_paused(e) {
-            panic!("Contract is already paused");
-        }
-
-        // Set paused state
-        e.storage().instance().set(&Self::paused_key(e), &true);
+        Self::set_paused(e, true);
 
         // Emit pause event
         Events::emit(e, symbol_short!("Pause"), ());
     }
 
+    /// Pause the contract, recording who paused it
+    ///
+    /// The `Pause` event data is `(by, timestamp)`.
+    ///
+    /// # Panics
+    /// Panics if contract is already paused
+    pub fn pause_by(e: &Env, by: &Address) {
+        Self::set_paused(e, true);
+        Events::emit(
+            e,
+            symbol_short!("Pause"),
+            (by.clone(), e.ledger().timestamp()),
+        );
+    }
+
     /// Unpause the contract
     ///
     /// # Arguments
@@ -56,17 +66,37 @@ impl Pausable {
     /// # Panics
     /// Panics if contract is already unpaused
     pub fn unpause(e: &Env) {
-        if !Self::is_paused(e) {
-            panic!("Contract is already unpaused");
-        }
-
-        // Clear paused state
-        e.storage().instance().set(&Self::paused_key(e), &false);
+        Self::set_paused(e, false);
 
         // Emit unpause event
         Events::emit(e, symbol_short!("Unpause"), ());
     }
 
+    /// Unpause the contract, recording who unpaused it
+    ///
+    /// The `Unpause` event data is `(by, timestamp)`.
+    ///
+    /// # Panics
+    /// Panics if contract is already unpaused
+    pub fn unpause_by(e: &Env, by: &Address) {
+        Self::set_paused(e, false);
+        Events::emit(
+            e,
+            symbol_short!("Unpause"),
+            (by.clone(), e.ledger().timestamp()),
+        );
+    }
+
+    fn set_paused(e: &Env, paused: bool) {
+        if paused && Self::is_paused(e) {
+            panic!("Contract is already paused");
+        }
+        if !paused && !Self::is_paused(e) {
+            panic!("Contract is already unpaused");
+        }
+        e.storage().instance().set(&Self::paused_key(e), &paused);
+    }
+
     /// Modifier to require that the contract is not paused
     ///
     /// # Arguments
@@ -82,9 +82,18 @@ impl RateLimiter {
     /// - Otherwise, maintains a fixed time window based on ledger timestamp.
     /// - Panics with `"Rate limit exceeded"` when limit is hit.
     pub fn check(e: &Env, address: &Address, function: &Symbol) {
+        if !Self::try_check(e, address, function) {
+            panic!("Rate limit exceeded");
+        }
+    }
+
+    /// Like `check`, but returns `false` instead of panicking when the limit
+    /// is hit, so callers can report their own error. The rejected call is
+    /// not counted.
+    pub fn try_check(e: &Env, address: &Address, function: &Symbol) -> bool {
         // Exempt addresses bypass rate limits
         if Self::is_exempt(e, address) {
-            return;
+            return true;
         }
 
         // Load configuration; if none, do nothing
@@ -93,7 +102,7 @@ impl RateLimiter {
 
         let (window_seconds, max_calls) = match config {
             Some(cfg) => cfg,
-            None => return,
+            None => return true,
         };
 
         let now = TimeUtils::now(e);
@@ -115,13 +124,14 @@ impl RateLimiter {
         // Enforce count
         let new_count = count.saturating_add(1);
         if new_count > max_calls {
-            panic!("Rate limit exceeded");
+            return false;
         }
 
         // Persist updated state
         e.storage()
             .instance()
             .set(&state_key, &(window_start, new_count));
+        true
     }
 }
 
@@ -144,6 +154,10 @@ mod tests {
             RateLimiter::check(&e, &caller, &fn_symbol);
         }
 
+        pub fn checked_call(e: Env, caller: Address) -> bool {
+            RateLimiter::try_check(&e, &caller, &symbol_short!("limited"))
+        }
+
         pub fn configure_limit(e: Env, function: Symbol, window_seconds: u64, max_calls: u32) {
             RateLimiter::set_limit(&e, &function, window_seconds, max_calls);
         }
@@ -186,6 +200,19 @@ mod tests {
         client.limited_call(&caller);
     }
 
+    #[test]
+    fn test_try_check_reports_exceeded_without_panicking() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, TestRateLimitContract);
+        let client = TestRateLimitContractClient::new(&env, &contract_id);
+
+        let caller = <Address as TestAddress>::generate(&env);
+        client.configure_limit(&symbol_short!("limited"), &60u64, &1u32);
+
+        assert!(client.checked_call(&caller));
+        assert!(!client.checked_call(&caller));
+    }
+
     #[test]
     fn test_rate_limit_resets_after_window() {
         let env = Env::default();
@@ -0,0 +1,61 @@
+//! Snapshot versioning for consistent multi-page reads
+//!
+//! Contracts bump a global mutation counter whenever paged collections
+//! change and return it with every page. A client that sees the version
+//! change between pages knows the export is inconsistent and should retry.
+use soroban_sdk::Env;
+
+pub mod keys {
+    use soroban_sdk::{symbol_short, Symbol};
+    pub const SNAPSHOT_VERSION: Symbol = symbol_short!("SNAP_VER");
+}
+
+pub struct SnapshotVersion;
+
+impl SnapshotVersion {
+    /// Current snapshot version (0 before any mutation)
+    pub fn current(e: &Env) -> u64 {
+        e.storage()
+            .instance()
+            .get::<_, u64>(&keys::SNAPSHOT_VERSION)
+            .unwrap_or(0)
+    }
+
+    /// Record a mutation of paged state
+    pub fn bump(e: &Env) {
+        let next = Self::current(e).wrapping_add(1);
+        e.storage().instance().set(&keys::SNAPSHOT_VERSION, &next);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl};
+
+    #[contract]
+    pub struct TestSnapshotContract;
+
+    #[contractimpl]
+    impl TestSnapshotContract {
+        pub fn mutate(e: Env) {
+            SnapshotVersion::bump(&e);
+        }
+
+        pub fn version(e: Env) -> u64 {
+            SnapshotVersion::current(&e)
+        }
+    }
+
+    #[test]
+    fn test_snapshot_version_bumps() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, TestSnapshotContract);
+        let client = TestSnapshotContractClient::new(&env, &contract_id);
+
+        assert_eq!(client.version(), 0);
+        client.mutate();
+        client.mutate();
+        assert_eq!(client.version(), 2);
+    }
+}
@@ -0,0 +1,138 @@
+//! Protocol sunset (wind-down) state
+//!
+//! Once a sunset begins no new positions are opened, while existing ones run
+//! to term. After the final date penalties are waived and leftover funds may
+//! be distributed. The sunset cannot be cancelled.
+use super::events::Events;
+use soroban_sdk::{contracttype, symbol_short, Env};
+
+pub mod keys {
+    use soroban_sdk::{symbol_short, Symbol};
+    pub const SUNSET_FINAL_DATE: Symbol = symbol_short!("SUNSET");
+}
+
+/// Lifecycle state of a contract with respect to a protocol sunset
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SunsetState {
+    /// Normal operation
+    Live,
+    /// Sunset announced: no new positions, existing ones run to term
+    Sunsetting,
+    /// Final date reached: penalties waived, remaining funds distributable
+    Final,
+}
+
+pub struct Sunset;
+
+impl Sunset {
+    /// Final date of the sunset, if one has begun
+    pub fn final_date(e: &Env) -> Option<u64> {
+        e.storage().instance().get(&keys::SUNSET_FINAL_DATE)
+    }
+
+    /// Current sunset state
+    pub fn state(e: &Env) -> SunsetState {
+        match Self::final_date(e) {
+            None => SunsetState::Live,
+            Some(final_date) if e.ledger().timestamp() < final_date => SunsetState::Sunsetting,
+            Some(_) => SunsetState::Final,
+        }
+    }
+
+    /// Begin the sunset with the given final date
+    ///
+    /// # Panics
+    /// Panics if a sunset has already begun or `final_date` is not in the future
+    pub fn begin(e: &Env, final_date: u64) {
+        if Self::final_date(e).is_some() {
+            panic!("Sunset already started");
+        }
+        let now = e.ledger().timestamp();
+        if final_date <= now {
+            panic!("Sunset final date must be in the future");
+        }
+        e.storage()
+            .instance()
+            .set(&keys::SUNSET_FINAL_DATE, &final_date);
+
+        Events::emit(e, symbol_short!("Sunset"), (final_date, now));
+    }
+
+    /// Require that no sunset has begun
+    pub fn require_live(e: &Env) {
+        if Self::final_date(e).is_some() {
+            panic!("Action not allowed during protocol sunset");
+        }
+    }
+
+    /// Require that the sunset final date has been reached
+    pub fn require_final(e: &Env) {
+        if Self::state(e) != SunsetState::Final {
+            panic!("Sunset final date not reached");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, testutils::Ledger};
+
+    #[contract]
+    pub struct TestSunsetContract;
+
+    #[contractimpl]
+    impl TestSunsetContract {
+        pub fn begin(e: Env, final_date: u64) {
+            Sunset::begin(&e, final_date);
+        }
+
+        pub fn state(e: Env) -> SunsetState {
+            Sunset::state(&e)
+        }
+
+        pub fn open_position(e: Env) {
+            Sunset::require_live(&e);
+        }
+    }
+
+    #[test]
+    fn test_sunset_state_transitions() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, TestSunsetContract);
+        let client = TestSunsetContractClient::new(&env, &contract_id);
+
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        assert_eq!(client.state(), SunsetState::Live);
+        client.open_position();
+
+        client.begin(&2_000);
+        assert_eq!(client.state(), SunsetState::Sunsetting);
+
+        env.ledger().with_mut(|li| li.timestamp = 2_000);
+        assert_eq!(client.state(), SunsetState::Final);
+    }
+
+    #[test]
+    #[should_panic(expected = "Action not allowed during protocol sunset")]
+    fn test_require_live_blocks_after_begin() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, TestSunsetContract);
+        let client = TestSunsetContractClient::new(&env, &contract_id);
+
+        client.begin(&100);
+        client.open_position();
+    }
+
+    #[test]
+    #[should_panic(expected = "Sunset already started")]
+    fn test_sunset_cannot_restart() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, TestSunsetContract);
+        let client = TestSunsetContractClient::new(&env, &contract_id);
+
+        client.begin(&100);
+        client.begin(&200);
+    }
+}
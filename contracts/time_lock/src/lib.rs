@@ -1,12 +1,16 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, String, Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, InvokeError,
+    String, Symbol, TryFromVal, Val, Vec,
 };
 
 /// Maximum delay allowed (30 days in seconds)
 const MAX_DELAY: u64 = 2592000;
 
+/// Maximum parameter changes described by one queued action
+pub const MAX_PARAM_CHANGES: u32 = 10;
+
 /// Different action types with their specific delay requirements
 #[contracttype]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -43,6 +47,61 @@ pub struct QueuedAction {
     pub cancelled: bool,
 }
 
+/// Value of a protocol parameter
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParamValue {
+    /// Any integer parameter (fees, basis points, delays, durations)
+    Int(i128),
+    Bool(bool),
+    Address(Address),
+    /// Getter failed or returned a type not listed above
+    Unsupported,
+}
+
+impl ParamValue {
+    fn from_val(env: &Env, val: Val) -> ParamValue {
+        if let Ok(v) = u32::try_from_val(env, &val) {
+            ParamValue::Int(v as i128)
+        } else if let Ok(v) = u64::try_from_val(env, &val) {
+            ParamValue::Int(v as i128)
+        } else if let Ok(v) = i128::try_from_val(env, &val) {
+            ParamValue::Int(v)
+        } else if let Ok(v) = bool::try_from_val(env, &val) {
+            ParamValue::Bool(v)
+        } else if let Ok(v) = Address::try_from_val(env, &val) {
+            ParamValue::Address(v)
+        } else {
+            ParamValue::Unsupported
+        }
+    }
+}
+
+/// A parameter the proposer says a queued action will change.
+///
+/// `getter` is a no-argument view on `contract` returning the current value.
+/// Nothing ties a change to the action's `data`: it is the proposer's claim,
+/// and reviewers must check it against the payload themselves.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParamChange {
+    pub contract: Address,
+    pub param: Symbol,
+    pub getter: Symbol,
+    pub new_value: ParamValue,
+}
+
+/// Current and proposed value of one parameter
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParamDiff {
+    pub contract: Address,
+    pub param: Symbol,
+    pub before: ParamValue,
+    pub after: ParamValue,
+    pub changed: bool,
+}
+
 /// Contract errors
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -58,6 +117,7 @@ pub enum Error {
     ActionAlreadyCancelled = 8,
     CannotCancelExecutedAction = 9,
     InvalidActionType = 10,
+    TooManyChanges = 11,
 }
 
 /// Storage keys
@@ -67,6 +127,7 @@ pub enum StorageKey {
     ActionCounter,
     Action(u64),
     ActionIds,
+    ActionChanges(u64),
 }
 
 #[contract]
@@ -171,6 +232,79 @@ impl TimelockContract {
         Ok(counter)
     }
 
+    /// Queue an action together with the parameter changes it claims to
+    /// make, so `preview_config_change` can show them before execution.
+    /// The changes are not checked against `data`.
+    ///
+    /// # Arguments
+    /// * `changes` - Declared parameter changes, at most `MAX_PARAM_CHANGES`
+    ///
+    /// # Returns
+    /// * Action ID
+    pub fn queue_config_change(
+        env: Env,
+        action_type: ActionType,
+        target: Address,
+        data: String,
+        delay: u64,
+        changes: Vec<ParamChange>,
+    ) -> Result<u64, Error> {
+        if changes.len() > MAX_PARAM_CHANGES {
+            return Err(Error::TooManyChanges);
+        }
+        let action_id = Self::queue_action(env.clone(), action_type, target, data, delay)?;
+        env.storage()
+            .persistent()
+            .set(&StorageKey::ActionChanges(action_id), &changes);
+        Ok(action_id)
+    }
+
+    /// Preview a pending action's parameter changes as before/after pairs
+    ///
+    /// Current values are read live from each contract's getter; a getter
+    /// that is missing or fails reads as `Unsupported`. The `after` values
+    /// are the proposer's unverified claims, not derived from `data`.
+    ///
+    /// # Arguments
+    /// * `action_id` - ID of the action
+    ///
+    /// # Returns
+    /// * One diff per declared change (empty if none were declared)
+    pub fn preview_config_change(env: Env, action_id: u64) -> Result<Vec<ParamDiff>, Error> {
+        let action = Self::get_action(env.clone(), action_id)?;
+        if action.executed {
+            return Err(Error::ActionAlreadyExecuted);
+        }
+        if action.cancelled {
+            return Err(Error::ActionCancelled);
+        }
+
+        let changes: Vec<ParamChange> = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::ActionChanges(action_id))
+            .unwrap_or(Vec::new(&env));
+        let mut diffs = Vec::new(&env);
+        for change in changes.iter() {
+            let before = match env.try_invoke_contract::<Val, InvokeError>(
+                &change.contract,
+                &change.getter,
+                Vec::new(&env),
+            ) {
+                Ok(Ok(val)) => ParamValue::from_val(&env, val),
+                _ => ParamValue::Unsupported,
+            };
+            diffs.push_back(ParamDiff {
+                contract: change.contract,
+                param: change.param,
+                changed: before != change.new_value,
+                before,
+                after: change.new_value,
+            });
+        }
+        Ok(diffs)
+    }
+
     /// Execute a queued action after the delay has passed
     /// Anyone can execute a queued action once the delay has passed
     ///
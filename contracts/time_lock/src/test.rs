@@ -511,3 +511,77 @@ fn test_max_delay_constant() {
 
     assert_eq!(client.get_max_delay(), 2592000); // 30 days
 }
+
+mod config_preview {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, symbol_short, vec};
+
+    #[contract]
+    pub struct MockConfigContract;
+
+    #[contractimpl]
+    impl MockConfigContract {
+        pub fn get_fee(_env: Env) -> u32 {
+            100
+        }
+    }
+
+    #[test]
+    fn test_preview_config_change_reports_before_and_after() {
+        let (env, admin, target) = create_test_env();
+        let contract_id = env.register_contract(None, TimelockContract);
+        let client = TimelockContractClient::new(&env, &contract_id);
+        let config_id = env.register_contract(None, MockConfigContract);
+        client.initialize(&admin);
+        env.mock_all_auths();
+
+        let changes = vec![
+            &env,
+            ParamChange {
+                contract: config_id.clone(),
+                param: symbol_short!("fee"),
+                getter: symbol_short!("get_fee"),
+                new_value: ParamValue::Int(250),
+            },
+            ParamChange {
+                contract: config_id.clone(),
+                param: symbol_short!("cap"),
+                getter: symbol_short!("get_cap"),
+                new_value: ParamValue::Int(10),
+            },
+        ];
+        let action_id = client.queue_config_change(
+            &ActionType::FeeChange,
+            &target,
+            &String::from_str(&env, "set_fee"),
+            &86400,
+            &changes,
+        );
+
+        let diffs = client.preview_config_change(&action_id);
+        assert_eq!(diffs.len(), 2);
+        let diff = diffs.get(0).unwrap();
+        assert_eq!(diff.contract, config_id);
+        assert_eq!(diff.param, symbol_short!("fee"));
+        assert_eq!(diff.before, ParamValue::Int(100));
+        assert_eq!(diff.after, ParamValue::Int(250));
+        assert!(diff.changed);
+        // A missing getter doesn't abort the preview
+        assert_eq!(diffs.get(1).unwrap().before, ParamValue::Unsupported);
+
+        // Plain actions preview as no changes; executed ones can't be previewed
+        let plain = client.queue_action(
+            &ActionType::ParameterChange,
+            &target,
+            &String::from_str(&env, "noop"),
+            &86400,
+        );
+        assert_eq!(client.preview_config_change(&plain).len(), 0);
+        env.ledger().with_mut(|li| li.timestamp += 86400);
+        client.execute_action(&action_id);
+        assert_eq!(
+            client.try_preview_config_change(&action_id),
+            Err(Ok(Error::ActionAlreadyExecuted))
+        );
+    }
+}
@@ -137,6 +137,7 @@ fn test_attest_succeeds_after_commitment_created() {
             amount,
             token.clone(),
             rules,
+            None,
         )
     });
 
@@ -6,7 +6,7 @@
 //! - Failure propagation between contracts
 //! - Multi-contract transaction flows
 
-use crate::harness::{TestHarness, SECONDS_PER_DAY};
+use crate::harness::{TestHarness, DEFAULT_USER_BALANCE, SECONDS_PER_DAY};
 use soroban_sdk::{
     testutils::{Address as _, Events},
     Address, Env, String, Symbol, IntoVal, Vec,
@@ -14,7 +14,7 @@ use soroban_sdk::{
 
 use commitment_core::{CommitmentCoreContract, CommitmentRules};
 use commitment_nft::{CommitmentNFTContract, ContractError as NftContractError};
-use attestation_engine::{AttestationEngineContract, AttestationError};
+use attestation_engine::{AttestationEngineContract, AttestationError, AttestationsPage};
 use allocation_logic::{AllocationStrategiesContract, RiskLevel, Strategy};
 
 /// Verify compliance integration between commitment_core and attestation_engine.
@@ -41,6 +41,7 @@ fn test_verify_compliance_uses_core_commitment_data() {
                 amount,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -63,6 +64,7 @@ fn test_verify_compliance_uses_core_commitment_data() {
         .as_contract(&harness.contracts.commitment_core, || {
             CommitmentCoreContract::update_value(
                 harness.env.clone(),
+                harness.accounts.admin.clone(),
                 commitment_id.clone(),
                 new_value,
             )
@@ -122,6 +124,7 @@ fn test_commitment_core_calls_nft_on_creation() {
                 amount,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -226,6 +229,7 @@ fn test_attestation_engine_verifies_commitment_exists() {
                 amount,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -461,6 +465,7 @@ fn test_attestation_succeeds_after_commitment_created() {
                 amount,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -507,6 +512,7 @@ fn test_multiple_attestations_cross_contract() {
                 amount,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -730,6 +736,7 @@ fn test_commitment_settlement_calls_nft_settle() {
                 amount,
                 harness.contracts.token.clone(),
                 rules,
+                None,
             )
         });
 
@@ -778,6 +785,84 @@ fn test_commitment_settlement_calls_nft_settle() {
     assert_eq!(commitment.status, String::from_str(&harness.env, "settled"));
 }
 
+/// Test: A keeper's NFT settle_expired cannot front-run core settlement
+#[test]
+fn test_nft_settle_expired_leaves_open_commitment_to_core() {
+    let harness = TestHarness::new();
+    let user = &harness.accounts.user1;
+    let keeper = &harness.accounts.user2;
+    let amount = 1_000_000_000_000i128;
+    harness.approve_tokens(user, &harness.contracts.commitment_core, amount);
+    let mut rules = harness.default_rules();
+    rules.duration_days = 1;
+    let commitment_id = harness.create_commitment(user, amount, &harness.contracts.token, rules);
+
+    harness.advance_days(2);
+    let result = harness
+        .env
+        .as_contract(&harness.contracts.commitment_nft, || {
+            CommitmentNFTContract::settle_expired(harness.env.clone(), keeper.clone(), 0)
+        });
+    assert_eq!(result, Err(NftContractError::CommitmentStillOpen));
+
+    // Core settlement still goes through and closes the token itself
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_core, || {
+            CommitmentCoreContract::settle(harness.env.clone(), commitment_id.clone())
+        });
+    assert_eq!(harness.balance(user), DEFAULT_USER_BALANCE);
+    let result = harness
+        .env
+        .as_contract(&harness.contracts.commitment_nft, || {
+            CommitmentNFTContract::settle_expired(harness.env.clone(), keeper.clone(), 0)
+        });
+    assert_eq!(result, Err(NftContractError::AlreadySettled));
+}
+
+/// Test: NFT batch_settle skips tokens whose commitments core still holds open
+#[test]
+fn test_nft_batch_settle_skips_open_commitments() {
+    let harness = TestHarness::new();
+    let user = &harness.accounts.user1;
+    let amount = 1_000_000_000_000i128;
+    harness.approve_tokens(user, &harness.contracts.commitment_core, 2 * amount);
+    let mut rules = harness.default_rules();
+    rules.duration_days = 1;
+    let settled_id =
+        harness.create_commitment(user, amount, &harness.contracts.token, rules.clone());
+    let open_id = harness.create_commitment(user, amount, &harness.contracts.token, rules);
+
+    harness.advance_days(2);
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_core, || {
+            CommitmentCoreContract::settle(harness.env.clone(), settled_id.clone())
+        });
+    let mut token_ids = Vec::new(&harness.env);
+    token_ids.push_back(0);
+    token_ids.push_back(1);
+    let result = harness
+        .env
+        .as_contract(&harness.contracts.commitment_nft, || {
+            CommitmentNFTContract::batch_settle(harness.env.clone(), token_ids)
+        });
+    assert_eq!(result.success_count, 0);
+    assert_eq!(result.errors.len(), 2);
+    assert_eq!(
+        result.errors.get(1).unwrap().error_code,
+        NftContractError::CommitmentStillOpen as u32
+    );
+
+    // Core can still settle the open commitment
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_core, || {
+            CommitmentCoreContract::settle(harness.env.clone(), open_id.clone())
+        });
+    assert_eq!(harness.balance(user), DEFAULT_USER_BALANCE);
+}
+
 /// Test: Allocation logic interacts with pools correctly
 #[test]
 #[ignore] // Temporarily disabled - allocation_logic not available
@@ -890,6 +975,7 @@ fn test_cross_contract_state_consistency() {
                 amount,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -944,6 +1030,7 @@ fn test_health_metrics_cross_contract_data() {
                 amount,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -1275,6 +1362,7 @@ fn test_early_exit_zero_current_value() {
         .as_contract(&harness.contracts.commitment_core, || {
             CommitmentCoreContract::update_value(
                 harness.env.clone(),
+                harness.accounts.admin.clone(),
                 commitment_id.clone(),
                 0,
             )
@@ -58,6 +58,7 @@ fn test_e2e_complete_commitment_lifecycle() {
                 amount,
                 harness.contracts.token.clone(),
                 rules,
+                None,
             )
         });
 
@@ -196,6 +197,7 @@ fn test_e2e_early_exit_with_penalty() {
                 amount,
                 harness.contracts.token.clone(),
                 rules,
+                None,
             )
         });
 
@@ -257,6 +259,7 @@ fn test_e2e_multiple_users_concurrent_commitments() {
                 amount1,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -269,6 +272,7 @@ fn test_e2e_multiple_users_concurrent_commitments() {
                 amount2,
                 harness.contracts.token.clone(),
                 harness.safe_rules(),
+                None,
             )
         });
 
@@ -339,6 +343,7 @@ fn test_e2e_commitment_with_allocation() {
                 amount,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -398,6 +403,7 @@ fn test_e2e_violation_detection_flow() {
                 amount,
                 harness.contracts.token.clone(),
                 rules,
+                None,
             )
         });
 
@@ -467,6 +473,7 @@ fn test_e2e_nft_transfer_between_users() {
                 amount,
                 harness.contracts.token.clone(),
                 rules,
+                None,
             )
         });
 
@@ -544,6 +551,7 @@ fn test_e2e_fee_generation_tracking() {
                 amount,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -602,6 +610,7 @@ fn test_e2e_oracle_price_monitoring() {
                 amount,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
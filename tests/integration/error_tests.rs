@@ -60,6 +60,7 @@ fn test_error_unauthorized_attestation() {
                 amount,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -122,6 +123,7 @@ fn test_error_unauthorized_early_exit() {
                 amount,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -158,6 +160,7 @@ fn test_error_unauthorized_nft_transfer() {
                 amount,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -198,6 +201,7 @@ fn test_error_zero_amount_commitment() {
                 0, // Zero amount
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 }
@@ -231,6 +235,7 @@ fn test_error_expiration_overflow() {
                 amount,
                 harness.contracts.token.clone(),
                 rules,
+                None,
             )
         });
 }
@@ -263,6 +268,7 @@ fn test_error_zero_duration_commitment() {
                 amount,
                 harness.contracts.token.clone(),
                 rules,
+                None,
             )
         });
 }
@@ -295,6 +301,7 @@ fn test_error_invalid_max_loss_percent() {
                 amount,
                 harness.contracts.token.clone(),
                 rules,
+                None,
             )
         });
 }
@@ -327,6 +334,7 @@ fn test_error_invalid_commitment_type() {
                 amount,
                 harness.contracts.token.clone(),
                 rules,
+                None,
             )
         });
 }
@@ -350,6 +358,7 @@ fn test_error_invalid_attestation_type() {
                 amount,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -543,6 +552,7 @@ fn test_error_double_settlement() {
                 amount,
                 harness.contracts.token.clone(),
                 rules,
+                None,
             )
         });
 
@@ -576,7 +586,10 @@ fn test_boundary_max_duration() {
     harness.approve_tokens(user, &harness.contracts.commitment_core, amount);
 
     let now = harness.current_timestamp();
-    let max_safe_days = ((u64::MAX - now) / SECONDS_PER_DAY).min(u32::MAX as u64) as u32;
+    // The NFT contract caps durations well below the u64 overflow boundary
+    let max_safe_days = ((u64::MAX - now) / SECONDS_PER_DAY)
+        .min(u32::MAX as u64)
+        .min(commitment_nft::DEFAULT_MAX_DURATION_DAYS as u64) as u32;
 
     let rules = CommitmentRules {
         duration_days: max_safe_days,
@@ -596,6 +609,7 @@ fn test_boundary_max_duration() {
                 amount,
                 harness.contracts.token.clone(),
                 rules,
+                None,
             )
         });
 
@@ -629,6 +643,7 @@ fn test_boundary_minimum_amount() {
                 amount,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -667,6 +682,7 @@ fn test_boundary_max_loss_percent_100() {
                 amount,
                 harness.contracts.token.clone(),
                 rules,
+                None,
             )
         });
 
@@ -705,6 +721,7 @@ fn test_boundary_max_loss_percent_0() {
                 amount,
                 harness.contracts.token.clone(),
                 rules,
+                None,
             )
         });
 
@@ -783,6 +800,7 @@ fn test_error_premature_settlement() {
                 amount,
                 harness.contracts.token.clone(),
                 harness.default_rules(), // 30 days
+                None,
             )
         });
 
@@ -81,6 +81,7 @@ fn test_frontend_create_commitment_flow() {
                 amount,
                 harness.contracts.token.clone(),
                 rules.clone(),
+                None,
             )
         });
 
@@ -121,6 +122,7 @@ fn test_frontend_view_user_commitments() {
                     amount,
                     harness.contracts.token.clone(),
                     harness.default_rules(),
+                    None,
                 )
             });
     }
@@ -156,6 +158,7 @@ fn test_frontend_view_user_nfts() {
                     amount,
                     harness.contracts.token.clone(),
                     harness.default_rules(),
+                    None,
                 )
             });
     }
@@ -209,6 +212,7 @@ fn test_frontend_total_value_locked_display() {
                 amount1,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -221,6 +225,7 @@ fn test_frontend_total_value_locked_display() {
                 amount2,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -262,6 +267,7 @@ fn test_frontend_total_commitments_display() {
                     amount,
                     harness.contracts.token.clone(),
                     harness.default_rules(),
+                    None,
                 )
             });
     }
@@ -303,6 +309,7 @@ fn test_frontend_nft_transfer_flow() {
                 amount,
                 harness.contracts.token.clone(),
                 rules,
+                None,
             )
         });
 
@@ -386,6 +393,7 @@ fn test_frontend_early_exit_flow() {
                 amount,
                 harness.contracts.token.clone(),
                 harness.default_rules(),
+                None,
             )
         });
 
@@ -456,6 +464,7 @@ fn test_frontend_commitment_type_rules_display() {
                     amount,
                     harness.contracts.token.clone(),
                     rules,
+                    None,
                 )
             });
         commitment_ids.push(id);
@@ -342,6 +342,7 @@ impl TestHarness {
                 amount,
                 asset.clone(),
                 rules,
+                None,
             )
         })
     }
@@ -16,6 +16,7 @@
 //! - `token_tests`: Token/asset interaction tests
 //! - `e2e_tests`: End-to-end flow tests
 //! - `error_tests`: Error and edge case tests
+//! - `scenario`: Replayable lifecycle scenarios with per-step invariant checks
 
 #![cfg(test)]
 
@@ -26,6 +27,7 @@ pub mod oracle_tests;
 pub mod token_tests;
 pub mod e2e_tests;
 pub mod error_tests;
+pub mod scenario;
 // TODO: Update attestation_validation_test to use new Soroban SDK API
 // pub mod attestation_validation_test;
 
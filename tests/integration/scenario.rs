@@ -0,0 +1,485 @@
+//! Replayable Scenario Runner
+//!
+//! Lifecycle scenarios are plain Rust data: a list of `Step`s that the
+//! `ScenarioRunner` replays against a fresh `TestHarness`. After every step the
+//! runner checks protocol-wide invariants, so a scenario doubles as a
+//! regression suite for any state reachable along its path.
+//!
+//! Commitments are referred to by a scenario-local label (e.g. `"c1"`), which
+//! the runner maps to the commitment and NFT ids assigned on creation.
+
+use std::collections::HashMap;
+
+use crate::harness::{TestHarness, SECONDS_PER_DAY};
+use soroban_sdk::{Address, String};
+
+use attestation_engine::AttestationEngineContract;
+use commitment_core::{Commitment, CommitmentCoreContract, CommitmentRules};
+use commitment_nft::CommitmentNFTContract;
+
+/// Test account acting in a step
+#[derive(Clone, Copy, Debug)]
+pub enum Actor {
+    User1,
+    User2,
+}
+
+/// Kind of attestation submitted by the harness verifier
+#[derive(Clone, Copy, Debug)]
+pub enum AttestKind {
+    /// Compliant health check
+    Health,
+    /// Non-compliant loss violation report
+    Violation,
+}
+
+/// A single scenario operation
+#[derive(Clone, Copy, Debug)]
+pub enum Step {
+    /// Create a commitment owned by `owner`
+    Create {
+        label: &'static str,
+        owner: Actor,
+        amount: i128,
+        duration_days: u32,
+        max_loss_percent: u32,
+    },
+    /// Submit an attestation from the harness verifier
+    Attest {
+        label: &'static str,
+        kind: AttestKind,
+    },
+    /// Push a new valuation into commitment_core
+    UpdateValue { label: &'static str, value: i128 },
+    /// Advance ledger time
+    AdvanceDays(u64),
+    /// Settle a matured commitment
+    Settle { label: &'static str },
+    /// Exit early as the commitment owner
+    EarlyExit { label: &'static str },
+    /// Transfer the commitment NFT from its current holder
+    Transfer { label: &'static str, to: Actor },
+    /// Assert the commitment status in commitment_core
+    ExpectStatus {
+        label: &'static str,
+        status: &'static str,
+    },
+    /// Assert the current NFT holder
+    ExpectHolder { label: &'static str, holder: Actor },
+}
+
+/// A named, replayable sequence of steps
+pub struct Scenario {
+    pub name: &'static str,
+    pub steps: &'static [Step],
+}
+
+/// Executes scenarios against a `TestHarness` with invariant checks
+pub struct ScenarioRunner {
+    pub harness: TestHarness,
+    commitments: HashMap<&'static str, String>,
+    initial_total_balance: i128,
+}
+
+impl ScenarioRunner {
+    /// Create a runner over a freshly deployed harness
+    pub fn new() -> Self {
+        let harness = TestHarness::new();
+        let initial_total_balance = Self::total_balance(&harness);
+        Self {
+            harness,
+            commitments: HashMap::new(),
+            initial_total_balance,
+        }
+    }
+
+    /// Replay every step of `scenario`, checking invariants after each one
+    pub fn run(&mut self, scenario: &Scenario) {
+        for (index, step) in scenario.steps.iter().enumerate() {
+            self.execute(step);
+            self.check_invariants(scenario.name, index, step);
+        }
+    }
+
+    fn address(&self, actor: Actor) -> Address {
+        match actor {
+            Actor::User1 => self.harness.accounts.user1.clone(),
+            Actor::User2 => self.harness.accounts.user2.clone(),
+        }
+    }
+
+    fn commitment_id(&self, label: &str) -> String {
+        self.commitments
+            .get(label)
+            .unwrap_or_else(|| panic!("unknown commitment label {}", label))
+            .clone()
+    }
+
+    /// Read a commitment by its scenario label
+    pub fn commitment(&self, label: &str) -> Commitment {
+        let commitment_id = self.commitment_id(label);
+        let h = &self.harness;
+        h.env.as_contract(&h.contracts.commitment_core, || {
+            CommitmentCoreContract::get_commitment(h.env.clone(), commitment_id)
+        })
+    }
+
+    fn nft_holder(&self, token_id: u32) -> Address {
+        let h = &self.harness;
+        h.env.as_contract(&h.contracts.commitment_nft, || {
+            CommitmentNFTContract::owner_of(h.env.clone(), token_id).unwrap()
+        })
+    }
+
+    fn execute(&mut self, step: &Step) {
+        let h = &self.harness;
+        match *step {
+            Step::Create {
+                label,
+                owner,
+                amount,
+                duration_days,
+                max_loss_percent,
+            } => {
+                let owner = self.address(owner);
+                let rules = CommitmentRules {
+                    duration_days,
+                    max_loss_percent,
+                    commitment_type: String::from_str(&h.env, "balanced"),
+                    early_exit_penalty: 5,
+                    min_fee_threshold: 1000,
+                    grace_period_days: 0,
+                };
+                h.approve_tokens(&owner, &h.contracts.commitment_core, amount);
+                let commitment_id = h.create_commitment(&owner, amount, &h.contracts.token, rules);
+                self.commitments.insert(label, commitment_id);
+            }
+            Step::Attest { label, kind } => {
+                let commitment_id = self.commitment_id(label);
+                let (attestation_type, data, is_compliant) = match kind {
+                    AttestKind::Health => ("health_check", h.health_check_data(), true),
+                    AttestKind::Violation => (
+                        "violation",
+                        h.violation_data("loss_exceeded", "high"),
+                        false,
+                    ),
+                };
+                h.env.as_contract(&h.contracts.attestation_engine, || {
+                    AttestationEngineContract::attest(
+                        h.env.clone(),
+                        h.accounts.verifier.clone(),
+                        commitment_id,
+                        String::from_str(&h.env, attestation_type),
+                        data,
+                        is_compliant,
+                    )
+                    .unwrap();
+                });
+            }
+            Step::UpdateValue { label, value } => {
+                let commitment_id = self.commitment_id(label);
+                h.env.as_contract(&h.contracts.commitment_core, || {
+                    CommitmentCoreContract::update_value(
+                        h.env.clone(),
+                        h.accounts.admin.clone(),
+                        commitment_id,
+                        value,
+                    )
+                });
+            }
+            Step::AdvanceDays(days) => h.advance_time(days * SECONDS_PER_DAY),
+            Step::Settle { label } => {
+                let commitment_id = self.commitment_id(label);
+                h.env.as_contract(&h.contracts.commitment_core, || {
+                    CommitmentCoreContract::settle(h.env.clone(), commitment_id)
+                });
+            }
+            Step::EarlyExit { label } => {
+                let commitment = self.commitment(label);
+                h.env.as_contract(&h.contracts.commitment_core, || {
+                    CommitmentCoreContract::early_exit(
+                        h.env.clone(),
+                        commitment.commitment_id,
+                        commitment.owner,
+                    )
+                });
+            }
+            Step::Transfer { label, to } => {
+                let token_id = self.commitment(label).nft_token_id;
+                let from = self.nft_holder(token_id);
+                let to = self.address(to);
+                h.env.as_contract(&h.contracts.commitment_nft, || {
+                    CommitmentNFTContract::transfer(h.env.clone(), from, to, token_id).unwrap()
+                });
+            }
+            Step::ExpectStatus { label, status } => {
+                assert_eq!(
+                    self.commitment(label).status,
+                    String::from_str(&h.env, status),
+                    "status of {}",
+                    label
+                );
+            }
+            Step::ExpectHolder { label, holder } => {
+                let token_id = self.commitment(label).nft_token_id;
+                assert_eq!(self.nft_holder(token_id), self.address(holder));
+            }
+        }
+    }
+
+    fn total_balance(h: &TestHarness) -> i128 {
+        h.balance(&h.accounts.user1)
+            + h.balance(&h.accounts.user2)
+            + h.balance(&h.contracts.commitment_core)
+    }
+
+    /// Protocol-wide invariants that must hold after every step
+    fn check_invariants(&self, scenario: &str, index: usize, step: &Step) {
+        let h = &self.harness;
+        let context = format!("{} step {} ({:?})", scenario, index, step);
+
+        // Tokens only move between participants and escrow
+        assert_eq!(
+            Self::total_balance(h),
+            self.initial_total_balance,
+            "token conservation after {}",
+            context
+        );
+
+        let settled = String::from_str(&h.env, "settled");
+        let early_exit = String::from_str(&h.env, "early_exit");
+        let active = String::from_str(&h.env, "active");
+        let mut open_value: i128 = 0;
+        for label in self.commitments.keys() {
+            let commitment = self.commitment(label);
+            let closed = commitment.status == settled || commitment.status == early_exit;
+            if !closed {
+                open_value += commitment.current_value;
+            }
+
+            // NFT lifecycle follows the commitment
+            let nft_active = h.env.as_contract(&h.contracts.commitment_nft, || {
+                CommitmentNFTContract::is_active(h.env.clone(), commitment.nft_token_id).unwrap()
+            });
+            assert_eq!(
+                nft_active, !closed,
+                "NFT activity of {} after {}",
+                label, context
+            );
+
+            // Locked NFTs cannot change hands, so holder and owner agree
+            if commitment.status == active {
+                assert_eq!(
+                    self.nft_holder(commitment.nft_token_id),
+                    commitment.owner,
+                    "NFT holder of {} after {}",
+                    label,
+                    context
+                );
+            }
+        }
+
+        let (tvl, conservation, supply) = (
+            h.env.as_contract(&h.contracts.commitment_core, || {
+                CommitmentCoreContract::get_total_value_locked(h.env.clone())
+            }),
+            h.env.as_contract(&h.contracts.commitment_core, || {
+                CommitmentCoreContract::verify_conservation(
+                    h.env.clone(),
+                    h.contracts.token.clone(),
+                )
+            }),
+            h.env.as_contract(&h.contracts.commitment_nft, || {
+                CommitmentNFTContract::total_supply(h.env.clone())
+            }),
+        );
+        assert_eq!(tvl, open_value, "TVL after {}", context);
+        assert!(
+            conservation.conserved,
+            "escrow conservation after {}",
+            context
+        );
+        assert_eq!(
+            supply as usize,
+            self.commitments.len(),
+            "NFT supply after {}",
+            context
+        );
+    }
+}
+
+impl Default for ScenarioRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const AMOUNT: i128 = 1_000_000_000_000;
+
+/// Create, monitor with health checks, mature and settle
+pub const CLEAN_COMPLETION: Scenario = Scenario {
+    name: "clean_completion",
+    steps: &[
+        Step::Create {
+            label: "c1",
+            owner: Actor::User1,
+            amount: AMOUNT,
+            duration_days: 7,
+            max_loss_percent: 10,
+        },
+        Step::AdvanceDays(3),
+        Step::Attest {
+            label: "c1",
+            kind: AttestKind::Health,
+        },
+        Step::AdvanceDays(3),
+        Step::Attest {
+            label: "c1",
+            kind: AttestKind::Health,
+        },
+        Step::AdvanceDays(2),
+        Step::Settle { label: "c1" },
+        Step::ExpectStatus {
+            label: "c1",
+            status: "settled",
+        },
+    ],
+};
+
+/// A reported loss beyond max_loss_percent marks the commitment violated
+pub const VIOLATION: Scenario = Scenario {
+    name: "violation",
+    steps: &[
+        Step::Create {
+            label: "c1",
+            owner: Actor::User1,
+            amount: AMOUNT,
+            duration_days: 30,
+            max_loss_percent: 10,
+        },
+        Step::AdvanceDays(5),
+        Step::Attest {
+            label: "c1",
+            kind: AttestKind::Violation,
+        },
+        Step::UpdateValue {
+            label: "c1",
+            value: AMOUNT * 80 / 100,
+        },
+        Step::ExpectStatus {
+            label: "c1",
+            status: "violated",
+        },
+    ],
+};
+
+/// The owner leaves before maturity and pays the early-exit penalty
+pub const EARLY_EXIT: Scenario = Scenario {
+    name: "early_exit",
+    steps: &[
+        Step::Create {
+            label: "c1",
+            owner: Actor::User1,
+            amount: AMOUNT,
+            duration_days: 30,
+            max_loss_percent: 10,
+        },
+        Step::Create {
+            label: "c2",
+            owner: Actor::User2,
+            amount: AMOUNT,
+            duration_days: 30,
+            max_loss_percent: 10,
+        },
+        Step::AdvanceDays(10),
+        Step::Attest {
+            label: "c1",
+            kind: AttestKind::Health,
+        },
+        Step::EarlyExit { label: "c1" },
+        Step::ExpectStatus {
+            label: "c1",
+            status: "early_exit",
+        },
+        Step::ExpectStatus {
+            label: "c2",
+            status: "active",
+        },
+    ],
+};
+
+/// A settled commitment NFT is sold on the secondary market
+pub const SECONDARY_SALE: Scenario = Scenario {
+    name: "secondary_sale",
+    steps: &[
+        Step::Create {
+            label: "c1",
+            owner: Actor::User1,
+            amount: AMOUNT,
+            duration_days: 1,
+            max_loss_percent: 10,
+        },
+        Step::AdvanceDays(2),
+        Step::Settle { label: "c1" },
+        Step::Transfer {
+            label: "c1",
+            to: Actor::User2,
+        },
+        Step::ExpectHolder {
+            label: "c1",
+            holder: Actor::User2,
+        },
+        Step::Transfer {
+            label: "c1",
+            to: Actor::User1,
+        },
+        Step::ExpectHolder {
+            label: "c1",
+            holder: Actor::User1,
+        },
+    ],
+};
+
+#[test]
+fn test_scenario_clean_completion() {
+    ScenarioRunner::new().run(&CLEAN_COMPLETION);
+}
+
+#[test]
+fn test_scenario_violation() {
+    ScenarioRunner::new().run(&VIOLATION);
+}
+
+#[test]
+fn test_scenario_early_exit() {
+    ScenarioRunner::new().run(&EARLY_EXIT);
+}
+
+#[test]
+fn test_scenario_secondary_sale() {
+    ScenarioRunner::new().run(&SECONDARY_SALE);
+}
+
+#[test]
+fn test_scenarios_replay_on_one_harness() {
+    let mut runner = ScenarioRunner::new();
+    runner.run(&CLEAN_COMPLETION);
+    runner.run(&Scenario {
+        name: "follow_up",
+        steps: &[
+            Step::Create {
+                label: "c2",
+                owner: Actor::User2,
+                amount: AMOUNT,
+                duration_days: 1,
+                max_loss_percent: 10,
+            },
+            Step::AdvanceDays(2),
+            Step::Settle { label: "c2" },
+            Step::ExpectStatus {
+                label: "c1",
+                status: "settled",
+            },
+        ],
+    });
+}
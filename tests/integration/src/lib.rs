@@ -93,6 +93,7 @@ fn test_create_commitment_with_attestation_flow() {
         &1000_0000000,
         &fixture.asset_address,
         &rules,
+        &None,
     );
 
     let commitment = fixture.core_client.get_commitment(&commitment_id);
@@ -140,12 +141,13 @@ fn test_commitment_value_update_with_health_tracking() {
         &1000_0000000,
         &fixture.asset_address,
         &rules,
+        &None,
     );
 
     // Update value in core contract
     fixture
         .core_client
-        .update_value(&commitment_id, &1050_0000000);
+        .update_value(&fixture.admin, &commitment_id, &1050_0000000);
 
     // Record health metrics in attestation engine
     fixture
@@ -180,6 +182,7 @@ fn test_settlement_flow_end_to_end() {
         &1000_0000000,
         &fixture.asset_address,
         &rules,
+        &None,
     );
 
     // Record some fees
@@ -224,12 +227,13 @@ fn test_early_exit_flow_end_to_end() {
         &1000_0000000,
         &fixture.asset_address,
         &rules,
+        &None,
     );
 
     // Update value
     fixture
         .core_client
-        .update_value(&commitment_id, &1100_0000000);
+        .update_value(&fixture.admin, &commitment_id, &1100_0000000);
 
     // Record attestation for early exit
     let mut data = Map::new(&fixture.env);
@@ -272,6 +276,7 @@ fn test_compliance_verification_flow() {
         &1000_0000000,
         &fixture.asset_address,
         &rules,
+        &None,
     );
 
     // Record fees and attest - commitment in good standing
@@ -321,6 +326,7 @@ fn test_gas_single_commitment_creation() {
         &1000_0000000,
         &fixture.asset_address,
         &rules,
+        &None,
     );
 }
 
@@ -337,18 +343,19 @@ fn test_gas_multiple_operations() {
         &1000_0000000,
         &fixture.asset_address,
         &rules,
+        &None,
     );
 
     // Multiple update operations
     fixture
         .core_client
-        .update_value(&commitment_id, &1010_0000000);
+        .update_value(&fixture.admin, &commitment_id, &1010_0000000);
     fixture
         .core_client
-        .update_value(&commitment_id, &1020_0000000);
+        .update_value(&fixture.admin, &commitment_id, &1020_0000000);
     fixture
         .core_client
-        .update_value(&commitment_id, &1030_0000000);
+        .update_value(&fixture.admin, &commitment_id, &1030_0000000);
 
     // Multiple attestation operations
     fixture
@@ -384,6 +391,7 @@ fn test_gas_batch_attestations() {
         &1000_0000000,
         &fixture.asset_address,
         &rules,
+        &None,
     );
 
     // Multiple attestations